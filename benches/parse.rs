@@ -0,0 +1,88 @@
+//! Parser construction, single user agent latency and batch throughput, over
+//! the inputs in `uaparser::bench_support`.
+//!
+//! Feature-gated behavior can be compared by saving a baseline and running
+//! again with the feature on:
+//!
+//! ```text
+//! cargo bench --bench parse -- --save-baseline plain
+//! cargo bench --bench parse --features <feature> -- --baseline plain
+//! ```
+
+use std::{fs, time::Duration};
+
+use criterion::{
+    black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput,
+};
+use uaparser::{
+    bench_support::{
+        self, CORPUS_LEN, MATCHED_EARLY, MATCHED_LATE, NEVER_MATCHING, RULES_PATH,
+    },
+    Parser, UserAgentParser,
+};
+
+fn bench_construction(c: &mut Criterion) {
+    let bytes = fs::read(RULES_PATH).expect("Rule file read failed");
+
+    c.bench_function("from_bytes", |b| {
+        b.iter(|| UserAgentParser::from_bytes(black_box(&bytes)).unwrap());
+    });
+}
+
+fn bench_latency(c: &mut Criterion) {
+    let parser = bench_support::parser();
+    let mut group = c.benchmark_group("parse");
+
+    for (name, user_agent) in [
+        ("matched_early", MATCHED_EARLY),
+        ("matched_late", MATCHED_LATE),
+        ("never_matching", NEVER_MATCHING),
+    ] {
+        group.bench_with_input(BenchmarkId::from_parameter(name), user_agent, |b, ua| {
+            b.iter(|| parser.parse(black_box(ua)));
+        });
+    }
+    group.finish();
+}
+
+fn bench_throughput(c: &mut Criterion) {
+    let parser = bench_support::parser();
+    let corpus = bench_support::mixed_corpus(CORPUS_LEN);
+    let mut group = c.benchmark_group("corpus");
+
+    group.throughput(Throughput::Elements(corpus.len() as u64));
+    group.bench_function("parse", |b| {
+        b.iter(|| {
+            for user_agent in &corpus {
+                black_box(parser.parse(user_agent));
+            }
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(
+    name = construction;
+    config = Criterion::default()
+        .sample_size(10)
+        .measurement_time(Duration::from_secs(30));
+    targets = bench_construction
+);
+criterion_group!(
+    name = latency;
+    config = Criterion::default()
+        .warm_up_time(Duration::from_secs(5))
+        .measurement_time(Duration::from_secs(15))
+        // degree of noise to ignore in measurements, here 1%
+        .noise_threshold(0.01);
+    targets = bench_latency
+);
+criterion_group!(
+    name = throughput;
+    config = Criterion::default()
+        .sample_size(20)
+        .measurement_time(Duration::from_secs(60))
+        .noise_threshold(0.01);
+    targets = bench_throughput
+);
+criterion_main!(construction, latency, throughput);
@@ -0,0 +1,94 @@
+//! Shares parse results between processes through a key/value store, such
+//! as Redis, by implementing `ParseCache` on top of it. Clients are stored
+//! as JSON under a prefixed key, and store errors count as cache misses.
+//! The store here keeps its values in memory; a Redis client would
+//! implement `KvStore` with `GET` and `SET`.
+//!
+//! ```sh
+//! cargo run --example kv_cache --features json
+//! ```
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    sync::{Arc, Mutex},
+};
+
+use uaparser::{
+    parse_cache::{CachedParser, ParseCache},
+    Client, Parser, UserAgentParser,
+};
+
+type StoreError = Box<dyn Error + Send + Sync>;
+
+/// The commands the cache needs from a key/value store
+trait KvStore: Send + Sync {
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, StoreError>;
+    fn set(&self, key: &str, value: Vec<u8>) -> Result<(), StoreError>;
+}
+
+/// Stands in for a Redis connection
+#[derive(Default)]
+struct MemoryStore(Mutex<HashMap<String, Vec<u8>>>);
+
+impl KvStore for MemoryStore {
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, StoreError> {
+        let values = self.0.lock().map_err(|error| error.to_string())?;
+        Ok(values.get(key).cloned())
+    }
+
+    fn set(&self, key: &str, value: Vec<u8>) -> Result<(), StoreError> {
+        let mut values = self.0.lock().map_err(|error| error.to_string())?;
+        values.insert(key.to_owned(), value);
+        Ok(())
+    }
+}
+
+/// A `ParseCache` storing clients as JSON in any `KvStore`
+struct KvCache {
+    store: Arc<dyn KvStore>,
+    prefix: &'static str,
+}
+
+impl KvCache {
+    fn key(&self, user_agent: &str) -> String {
+        format!("{}{user_agent}", self.prefix)
+    }
+}
+
+impl ParseCache for KvCache {
+    fn get(&self, user_agent: &str) -> Option<Client<'static>> {
+        let bytes = self.store.get(&self.key(user_agent)).ok()??;
+        let client: Client<'_> = serde_json::from_slice(&bytes).ok()?;
+        Some(client.into_owned())
+    }
+
+    fn put(&self, user_agent: &str, client: &Client<'static>) {
+        if let Ok(bytes) = serde_json::to_vec(client) {
+            let _ = self.store.set(&self.key(user_agent), bytes);
+        }
+    }
+}
+
+fn main() {
+    let parser = Arc::new(
+        UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed"),
+    );
+    let store: Arc<dyn KvStore> = Arc::new(MemoryStore::default());
+    let cache = || KvCache {
+        store: Arc::clone(&store),
+        prefix: "uaparser:",
+    };
+
+    // Two workers, each with a cache of its own over the one store
+    let first = CachedParser::new(Arc::clone(&parser), cache());
+    let second = CachedParser::new(parser, cache());
+    let user_agent = "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 \
+                      (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36";
+
+    let client = first.parse(user_agent);
+    assert!(second.cache().get(user_agent).is_some());
+    assert_eq!(second.parse(user_agent), client);
+    println!("{client:?}");
+}
@@ -0,0 +1,23 @@
+//! Builds a parser from rules embedded in the binary and parses a user
+//! agent, using only the API available on WebAssembly targets:
+//!
+//! ```sh
+//! cargo build --example wasm --target wasm32-unknown-unknown
+//! cargo run --example wasm
+//! ```
+
+use uaparser::{Parser, UserAgentParser};
+
+const REGEXES: &[u8] = include_bytes!("../data/regexes_mini.yaml");
+
+fn main() {
+    let parser = UserAgentParser::from_bytes(REGEXES).expect("Parser creation failed");
+    let client = parser.parse(
+        "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 \
+         (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36",
+    );
+
+    assert_eq!(client.user_agent.family, "Chrome Mobile");
+    assert_eq!(client.os.family, "Android");
+    println!("{client:?}");
+}
@@ -0,0 +1,52 @@
+#!/usr/bin/env python3
+"""Regenerates src/devices/apple_table.rs from data/apple_devices.tsv.
+
+Each line of the data file is `identifier<TAB>marketing name<TAB>class`, where
+class is one of the `AppleDeviceClass` variants. Lines starting with `#` are
+ignored.
+
+Usage: python3 scripts/generate_apple_devices.py > src/devices/apple_table.rs
+"""
+
+import os
+import sys
+
+ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+DATA = os.path.join(ROOT, "data", "apple_devices.tsv")
+CLASSES = {"Phone", "Tablet", "MediaPlayer"}
+
+
+def main():
+    rows = []
+    with open(DATA, encoding="utf-8") as f:
+        for number, line in enumerate(f, 1):
+            line = line.rstrip("\n")
+            if not line or line.startswith("#"):
+                continue
+            identifier, name, class_ = line.split("\t")
+            if class_ not in CLASSES:
+                sys.exit("line %d: unknown class %r" % (number, class_))
+            rows.append((identifier, name, class_))
+
+    # Sorted by code point, which matches the byte order `binary_search` uses.
+    rows.sort()
+    identifiers = [row[0] for row in rows]
+    if len(set(identifiers)) != len(identifiers):
+        sys.exit("duplicate identifiers in %s" % DATA)
+
+    out = sys.stdout
+    out.write("// @generated by scripts/generate_apple_devices.py from data/apple_devices.tsv.\n")
+    out.write("// Do not edit by hand.\n\n")
+    out.write("use super::{AppleDeviceClass, AppleModel};\n\n")
+    out.write("pub(super) static APPLE_MODELS: [AppleModel; %d] = [\n" % len(rows))
+    for identifier, name, class_ in rows:
+        out.write("    AppleModel {\n")
+        out.write('        identifier: "%s",\n' % identifier)
+        out.write('        name: "%s",\n' % name)
+        out.write("        class: AppleDeviceClass::%s,\n" % class_)
+        out.write("    },\n")
+    out.write("];\n")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,140 @@
+#!/usr/bin/env python3
+"""Regenerates src/families/table.rs, the common family and brand constants,
+from src/core/regexes.yaml.
+
+A name is only emitted if the rules can produce it verbatim: as a static
+replacement, or as a capture group matching nothing but the name, such as
+`(Firefox)/`. Names the rules no longer produce are an error, so a rule
+update that renames a family fails here instead of shipping a stale constant.
+
+Usage: python3 scripts/generate_families.py [regexes.yaml] > src/families/table.rs
+"""
+
+import os
+import re
+import sys
+
+import yaml
+
+ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+REGEXES = os.path.join(ROOT, "src", "core", "regexes.yaml")
+
+# (heading, section, replacement key, names), in output order. `OTHER` and
+# `SPIDER` are written by hand in src/families/mod.rs.
+GROUPS = [
+    (
+        "User agent families",
+        "user_agent_parsers",
+        "family_replacement",
+        [
+            "Chrome",
+            "Chrome Mobile",
+            "Chrome Mobile iOS",
+            "Chrome Mobile WebView",
+            "Edge",
+            "Edge Mobile",
+            "Facebook",
+            "Firefox",
+            "Firefox Mobile",
+            "Firefox iOS",
+            "IE",
+            "IE Mobile",
+            "Mobile Safari",
+            "Mobile Safari UI/WKWebView",
+            "Opera",
+            "Opera Mobile",
+            "Safari",
+            "Samsung Internet",
+            "UC Browser",
+            "Yandex Browser",
+        ],
+    ),
+    (
+        "OS families",
+        "os_parsers",
+        "os_replacement",
+        [
+            "Android",
+            "Chrome OS",
+            "FreeBSD",
+            "iOS",
+            "KaiOS",
+            "Linux",
+            "Mac OS X",
+            "Windows",
+            "Windows Phone",
+        ],
+    ),
+    (
+        "Device brands",
+        "device_parsers",
+        "brand_replacement",
+        [
+            "Amazon",
+            "Apple",
+            "Asus",
+            "Generic",
+            "Generic_Android",
+            "Google",
+            "HTC",
+            "Huawei",
+            "Lenovo",
+            "LG",
+            "Motorola",
+            "Nokia",
+            "OnePlus",
+            "Oppo",
+            "Samsung",
+            "Sony",
+            "ZTE",
+        ],
+    ),
+]
+
+
+def produced(rules, section, key):
+    names = set()
+    for entry in rules.get(section) or []:
+        value = entry.get(key)
+        if value and "$" not in value:
+            names.add(value)
+        # Without a replacement, the first group is the family; a group of
+        # plain words, such as `(Chromium|Chrome)`, can only capture those.
+        if not value:
+            group = re.search(r"(?<!\\)\((?!\?)([^()]*)\)", entry["regex"])
+            opening = re.search(r"(?<!\\)\((?!\?)", entry["regex"])
+            if group and group.start() == opening.start():
+                words = group.group(1).split("|")
+                if all(re.fullmatch(r"[\w .-]+", word) for word in words):
+                    names.update(words)
+    return names
+
+
+def const_name(name):
+    return re.sub(r"[^A-Za-z0-9]+", "_", name).strip("_").upper()
+
+
+def main():
+    path = sys.argv[1] if len(sys.argv) > 1 else REGEXES
+    with open(path, encoding="utf-8") as f:
+        rules = yaml.safe_load(f)
+
+    seen = {"OTHER", "SPIDER"}
+    out = sys.stdout
+    out.write("// @generated by scripts/generate_families.py from src/core/regexes.yaml.\n")
+    out.write("// Do not edit by hand.\n")
+    for heading, section, key, names in GROUPS:
+        available = produced(rules, section, key)
+        out.write("\n// %s\n" % heading)
+        for name in names:
+            if name not in available:
+                sys.exit("%s: no %s rule produces %r" % (path, section, name))
+            const = const_name(name)
+            if const in seen:
+                sys.exit("duplicate constant %s for %r" % (const, name))
+            seen.add(const)
+            out.write('pub const %s: &str = "%s";\n' % (const, name))
+
+
+if __name__ == "__main__":
+    main()
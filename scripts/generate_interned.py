@@ -0,0 +1,50 @@
+#!/usr/bin/env python3
+"""Regenerates src/parser/interned.rs from the static family, OS, device and
+brand replacements in src/core/regexes.yaml.
+
+Usage: python3 scripts/generate_interned.py [regexes.yaml] > src/parser/interned.rs
+"""
+
+import os
+import sys
+
+import yaml
+
+ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+REGEXES = os.path.join(ROOT, "src", "core", "regexes.yaml")
+
+SECTIONS = {
+    "user_agent_parsers": ["family_replacement"],
+    "os_parsers": ["os_replacement"],
+    "device_parsers": ["device_replacement", "brand_replacement"],
+}
+
+
+def main():
+    path = sys.argv[1] if len(sys.argv) > 1 else REGEXES
+    with open(path, encoding="utf-8") as f:
+        rules = yaml.safe_load(f)
+
+    strings = set()
+    for section, keys in SECTIONS.items():
+        for entry in rules.get(section) or []:
+            for key in keys:
+                value = entry.get(key)
+                if value and "$" not in value:
+                    strings.add(value)
+
+    # Sorted by code point, which matches the byte order `binary_search` uses.
+    strings = sorted(s for s in strings if s)
+
+    out = sys.stdout
+    out.write("// @generated by scripts/generate_interned.py from src/core/regexes.yaml.\n")
+    out.write("// Do not edit by hand.\n\n")
+    out.write("pub(super) static INTERNED: [&str; %d] = [\n" % len(strings))
+    for s in strings:
+        escaped = s.replace("\\", "\\\\").replace('"', '\\"')
+        out.write('    "%s",\n' % escaped)
+    out.write("];\n")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,109 @@
+#!/usr/bin/env python3
+"""Regenerates data/regexes_mini.yaml, the subset of src/core/regexes.yaml
+embedded by the `mini-regexes` feature.
+
+A rule is kept when the family it produces is on the allowlist of its section.
+The family is the static replacement when there is one, otherwise the literal
+alternatives of the first capture group. Rules whose family can't be worked
+out that way are kept when an allowlisted name appears in the pattern. Kept
+rules stay in their original order, so anything the subset matches is matched
+by the same rule as in the full file.
+
+Usage: python3 scripts/generate_mini_regexes.py [regexes.yaml] > data/regexes_mini.yaml
+"""
+
+import os
+import re
+import sys
+
+import yaml
+
+ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+REGEXES = os.path.join(ROOT, "src", "core", "regexes.yaml")
+
+USER_AGENT_FAMILIES = {
+    "Chrome",
+    "Chrome Mobile",
+    "Chrome Mobile iOS",
+    "Chrome Mobile WebView",
+    "Chromium",
+    "Edge",
+    "Edge Mobile",
+    "Firefox",
+    "Firefox Mobile",
+    "Firefox iOS",
+    "Mobile Safari",
+    "Mobile Safari UI/WKWebView",
+    "Safari",
+    "Samsung Internet",
+    "Spider",
+}
+
+OS_FAMILIES = {
+    "Android",
+    "Chrome OS",
+    "iOS",
+    "Linux",
+    "Mac OS X",
+    "Windows",
+}
+
+DEVICE_FAMILIES = {
+    "Generic Feature Phone",
+    "Generic Smartphone",
+    "Generic Tablet",
+    "Spider",
+}
+
+DEVICE_BRANDS = {"Apple"}
+
+SECTIONS = [
+    ("user_agent_parsers", "family_replacement", USER_AGENT_FAMILIES),
+    ("os_parsers", "os_replacement", OS_FAMILIES),
+    ("device_parsers", "device_replacement", DEVICE_FAMILIES),
+]
+
+# The first capture group when it's made only of literal alternatives.
+LITERAL_GROUP = re.compile(r"^[^(]*\(((?:[\w ./-]|\\[./ -])+(?:\|(?:[\w ./-]|\\[./ -])+)*)\)")
+
+
+def families(entry, replacement_key):
+    """The families a rule can produce, or None when unknown"""
+    replacement = entry.get(replacement_key)
+    if replacement is not None and "$" not in replacement:
+        return {replacement}
+    if replacement is not None and replacement != "$1":
+        return None
+    group = LITERAL_GROUP.match(entry["regex"])
+    if group is None:
+        return None
+    return {alternative.replace("\\", "") for alternative in group.group(1).split("|")}
+
+
+def keep(entry, replacement_key, allowlist):
+    if entry.get("brand_replacement") in DEVICE_BRANDS:
+        return True
+    found = families(entry, replacement_key)
+    if found is None:
+        return any(name in entry["regex"] for name in allowlist)
+    return bool(found & allowlist)
+
+
+def main():
+    path = sys.argv[1] if len(sys.argv) > 1 else REGEXES
+    with open(path, encoding="utf-8") as f:
+        rules = yaml.safe_load(f)
+
+    out = sys.stdout
+    out.write("# @generated by scripts/generate_mini_regexes.py from src/core/regexes.yaml.\n")
+    out.write("# Do not edit by hand.\n")
+    for section, replacement_key, allowlist in SECTIONS:
+        entries = rules.get(section) or []
+        kept = [entry for entry in entries if keep(entry, replacement_key, allowlist)]
+        out.write("\n%s:\n" % section)
+        for entry in kept:
+            out.write(yaml.safe_dump([entry], allow_unicode=True, width=1 << 16, sort_keys=False))
+
+
+if __name__ == "__main__":
+    main()
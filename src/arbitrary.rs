@@ -0,0 +1,206 @@
+//! `arbitrary::Arbitrary` for the owned parse results, drawing plausible
+//! families and version numbers with most optional fields left `None`, and
+//! for `SyntheticUserAgent` strings to fuzz the parser itself.
+//!
+//! ```rust
+//! # use uaparser::{arbitrary::SyntheticUserAgent, Client, Parser, UserAgentParser};
+//! use arbitrary::{Arbitrary, Unstructured};
+//!
+//! let mut unstructured = Unstructured::new(&[7, 1, 42, 3, 250, 9, 64, 18, 5]);
+//! let client = Client::arbitrary(&mut unstructured).unwrap();
+//! let user_agent = SyntheticUserAgent::arbitrary(&mut unstructured).unwrap();
+//!
+//! let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+//! parser.parse(&user_agent.0);
+//! ```
+
+use std::borrow::Cow;
+
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{
+    synthetic::{
+        compose, Token, BRANDS, COMMENTS, DEVICE_FAMILIES, MAX_TOKENS, MODELS, NONE_ODDS,
+        NONE_OUT_OF, OS_FAMILIES, PRODUCTS, USER_AGENT_FAMILIES, VERSION_LIMITS,
+    },
+    Client, Device, UserAgent, OS,
+};
+
+/// A user agent string made of product tokens, such as
+/// `Mozilla/5.0 (X11; Linux x86_64) Firefox/115.0`, with the odd run of
+/// arbitrary text appended
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SyntheticUserAgent(pub String);
+
+impl<'a> Arbitrary<'a> for SyntheticUserAgent {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let len = u.int_in_range(1..=MAX_TOKENS)?;
+        let tokens = (0..len)
+            .map(|_| {
+                Ok(Token {
+                    product: u.choose(PRODUCTS)?,
+                    version: versions(u, &VERSION_LIMITS)?
+                        .into_iter()
+                        .flatten()
+                        .collect(),
+                    comment: optional(u, |u| u.choose(COMMENTS).copied())?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let noise = if u.ratio(1, 8)? {
+            Some(<&str>::arbitrary(u)?)
+        } else {
+            None
+        };
+        Ok(SyntheticUserAgent(compose(&tokens, noise)))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Device<'static> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Device {
+            family: pick(u, DEVICE_FAMILIES)?,
+            brand: optional(u, |u| pick(u, BRANDS))?,
+            model: optional(u, |u| pick(u, MODELS))?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for OS<'static> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let family = pick(u, OS_FAMILIES)?;
+        let mut versions = version_strings(u, &VERSION_LIMITS)?.into_iter();
+        Ok(OS {
+            family,
+            major: versions.next().flatten(),
+            minor: versions.next().flatten(),
+            patch: versions.next().flatten(),
+            patch_minor: versions.next().flatten(),
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for UserAgent<'static> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let family = pick(u, USER_AGENT_FAMILIES)?;
+        let mut versions = version_strings(u, &VERSION_LIMITS)?.into_iter();
+        Ok(UserAgent {
+            family,
+            major: versions.next().flatten(),
+            minor: versions.next().flatten(),
+            patch: versions.next().flatten(),
+            patch_minor: versions.next().flatten(),
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Client<'static> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Client {
+            device: Device::arbitrary(u)?,
+            os: OS::arbitrary(u)?,
+            user_agent: UserAgent::arbitrary(u)?,
+        })
+    }
+}
+
+fn pick(u: &mut Unstructured<'_>, words: &[&'static str]) -> Result<Cow<'static, str>> {
+    Ok(Cow::Borrowed(u.choose(words)?))
+}
+
+fn optional<'a, T>(
+    u: &mut Unstructured<'a>,
+    value: impl FnOnce(&mut Unstructured<'a>) -> Result<T>,
+) -> Result<Option<T>> {
+    if u.ratio(NONE_ODDS, NONE_OUT_OF)? {
+        Ok(None)
+    } else {
+        value(u).map(Some)
+    }
+}
+
+/// Version numbers below each of `limits`, where every one after the first
+/// `None` is `None` too, as a parser would give
+fn versions(u: &mut Unstructured<'_>, limits: &[u32]) -> Result<Vec<Option<u32>>> {
+    let mut present = true;
+    limits
+        .iter()
+        .map(|&limit| {
+            present = present && !u.ratio(NONE_ODDS, NONE_OUT_OF)?;
+            if present {
+                u.int_in_range(0..=limit - 1).map(Some)
+            } else {
+                Ok(None)
+            }
+        })
+        .collect()
+}
+
+fn version_strings(
+    u: &mut Unstructured<'_>,
+    limits: &[u32],
+) -> Result<Vec<Option<Cow<'static, str>>>> {
+    Ok(versions(u, limits)?
+        .into_iter()
+        .map(|version| version.map(|version| Cow::Owned(version.to_string())))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{Parser, UserAgentParser};
+
+    #[derive(Debug, Arbitrary)]
+    struct Event {
+        client: Client<'static>,
+        user_agent: SyntheticUserAgent,
+    }
+
+    fn bytes(seed: u64) -> Vec<u8> {
+        let mut bytes = vec![0; 1 << 16];
+        StdRng::seed_from_u64(seed).fill_bytes(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn derived_impls_round_trip() {
+        let bytes = bytes(1);
+        let mut u = Unstructured::new(&bytes);
+        let mut without_major = 0;
+
+        for _ in 0..200 {
+            let event = Event::arbitrary(&mut u).expect("Generation failed");
+            let yaml =
+                serde_yaml::to_string(&event.client).expect("Serialization failed");
+            let client = Client::deserialize(serde_yaml::Deserializer::from_str(&yaml))
+                .expect("Deserialization failed");
+            assert_eq!(client, event.client);
+            assert!(!event.user_agent.0.is_empty());
+            assert!(USER_AGENT_FAMILIES.contains(&&*client.user_agent.family));
+            assert!(client.os.major.is_some() || client.os.minor.is_none());
+            without_major += usize::from(client.user_agent.major.is_none());
+        }
+        assert!(without_major > 100);
+    }
+
+    #[test]
+    fn synthetic_user_agents_parse() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let bytes = bytes(2);
+        let mut u = Unstructured::new(&bytes);
+
+        for _ in 0..200 {
+            let SyntheticUserAgent(user_agent) =
+                SyntheticUserAgent::arbitrary(&mut u).expect("Generation failed");
+            assert!(PRODUCTS
+                .iter()
+                .any(|product| user_agent.starts_with(product)));
+            parser.parse(&user_agent);
+        }
+    }
+}
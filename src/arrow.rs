@@ -0,0 +1,141 @@
+//! Parses a column of user agents straight into an Arrow `RecordBatch`.
+//!
+//! The output schema is stable: thirteen nullable `Utf8` columns named
+//! `ua_family`, `ua_major`, `ua_minor`, `ua_patch`, `os_family`, `os_major`,
+//! `os_minor`, `os_patch`, `os_patch_minor`, `device_family`, `device_brand`,
+//! `device_model` and `ua_patch_minor`, in that order. New columns will only
+//! ever be appended.
+
+use std::{collections::HashMap, sync::Arc};
+
+use arrow_array::{builder::StringBuilder, Array, ArrayRef, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+
+use crate::{client::FLAT_KEYS, Client, Parser, UserAgentParser};
+
+/// The schema of every batch returned by `parse_to_record_batch`
+#[must_use]
+pub fn schema() -> SchemaRef {
+    Arc::new(Schema::new(
+        FLAT_KEYS
+            .iter()
+            .map(|name| Field::new(*name, DataType::Utf8, true))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+/// Parses each user agent in `user_agents` into one row. Null entries give
+/// rows that are null in every column, while unmatched user agents get the
+/// usual `Other` families. Each distinct user agent is parsed only once, so
+/// columns with many repeats are cheap.
+///
+/// ```rust
+/// # use arrow_array::{Array, StringArray};
+/// # use uaparser::*;
+/// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+/// let user_agents = StringArray::from(vec![Some("curl/8.4.0"), None]);
+/// let batch = uaparser::arrow::parse_to_record_batch(&parser, &user_agents);
+///
+/// assert_eq!(batch.num_rows(), 2);
+/// assert!(batch.column(0).is_null(1));
+/// ```
+// The only panic is on a column count or type mismatch, which can't happen
+#[allow(clippy::missing_panics_doc)]
+#[must_use]
+pub fn parse_to_record_batch(
+    parser: &UserAgentParser,
+    user_agents: &StringArray,
+) -> RecordBatch {
+    let mut parsed: HashMap<&str, Client<'_>> = HashMap::new();
+    let mut builders: Vec<StringBuilder> = FLAT_KEYS
+        .iter()
+        .map(|_| StringBuilder::with_capacity(user_agents.len(), 0))
+        .collect();
+
+    for user_agent in user_agents {
+        match user_agent {
+            Some(user_agent) => {
+                let client = parsed
+                    .entry(user_agent)
+                    .or_insert_with(|| parser.parse(user_agent));
+                for (builder, field) in builders.iter_mut().zip(client.flatten()) {
+                    builder.append_option(field);
+                }
+            }
+            None => builders.iter_mut().for_each(StringBuilder::append_null),
+        }
+    }
+
+    let columns = builders
+        .iter_mut()
+        .map(|builder| Arc::new(builder.finish()) as ArrayRef)
+        .collect();
+    RecordBatch::try_new(schema(), columns).expect("columns match the schema")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column<'b>(batch: &'b RecordBatch, name: &str) -> &'b StringArray {
+        batch
+            .column_by_name(name)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap()
+    }
+
+    #[test]
+    fn schema_is_stable() {
+        let schema = schema();
+
+        assert_eq!(schema.fields().len(), 13);
+        assert_eq!(schema.field(0).name(), "ua_family");
+        assert_eq!(schema.field(11).name(), "device_model");
+        assert_eq!(schema.field(12).name(), "ua_patch_minor");
+        assert!(schema
+            .fields()
+            .iter()
+            .all(|field| field.is_nullable() && field.data_type() == &DataType::Utf8));
+    }
+
+    #[test]
+    fn parses_columns() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let chrome = "Mozilla/5.0 (Linux; Android 13; SM-S901B) AppleWebKit/537.36 \
+                      (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36";
+        let user_agents = StringArray::from(vec![
+            Some(chrome),
+            None,
+            Some("unknown-thing"),
+            Some(chrome),
+        ]);
+
+        let batch = parse_to_record_batch(&parser, &user_agents);
+
+        assert_eq!(batch.schema(), schema());
+        assert_eq!(batch.num_rows(), 4);
+
+        let ua_family = column(&batch, "ua_family");
+        assert_eq!(ua_family.value(0), "Chrome Mobile");
+        assert!(ua_family.is_null(1));
+        assert_eq!(ua_family.value(2), "Other");
+        assert_eq!(ua_family.value(3), "Chrome Mobile");
+
+        let ua_major = column(&batch, "ua_major");
+        assert_eq!(ua_major.value(0), "120");
+        assert!(ua_major.is_null(2));
+
+        let device_brand = column(&batch, "device_brand");
+        assert_eq!(device_brand.value(0), "Samsung");
+        assert!(device_brand.is_null(1));
+        assert!(device_brand.is_null(2));
+
+        for array in batch.columns() {
+            assert!(array.is_null(1));
+        }
+        assert_eq!(column(&batch, "device_family").null_count(), 1);
+    }
+}
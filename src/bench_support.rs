@@ -0,0 +1,123 @@
+//! Inputs shared by the benchmarks in `benches/`, exposed so downstream
+//! crates can benchmark their own parser setups against the same numbers.
+//!
+//! ```rust
+//! # use uaparser::{bench_support, Parser};
+//! let parser = bench_support::parser();
+//! let corpus = bench_support::mixed_corpus(1_000);
+//!
+//! for user_agent in &corpus {
+//!     parser.parse(user_agent);
+//! }
+//! ```
+
+#[cfg(not(target_family = "wasm"))]
+use std::{fs, io, path::Path};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::testing::{random_ua, Category};
+#[cfg(not(target_family = "wasm"))]
+use crate::UserAgentParser;
+
+/// The rule file bundled with the crate
+pub const RULES_PATH: &str = "./src/core/regexes.yaml";
+
+/// The size of the corpus used for throughput benchmarks
+pub const CORPUS_LEN: usize = 10_000;
+
+/// Matched by the very first user agent rule
+pub const MATCHED_EARLY: &str = "GeoEvent Server 10.8.1";
+
+/// Matched by the last user agent rule, after every other one has been tried
+pub const MATCHED_LATE: &str = "HTTrack 3.49-2";
+
+/// Not matched by any rule in any category
+pub const NEVER_MATCHING: &str = "qjx-7 zv/ kpq (wbt; mmx) 0815 xqz";
+
+/// Seeds the corpus generator, so every run benchmarks the same strings
+const SEED: u64 = 0x0075_6170;
+
+/// One in this many corpus entries never matches, as with the junk every
+/// real log carries
+const NEVER_MATCHING_EVERY: usize = 50;
+
+/// Builds a parser from `RULES_PATH`
+///
+/// # Panics
+///
+/// When the bundled rule file can't be loaded
+#[cfg(not(target_family = "wasm"))]
+#[must_use]
+pub fn parser() -> UserAgentParser {
+    UserAgentParser::from_yaml(RULES_PATH).expect("Parser creation failed")
+}
+
+/// `len` user agents drawn from every category of `testing::corpus`, with
+/// the odd never matching one mixed in. The same `len` always gives the same
+/// corpus.
+#[must_use]
+pub fn mixed_corpus(len: usize) -> Vec<&'static str> {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    (0..len)
+        .map(|index| {
+            if index % NEVER_MATCHING_EVERY == NEVER_MATCHING_EVERY - 1 {
+                NEVER_MATCHING
+            } else {
+                let category = Category::ALL[rng.gen_range(0..Category::ALL.len())];
+                random_ua(&mut rng, category)
+            }
+        })
+        .collect()
+}
+
+/// Reads a corpus with one user agent per line, skipping blank lines and
+/// `#` comments
+#[cfg(not(target_family = "wasm"))]
+pub fn load_corpus(path: impl AsRef<Path>) -> io::Result<Vec<String>> {
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(str::to_owned)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Client, Parser};
+
+    #[test]
+    fn latency_inputs_match_as_described() {
+        let parser = parser();
+        let first_rule = UserAgentParser::from_bytes(
+            br"
+user_agent_parsers:
+  - regex: '(GeoEvent Server) (\d+)(?:\.(\d+)(?:\.(\d+)|)|)'
+",
+        )
+        .expect("Parser creation failed");
+
+        assert_eq!(
+            parser.parse_user_agent(MATCHED_EARLY),
+            first_rule.parse_user_agent(MATCHED_EARLY)
+        );
+        assert_eq!(parser.parse_user_agent(MATCHED_LATE).family, "HTTrack");
+        assert_eq!(parser.parse(NEVER_MATCHING), Client::default());
+    }
+
+    #[test]
+    fn corpus_is_deterministic() {
+        let corpus = mixed_corpus(CORPUS_LEN);
+
+        assert_eq!(corpus.len(), CORPUS_LEN);
+        assert_eq!(corpus, mixed_corpus(CORPUS_LEN));
+        assert_eq!(
+            corpus
+                .iter()
+                .filter(|user_agent| **user_agent == NEVER_MATCHING)
+                .count(),
+            CORPUS_LEN / NEVER_MATCHING_EVERY
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use derive_more::{Display, From};
+use regex::Regex;
+
+use crate::{parser::compile_rule, Client, Deserialize, Serialize};
+
+#[derive(Debug, Display, From)]
+pub enum Error {
+    Json(serde_json::Error),
+    /// A pattern that doesn't compile, with its index in the dataset
+    #[from(ignore)]
+    #[display(fmt = "crawler pattern {_0}: {_1}")]
+    Pattern(usize, regex::Error),
+}
+
+/// One crawler of the dataset
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CrawlerEntry {
+    /// The regex matched against user agents
+    pub pattern: String,
+    /// When the crawler was added, such as `2022/08/20`
+    #[serde(default)]
+    pub addition_date: Option<String>,
+    /// A page describing the crawler
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Sample user agents of the crawler
+    #[serde(default)]
+    pub instances: Vec<String>,
+}
+
+/// The compiled patterns of a crawler dataset, such as the community
+/// [crawler-user-agents] one, which tracks crawlers the uap-core `Spider`
+/// rules don't know yet
+///
+/// ```rust
+/// # use uaparser::bots::CrawlerList;
+/// let crawlers = CrawlerList::from_json(br#"[{"pattern": "Amazonbot", "url": "https://developer.amazon.com/support/amazonbot"}]"#)
+///     .unwrap();
+/// let entry = crawlers.matches("Mozilla/5.0 (compatible; Amazonbot/0.1)").unwrap();
+///
+/// assert_eq!(entry.pattern, "Amazonbot");
+/// ```
+///
+/// [crawler-user-agents]: https://github.com/monperrus/crawler-user-agents
+#[derive(Clone, Debug)]
+pub struct CrawlerList {
+    entries: Vec<CrawlerEntry>,
+    regexes: Vec<Regex>,
+}
+
+impl CrawlerList {
+    /// Reads the dataset's JSON array of entries, compiling the patterns as
+    /// the parser compiles its rules
+    pub fn from_json(bytes: &[u8]) -> Result<CrawlerList, Error> {
+        let entries: Vec<CrawlerEntry> = serde_json::from_slice(bytes)?;
+        let regexes = entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                compile_rule(&entry.pattern).map_err(|error| Error::Pattern(index, error))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(CrawlerList { entries, regexes })
+    }
+
+    /// The first entry whose pattern matches `user_agent`
+    #[must_use]
+    pub fn matches(&self, user_agent: &str) -> Option<&CrawlerEntry> {
+        self.regexes
+            .iter()
+            .position(|regex| regex.is_match(user_agent))
+            .map(|index| &self.entries[index])
+    }
+
+    #[must_use]
+    pub fn entries(&self) -> &[CrawlerEntry] {
+        &self.entries
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Client<'_> {
+    /// Like `is_bot`, and also true when `user_agent`, the string this client
+    /// was parsed from, is on the crawler list
+    #[must_use]
+    pub fn is_bot_with(&self, user_agent: &str, crawlers: &CrawlerList) -> bool {
+        self.is_bot() || crawlers.matches(user_agent).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, UserAgentParser};
+
+    const FIXTURE: &[u8] =
+        include_bytes!("../../tests/fixtures/crawler-user-agents.json");
+    const CHROME: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                          (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+    #[test]
+    fn matches_return_the_entry() {
+        let crawlers = CrawlerList::from_json(FIXTURE).expect("Loading failed");
+        assert_eq!(crawlers.len(), 4);
+
+        let amazon = "Mozilla/5.0 AppleWebKit/537.36 (KHTML, like Gecko; compatible; \
+                      Amazonbot/0.1; +https://developer.amazon.com/support/amazonbot) \
+                      Chrome/119.0.6045.214 Safari/537.36";
+        let entry = crawlers.matches(amazon).expect("Amazonbot is listed");
+        assert_eq!(entry.pattern, "Amazonbot");
+        assert_eq!(entry.addition_date.as_deref(), Some("2022/08/20"));
+        assert_eq!(
+            entry.url.as_deref(),
+            Some("https://developer.amazon.com/support/amazonbot")
+        );
+
+        assert_eq!(crawlers.matches(CHROME), None);
+        for entry in crawlers.entries() {
+            for instance in &entry.instances {
+                assert_eq!(crawlers.matches(instance), Some(entry));
+            }
+        }
+    }
+
+    #[test]
+    fn bad_patterns_are_located() {
+        let error =
+            CrawlerList::from_json(br#"[{"pattern": "ok"}, {"pattern": "(unclosed"}]"#)
+                .expect_err("The pattern doesn't compile");
+        assert!(matches!(error, Error::Pattern(1, _)));
+        assert!(matches!(CrawlerList::from_json(b"{}"), Err(Error::Json(_))));
+    }
+
+    #[test]
+    fn crawler_list_is_a_second_opinion() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let crawlers = CrawlerList::from_json(FIXTURE).expect("Loading failed");
+        let headless = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 \
+                        (KHTML, like Gecko) HeadlessChrome/120.0.0.0 Safari/537.36";
+
+        let client = parser.parse(headless);
+        assert!(!client.is_bot());
+        assert!(client.is_bot_with(headless, &crawlers));
+
+        let client = parser.parse(CHROME);
+        assert!(!client.is_bot_with(CHROME, &crawlers));
+
+        let googlebot =
+            "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)";
+        assert!(parser.parse(googlebot).is_bot());
+    }
+}
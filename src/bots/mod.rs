@@ -0,0 +1,130 @@
+//! Bot classification: `categorize` sorts crawlers into the kind of operator
+//! behind them, and with the `crawler-list` feature, `CrawlerList` spots
+//! crawlers from the community [crawler-user-agents] dataset that the
+//! uap-core `Spider` rules don't know yet.
+//!
+//! [crawler-user-agents]: https://github.com/monperrus/crawler-user-agents
+
+#[cfg(feature = "crawler-list")]
+mod crawler_list;
+mod taxonomy;
+
+#[cfg(feature = "crawler-list")]
+pub use crawler_list::{CrawlerEntry, CrawlerList, Error};
+
+use super::Client;
+
+/// The kind of operator behind a crawler, from `categorize`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BotCategory {
+    /// Indexes pages for a search engine, such as `Googlebot`
+    SearchEngine,
+    /// Fetches link previews for a chat or social app, such as `Twitterbot`
+    SocialPreview,
+    /// Checks uptime or performance, such as `UptimeRobot`
+    Monitoring,
+    /// Crawls for an SEO or backlink tool, such as `AhrefsBot`
+    SeoTool,
+    /// Collects training data or answers for an AI product, such as `GPTBot`
+    AiCrawler,
+    /// A crawler of no known category
+    GenericBot,
+    NotABot,
+}
+
+/// Sorts a parsed client into a `BotCategory`, by the user agent family the
+/// rules gave it, or for crawlers the rules lump together, by a string in
+/// `user_agent`, the one `client` was parsed from. Other clients the rules
+/// identified as crawlers are `GenericBot`.
+///
+/// ```rust
+/// # use uaparser::*;
+/// # use uaparser::bots::{categorize, BotCategory};
+/// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+/// let user_agent = "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)";
+///
+/// assert_eq!(categorize(&parser.parse(user_agent), user_agent), BotCategory::SearchEngine);
+/// ```
+#[must_use]
+pub fn categorize(client: &Client<'_>, user_agent: &str) -> BotCategory {
+    taxonomy::FAMILIES
+        .iter()
+        .find(|bot| bot.name == client.user_agent.family)
+        .or_else(|| {
+            taxonomy::TOKENS
+                .iter()
+                .find(|bot| user_agent.contains(bot.name))
+        })
+        .map_or_else(
+            || {
+                if client.is_bot() {
+                    BotCategory::GenericBot
+                } else {
+                    BotCategory::NotABot
+                }
+            },
+            |bot| bot.category,
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, UserAgentParser};
+
+    fn parser() -> UserAgentParser {
+        UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed")
+    }
+
+    fn category(parser: &UserAgentParser, user_agent: &str) -> BotCategory {
+        categorize(&parser.parse(user_agent), user_agent)
+    }
+
+    #[test]
+    fn every_entry_matches_its_example() {
+        let parser = parser();
+        for bot in taxonomy::FAMILIES {
+            assert_eq!(parser.parse(bot.example).user_agent.family, bot.name);
+            assert_eq!(category(&parser, bot.example), bot.category, "{}", bot.name);
+        }
+        for bot in taxonomy::TOKENS {
+            assert_eq!(category(&parser, bot.example), bot.category, "{}", bot.name);
+        }
+    }
+
+    #[test]
+    fn operators_are_told_apart() {
+        let parser = parser();
+        let find = |name: &str| {
+            taxonomy::FAMILIES
+                .iter()
+                .find(|bot| bot.name == name)
+                .map(|bot| bot.example)
+                .expect("Unknown crawler")
+        };
+
+        assert_eq!(
+            category(&parser, find("Googlebot")),
+            BotCategory::SearchEngine
+        );
+        assert_eq!(category(&parser, find("GPTBot")), BotCategory::AiCrawler);
+        assert_eq!(
+            category(&parser, find("UptimeRobot")),
+            BotCategory::Monitoring
+        );
+        assert_eq!(
+            category(&parser, "Mozilla/5.0 (compatible; SomeRandomBot/1.0)"),
+            BotCategory::GenericBot
+        );
+        assert_eq!(
+            category(
+                &parser,
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36"
+            ),
+            BotCategory::NotABot
+        );
+        assert_eq!(category(&parser, "curl/8.4.0"), BotCategory::NotABot);
+    }
+}
@@ -0,0 +1,174 @@
+//! The crawlers `categorize` knows, each with a user agent it sends. Add new
+//! crawlers here; the tests check every entry against its example.
+
+use super::BotCategory::{
+    self, AiCrawler, Monitoring, SearchEngine, SeoTool, SocialPreview,
+};
+
+/// A known crawler
+pub(super) struct Bot {
+    /// The user agent family the rules give it, or for `TOKENS`, a string
+    /// its user agents contain
+    pub(super) name: &'static str,
+    pub(super) category: BotCategory,
+    /// A user agent the crawler sends
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub(super) example: &'static str,
+}
+
+const fn bot(name: &'static str, category: BotCategory, example: &'static str) -> Bot {
+    Bot {
+        name,
+        category,
+        example,
+    }
+}
+
+/// Crawlers by the user agent family the stock rules give them
+pub(super) const FAMILIES: &[Bot] = &[
+    bot(
+        "Googlebot",
+        SearchEngine,
+        "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)",
+    ),
+    bot("Googlebot-Image", SearchEngine, "Googlebot-Image/1.0"),
+    bot(
+        "bingbot",
+        SearchEngine,
+        "Mozilla/5.0 (compatible; bingbot/2.0; +http://www.bing.com/bingbot.htm)",
+    ),
+    bot(
+        "YandexBot",
+        SearchEngine,
+        "Mozilla/5.0 (compatible; YandexBot/3.0; +http://yandex.com/bots)",
+    ),
+    bot(
+        "Baiduspider",
+        SearchEngine,
+        "Mozilla/5.0 (compatible; Baiduspider/2.0; \
+         +http://www.baidu.com/search/spider.html)",
+    ),
+    bot(
+        "DuckDuckBot",
+        SearchEngine,
+        "DuckDuckBot/1.1; (+http://duckduckgo.com/duckduckbot.html)",
+    ),
+    bot(
+        "FacebookBot",
+        SocialPreview,
+        "facebookexternalhit/1.1 (+http://www.facebook.com/externalhit_uatext.php)",
+    ),
+    bot("Twitterbot", SocialPreview, "Twitterbot/1.0"),
+    bot(
+        "LinkedInBot",
+        SocialPreview,
+        "LinkedInBot/1.0 (compatible; Mozilla/5.0; Apache-HttpClient \
+         +http://www.linkedin.com)",
+    ),
+    bot(
+        "Slackbot-LinkExpanding",
+        SocialPreview,
+        "Slackbot-LinkExpanding 1.0 (+https://api.slack.com/robots)",
+    ),
+    bot(
+        "Discordbot",
+        SocialPreview,
+        "Mozilla/5.0 (compatible; Discordbot/2.0; +https://discordapp.com)",
+    ),
+    bot("WhatsApp", SocialPreview, "WhatsApp/2.23.20.0 A"),
+    bot(
+        "TelegramBot",
+        SocialPreview,
+        "TelegramBot (like TwitterBot)",
+    ),
+    bot(
+        "UptimeRobot",
+        Monitoring,
+        "Mozilla/5.0+(compatible; UptimeRobot/2.0; http://www.uptimerobot.com/)",
+    ),
+    bot(
+        "PingdomBot",
+        Monitoring,
+        "Pingdom.com_bot_version_1.4_(http://www.pingdom.com/)",
+    ),
+    bot(
+        "StatusCakeBot",
+        Monitoring,
+        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+         (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36 (StatusCake)",
+    ),
+    bot(
+        "AhrefsBot",
+        SeoTool,
+        "Mozilla/5.0 (compatible; AhrefsBot/7.0; +http://ahrefs.com/robot/)",
+    ),
+    bot(
+        "SemrushBot",
+        SeoTool,
+        "Mozilla/5.0 (compatible; SemrushBot/7~bl; +http://www.semrush.com/bot.html)",
+    ),
+    bot(
+        "MJ12bot",
+        SeoTool,
+        "Mozilla/5.0 (compatible; MJ12bot/v1.4.8; http://mj12bot.com/)",
+    ),
+    bot(
+        "DotBot",
+        SeoTool,
+        "Mozilla/5.0 (compatible; DotBot/1.2; +https://opensiteexplorer.org/dotbot; \
+         help@moz.com)",
+    ),
+    bot(
+        "Screaming Frog SEO Spider",
+        SeoTool,
+        "Screaming Frog SEO Spider/19.4",
+    ),
+    bot(
+        "GPTBot",
+        AiCrawler,
+        "Mozilla/5.0 AppleWebKit/537.36 (KHTML, like Gecko); compatible; GPTBot/1.0; \
+         +https://openai.com/gptbot",
+    ),
+    bot(
+        "ClaudeBot",
+        AiCrawler,
+        "Mozilla/5.0 AppleWebKit/537.36 (KHTML, like Gecko; compatible; ClaudeBot/1.0; \
+         +claudebot@anthropic.com)",
+    ),
+    bot(
+        "CCBot",
+        AiCrawler,
+        "CCBot/2.0 (https://commoncrawl.org/faq/)",
+    ),
+    bot(
+        "PerplexityBot",
+        AiCrawler,
+        "Mozilla/5.0 AppleWebKit/537.36 (KHTML, like Gecko; compatible; \
+         PerplexityBot/1.0; +https://perplexity.ai/perplexitybot)",
+    ),
+];
+
+/// Crawlers the stock rules don't tell apart, or don't see as crawlers, by a
+/// string their user agents contain. Checked in order after `FAMILIES`.
+pub(super) const TOKENS: &[Bot] = &[
+    bot(
+        "Applebot",
+        SearchEngine,
+        "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_5) AppleWebKit/605.1.15 \
+         (KHTML, like Gecko) Version/13.1.1 Safari/605.1.15 (Applebot/0.1; \
+         +http://www.apple.com/go/applebot)",
+    ),
+    bot("Datadog/Synthetics", Monitoring, "Datadog/Synthetics"),
+    bot(
+        "ChatGPT-User",
+        AiCrawler,
+        "Mozilla/5.0 AppleWebKit/537.36 (KHTML, like Gecko); compatible; \
+         ChatGPT-User/1.0; +https://openai.com/bot",
+    ),
+    bot(
+        "Bytespider",
+        AiCrawler,
+        "Mozilla/5.0 (Linux; Android 5.0) AppleWebKit/537.36 (KHTML, like Gecko) \
+         Mobile Safari/537.36 (compatible; Bytespider; spider-feedback@bytedance.com)",
+    ),
+];
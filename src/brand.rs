@@ -0,0 +1,152 @@
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+use std::{borrow::Cow, collections::HashMap, sync::LazyLock};
+
+/// Canonical brand casings and the other spellings seen in rule captures.
+/// Lookups ignore case, so only spellings that differ by more than case are
+/// listed as aliases.
+const BRANDS: &[(&str, &[&str])] = &[
+    ("Acer", &[]),
+    ("Alcatel", &[]),
+    ("Amazon", &[]),
+    ("Apple", &[]),
+    ("Asus", &[]),
+    ("BlackBerry", &[]),
+    ("Coolpad", &[]),
+    ("Dell", &[]),
+    ("Google", &[]),
+    ("HP", &[]),
+    ("HTC", &[]),
+    ("Hisense", &[]),
+    ("Huawei", &[]),
+    ("Infinix", &[]),
+    ("Kyocera", &[]),
+    ("LG", &["LGE", "LG Electronics"]),
+    ("Lenovo", &[]),
+    ("Meizu", &[]),
+    ("Micromax", &[]),
+    ("Microsoft", &[]),
+    ("Motorola", &["Moto"]),
+    ("Nokia", &[]),
+    ("OPPO", &[]),
+    ("OnePlus", &["One Plus"]),
+    ("Packard Bell", &["PackardBell"]),
+    ("Panasonic", &[]),
+    ("Samsung", &[]),
+    ("Sharp", &[]),
+    ("Sony", &[]),
+    ("Sony Ericsson", &["SonyEricsson"]),
+    ("T-Mobile", &["Tmobile"]),
+    ("Tecno", &[]),
+    ("Toshiba", &[]),
+    ("Xiaomi", &[]),
+    ("ZTE", &[]),
+    ("vivo", &[]),
+];
+
+pub(crate) static DEFAULT: LazyLock<BrandNormalizer> =
+    LazyLock::new(BrandNormalizer::new);
+
+/// Maps device brands to one casing per manufacturer, such as `SAMSUNG` and
+/// `samsung` to `Samsung`, so results group together. Starts from a built-in
+/// table of common brands; unknown brands are returned unchanged.
+#[derive(Clone, Debug)]
+pub struct BrandNormalizer(HashMap<String, Cow<'static, str>>);
+
+impl BrandNormalizer {
+    #[must_use]
+    pub fn new() -> Self {
+        let mut canonical = HashMap::new();
+        for &(brand, aliases) in BRANDS {
+            for &alias in aliases.iter().chain([brand].iter()) {
+                canonical.insert(alias.to_lowercase(), Cow::Borrowed(brand));
+            }
+        }
+        BrandNormalizer(canonical)
+    }
+
+    /// Adds `(alias, canonical)` pairs, replacing built-in entries for the
+    /// same spelling. Each canonical brand also becomes an alias of itself.
+    #[must_use]
+    pub fn with_aliases<I, A, C>(mut self, aliases: I) -> Self
+    where
+        I: IntoIterator<Item = (A, C)>,
+        A: AsRef<str>,
+        C: Into<String>,
+    {
+        for (alias, brand) in aliases {
+            let brand = brand.into();
+            self.0
+                .insert(brand.to_lowercase(), Cow::Owned(brand.clone()));
+            self.0
+                .insert(alias.as_ref().to_lowercase(), Cow::Owned(brand));
+        }
+        self
+    }
+
+    /// The canonical spelling of `brand`, or `brand` itself when it isn't
+    /// known
+    #[must_use]
+    pub fn normalize<'a>(&'a self, brand: &'a str) -> Cow<'a, str> {
+        match self.0.get(&brand.to_lowercase()) {
+            Some(canonical) => Cow::Borrowed(canonical),
+            None => Cow::Borrowed(brand),
+        }
+    }
+}
+
+impl Default for BrandNormalizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Device;
+
+    fn device(brand: &str) -> Device<'_> {
+        Device {
+            brand: Some(Cow::Borrowed(brand)),
+            ..Device::default()
+        }
+    }
+
+    #[test]
+    fn casings_normalize_identically() {
+        for brand in ["samsung", "SAMSUNG", "Samsung"] {
+            assert_eq!(device(brand).brand_normalized().as_deref(), Some("Samsung"));
+        }
+        assert_eq!(
+            device("XiaoMi").brand_normalized().as_deref(),
+            Some("Xiaomi")
+        );
+        assert_eq!(device("Oppo").brand_normalized().as_deref(), Some("OPPO"));
+        assert_eq!(
+            device("SonyEricsson").brand_normalized().as_deref(),
+            Some("Sony Ericsson")
+        );
+    }
+
+    #[test]
+    fn unknown_brands_pass_through() {
+        let device = device("AcmeFone");
+
+        assert_eq!(device.brand_normalized().as_deref(), Some("AcmeFone"));
+        assert_eq!(device.brand.as_deref(), Some("AcmeFone"));
+        assert_eq!(Device::default().brand_normalized(), None);
+    }
+
+    #[test]
+    fn runtime_aliases_take_effect() {
+        let normalizer = BrandNormalizer::new()
+            .with_aliases([("Acme Mobile", "Acme"), ("ACMEFONE", "Acme")]);
+
+        assert_eq!(normalizer.normalize("acme mobile"), "Acme");
+        assert_eq!(normalizer.normalize("AcmeFone"), "Acme");
+        assert_eq!(normalizer.normalize("ACME"), "Acme");
+        assert_eq!(normalizer.normalize("samsung"), "Samsung");
+        assert_eq!(BrandNormalizer::new().normalize("AcmeFone"), "AcmeFone");
+    }
+}
@@ -1,10 +1,292 @@
-use super::{Deserialize, Device, Serialize, UserAgent, OS};
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+use std::{borrow::Cow, collections::BTreeMap};
+
+use super::{
+    families, Deserialize, Device, DeviceNameResolver, Serialize, UserAgent, OS,
+};
 
 /// Houses the `Device`, `OS`, and `UserAgent` structs, which each get parsed
 /// out from a user agent string by a `UserAgentParser`.
-#[derive(Clone, Debug, Serialize, Deserialize, Eq, Hash, PartialEq)]
+///
+/// Deserializing borrows strings from the input where the format allows,
+/// such as unescaped strings with `serde_json::from_str`, and copies the
+/// rest. The result can't outlive the input, so read streamed input into a
+/// buffer first, and call `into_owned` to keep a result around. Functions
+/// bound on `DeserializeOwned`, such as `serde_yaml::from_str`, don't accept
+/// these types; use `Client::deserialize` on the format's `Deserializer`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Client<'a> {
+    /// The hardware the user agent runs on
+    #[serde(borrow)]
     pub device: Device<'a>,
+    /// The operating system the user agent runs on
+    #[serde(borrow)]
     pub os: OS<'a>,
+    /// The browser, app or bot itself
+    #[serde(borrow)]
     pub user_agent: UserAgent<'a>,
 }
+
+/// The names of the flattened fields, in the order used by every tabular
+/// output and by `Client::fields`. New fields are only ever appended, so
+/// existing columns keep their positions.
+pub(crate) const FLAT_KEYS: [&str; 13] = [
+    "ua_family",
+    "ua_major",
+    "ua_minor",
+    "ua_patch",
+    "os_family",
+    "os_major",
+    "os_minor",
+    "os_patch",
+    "os_patch_minor",
+    "device_family",
+    "device_brand",
+    "device_model",
+    "ua_patch_minor",
+];
+
+impl Client<'_> {
+    /// Copies any borrowed fields, so the result outlives the user agent
+    /// string and the parser
+    #[must_use]
+    pub fn into_owned(self) -> Client<'static> {
+        Client {
+            device: self.device.into_owned(),
+            os: self.os.into_owned(),
+            user_agent: self.user_agent.into_owned(),
+        }
+    }
+
+    /// Whether the rules identified a crawler, which they report with the
+    /// `Spider` device family
+    #[must_use]
+    pub fn is_bot(&self) -> bool {
+        self.device.family == families::SPIDER
+    }
+
+    /// Whether no user agent rule matched, leaving the `Other` family
+    #[must_use]
+    pub fn is_other_user_agent(&self) -> bool {
+        self.user_agent.family == families::OTHER
+    }
+
+    /// Whether no OS rule matched, leaving the `Other` family
+    #[must_use]
+    pub fn is_other_os(&self) -> bool {
+        self.os.family == families::OTHER
+    }
+
+    /// Whether no device rule matched, leaving the `Other` family, as for
+    /// most desktop browsers
+    #[must_use]
+    pub fn is_other_device(&self) -> bool {
+        self.device.family == families::OTHER
+    }
+
+    /// The parsed fields in the order of `FLAT_KEYS`
+    pub(crate) fn flatten(&self) -> [Option<&str>; 13] {
+        let user_agent = &self.user_agent;
+        let os = &self.os;
+        let device = &self.device;
+        [
+            Some(&user_agent.family),
+            user_agent.major.as_deref(),
+            user_agent.minor.as_deref(),
+            user_agent.patch.as_deref(),
+            Some(&os.family),
+            os.major.as_deref(),
+            os.minor.as_deref(),
+            os.patch.as_deref(),
+            os.patch_minor.as_deref(),
+            Some(&device.family),
+            device.brand.as_deref(),
+            device.model.as_deref(),
+            user_agent.patch_minor.as_deref(),
+        ]
+    }
+
+    /// The parsed fields as flat `(key, value)` pairs, such as
+    /// `("ua_family", "Firefox")`, with absent fields left out. The keys are
+    /// the ones used by every flattened output.
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+    /// let client = parser.parse("curl/8.4.0");
+    ///
+    /// assert_eq!(client.fields().next(), Some(("ua_family", "curl")));
+    /// assert_eq!(client.to_map()["ua_major"], "8");
+    /// ```
+    pub fn fields(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        FLAT_KEYS
+            .iter()
+            .copied()
+            .zip(self.flatten())
+            .filter_map(|(key, value)| Some((key, value?)))
+    }
+
+    /// Collects `fields` into a map
+    #[must_use]
+    pub fn to_map(&self) -> BTreeMap<&'static str, Cow<'_, str>> {
+        self.fields()
+            .map(|(key, value)| (key, Cow::Borrowed(value)))
+            .collect()
+    }
+
+    /// Whether the parsed user agent family is one of the HTTP libraries or
+    /// tools known to `extras::classify_tool`
+    #[must_use]
+    pub fn is_http_tool(&self) -> bool {
+        crate::extras::is_tool_family(&self.user_agent.family)
+    }
+
+    /// Looks up the retail name of the parsed device, such as `Galaxy S21 5G`
+    /// for brand `Samsung` and model `SM-G991B`
+    #[must_use]
+    pub fn resolve_device_name<'r>(
+        &self,
+        resolver: &'r DeviceNameResolver,
+    ) -> Option<&'r str> {
+        resolver.resolve(self.device.brand.as_deref()?, self.device.model.as_deref()?)
+    }
+
+    /// A stable key over the parsed fields only, grouping every user agent
+    /// that classifies the same way. See `hash` for the encoding.
+    #[cfg(feature = "hash")]
+    #[must_use]
+    pub fn classification_key(&self) -> [u8; 16] {
+        crate::hash::classification_key(self)
+    }
+
+    /// The parsed fields as OpenTelemetry semantic convention attributes,
+    /// using the key constants in `otel`. Unknown fields are left out, and
+    /// `user_agent.original` is only included when `original` is given.
+    #[must_use]
+    pub fn otel_attributes<'s>(
+        &'s self,
+        original: Option<&'s str>,
+    ) -> Vec<(&'static str, Cow<'s, str>)> {
+        crate::otel::attributes(self, original)
+    }
+
+    /// The parsed fields in the shape ua-parser-js returns, for
+    /// serializing to consumers that expect it. See `uaparser_js`.
+    #[must_use]
+    pub fn as_uaparser_js(&self) -> crate::uaparser_js::UaParserJsView<'_> {
+        crate::uaparser_js::view(self)
+    }
+
+    /// Sets the common parsed fields as individual fields on `span`, for
+    /// subscribers that don't record structured values. Tracing only keeps
+    /// fields declared when the span was created, so declare
+    /// `user_agent.family`, `user_agent.major`, `os.family`, `os.major`,
+    /// `device.family`, `device.brand` and `device.model` as
+    /// `tracing::field::Empty`. Absent fields are left unset.
+    #[cfg(feature = "valuable")]
+    pub fn record_into(&self, span: &tracing::Span) {
+        crate::valuable::record_into(self, span);
+    }
+}
+
+/// Deserializes optional strings borrowing from the input where the format
+/// allows, which `#[serde(borrow)]` alone only does for bare `Cow<str>`
+pub(crate) mod borrowed {
+    use std::borrow::Cow;
+
+    use serde::{Deserialize, Deserializer};
+
+    #[derive(Deserialize)]
+    struct Borrowed<'a>(#[serde(borrow)] Cow<'a, str>);
+
+    pub fn option<'de: 'a, 'a, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Cow<'a, str>>, D::Error> {
+        Ok(Option::<Borrowed<'a>>::deserialize(deserializer)?.map(|borrowed| borrowed.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_client_has_every_key() {
+        let full = |value: &'static str| Some(Cow::Borrowed(value));
+        let client = Client {
+            device: Device {
+                family: Cow::Borrowed("iPhone"),
+                brand: full("Apple"),
+                model: full("iPhone"),
+            },
+            os: OS {
+                family: Cow::Borrowed("iOS"),
+                major: full("17"),
+                minor: full("2"),
+                patch: full("1"),
+                patch_minor: full("0"),
+            },
+            user_agent: UserAgent {
+                family: Cow::Borrowed("Mobile Safari"),
+                major: full("17"),
+                minor: full("2"),
+                patch: full("0"),
+                patch_minor: full("1"),
+            },
+        };
+
+        let map = client.to_map();
+        assert_eq!(map.len(), FLAT_KEYS.len());
+        assert!(FLAT_KEYS.iter().all(|key| map.contains_key(key)));
+        assert_eq!(map["os_patch_minor"], "0");
+        assert_eq!(map["device_brand"], "Apple");
+        assert_eq!(map["ua_patch_minor"], "1");
+        assert_eq!(
+            client.fields().map(|(key, _)| key).collect::<Vec<_>>(),
+            FLAT_KEYS
+        );
+    }
+
+    #[test]
+    fn unmatched_client_has_family_keys() {
+        let client = Client::default();
+        let map = client.to_map();
+
+        assert_eq!(
+            map.into_iter().collect::<Vec<_>>(),
+            [
+                ("device_family", Cow::Borrowed("Other")),
+                ("os_family", Cow::Borrowed("Other")),
+                ("ua_family", Cow::Borrowed("Other")),
+            ]
+        );
+    }
+
+    #[test]
+    fn deserializing_borrows_unescaped_strings() {
+        let json = r#"{
+            "device": {"family": "iPhone", "brand": "Apple", "model": null},
+            "os": {"family": "iOS", "major": "17", "minor": "2"},
+            "user_agent": {"family": "Mobile \"Safari\"", "major": "17", "minor": "2\u002e1"}
+        }"#;
+        let client: Client<'_> =
+            serde_json::from_str(json).expect("Deserialization failed");
+
+        assert!(matches!(client.device.family, Cow::Borrowed("iPhone")));
+        assert!(matches!(client.device.brand, Some(Cow::Borrowed("Apple"))));
+        assert_eq!(client.device.model, None);
+        assert!(matches!(client.os.major, Some(Cow::Borrowed("17"))));
+        assert_eq!(client.os.patch, None);
+        assert!(matches!(client.user_agent.family, Cow::Owned(_)));
+        assert_eq!(client.user_agent.family, r#"Mobile "Safari""#);
+        assert!(matches!(client.user_agent.minor, Some(Cow::Owned(_))));
+        assert_eq!(client.user_agent.minor.as_deref(), Some("2.1"));
+
+        let serialized = serde_json::to_string(&client).expect("Serialization failed");
+        let reparsed: Client<'_> =
+            serde_json::from_str(&serialized).expect("Deserialization failed");
+        assert_eq!(reparsed, client);
+    }
+}
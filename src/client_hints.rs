@@ -0,0 +1,283 @@
+//! Reads the browser from the `Sec-CH-UA` and `Sec-CH-UA-Full-Version-List`
+//! client hint headers, which list brands with their versions as structured
+//! fields. The basic list only carries major versions; the full version list
+//! has the complete ones.
+//!
+//! ```rust
+//! # use uaparser::client_hints::user_agent_from_hints;
+//! let user_agent = user_agent_from_hints(
+//!     r#""Not_A Brand";v="8", "Chromium";v="120", "Google Chrome";v="120""#,
+//!     Some(r#""Not_A Brand";v="8.0.0.0", "Chromium";v="120.0.6099.130", "Google Chrome";v="120.0.6099.130""#),
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(user_agent.family, "Chrome");
+//! assert_eq!(user_agent.patch_minor.as_deref(), Some("130"));
+//! ```
+
+use std::borrow::Cow;
+
+use super::UserAgent;
+
+/// Brands reported under another family by the regex rules
+const FAMILIES: &[(&str, &str)] =
+    &[("Google Chrome", "Chrome"), ("Microsoft Edge", "Edge")];
+
+/// One `"brand";v="version"` entry of a client hint brand list
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Brand<'a> {
+    pub name: Cow<'a, str>,
+    pub version: Cow<'a, str>,
+}
+
+impl Brand<'_> {
+    /// Whether this is one of the made up brands, such as `Not_A Brand`,
+    /// that browsers add so servers don't rely on an exact list
+    #[must_use]
+    pub fn is_grease(&self) -> bool {
+        self.name.starts_with("Not") && self.name.ends_with("Brand")
+    }
+}
+
+/// The brands of a `Sec-CH-UA` header, which carry major versions only,
+/// without GREASE entries
+#[must_use]
+pub fn parse_sec_ch_ua(value: &str) -> Vec<Brand<'_>> {
+    brand_list(value)
+}
+
+/// The brands of a `Sec-CH-UA-Full-Version-List` header, such as
+/// `"Chromium";v="120.0.6099.130"`, without GREASE entries
+#[must_use]
+pub fn parse_full_version_list(value: &str) -> Vec<Brand<'_>> {
+    brand_list(value)
+}
+
+/// The browser described by a `Sec-CH-UA` header and, when it was sent, the
+/// `Sec-CH-UA-Full-Version-List` header. Versions come from the full list.
+/// When the two lists name different brands only those in both are used,
+/// unless they have none in common, in which case the basic list is trusted.
+/// `None` when no brand besides GREASE is listed.
+#[must_use]
+pub fn user_agent_from_hints<'a>(
+    sec_ch_ua: &'a str,
+    full_version_list: Option<&'a str>,
+) -> Option<UserAgent<'a>> {
+    let basic = parse_sec_ch_ua(sec_ch_ua);
+    let full = full_version_list.map_or_else(Vec::new, parse_full_version_list);
+
+    let brands = if basic.is_empty() {
+        full
+    } else {
+        let common: Vec<_> = full
+            .into_iter()
+            .filter(|brand| basic.iter().any(|basic| basic.name == brand.name))
+            .collect();
+        if common.is_empty() {
+            basic
+        } else {
+            common
+        }
+    };
+
+    significant_brand(brands).map(|brand| {
+        let family = FAMILIES
+            .iter()
+            .find(|(name, _)| *name == brand.name)
+            .map_or(brand.name, |(_, family)| Cow::Borrowed(family));
+        let mut versions = version_parts(brand.version).into_iter();
+        UserAgent {
+            family,
+            major: versions.next(),
+            minor: versions.next(),
+            patch: versions.next(),
+            patch_minor: versions.next(),
+        }
+    })
+}
+
+/// The browser's own brand, preferring any other over the `Chromium` engine
+/// brand that Chromium based browsers list alongside their own
+fn significant_brand(brands: Vec<Brand<'_>>) -> Option<Brand<'_>> {
+    let mut engine = None;
+    for brand in brands {
+        if brand.name == "Chromium" {
+            engine = Some(brand);
+        } else {
+            return Some(brand);
+        }
+    }
+    engine
+}
+
+fn version_parts(version: Cow<'_, str>) -> Vec<Cow<'_, str>> {
+    match version {
+        Cow::Borrowed(version) => version
+            .split('.')
+            .filter(|part| !part.is_empty())
+            .map(Cow::Borrowed)
+            .collect(),
+        Cow::Owned(version) => version
+            .split('.')
+            .filter(|part| !part.is_empty())
+            .map(|part| Cow::Owned(part.to_owned()))
+            .collect(),
+    }
+}
+
+/// Parses a structured field list of strings with a `v` parameter, stopping
+/// at the first item that isn't a string
+fn brand_list(value: &str) -> Vec<Brand<'_>> {
+    let mut brands = Vec::new();
+    let mut rest = value;
+
+    loop {
+        rest = rest.trim_start();
+        let Some((name, after)) = string(rest) else {
+            break;
+        };
+        rest = after;
+
+        let mut version = None;
+        while let Some(after) = rest.trim_start().strip_prefix(';') {
+            let after = after.trim_start();
+            let key_end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || "_-.*".contains(c)))
+                .unwrap_or(after.len());
+            let (key, after) = after.split_at(key_end);
+            let Some(after) = after.strip_prefix('=') else {
+                rest = after;
+                continue;
+            };
+            let (value, after) = string(after).unwrap_or_else(|| {
+                let end = after.find([',', ';']).unwrap_or(after.len());
+                (Cow::Borrowed(after[..end].trim_end()), &after[end..])
+            });
+            if key == "v" {
+                version = Some(value);
+            }
+            rest = after;
+        }
+
+        let brand = Brand {
+            name,
+            version: version.unwrap_or(Cow::Borrowed("")),
+        };
+        if !brand.is_grease() {
+            brands.push(brand);
+        }
+
+        match rest.trim_start().strip_prefix(',') {
+            Some(after) => rest = after,
+            None => break,
+        }
+    }
+    brands
+}
+
+/// A quoted structured field string at the start of `text`, unescaped, and
+/// the text after it
+fn string(text: &str) -> Option<(Cow<'_, str>, &str)> {
+    let text = text.strip_prefix('"')?;
+    let mut unescaped: Option<String> = None;
+    let mut chars = text.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => {
+                let value = unescaped.map_or(Cow::Borrowed(&text[..index]), Cow::Owned);
+                return Some((value, &text[index + 1..]));
+            }
+            '\\' => {
+                let (_, escaped) = chars.next()?;
+                unescaped
+                    .get_or_insert_with(|| text[..index].to_owned())
+                    .push(escaped);
+            }
+            _ => {
+                if let Some(unescaped) = &mut unescaped {
+                    unescaped.push(c);
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHROME: &str =
+        r#""Not_A Brand";v="8", "Chromium";v="120", "Google Chrome";v="120""#;
+    const CHROME_FULL: &str = r#""Not_A Brand";v="8.0.0.0", "Chromium";v="120.0.6099.130", "Google Chrome";v="120.0.6099.130""#;
+    const EDGE: &str =
+        r#""Not_A Brand";v="8", "Chromium";v="120", "Microsoft Edge";v="120""#;
+    const EDGE_FULL: &str = r#""Not_A Brand";v="8.0.0.0", "Chromium";v="120.0.6099.217", "Microsoft Edge";v="120.0.2210.121""#;
+
+    fn version<'a>(user_agent: &'a UserAgent<'_>) -> [Option<&'a str>; 4] {
+        [
+            user_agent.major.as_deref(),
+            user_agent.minor.as_deref(),
+            user_agent.patch.as_deref(),
+            user_agent.patch_minor.as_deref(),
+        ]
+    }
+
+    #[test]
+    fn full_versions_win() {
+        let chrome = user_agent_from_hints(CHROME, Some(CHROME_FULL)).unwrap();
+        assert_eq!(chrome.family, "Chrome");
+        assert_eq!(
+            version(&chrome),
+            [Some("120"), Some("0"), Some("6099"), Some("130")]
+        );
+
+        let edge = user_agent_from_hints(EDGE, Some(EDGE_FULL)).unwrap();
+        assert_eq!(edge.family, "Edge");
+        assert_eq!(
+            version(&edge),
+            [Some("120"), Some("0"), Some("2210"), Some("121")]
+        );
+
+        let basic_only = user_agent_from_hints(EDGE, None).unwrap();
+        assert_eq!(basic_only.family, "Edge");
+        assert_eq!(version(&basic_only), [Some("120"), None, None, None]);
+    }
+
+    #[test]
+    fn disagreeing_lists_use_common_brands() {
+        let full = r#""Opera";v="106.0.4998.19", "Chromium";v="120.0.6099.130""#;
+        let user_agent = user_agent_from_hints(CHROME, Some(full)).unwrap();
+        assert_eq!(user_agent.family, "Chromium");
+        assert_eq!(user_agent.patch_minor.as_deref(), Some("130"));
+
+        let unrelated = r#""Opera";v="106.0.4998.19""#;
+        let user_agent = user_agent_from_hints(CHROME, Some(unrelated)).unwrap();
+        assert_eq!(user_agent.family, "Chrome");
+        assert_eq!(version(&user_agent), [Some("120"), None, None, None]);
+    }
+
+    #[test]
+    fn grease_is_filtered() {
+        let brands = parse_full_version_list(
+            r#""Not)A;Brand";v="99.0.0.0", "Google Chrome";v="127.0.6533.89", "Not.A/Brand" ; v="8""#,
+        );
+        assert_eq!(
+            brands,
+            [Brand {
+                name: Cow::Borrowed("Google Chrome"),
+                version: Cow::Borrowed("127.0.6533.89"),
+            }]
+        );
+        assert_eq!(user_agent_from_hints(r#""Not_A Brand";v="8""#, None), None);
+    }
+
+    #[test]
+    fn strings_are_unescaped() {
+        let brands = parse_sec_ch_ua(r#""Acme \"Quoted\" Browser";v="3";x=1, ?1"#);
+        assert_eq!(brands.len(), 1);
+        assert_eq!(brands[0].name, r#"Acme "Quoted" Browser"#);
+        assert_eq!(brands[0].version, "3");
+    }
+}
@@ -0,0 +1,164 @@
+//! Writes parse results as CSV, one row per user agent with the flattened
+//! `ua_*`, `os_*` and `device_*` fields.
+//!
+//! ```rust
+//! # use uaparser::*;
+//! # use uaparser::csv::{write_csv, CsvOptions};
+//! let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+//! let mut output = Vec::new();
+//! let rows = write_csv(&parser, ["curl/8.4.0"].iter(), &mut output, CsvOptions::default()).unwrap();
+//!
+//! assert_eq!(rows, 1);
+//! assert!(String::from_utf8(output).unwrap().starts_with("user_agent,ua_family,"));
+//! ```
+
+use std::io::Write;
+
+use derive_more::{Display, From};
+
+use crate::{client::FLAT_KEYS, ClientBuf, UserAgentParser};
+
+#[derive(Debug, Display, From)]
+pub enum Error {
+    IO(std::io::Error),
+    Csv(::csv::Error),
+}
+
+/// Controls the columns written by `write_csv`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CsvOptions {
+    /// Writes the raw user agent as the first column, named `user_agent`
+    pub include_user_agent: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            include_user_agent: true,
+        }
+    }
+}
+
+/// Parses each user agent and writes a header row followed by one row per
+/// user agent. Fields that weren't found are written as empty cells, and
+/// values containing commas, quotes or line breaks are quoted. Returns the
+/// number of rows written, not counting the header.
+pub fn write_csv<W, I>(
+    parser: &UserAgentParser,
+    user_agents: I,
+    writer: W,
+    options: CsvOptions,
+) -> Result<u64, Error>
+where
+    W: Write,
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    let mut writer = ::csv::Writer::from_writer(writer);
+
+    if options.include_user_agent {
+        writer.write_field("user_agent")?;
+    }
+    writer.write_record(FLAT_KEYS)?;
+
+    let mut rows = 0;
+    let mut client = ClientBuf::new();
+    for user_agent in user_agents {
+        let user_agent = user_agent.as_ref();
+        parser.parse_into(user_agent, &mut client);
+        if options.include_user_agent {
+            writer.write_field(user_agent)?;
+        }
+        writer.write_record(
+            client
+                .flatten()
+                .iter()
+                .map(|field| field.unwrap_or_default()),
+        )?;
+        rows += 1;
+    }
+
+    writer.flush()?;
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_tricky_user_agents() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let user_agents = [
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) \
+             Chrome/120.0.0.0 Safari/537.36",
+            "Custom/1.0 (comma, separated, values)",
+            r#"Quoted/2.0 ("double" quotes)"#,
+            "Ünïcödé/3.0 (日本語; Ελληνικά)\nnext line",
+        ];
+
+        let mut output = Vec::new();
+        let rows = write_csv(
+            &parser,
+            user_agents.iter(),
+            &mut output,
+            CsvOptions::default(),
+        )
+        .expect("Writing CSV failed");
+        assert_eq!(rows, 4);
+
+        let mut reader = ::csv::Reader::from_reader(output.as_slice());
+        let headers = reader.headers().unwrap().clone();
+        assert_eq!(headers.len(), 14);
+        assert_eq!(&headers[0], "user_agent");
+        assert_eq!(&headers[1], "ua_family");
+        assert_eq!(&headers[12], "device_model");
+        assert_eq!(&headers[13], "ua_patch_minor");
+
+        let records: Vec<::csv::StringRecord> =
+            reader.records().collect::<Result<_, _>>().unwrap();
+        assert_eq!(records.len(), 4);
+        for (record, user_agent) in records.iter().zip(&user_agents) {
+            assert_eq!(&record[0], *user_agent);
+        }
+        assert_eq!(&records[0][1], "Chrome");
+        assert_eq!(&records[0][2], "120");
+        // Missing fields are empty cells
+        assert_eq!(&records[0][11], "");
+        assert!(!output.windows(4).any(|window| window == b"None"));
+    }
+
+    #[test]
+    fn omits_user_agent_column() {
+        let parser = UserAgentParser::from_bytes(
+            br"
+user_agent_parsers:
+  - regex: '(Foo)/(\d+)'
+os_parsers: []
+device_parsers: []
+",
+        )
+        .expect("Parser creation failed");
+        let options = CsvOptions {
+            include_user_agent: false,
+        };
+
+        let mut output = Vec::new();
+        let rows = write_csv(
+            &parser,
+            vec!["Foo/1".to_owned()].into_iter(),
+            &mut output,
+            options,
+        )
+        .expect("Writing CSV failed");
+
+        assert_eq!(rows, 1);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "ua_family,ua_major,ua_minor,ua_patch,os_family,os_major,os_minor,os_patch,\
+             os_patch_minor,device_family,device_brand,device_model,ua_patch_minor\n\
+             Foo,1,,,Other,,,,,Other,,,\n"
+        );
+    }
+}
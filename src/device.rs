@@ -1,14 +1,46 @@
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
 use super::{Deserialize, Serialize};
 use std::borrow::Cow;
 
 /// Describes the `Family`, `Brand` and `Model` of a `Device`
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Device<'a> {
+    /// The device name, `Other` when no rule matched
+    #[serde(borrow)]
     pub family: Cow<'a, str>,
+    /// The manufacturer, such as `Apple` or `Samsung`
+    #[serde(borrow, default, deserialize_with = "crate::client::borrowed::option")]
     pub brand: Option<Cow<'a, str>>,
+    /// The model name or number, such as `iPhone` or `SM-S901B`
+    #[serde(borrow, default, deserialize_with = "crate::client::borrowed::option")]
     pub model: Option<Cow<'a, str>>,
 }
 
+impl Device<'_> {
+    /// Copies any borrowed fields, so the result outlives the user agent
+    /// string and the parser
+    #[must_use]
+    pub fn into_owned(self) -> Device<'static> {
+        Device {
+            family: Cow::Owned(self.family.into_owned()),
+            brand: self.brand.map(|brand| Cow::Owned(brand.into_owned())),
+            model: self.model.map(|model| Cow::Owned(model.into_owned())),
+        }
+    }
+
+    /// The brand in the casing of the built-in `BrandNormalizer` table, such
+    /// as `Samsung` for `SAMSUNG`. The `brand` field itself is left as
+    /// captured.
+    #[must_use]
+    pub fn brand_normalized(&self) -> Option<Cow<'_, str>> {
+        self.brand
+            .as_deref()
+            .map(|brand| crate::brand::DEFAULT.normalize(brand))
+    }
+}
+
 impl<'a> Default for Device<'a> {
     fn default() -> Self {
         Self {
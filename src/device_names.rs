@@ -0,0 +1,250 @@
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+use std::{collections::HashMap, convert::TryFrom, io::Read};
+
+use derive_more::{Display, From};
+
+#[derive(Debug, Display, From)]
+pub enum Error {
+    IO(std::io::Error),
+    /// A required CSV column was not found in the header row
+    #[from(ignore)]
+    MissingColumn(&'static str),
+    /// More distinct brands or names than fit in a `u32` id
+    #[display(fmt = "too many distinct strings")]
+    TooManyStrings,
+}
+
+const BRAND_COLUMN: &str = "Retail Branding";
+const NAME_COLUMN: &str = "Marketing Name";
+const MODEL_COLUMN: &str = "Model";
+
+/// Maps device model codes such as `SM-G991B` to retail names such as
+/// `Galaxy S21 5G`, using a dataset in the format of the Google Play
+/// supported devices CSV. Brands and names are interned, so a catalogue with
+/// tens of thousands of rows stores each distinct string once.
+#[derive(Debug, Default)]
+pub struct DeviceNameResolver {
+    brands: Vec<Box<str>>,
+    names: Vec<Box<str>>,
+    models: HashMap<Box<str>, Vec<(u32, u32)>>,
+}
+
+impl DeviceNameResolver {
+    /// Loads a CSV with `Retail Branding`, `Marketing Name` and `Model`
+    /// columns, in any order alongside other columns. Rows without a
+    /// marketing name are skipped, and the first row wins when a brand and
+    /// model appear more than once.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<DeviceNameResolver, Error> {
+        let mut data = String::new();
+        reader.read_to_string(&mut data)?;
+        let mut records = Records::new(data.trim_start_matches('\u{feff}'));
+
+        let header = records.next().unwrap_or_default();
+        let column = |name: &'static str| {
+            header
+                .iter()
+                .position(|field| field.trim() == name)
+                .ok_or(Error::MissingColumn(name))
+        };
+        let (brand_column, name_column, model_column) = (
+            column(BRAND_COLUMN)?,
+            column(NAME_COLUMN)?,
+            column(MODEL_COLUMN)?,
+        );
+
+        let mut resolver = DeviceNameResolver::default();
+        let mut brand_ids = HashMap::new();
+        let mut name_ids = HashMap::new();
+
+        for record in records {
+            let field = |index: usize| record.get(index).map_or("", |field| field.trim());
+            let (brand, name, model) =
+                (field(brand_column), field(name_column), field(model_column));
+            if name.is_empty() || model.is_empty() {
+                continue;
+            }
+
+            let brand = intern(&mut resolver.brands, &mut brand_ids, brand)?;
+            let name = intern(&mut resolver.names, &mut name_ids, name)?;
+            let brands = &resolver.brands;
+            let entries = resolver.models.entry(model.into()).or_default();
+            if !entries.iter().any(|&(known, _)| {
+                brands[known as usize].eq_ignore_ascii_case(&brands[brand as usize])
+            }) {
+                entries.push((brand, name));
+            }
+        }
+
+        resolver.models.shrink_to_fit();
+        Ok(resolver)
+    }
+
+    /// Returns the retail name for a model code, matching the brand without
+    /// regard to ASCII case
+    #[must_use]
+    pub fn resolve(&self, brand: &str, model: &str) -> Option<&str> {
+        self.models
+            .get(model)?
+            .iter()
+            .find(|&&(known, _)| self.brands[known as usize].eq_ignore_ascii_case(brand))
+            .map(|&(_, name)| &*self.names[name as usize])
+    }
+
+    /// The number of distinct brand and model pairs loaded
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.models.values().map(Vec::len).sum()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.models.is_empty()
+    }
+}
+
+fn intern(
+    strings: &mut Vec<Box<str>>,
+    ids: &mut HashMap<Box<str>, u32>,
+    value: &str,
+) -> Result<u32, Error> {
+    if let Some(&id) = ids.get(value) {
+        return Ok(id);
+    }
+    let id = u32::try_from(strings.len()).map_err(|_| Error::TooManyStrings)?;
+    strings.push(value.into());
+    ids.insert(value.into(), id);
+    Ok(id)
+}
+
+/// Splits CSV text into records, honouring quoted fields with `""` escapes
+/// and line breaks inside quotes
+struct Records<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Records<'a> {
+    fn new(data: &'a str) -> Self {
+        Records { rest: data }
+    }
+}
+
+impl Iterator for Records<'_> {
+    type Item = Vec<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chars = self.rest.char_indices().peekable();
+        chars.peek()?;
+
+        let mut record = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '"' if quoted => {
+                    if chars.peek().map(|&(_, next)| next) == Some('"') {
+                        field.push('"');
+                        chars.next();
+                    } else {
+                        quoted = false;
+                    }
+                }
+                '"' if field.is_empty() => quoted = true,
+                ',' if !quoted => record.push(std::mem::take(&mut field)),
+                '\n' if !quoted => {
+                    self.rest = &self.rest[index + 1..];
+                    record.push(field);
+                    return Some(record);
+                }
+                '\r' if !quoted => {}
+                c => field.push(c),
+            }
+        }
+
+        self.rest = "";
+        record.push(field);
+        Some(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Client, Device, UserAgent, OS};
+    use std::borrow::Cow;
+
+    const CATALOGUE: &[u8] = b"\xef\xbb\xbfRetail Branding,Marketing Name,Device,Model\r
+Samsung,Galaxy S21 5G,o1s,SM-G991B\r
+Samsung,Galaxy S21 5G,o1s,SM-G991U\r
+Xiaomi,\"Redmi Note 12, 5G\",sunstone,22111317G\r
+Google,Pixel 7a,lynx,Pixel 7a\r
+Unnamed,,x,X-1\r
+";
+
+    fn resolver() -> DeviceNameResolver {
+        DeviceNameResolver::from_reader(CATALOGUE).expect("Resolver creation failed")
+    }
+
+    #[test]
+    fn resolves_model_codes() {
+        let resolver = resolver();
+
+        assert_eq!(
+            resolver.resolve("Samsung", "SM-G991B"),
+            Some("Galaxy S21 5G")
+        );
+        assert_eq!(
+            resolver.resolve("samsung", "SM-G991U"),
+            Some("Galaxy S21 5G")
+        );
+        assert_eq!(
+            resolver.resolve("Xiaomi", "22111317G"),
+            Some("Redmi Note 12, 5G")
+        );
+        assert_eq!(resolver.len(), 4);
+        assert_eq!(resolver.names.len(), 3);
+    }
+
+    #[test]
+    fn misses() {
+        let resolver = resolver();
+
+        assert_eq!(resolver.resolve("Samsung", "SM-XXXX"), None);
+        assert_eq!(resolver.resolve("Google", "SM-G991B"), None);
+        assert_eq!(resolver.resolve("Unnamed", "X-1"), None);
+    }
+
+    #[test]
+    fn requires_columns() {
+        let error =
+            DeviceNameResolver::from_reader(&b"Brand,Model\nSamsung,SM-G991B\n"[..])
+                .unwrap_err();
+
+        assert!(matches!(error, Error::MissingColumn(BRAND_COLUMN)));
+    }
+
+    #[test]
+    fn resolves_client() {
+        let resolver = resolver();
+        let client = Client {
+            device: Device {
+                family: Cow::Borrowed("Samsung SM-G991B"),
+                brand: Some(Cow::Borrowed("Samsung")),
+                model: Some(Cow::Borrowed("SM-G991B")),
+            },
+            os: OS::default(),
+            user_agent: UserAgent::default(),
+        };
+
+        assert_eq!(client.resolve_device_name(&resolver), Some("Galaxy S21 5G"));
+        assert_eq!(
+            Client {
+                device: Device::default(),
+                ..client
+            }
+            .resolve_device_name(&resolver),
+            None
+        );
+    }
+}
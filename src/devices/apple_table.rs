@@ -0,0 +1,787 @@
+// @generated by scripts/generate_apple_devices.py from data/apple_devices.tsv.
+// Do not edit by hand.
+
+use super::{AppleDeviceClass, AppleModel};
+
+pub(super) static APPLE_MODELS: [AppleModel; 156] = [
+    AppleModel {
+        identifier: "iPad1,1",
+        name: "iPad",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad11,1",
+        name: "iPad mini (5th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad11,2",
+        name: "iPad mini (5th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad11,3",
+        name: "iPad Air (3rd generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad11,4",
+        name: "iPad Air (3rd generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad11,6",
+        name: "iPad (8th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad11,7",
+        name: "iPad (8th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad12,1",
+        name: "iPad (9th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad12,2",
+        name: "iPad (9th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad13,1",
+        name: "iPad Air (4th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad13,10",
+        name: "iPad Pro (12.9-inch) (5th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad13,11",
+        name: "iPad Pro (12.9-inch) (5th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad13,16",
+        name: "iPad Air (5th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad13,17",
+        name: "iPad Air (5th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad13,18",
+        name: "iPad (10th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad13,19",
+        name: "iPad (10th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad13,2",
+        name: "iPad Air (4th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad13,4",
+        name: "iPad Pro (11-inch) (3rd generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad13,5",
+        name: "iPad Pro (11-inch) (3rd generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad13,6",
+        name: "iPad Pro (11-inch) (3rd generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad13,7",
+        name: "iPad Pro (11-inch) (3rd generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad13,8",
+        name: "iPad Pro (12.9-inch) (5th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad13,9",
+        name: "iPad Pro (12.9-inch) (5th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad14,1",
+        name: "iPad mini (6th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad14,10",
+        name: "iPad Air 13-inch (M2)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad14,11",
+        name: "iPad Air 13-inch (M2)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad14,2",
+        name: "iPad mini (6th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad14,3",
+        name: "iPad Pro (11-inch) (4th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad14,4",
+        name: "iPad Pro (11-inch) (4th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad14,5",
+        name: "iPad Pro (12.9-inch) (6th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad14,6",
+        name: "iPad Pro (12.9-inch) (6th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad14,8",
+        name: "iPad Air 11-inch (M2)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad14,9",
+        name: "iPad Air 11-inch (M2)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad16,1",
+        name: "iPad mini (A17 Pro)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad16,2",
+        name: "iPad mini (A17 Pro)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad16,3",
+        name: "iPad Pro 11-inch (M4)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad16,4",
+        name: "iPad Pro 11-inch (M4)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad16,5",
+        name: "iPad Pro 13-inch (M4)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad16,6",
+        name: "iPad Pro 13-inch (M4)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad2,1",
+        name: "iPad 2",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad2,2",
+        name: "iPad 2",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad2,3",
+        name: "iPad 2",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad2,4",
+        name: "iPad 2",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad2,5",
+        name: "iPad mini",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad2,6",
+        name: "iPad mini",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad2,7",
+        name: "iPad mini",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad3,1",
+        name: "iPad (3rd generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad3,2",
+        name: "iPad (3rd generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad3,3",
+        name: "iPad (3rd generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad3,4",
+        name: "iPad (4th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad3,5",
+        name: "iPad (4th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad3,6",
+        name: "iPad (4th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad4,1",
+        name: "iPad Air",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad4,2",
+        name: "iPad Air",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad4,3",
+        name: "iPad Air",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad4,4",
+        name: "iPad mini 2",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad4,5",
+        name: "iPad mini 2",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad4,6",
+        name: "iPad mini 2",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad4,7",
+        name: "iPad mini 3",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad4,8",
+        name: "iPad mini 3",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad4,9",
+        name: "iPad mini 3",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad5,1",
+        name: "iPad mini 4",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad5,2",
+        name: "iPad mini 4",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad5,3",
+        name: "iPad Air 2",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad5,4",
+        name: "iPad Air 2",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad6,11",
+        name: "iPad (5th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad6,12",
+        name: "iPad (5th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad6,3",
+        name: "iPad Pro (9.7-inch)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad6,4",
+        name: "iPad Pro (9.7-inch)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad6,7",
+        name: "iPad Pro (12.9-inch)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad6,8",
+        name: "iPad Pro (12.9-inch)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad7,1",
+        name: "iPad Pro (12.9-inch) (2nd generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad7,11",
+        name: "iPad (7th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad7,12",
+        name: "iPad (7th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad7,2",
+        name: "iPad Pro (12.9-inch) (2nd generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad7,3",
+        name: "iPad Pro (10.5-inch)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad7,4",
+        name: "iPad Pro (10.5-inch)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad7,5",
+        name: "iPad (6th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad7,6",
+        name: "iPad (6th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad8,1",
+        name: "iPad Pro (11-inch)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad8,10",
+        name: "iPad Pro (11-inch) (2nd generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad8,11",
+        name: "iPad Pro (12.9-inch) (4th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad8,12",
+        name: "iPad Pro (12.9-inch) (4th generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad8,2",
+        name: "iPad Pro (11-inch)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad8,3",
+        name: "iPad Pro (11-inch)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad8,4",
+        name: "iPad Pro (11-inch)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad8,5",
+        name: "iPad Pro (12.9-inch) (3rd generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad8,6",
+        name: "iPad Pro (12.9-inch) (3rd generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad8,7",
+        name: "iPad Pro (12.9-inch) (3rd generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad8,8",
+        name: "iPad Pro (12.9-inch) (3rd generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPad8,9",
+        name: "iPad Pro (11-inch) (2nd generation)",
+        class: AppleDeviceClass::Tablet,
+    },
+    AppleModel {
+        identifier: "iPhone1,1",
+        name: "iPhone",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone1,2",
+        name: "iPhone 3G",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone10,1",
+        name: "iPhone 8",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone10,2",
+        name: "iPhone 8 Plus",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone10,3",
+        name: "iPhone X",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone10,4",
+        name: "iPhone 8",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone10,5",
+        name: "iPhone 8 Plus",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone10,6",
+        name: "iPhone X",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone11,2",
+        name: "iPhone XS",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone11,4",
+        name: "iPhone XS Max",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone11,6",
+        name: "iPhone XS Max",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone11,8",
+        name: "iPhone XR",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone12,1",
+        name: "iPhone 11",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone12,3",
+        name: "iPhone 11 Pro",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone12,5",
+        name: "iPhone 11 Pro Max",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone12,8",
+        name: "iPhone SE (2nd generation)",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone13,1",
+        name: "iPhone 12 mini",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone13,2",
+        name: "iPhone 12",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone13,3",
+        name: "iPhone 12 Pro",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone13,4",
+        name: "iPhone 12 Pro Max",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone14,2",
+        name: "iPhone 13 Pro",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone14,3",
+        name: "iPhone 13 Pro Max",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone14,4",
+        name: "iPhone 13 mini",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone14,5",
+        name: "iPhone 13",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone14,6",
+        name: "iPhone SE (3rd generation)",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone14,7",
+        name: "iPhone 14",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone14,8",
+        name: "iPhone 14 Plus",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone15,2",
+        name: "iPhone 14 Pro",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone15,3",
+        name: "iPhone 14 Pro Max",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone15,4",
+        name: "iPhone 15",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone15,5",
+        name: "iPhone 15 Plus",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone16,1",
+        name: "iPhone 15 Pro",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone16,2",
+        name: "iPhone 15 Pro Max",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone17,1",
+        name: "iPhone 16 Pro",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone17,2",
+        name: "iPhone 16 Pro Max",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone17,3",
+        name: "iPhone 16",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone17,4",
+        name: "iPhone 16 Plus",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone17,5",
+        name: "iPhone 16e",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone2,1",
+        name: "iPhone 3GS",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone3,1",
+        name: "iPhone 4",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone3,2",
+        name: "iPhone 4",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone3,3",
+        name: "iPhone 4",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone4,1",
+        name: "iPhone 4S",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone5,1",
+        name: "iPhone 5",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone5,2",
+        name: "iPhone 5",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone5,3",
+        name: "iPhone 5c",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone5,4",
+        name: "iPhone 5c",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone6,1",
+        name: "iPhone 5s",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone6,2",
+        name: "iPhone 5s",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone7,1",
+        name: "iPhone 6 Plus",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone7,2",
+        name: "iPhone 6",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone8,1",
+        name: "iPhone 6s",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone8,2",
+        name: "iPhone 6s Plus",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone8,4",
+        name: "iPhone SE",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone9,1",
+        name: "iPhone 7",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone9,2",
+        name: "iPhone 7 Plus",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone9,3",
+        name: "iPhone 7",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPhone9,4",
+        name: "iPhone 7 Plus",
+        class: AppleDeviceClass::Phone,
+    },
+    AppleModel {
+        identifier: "iPod1,1",
+        name: "iPod touch",
+        class: AppleDeviceClass::MediaPlayer,
+    },
+    AppleModel {
+        identifier: "iPod2,1",
+        name: "iPod touch (2nd generation)",
+        class: AppleDeviceClass::MediaPlayer,
+    },
+    AppleModel {
+        identifier: "iPod3,1",
+        name: "iPod touch (3rd generation)",
+        class: AppleDeviceClass::MediaPlayer,
+    },
+    AppleModel {
+        identifier: "iPod4,1",
+        name: "iPod touch (4th generation)",
+        class: AppleDeviceClass::MediaPlayer,
+    },
+    AppleModel {
+        identifier: "iPod5,1",
+        name: "iPod touch (5th generation)",
+        class: AppleDeviceClass::MediaPlayer,
+    },
+    AppleModel {
+        identifier: "iPod7,1",
+        name: "iPod touch (6th generation)",
+        class: AppleDeviceClass::MediaPlayer,
+    },
+    AppleModel {
+        identifier: "iPod9,1",
+        name: "iPod touch (7th generation)",
+        class: AppleDeviceClass::MediaPlayer,
+    },
+];
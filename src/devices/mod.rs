@@ -0,0 +1,96 @@
+//! Lookup tables for hardware identifiers that show up in user agents but
+//! carry no human readable name.
+//!
+//! The Apple table is generated from `data/apple_devices.tsv` by
+//! `scripts/generate_apple_devices.py`; add new models to the data file and
+//! rerun the script rather than editing the table by hand.
+
+mod apple_table;
+
+use apple_table::APPLE_MODELS;
+
+/// The broad kind of hardware an Apple model identifier belongs to
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AppleDeviceClass {
+    Phone,
+    Tablet,
+    MediaPlayer,
+}
+
+/// The marketing name and class of an Apple hardware identifier such as
+/// `iPhone14,2`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct AppleModel {
+    pub identifier: &'static str,
+    pub name: &'static str,
+    pub class: AppleDeviceClass,
+}
+
+/// Maps an Apple hardware identifier to its marketing name, returning `None`
+/// for identifiers missing from the table.
+///
+/// ```rust
+/// # use uaparser::devices::{apple_model, AppleDeviceClass};
+/// let model = apple_model("iPhone14,2").unwrap();
+///
+/// assert_eq!(model.name, "iPhone 13 Pro");
+/// assert_eq!(model.class, AppleDeviceClass::Phone);
+/// ```
+#[must_use]
+pub fn apple_model(identifier: &str) -> Option<AppleModel> {
+    APPLE_MODELS
+        .binary_search_by(|model| model.identifier.cmp(identifier))
+        .ok()
+        .map(|index| APPLE_MODELS[index])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extras::extract_apple_identifier;
+
+    #[test]
+    fn table_is_sorted() {
+        assert!(APPLE_MODELS
+            .windows(2)
+            .all(|pair| pair[0].identifier < pair[1].identifier));
+    }
+
+    #[test]
+    fn resolves_cfnetwork_identifier() {
+        let identifier = extract_apple_identifier(
+            "MyApp/7.2.0 (iPhone14,2; iOS 17.1.1; Scale/3.00) CFNetwork/1474 \
+             Darwin/23.1.0",
+        )
+        .unwrap();
+
+        assert_eq!(identifier, "iPhone14,2");
+        assert_eq!(
+            apple_model(identifier),
+            Some(AppleModel {
+                identifier: "iPhone14,2",
+                name: "iPhone 13 Pro",
+                class: AppleDeviceClass::Phone,
+            })
+        );
+    }
+
+    #[test]
+    fn resolves_tablets_and_media_players() {
+        assert_eq!(
+            apple_model("iPad13,4").map(|model| model.name),
+            Some("iPad Pro (11-inch) (3rd generation)")
+        );
+        assert_eq!(
+            apple_model("iPod9,1").map(|model| model.class),
+            Some(AppleDeviceClass::MediaPlayer)
+        );
+    }
+
+    #[test]
+    fn unknown_identifiers() {
+        assert_eq!(apple_model("iPhone99,9"), None);
+        assert_eq!(apple_model("iphone14,2"), None);
+        assert_eq!(apple_model(""), None);
+    }
+}
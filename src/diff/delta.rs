@@ -0,0 +1,226 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use crate::file::{DeviceParserEntry, OSParserEntry, RegexFile, UserAgentParserEntry};
+
+/// The changes between two versions of a rule file, for
+/// `UserAgentParser::apply_delta` to update a parser built from the old one
+/// without recompiling the rules they share. Entries are told apart by
+/// content, not position, so moving a rule counts as neither adding nor
+/// removing it.
+///
+/// ```rust
+/// # use uaparser::*;
+/// # use uaparser::diff::RuleSetDelta;
+/// let old: RegexFile = serde_yaml::from_str("
+/// user_agent_parsers:
+///   - regex: '(curl)/(\\d+)'
+///   - regex: '(Wget)/(\\d+)'
+/// ").unwrap();
+/// let mut new = old.clone();
+/// new.user_agent_parsers[1].family_replacement = Some("GNU Wget".to_owned());
+///
+/// let delta = RuleSetDelta::compute(&old, &new);
+/// assert_eq!(delta.user_agent.modified, [(1, 1)]);
+///
+/// let mut parser = UserAgentParser::try_from(old).unwrap();
+/// let report = parser.apply_delta(&delta).unwrap();
+/// assert_eq!((report.compiled.user_agent, report.reused.user_agent), (1, 1));
+/// assert_eq!(parser.parse_user_agent("Wget/1.21").family, "GNU Wget");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuleSetDelta {
+    pub user_agent: CategoryDelta<UserAgentParserEntry>,
+    pub os: CategoryDelta<OSParserEntry>,
+    pub device: CategoryDelta<DeviceParserEntry>,
+}
+
+/// The changes to one category's entries, by index into the old and new
+/// entries
+#[derive(Clone, Debug, PartialEq)]
+pub struct CategoryDelta<E> {
+    /// New entries whose regex no old entry has
+    pub added: Vec<usize>,
+    /// Old entries no new entry takes the place of
+    pub removed: Vec<usize>,
+    /// `(old, new)` pairs of entries with the same regex whose other keys,
+    /// such as a replacement or the priority, changed
+    pub modified: Vec<(usize, usize)>,
+    /// How to obtain each new entry's matcher, in the order of the new file
+    pub(crate) steps: Vec<Step<E>>,
+    /// The pattern and priority of each old entry, to find its matcher in a
+    /// parser and check the parser was built from them
+    pub(crate) old: Vec<(String, i32)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Step<E> {
+    /// The matcher compiled from this old entry
+    Reuse(usize),
+    Compile(E),
+}
+
+/// The keys of a rule file entry that place its matcher
+pub(crate) trait Entry: Clone + PartialEq {
+    fn regex(&self) -> &str;
+    fn priority(&self) -> Option<i32>;
+
+    /// The pattern its matcher compiles, before escapes are cleaned up
+    fn pattern(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.regex())
+    }
+}
+
+macro_rules! impl_entry {
+    ($($entry:ty),*) => {$(
+        impl Entry for $entry {
+            fn regex(&self) -> &str {
+                &self.regex
+            }
+
+            fn priority(&self) -> Option<i32> {
+                self.priority
+            }
+        }
+    )*};
+}
+
+impl_entry!(UserAgentParserEntry, OSParserEntry);
+
+impl Entry for DeviceParserEntry {
+    fn regex(&self) -> &str {
+        &self.regex
+    }
+
+    fn priority(&self) -> Option<i32> {
+        self.priority
+    }
+
+    fn pattern(&self) -> Cow<'_, str> {
+        self.flagged_regex()
+    }
+}
+
+impl RuleSetDelta {
+    #[must_use]
+    pub fn compute(old: &RegexFile, new: &RegexFile) -> RuleSetDelta {
+        RuleSetDelta {
+            user_agent: compute(&old.user_agent_parsers, &new.user_agent_parsers),
+            os: compute(&old.os_parsers, &new.os_parsers),
+            device: compute(&old.device_parsers, &new.device_parsers),
+        }
+    }
+
+    /// Whether the new rules are the old ones, in the same order
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.user_agent.is_empty() && self.os.is_empty() && self.device.is_empty()
+    }
+}
+
+/// Matches each new entry to an old one with the same content, or failing
+/// that, the same regex
+fn compute<E: Entry>(old: &[E], new: &[E]) -> CategoryDelta<E> {
+    let mut by_regex: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, entry) in old.iter().enumerate() {
+        by_regex.entry(entry.regex()).or_default().push(index);
+    }
+    let mut used = vec![false; old.len()];
+    let mut take = |entry: &E, exact: bool| {
+        let index = by_regex
+            .get(entry.regex())?
+            .iter()
+            .copied()
+            .find(|&index| !used[index] && (!exact || old[index] == *entry))?;
+        used[index] = true;
+        Some(index)
+    };
+
+    // Unchanged entries first, so that of two old entries with the same
+    // regex, a modified one doesn't claim the unchanged one's matcher
+    let reused: Vec<_> = new.iter().map(|entry| take(entry, true)).collect();
+    let mut delta = CategoryDelta {
+        added: Vec::new(),
+        removed: Vec::new(),
+        modified: Vec::new(),
+        steps: Vec::with_capacity(new.len()),
+        old: old
+            .iter()
+            .map(|entry| {
+                (
+                    entry.pattern().into_owned(),
+                    entry.priority().unwrap_or_default(),
+                )
+            })
+            .collect(),
+    };
+    for (index, (entry, reused)) in new.iter().zip(reused).enumerate() {
+        if let Some(old_index) = reused {
+            delta.steps.push(Step::Reuse(old_index));
+            continue;
+        }
+        match take(entry, false) {
+            Some(old_index) => delta.modified.push((old_index, index)),
+            None => delta.added.push(index),
+        }
+        delta.steps.push(Step::Compile(entry.clone()));
+    }
+    delta.removed = (0..old.len()).filter(|&index| !used[index]).collect();
+    delta
+}
+
+impl<E> CategoryDelta<E> {
+    /// Whether the new entries are the old ones, in the same order
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.steps.len() == self.old.len()
+            && self
+                .steps
+                .iter()
+                .enumerate()
+                .all(|(index, step)| matches!(step, Step::Reuse(old) if *old == index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(yaml: &str) -> RegexFile {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn tells_changes_apart_by_content() {
+        let old = file(
+            r"
+user_agent_parsers:
+  - regex: 'a'
+  - regex: 'b'
+  - regex: 'b'
+    family_replacement: 'B'
+  - regex: 'c'
+",
+        );
+        let new = file(
+            r"
+user_agent_parsers:
+  - regex: 'c'
+  - regex: 'b'
+    family_replacement: 'B'
+  - regex: 'b'
+    priority: 1
+  - regex: 'd'
+",
+        );
+        let delta = RuleSetDelta::compute(&old, &new).user_agent;
+
+        assert_eq!(delta.added, [3]);
+        assert_eq!(delta.removed, [0]);
+        assert_eq!(delta.modified, [(1, 2)]);
+        assert_eq!(delta.steps[0], Step::Reuse(3));
+        assert_eq!(delta.steps[1], Step::Reuse(2));
+        assert!(!delta.is_empty());
+
+        assert!(RuleSetDelta::compute(&old, &old).is_empty());
+    }
+}
@@ -0,0 +1,320 @@
+//! Compares the output of two parsers over the same user agents, such as the
+//! current rule file and a candidate update, to show what would change.
+//!
+//! ```rust
+//! # use uaparser::*;
+//! let old = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+//! let new = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+//! let report = uaparser::diff::compare(&old, &new, ["curl/8.4.0"].iter());
+//!
+//! assert_eq!(report.compared, 1);
+//! assert!(report.transitions.is_empty());
+//! ```
+
+mod delta;
+
+use std::{collections::HashMap, fmt};
+
+use derive_more::Display;
+
+use super::{Deserialize, Serialize};
+use crate::{client::FLAT_KEYS, Parser, UserAgentParser};
+
+pub(crate) use delta::Step;
+pub use delta::{CategoryDelta, RuleSetDelta};
+
+/// Controls which differences are recorded by `compare_with_options`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DiffOptions {
+    /// Flattened field names whose changes are ignored, all taken from
+    /// `FLAT_KEYS` by `ignore_field`
+    ignored_fields: Vec<&'static str>,
+    /// The number of example user agents kept for each transition
+    pub max_examples: usize,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            ignored_fields: Vec::new(),
+            max_examples: 3,
+        }
+    }
+}
+
+impl DiffOptions {
+    /// Ignores changes to a flattened field, such as `ua_patch` or
+    /// `os_patch_minor`, rejecting names that no client field flattens to
+    pub fn ignore_field(mut self, field: &str) -> Result<Self, UnknownField> {
+        let key = FLAT_KEYS
+            .iter()
+            .find(|key| **key == field)
+            .ok_or_else(|| UnknownField(field.to_owned()))?;
+        if !self.ignored_fields.contains(key) {
+            self.ignored_fields.push(key);
+        }
+        Ok(self)
+    }
+
+    /// The flattened field names whose changes are ignored
+    #[must_use]
+    pub fn ignored_fields(&self) -> &[&'static str] {
+        &self.ignored_fields
+    }
+}
+
+/// Why `DiffOptions::ignore_field` rejected a name
+#[derive(Clone, Debug, Display, Eq, PartialEq)]
+#[display(fmt = "unknown field `{_0}`, expected one of {FLAT_KEYS:?}")]
+pub struct UnknownField(pub String);
+
+impl std::error::Error for UnknownField {}
+
+/// Every field level difference found by `compare`, grouped into
+/// transitions
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct DiffReport {
+    /// The number of user agents parsed by both parsers
+    pub compared: u64,
+    /// The number of user agents with at least one recorded difference
+    pub changed: u64,
+    /// Ordered by descending count, then by field
+    pub transitions: Vec<Transition>,
+}
+
+/// One field changing from one value to another, with the user agents it
+/// happened to
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, Hash, PartialEq)]
+pub struct Transition {
+    /// The flattened field name, such as `device_family`
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+    pub count: u64,
+    /// The first user agents seen with this transition
+    pub examples: Vec<String>,
+}
+
+/// Parses each user agent with both parsers and records every field that
+/// differs, under the default `DiffOptions`
+pub fn compare<I>(old: &UserAgentParser, new: &UserAgentParser, corpus: I) -> DiffReport
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    compare_with_options(old, new, corpus, &DiffOptions::default())
+}
+
+/// Like `compare`, with control over ignored fields and examples
+pub fn compare_with_options<I>(
+    old: &UserAgentParser,
+    new: &UserAgentParser,
+    corpus: I,
+    options: &DiffOptions,
+) -> DiffReport
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    let compared_fields: Vec<bool> = FLAT_KEYS
+        .iter()
+        .map(|key| !options.ignored_fields.contains(key))
+        .collect();
+    let mut report = DiffReport::default();
+    let mut transitions: HashMap<(usize, Option<String>, Option<String>), Transition> =
+        HashMap::new();
+
+    for user_agent in corpus {
+        let user_agent = user_agent.as_ref();
+        let old_client = old.parse(user_agent);
+        let new_client = new.parse(user_agent);
+        report.compared += 1;
+
+        let mut changed = false;
+        let old_fields = old_client.flatten();
+        let new_fields = new_client.flatten();
+        for (index, (old_value, new_value)) in
+            old_fields.iter().zip(&new_fields).enumerate()
+        {
+            if !compared_fields[index] || old_value == new_value {
+                continue;
+            }
+            changed = true;
+
+            let old_value = old_value.map(str::to_owned);
+            let new_value = new_value.map(str::to_owned);
+            let transition = transitions
+                .entry((index, old_value.clone(), new_value.clone()))
+                .or_insert_with(|| Transition {
+                    field: FLAT_KEYS[index].to_owned(),
+                    old: old_value,
+                    new: new_value,
+                    count: 0,
+                    examples: Vec::new(),
+                });
+            transition.count += 1;
+            if transition.examples.len() < options.max_examples {
+                transition.examples.push(user_agent.to_owned());
+            }
+        }
+
+        if changed {
+            report.changed += 1;
+        }
+    }
+
+    let mut transitions: Vec<_> = transitions.into_iter().collect();
+    transitions.sort_by(|(a_key, a), (b_key, b)| {
+        b.count.cmp(&a.count).then_with(|| a_key.cmp(b_key))
+    });
+    report.transitions = transitions
+        .into_iter()
+        .map(|(_, transition)| transition)
+        .collect();
+    report
+}
+
+impl fmt::Display for DiffReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} user agents changed",
+            self.changed, self.compared
+        )?;
+        for transition in &self.transitions {
+            write!(f, "\n{transition}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Transition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} -> {} ({})",
+            self.field,
+            Value(self.old.as_deref()),
+            Value(self.new.as_deref()),
+            self.count
+        )?;
+        for example in &self.examples {
+            write!(f, "\n    {example}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Displays a field value quoted, or `none` when absent
+struct Value<'a>(Option<&'a str>);
+
+impl fmt::Display for Value<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(value) => write!(f, "{value:?}"),
+            None => f.write_str("none"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CORPUS: [&str; 4] = [
+        "Bar/1.2.3 (FooPhone)",
+        "Baz/2.0 (FooPhone)",
+        "Bar/1.1 (Other)",
+        "Qux/3.0 (FooPhone)",
+    ];
+
+    fn parser(device_replacement: &str, user_agent_regex: &str) -> UserAgentParser {
+        let yaml = format!(
+            "
+user_agent_parsers:
+  - regex: '{user_agent_regex}'
+os_parsers: []
+device_parsers:
+  - regex: '(FooPhone)'
+    device_replacement: '{device_replacement}'
+"
+        );
+        UserAgentParser::from_bytes(yaml.as_bytes()).expect("Parser creation failed")
+    }
+
+    #[test]
+    fn reports_changed_device_rule() {
+        let old = parser("FooPhone", r"(Bar)/(\d+)\.(\d+)");
+        let new = parser("Foo Phone", r"(Bar)/(\d+)\.(\d+)");
+        let options = DiffOptions {
+            max_examples: 2,
+            ..DiffOptions::default()
+        };
+
+        let report = compare_with_options(&old, &new, CORPUS.iter(), &options);
+
+        assert_eq!(
+            report,
+            DiffReport {
+                compared: 4,
+                changed: 3,
+                transitions: vec![Transition {
+                    field: "device_family".to_owned(),
+                    old: Some("FooPhone".to_owned()),
+                    new: Some("Foo Phone".to_owned()),
+                    count: 3,
+                    examples: vec![CORPUS[0].to_owned(), CORPUS[1].to_owned()],
+                }],
+            }
+        );
+        assert_eq!(
+            report.to_string(),
+            "3 of 4 user agents changed\n\
+             device_family: \"FooPhone\" -> \"Foo Phone\" (3)\n    \
+             Bar/1.2.3 (FooPhone)\n    \
+             Baz/2.0 (FooPhone)"
+        );
+
+        let round_trip: DiffReport =
+            serde_yaml::from_str(&serde_yaml::to_string(&report).unwrap()).unwrap();
+        assert_eq!(round_trip, report);
+    }
+
+    #[test]
+    fn ignores_fields() {
+        let old = parser("FooPhone", r"(Bar)/(\d+)\.(\d+)");
+        let new = parser("FooPhone", r"(Bar)/(\d+)\.(\d+)\.?(\d+)?");
+
+        let report = compare(&old, &new, CORPUS.iter());
+        assert_eq!(report.changed, 1);
+        assert_eq!(report.transitions.len(), 1);
+        assert_eq!(
+            report.transitions[0].to_string(),
+            "ua_patch: none -> \"3\" (1)\n    Bar/1.2.3 (FooPhone)"
+        );
+
+        let options = DiffOptions::default().ignore_field("ua_patch").unwrap();
+        assert_eq!(options.ignored_fields(), ["ua_patch"]);
+        let report = compare_with_options(&old, &new, CORPUS.iter(), &options);
+        assert_eq!(report.compared, 4);
+        assert_eq!(report.changed, 0);
+        assert!(report.transitions.is_empty());
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        let error = DiffOptions::default()
+            .ignore_field("ua_patch_major")
+            .unwrap_err();
+        assert_eq!(error, UnknownField("ua_patch_major".to_owned()));
+        assert!(error.to_string().starts_with(
+            "unknown field `ua_patch_major`, expected one of [\"ua_family\""
+        ));
+
+        let options = DiffOptions::default()
+            .ignore_field("os_patch")
+            .and_then(|options| options.ignore_field("os_patch"))
+            .unwrap();
+        assert_eq!(options.ignored_fields(), ["os_patch"]);
+    }
+}
@@ -0,0 +1,290 @@
+//! Splices parsed user agent fields into JSON events, one value at a time or
+//! as a stream of newline delimited JSON.
+//!
+//! ```rust
+//! # use uaparser::*;
+//! # use uaparser::enrich::{enrich_value, EnrichConfig};
+//! let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+//! let mut event = serde_json::json!({ "user_agent": "curl/8.4.0" });
+//!
+//! enrich_value(&parser, &mut event, &EnrichConfig::default());
+//!
+//! assert_eq!(event["ua_family"], "curl");
+//! assert_eq!(event["device_brand"], serde_json::Value::Null);
+//! ```
+
+use std::io::{BufRead, Write};
+
+use derive_more::{Display, From};
+use serde_json::Value;
+
+use crate::{client::FLAT_KEYS, extras::cpu_arch, Parser, UserAgentParser};
+
+#[derive(Debug, Display, From)]
+pub enum Error {
+    IO(std::io::Error),
+    Json(serde_json::Error),
+}
+
+/// Where parsed fields are written on an event
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OutputStyle {
+    /// Flat top level keys such as `ua_family` and `os_major`, each preceded
+    /// by the given prefix
+    Prefixed(String),
+    /// A single top level key holding the serialized `Client`, with
+    /// `user_agent`, `os` and `device` objects
+    Nested(String),
+}
+
+/// Configures which field is parsed and how the results are written
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EnrichConfig {
+    /// The field holding the user agent, as a dotted path into nested objects
+    /// such as `http.request.headers.user-agent`
+    pub source_field: String,
+    pub output: OutputStyle,
+    /// Removes the raw user agent once it has been parsed
+    pub drop_source: bool,
+    /// Also writes the `extras::cpu_arch` of the user agent, such as
+    /// `amd64`, as a `cpu_arch` key next to the others
+    pub cpu_arch: bool,
+}
+
+impl Default for EnrichConfig {
+    fn default() -> Self {
+        Self {
+            source_field: "user_agent".to_owned(),
+            output: OutputStyle::Prefixed(String::new()),
+            drop_source: false,
+            cpu_arch: false,
+        }
+    }
+}
+
+/// Parses the user agent found at `config.source_field` and adds the results
+/// to `value`. Returns `false`, leaving the event untouched, when `value` is
+/// not an object or the source field is missing or not a string.
+pub fn enrich_value(
+    parser: &UserAgentParser,
+    value: &mut Value,
+    config: &EnrichConfig,
+) -> bool {
+    let user_agent = match lookup(value, &config.source_field) {
+        Some(Value::String(user_agent)) => user_agent.clone(),
+        _ => return false,
+    };
+    let client = parser.parse(&user_agent);
+    let cpu_arch = config
+        .cpu_arch
+        .then(|| cpu_arch(&user_agent).map_or(Value::Null, |arch| arch.as_str().into()));
+
+    if config.drop_source {
+        remove(value, &config.source_field);
+    }
+
+    let Some(object) = value.as_object_mut() else {
+        return false;
+    };
+    match &config.output {
+        OutputStyle::Prefixed(prefix) => {
+            for (key, field) in FLAT_KEYS.iter().zip(client.flatten()) {
+                let field = field.map_or(Value::Null, Value::from);
+                object.insert(format!("{prefix}{key}"), field);
+            }
+            if let Some(cpu_arch) = cpu_arch {
+                object.insert(format!("{prefix}cpu_arch"), cpu_arch);
+            }
+        }
+        OutputStyle::Nested(key) => {
+            let mut nested = serde_json::to_value(&client).unwrap_or_default();
+            if let (Some(cpu_arch), Some(nested)) = (cpu_arch, nested.as_object_mut()) {
+                nested.insert("cpu_arch".to_owned(), cpu_arch);
+            }
+            object.insert(key.clone(), nested);
+        }
+    }
+
+    true
+}
+
+/// Enriches each line of newline delimited JSON from `reader`, writing the
+/// results to `writer` without holding more than one line in memory. Blank
+/// lines are skipped, and lines that aren't valid JSON or have no usable user
+/// agent are written back unchanged, so one bad event doesn't stop the
+/// stream. Returns the number of lines written.
+pub fn enrich_ndjson<R: BufRead, W: Write>(
+    parser: &UserAgentParser,
+    reader: R,
+    mut writer: W,
+    config: &EnrichConfig,
+) -> Result<u64, Error> {
+    let mut written = 0;
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str::<Value>(&line) {
+            Ok(mut value) => {
+                enrich_value(parser, &mut value, config);
+                serde_json::to_writer(&mut writer, &value)?;
+            }
+            Err(_) => writer.write_all(line.as_bytes())?,
+        }
+        writer.write_all(b"\n")?;
+        written += 1;
+    }
+
+    writer.flush()?;
+    Ok(written)
+}
+
+fn lookup<'v>(value: &'v Value, path: &str) -> Option<&'v Value> {
+    path.split('.').try_fold(value, |value, key| value.get(key))
+}
+
+fn remove(value: &mut Value, path: &str) {
+    let (parent, key) = match path.rsplit_once('.') {
+        Some((parent, key)) => (
+            parent
+                .split('.')
+                .try_fold(value, |value, key| value.get_mut(key)),
+            key,
+        ),
+        None => (Some(value), path),
+    };
+
+    if let Some(Value::Object(parent)) = parent {
+        parent.remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const EVENTS: &str = include_str!("../tests/fixtures/events.ndjson");
+
+    fn parser() -> UserAgentParser {
+        UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed")
+    }
+
+    fn headers_config() -> EnrichConfig {
+        EnrichConfig {
+            source_field: "http.request.headers.user-agent".to_owned(),
+            ..EnrichConfig::default()
+        }
+    }
+
+    #[test]
+    fn enriches_ndjson() {
+        let mut output = Vec::new();
+        let written =
+            enrich_ndjson(&parser(), EVENTS.as_bytes(), &mut output, &headers_config())
+                .expect("Enrichment failed");
+        let events: Vec<Value> = std::str::from_utf8(&output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(written, 3);
+        assert_eq!(events.len(), 3);
+
+        assert_eq!(events[0]["id"], 1);
+        assert_eq!(events[0]["ua_family"], "Chrome");
+        assert_eq!(events[0]["ua_major"], "120");
+        assert_eq!(events[0]["os_family"], "Windows");
+        assert_eq!(events[0]["device_brand"], Value::Null);
+        assert!(events[0]["http"]["request"]["headers"]["user-agent"].is_string());
+
+        assert_eq!(events[1]["ua_family"], "Other");
+        assert_eq!(events[1]["os_family"], "Other");
+        assert_eq!(events[1]["device_family"], "Other");
+        assert_eq!(events[1]["ua_major"], Value::Null);
+
+        assert_eq!(
+            events[2],
+            json!({"id": 3, "http": {"request": {"headers": {}}}})
+        );
+    }
+
+    #[test]
+    fn passes_malformed_lines_through() {
+        let input = "{\"user_agent\": \"curl/8.4.0\"}\n{\"user_agent\": \n[1, 2]\n";
+        let mut output = Vec::new();
+        let written = enrich_ndjson(
+            &parser(),
+            input.as_bytes(),
+            &mut output,
+            &EnrichConfig::default(),
+        )
+        .expect("Enrichment failed");
+        let lines: Vec<&str> = std::str::from_utf8(&output).unwrap().lines().collect();
+
+        assert_eq!(written, 3);
+        let first: Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first["ua_family"], "curl");
+        assert_eq!(lines[1..], ["{\"user_agent\": ", "[1,2]"]);
+    }
+
+    #[test]
+    fn nests_and_drops_source() {
+        let mut event = json!({"id": 7, "http": {"request": {"headers": {
+            "user-agent": "Mozilla/5.0 (X11; Ubuntu; Linux x86_64; rv:121.0) Gecko/20100101 Firefox/121.0"
+        }}}});
+        let config = EnrichConfig {
+            output: OutputStyle::Nested("client".to_owned()),
+            drop_source: true,
+            ..headers_config()
+        };
+
+        assert!(enrich_value(&parser(), &mut event, &config));
+        assert_eq!(event["http"]["request"]["headers"], json!({}));
+        assert_eq!(event["client"]["user_agent"]["family"], "Firefox");
+        assert_eq!(event["client"]["user_agent"]["major"], "121");
+        assert_eq!(event["client"]["os"]["family"], "Ubuntu");
+        assert_eq!(event["client"]["os"]["patch_minor"], Value::Null);
+        assert_eq!(event["client"]["device"]["family"], "Other");
+        assert!(event["client"].get("cpu_arch").is_none());
+
+        let mut event = json!({"user_agent": "Mozilla/5.0 (X11; Linux aarch64; rv:121.0) Gecko/20100101 Firefox/121.0"});
+        let config = EnrichConfig {
+            output: OutputStyle::Nested("client".to_owned()),
+            cpu_arch: true,
+            ..EnrichConfig::default()
+        };
+        assert!(enrich_value(&parser(), &mut event, &config));
+        assert_eq!(event["client"]["cpu_arch"], "arm64");
+    }
+
+    #[test]
+    fn prefixes_keys() {
+        let mut event = json!({"user_agent": "curl/8.4.0"});
+        let config = EnrichConfig {
+            output: OutputStyle::Prefixed("client.".to_owned()),
+            ..EnrichConfig::default()
+        };
+
+        assert!(enrich_value(&parser(), &mut event, &config));
+        assert_eq!(event["client.ua_family"], "curl");
+        assert!(event.get("client.cpu_arch").is_none());
+
+        let config = EnrichConfig {
+            cpu_arch: true,
+            ..config
+        };
+        assert!(enrich_value(&parser(), &mut event, &config));
+        assert_eq!(event["client.cpu_arch"], Value::Null);
+        assert!(!enrich_value(&parser(), &mut json!([1, 2]), &config));
+        assert!(!enrich_value(
+            &parser(),
+            &mut json!({"user_agent": 5}),
+            &config
+        ));
+    }
+}
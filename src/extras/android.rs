@@ -0,0 +1,192 @@
+use std::convert::TryFrom;
+
+use crate::tokens::{tokenize, UaToken};
+
+use super::locale::{parse_tag, Locale};
+
+/// Android release versions by API level, starting at level 1
+const API_LEVELS: &[&str] = &[
+    "1.0", "1.1", "1.5", "1.6", "2.0", "2.0.1", "2.1", "2.2", "2.3", "2.3.3", "3.0",
+    "3.1", "3.2", "4.0", "4.0.3", "4.1", "4.2", "4.3", "4.4", "4.4", "5.0", "5.1", "6.0",
+    "7.0", "7.1", "8.0", "8.1", "9", "10", "11", "12", "12", "13", "14", "15", "16",
+];
+
+/// Comment parts that take the place of a model without naming one
+const NOT_MODELS: &[&str] = &["U", "K", "wv", "Linux", "Mobile", "Tablet"];
+
+/// Android details found in the comment of a user agent, borrowed from it
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct AndroidDetails<'a> {
+    /// The declared Android version, or the one `api_level` maps to when
+    /// only that is given
+    pub version: Option<&'a str>,
+    /// The SDK level from an `API 34` style part
+    pub api_level: Option<u32>,
+    /// The part before the build tag, or else the first part after the
+    /// version that isn't a locale or filler like `U` and `wv`
+    pub model: Option<&'a str>,
+    /// The build ID following `Build/`
+    pub build: Option<&'a str>,
+}
+
+/// Extracts the Android version, API level, model and build tag from the
+/// comment of a user agent, as sent by native HTTP stacks like Dalvik and
+/// SDKs that only report an API level. Returns `None` when no comment
+/// mentions Android or an API level, as for a bare `okhttp/4.12.0`.
+///
+/// ```rust
+/// # use uaparser::extras::android_details;
+/// let details = android_details("MyApp/5.1.0 (Android; API 34; Pixel 8 Pro)").unwrap();
+///
+/// assert_eq!(details.version, Some("14"));
+/// assert_eq!(details.model, Some("Pixel 8 Pro"));
+/// ```
+#[must_use]
+pub fn android_details(user_agent: &str) -> Option<AndroidDetails<'_>> {
+    let tokens = tokenize(user_agent);
+    let parts = tokens.iter().find_map(|token| match token {
+        UaToken::Comment { parts } if parts.iter().any(|part| is_platform(part)) => {
+            Some(parts)
+        }
+        _ => None,
+    })?;
+
+    let mut details = AndroidDetails::default();
+    let mut after_platform = false;
+    for &part in parts {
+        if let Some(version) = android_version(part) {
+            if details.version.is_none() && !version.is_empty() {
+                details.version = Some(version);
+            }
+            after_platform = true;
+        } else if let Some(level) = api_level(part) {
+            details.api_level = details.api_level.or(Some(level));
+            after_platform = true;
+        } else if let Some(start) = part.find("Build/") {
+            details.build =
+                Some(&part[start + "Build/".len()..]).filter(|x| !x.is_empty());
+            let model = part[..start].trim();
+            if !model.is_empty() {
+                details.model = Some(model);
+            }
+        } else if after_platform && details.model.is_none() && is_model(part) {
+            details.model = Some(part);
+        }
+    }
+
+    if details.version.is_none() {
+        details.version = details
+            .api_level
+            .and_then(|level| {
+                API_LEVELS.get(usize::try_from(level).ok()?.checked_sub(1)?)
+            })
+            .copied();
+    }
+    Some(details)
+}
+
+fn is_platform(part: &str) -> bool {
+    android_version(part).is_some() || api_level(part).is_some()
+}
+
+/// Reads the version of `Android 13`, or an empty one of a bare `Android`,
+/// but not `Android SDK built for x86`, which names the emulator's model
+fn android_version(part: &str) -> Option<&str> {
+    let rest = part.strip_prefix("Android")?.trim_start();
+    if rest.is_empty() || rest.starts_with(|c: char| c.is_ascii_digit()) {
+        Some(rest.split_whitespace().next().unwrap_or_default())
+    } else {
+        None
+    }
+}
+
+/// Reads `API 34`, `API-34`, `API/34` or the same with `SDK`
+fn api_level(part: &str) -> Option<u32> {
+    let rest = part
+        .strip_prefix("API")
+        .or_else(|| part.strip_prefix("SDK"))?
+        .trim_start_matches([' ', '-', '/', ':']);
+    rest.parse().ok()
+}
+
+fn is_model(part: &str) -> bool {
+    !part.is_empty()
+        && !part.contains('/')
+        && !NOT_MODELS.contains(&part)
+        && !matches!(
+            parse_tag(part),
+            Some(Locale {
+                region: Some(_),
+                ..
+            })
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dalvik() {
+        assert_eq!(
+            android_details(
+                "Dalvik/2.1.0 (Linux; U; Android 13; Pixel 7 Build/TQ3A.230901.001)"
+            ),
+            Some(AndroidDetails {
+                version: Some("13"),
+                api_level: None,
+                model: Some("Pixel 7"),
+                build: Some("TQ3A.230901.001"),
+            })
+        );
+
+        let emulator = android_details(
+            "Dalvik/2.1.0 (Linux; U; Android 11; Android SDK built for x86 \
+             Build/RSR1.201013.001)",
+        )
+        .unwrap();
+        assert_eq!(emulator.version, Some("11"));
+        assert_eq!(emulator.model, Some("Android SDK built for x86"));
+
+        let localized =
+            android_details("Dalvik/1.6.0 (Linux; U; Android 4.4.2; de-de; GT-I9505)")
+                .unwrap();
+        assert_eq!(localized.model, Some("GT-I9505"));
+        assert_eq!(localized.build, None);
+    }
+
+    #[test]
+    fn api_level_only() {
+        assert_eq!(
+            android_details("MyApp/5.1.0 (Android; API 34; Pixel 8 Pro)"),
+            Some(AndroidDetails {
+                version: Some("14"),
+                api_level: Some(34),
+                model: Some("Pixel 8 Pro"),
+                build: None,
+            })
+        );
+        assert_eq!(
+            android_details("Sdk/2.0 (API-21)").and_then(|details| details.version),
+            Some("5.0")
+        );
+
+        let future = android_details("MyApp/1.0 (Android; API 99)").unwrap();
+        assert_eq!(future.api_level, Some(99));
+        assert_eq!(future.version, None);
+        assert_eq!(
+            android_details("MyApp/1.0 (API 0)").and_then(|details| details.version),
+            None
+        );
+    }
+
+    #[test]
+    fn no_android_comment() {
+        assert_eq!(android_details("okhttp/4.12.0"), None);
+        assert_eq!(android_details("Dalvik/2.1.0"), None);
+        assert_eq!(
+            android_details("MyApp/7.2.0 (iPhone14,2; iOS 17.1.1; Scale/3.00)"),
+            None
+        );
+    }
+}
@@ -0,0 +1,84 @@
+/// Product families that use `<family><major>,<minor>` hardware identifiers
+const APPLE_FAMILIES: &[&str] = &["iPhone", "iPad", "iPod"];
+
+/// Finds an Apple hardware identifier such as `iPhone14,2` or `iPad13,4` in a
+/// user agent. Native apps built on `CFNetwork` and many SDKs report these in
+/// place of the generic `iPhone` seen in browser user agents. With the
+/// `apple-devices` feature enabled, `uaparser::devices::apple_model` maps the
+/// identifier to a marketing name.
+///
+/// ```rust
+/// # use uaparser::extras::extract_apple_identifier;
+/// assert_eq!(
+///     extract_apple_identifier("MyApp/7.2.0 (iPhone14,2; iOS 17.1.1; Scale/3.00)"),
+///     Some("iPhone14,2"),
+/// );
+/// ```
+#[must_use]
+pub fn extract_apple_identifier(user_agent: &str) -> Option<&str> {
+    APPLE_FAMILIES.iter().find_map(|family| {
+        user_agent.match_indices(family).find_map(|(start, _)| {
+            let len = family.len()
+                + identifier_suffix_len(&user_agent[start + family.len()..])?;
+            Some(&user_agent[start..start + len])
+        })
+    })
+}
+
+/// Measures a `<digits>,<digits>` suffix, returning `None` when `rest` does
+/// not start with one or when it runs into further alphanumerics
+fn identifier_suffix_len(rest: &str) -> Option<usize> {
+    let digits = |s: &str| s.bytes().take_while(u8::is_ascii_digit).count();
+
+    let major = digits(rest);
+    if major == 0 || rest.as_bytes().get(major) != Some(&b',') {
+        return None;
+    }
+    let minor = digits(&rest[major + 1..]);
+    let len = major + 1 + minor;
+    if minor == 0 || rest[len..].starts_with(|c: char| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+
+    Some(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_identifiers() {
+        assert_eq!(
+            extract_apple_identifier(
+                "MyApp/7.2.0 (iPhone14,2; iOS 17.1.1; Scale/3.00) CFNetwork/1474 \
+                 Darwin/23.1.0"
+            ),
+            Some("iPhone14,2")
+        );
+        assert_eq!(
+            extract_apple_identifier(
+                "Reader/1.0 (com.example.reader; build:12; iPad13,16)"
+            ),
+            Some("iPad13,16")
+        );
+        assert_eq!(
+            extract_apple_identifier("Podcasts/1.0 iPod9,1"),
+            Some("iPod9,1")
+        );
+    }
+
+    #[test]
+    fn ignores_browser_user_agents() {
+        assert_eq!(
+            extract_apple_identifier(
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1 like Mac OS X) \
+                 AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1 Mobile/15E148 \
+                 Safari/604.1"
+            ),
+            None
+        );
+        assert_eq!(extract_apple_identifier("iPhone14,"), None);
+        assert_eq!(extract_apple_identifier("iPhone14,2x"), None);
+    }
+}
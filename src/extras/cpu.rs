@@ -0,0 +1,140 @@
+/// The instruction set a user agent says it runs on, named as ua-parser-js
+/// names them
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CpuArch {
+    /// 64-bit x86, also known as `x86_64` and `x64`
+    Amd64,
+    /// 32-bit x86
+    Ia32,
+    /// 64-bit ARM, also known as `aarch64`
+    Arm64,
+    /// 32-bit ARM
+    Arm,
+}
+
+impl CpuArch {
+    /// The lowercase name, such as `amd64`
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CpuArch::Amd64 => "amd64",
+            CpuArch::Ia32 => "ia32",
+            CpuArch::Arm64 => "arm64",
+            CpuArch::Arm => "arm",
+        }
+    }
+}
+
+/// Tokens naming an architecture, matched in any case, in the order they
+/// are tried. 64-bit tokens come first, as a 64-bit system may also list
+/// the 32-bit architecture it runs, such as `WOW64` builds naming `x86`.
+const TOKENS: &[(&str, CpuArch)] = &[
+    ("aarch64", CpuArch::Arm64),
+    ("arm64", CpuArch::Arm64),
+    ("arm_64", CpuArch::Arm64),
+    ("armv8", CpuArch::Arm64),
+    ("x86_64", CpuArch::Amd64),
+    ("x86-64", CpuArch::Amd64),
+    ("amd64", CpuArch::Amd64),
+    ("win64", CpuArch::Amd64),
+    ("wow64", CpuArch::Amd64),
+    ("x64;", CpuArch::Amd64),
+    ("x64)", CpuArch::Amd64),
+    ("armv7", CpuArch::Arm),
+    ("armv6", CpuArch::Arm),
+    ("armhf", CpuArch::Arm),
+    ("i686", CpuArch::Ia32),
+    ("i586", CpuArch::Ia32),
+    ("i386", CpuArch::Ia32),
+    ("ia32", CpuArch::Ia32),
+    ("x86;", CpuArch::Ia32),
+    ("x86)", CpuArch::Ia32),
+];
+
+/// Reads the CPU architecture off a user agent, as for picking a download
+/// for the visitor's machine. `None` when the user agent doesn't say.
+///
+/// `WOW64` marks a 32-bit browser running on 64-bit Windows and reads as
+/// `Amd64`, the architecture of the machine, as with ua-parser-js. Macs
+/// report `Intel Mac OS X` whatever their CPU, so Apple Silicon can't be
+/// told apart from an Intel Mac and Macs give `None`. iPhones and iPads
+/// read as `Arm64`, which every model supported by a current iOS is.
+///
+/// ```rust
+/// # use uaparser::extras::{cpu_arch, CpuArch};
+/// assert_eq!(
+///     cpu_arch("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36"),
+///     Some(CpuArch::Amd64)
+/// );
+/// ```
+#[must_use]
+pub fn cpu_arch(user_agent: &str) -> Option<CpuArch> {
+    let lower = user_agent.to_ascii_lowercase();
+    TOKENS
+        .iter()
+        .find(|(token, _)| lower.contains(token))
+        .map(|&(_, arch)| arch)
+        .or_else(|| {
+            (user_agent.contains("(iPhone;") || user_agent.contains("(iPad;"))
+                .then_some(CpuArch::Arm64)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_architectures() {
+        let cases = [
+            (
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+                Some(CpuArch::Amd64),
+            ),
+            (
+                "Mozilla/5.0 (Windows NT 6.1; WOW64; Trident/7.0; rv:11.0) like Gecko",
+                Some(CpuArch::Amd64),
+            ),
+            (
+                "Mozilla/5.0 (Windows NT 10.0; rv:121.0) Gecko/20100101 Firefox/121.0",
+                None,
+            ),
+            (
+                "Mozilla/5.0 (X11; Linux aarch64; rv:121.0) Gecko/20100101 Firefox/121.0",
+                Some(CpuArch::Arm64),
+            ),
+            (
+                "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) \
+                 Chrome/120.0.0.0 Safari/537.36",
+                Some(CpuArch::Amd64),
+            ),
+            (
+                "Mozilla/5.0 (X11; Linux i686; rv:109.0) Gecko/20100101 Firefox/115.0",
+                Some(CpuArch::Ia32),
+            ),
+            (
+                "Mozilla/5.0 (X11; CrOS armv7l 15633.69.0) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/119.0.0.0 Safari/537.36",
+                Some(CpuArch::Arm),
+            ),
+            (
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1_2 like Mac OS X) \
+                 AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1 Mobile/15E148 \
+                 Safari/604.1",
+                Some(CpuArch::Arm64),
+            ),
+            (
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 \
+                 (KHTML, like Gecko) Version/17.1 Safari/605.1.15",
+                None,
+            ),
+            ("curl/8.4.0", None),
+        ];
+
+        for (user_agent, arch) in cases {
+            assert_eq!(cpu_arch(user_agent), arch, "{user_agent}");
+        }
+        assert_eq!(CpuArch::Arm64.as_str(), "arm64");
+    }
+}
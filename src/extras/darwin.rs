@@ -0,0 +1,192 @@
+use std::{borrow::Cow, convert::TryFrom};
+
+use crate::{
+    tokens::{tokenize, UaToken},
+    OS,
+};
+
+/// The iOS major, macOS major and macOS minor versions released with each
+/// Darwin major version, starting at Darwin 9
+const RELEASES: &[(&str, &str, Option<&str>)] = &[
+    ("2", "10", Some("5")),
+    ("4", "10", Some("6")),
+    ("5", "10", Some("7")),
+    ("6", "10", Some("8")),
+    ("7", "10", Some("9")),
+    ("8", "10", Some("10")),
+    ("9", "10", Some("11")),
+    ("10", "10", Some("12")),
+    ("11", "10", Some("13")),
+    ("12", "10", Some("14")),
+    ("13", "10", Some("15")),
+    ("14", "11", None),
+    ("15", "12", None),
+    ("16", "13", None),
+    ("17", "14", None),
+    ("18", "15", None),
+    ("26", "26", None),
+];
+
+/// The first Darwin major version whose minor versions line up with those
+/// of iOS
+const IOS_MINOR_ALIGNED: u32 = 21;
+
+/// Which Apple OS a Darwin version was read from. Every Darwin release
+/// ships in both iOS and macOS, so without other hints it could be either.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DarwinPlatform {
+    Ios,
+    MacOs,
+    /// Nothing else in the user agent told the two apart
+    Either,
+}
+
+/// An Apple OS release read off the Darwin version of a user agent
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DarwinRelease {
+    pub platform: DarwinPlatform,
+    /// The release, as iOS when the platform is `Either`
+    pub os: OS<'static>,
+}
+
+/// Reads the OS of an Apple native app user agent, such as
+/// `MyApp/7.2 CFNetwork/1474 Darwin/23.0.0`, from the Darwin kernel version
+/// it sends in place of an OS name. See `darwin_release` for the platform
+/// the version was attributed to.
+///
+/// ```rust
+/// # use uaparser::extras::darwin_os;
+/// let os = darwin_os("MyApp/7.2 CFNetwork/1474 Darwin/23.0.0").unwrap();
+///
+/// assert_eq!(os.family, "iOS");
+/// assert_eq!(os.major.as_deref(), Some("17"));
+/// ```
+#[must_use]
+pub fn darwin_os(user_agent: &str) -> Option<OS<'static>> {
+    darwin_release(user_agent).map(|release| release.os)
+}
+
+/// Like `darwin_os`, but also reports whether the release is iOS or macOS.
+/// Hints such as an `(x86_64)` comment or a Mac model identifier mean
+/// macOS, and `iPhone`, `iPad` or `iOS` mean iOS. Without either, the
+/// platform is `Either` and the release is given as iOS, which sends most
+/// of this traffic. The macOS minor version is only known for Darwin 19 and
+/// earlier, and the iOS one only from Darwin 21.
+#[must_use]
+pub fn darwin_release(user_agent: &str) -> Option<DarwinRelease> {
+    let tokens = tokenize(user_agent);
+    let product = |wanted: &str| {
+        tokens.iter().find_map(|token| match token {
+            UaToken::Product { name, version } if *name == wanted => *version,
+            _ => None,
+        })
+    };
+    product("CFNetwork")?;
+    let mut darwin = product("Darwin")?.split('.');
+    let major: u32 = darwin.next()?.parse().ok()?;
+    let minor: u32 = darwin
+        .next()
+        .and_then(|minor| minor.parse().ok())
+        .unwrap_or(0);
+    let &(ios, mac_os, mac_os_minor) =
+        RELEASES.get(usize::try_from(major.checked_sub(9)?).ok()?)?;
+
+    let platform = platform(&tokens);
+    let os = if platform == DarwinPlatform::MacOs {
+        OS {
+            family: Cow::Borrowed("Mac OS X"),
+            major: Some(Cow::Borrowed(mac_os)),
+            minor: mac_os_minor.map(Cow::Borrowed),
+            ..OS::default()
+        }
+    } else {
+        OS {
+            family: Cow::Borrowed("iOS"),
+            major: Some(Cow::Borrowed(ios)),
+            minor: (major >= IOS_MINOR_ALIGNED).then(|| Cow::Owned(minor.to_string())),
+            ..OS::default()
+        }
+    };
+    Some(DarwinRelease { platform, os })
+}
+
+fn platform(tokens: &[UaToken<'_>]) -> DarwinPlatform {
+    let mut platform = DarwinPlatform::Either;
+    for token in tokens {
+        let names: &[&str] = match token {
+            UaToken::Product { name, .. } => std::slice::from_ref(name),
+            UaToken::Comment { parts } => parts,
+        };
+        for name in names {
+            if ["x86_64", "i386", "macOS"].contains(name) || name.starts_with("Mac") {
+                return DarwinPlatform::MacOs;
+            }
+            if ["iPhone", "iPad", "iPod", "iOS"]
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+            {
+                platform = DarwinPlatform::Ios;
+            }
+        }
+    }
+    platform
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_darwin() {
+        let release = darwin_release("MyApp/7.2 CFNetwork/1474 Darwin/23.0.0")
+            .expect("No Darwin release");
+        assert_eq!(release.platform, DarwinPlatform::Either);
+        assert_eq!(release.os.family, "iOS");
+        assert_eq!(release.os.major.as_deref(), Some("17"));
+        assert_eq!(release.os.minor.as_deref(), Some("0"));
+
+        let iphone = darwin_release(
+            "MyApp/7.2.0 (iPhone14,2; iOS 17.1.1; Scale/3.00) CFNetwork/1474 \
+             Darwin/23.1.0",
+        )
+        .expect("No Darwin release");
+        assert_eq!(iphone.platform, DarwinPlatform::Ios);
+        assert_eq!(iphone.os.minor.as_deref(), Some("1"));
+
+        let mac =
+            darwin_release("Podcasts/1.0 CFNetwork/1490.0.4 Darwin/23.2.0 (x86_64)")
+                .expect("No Darwin release");
+        assert_eq!(mac.platform, DarwinPlatform::MacOs);
+        assert_eq!(mac.os.family, "Mac OS X");
+        assert_eq!(mac.os.major.as_deref(), Some("14"));
+        assert_eq!(mac.os.minor, None);
+    }
+
+    #[test]
+    fn old_darwin() {
+        let os = darwin_os("Pinterest/3356 CFNetwork/808.2.16 Darwin/16.3.0")
+            .expect("No Darwin release");
+        assert_eq!(os.family, "iOS");
+        assert_eq!(os.major.as_deref(), Some("10"));
+        assert_eq!(os.minor, None);
+
+        let mac = darwin_os(
+            "Safari/12602.4.8 CFNetwork/807.2.14 Darwin/16.4.0 (x86_64) \
+             (MacBookPro11,1)",
+        )
+        .expect("No Darwin release");
+        assert_eq!(mac.family, "Mac OS X");
+        assert_eq!(
+            (mac.major.as_deref(), mac.minor.as_deref()),
+            (Some("10"), Some("12"))
+        );
+    }
+
+    #[test]
+    fn needs_both_tokens() {
+        assert_eq!(darwin_os("MyApp/7.2 Darwin/23.0.0"), None);
+        assert_eq!(darwin_os("MyApp/7.2 CFNetwork/1474"), None);
+        assert_eq!(darwin_os("MyApp/7.2 CFNetwork/1474 Darwin/8.0.0"), None);
+        assert_eq!(darwin_os("MyApp/7.2 CFNetwork/1474 Darwin/99.0.0"), None);
+    }
+}
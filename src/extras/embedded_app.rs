@@ -0,0 +1,152 @@
+use crate::tokens::{tokenize, UaToken};
+
+/// The desktop application framework that embeds a Chromium engine
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Framework {
+    Electron,
+    NwJs,
+    Cef,
+}
+
+/// An application built on an embedded browser framework, as described by
+/// its user agent string
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct EmbeddedApp<'a> {
+    pub framework: Framework,
+    pub framework_version: Option<&'a str>,
+    /// The leading product token that isn't part of the standard browser
+    /// boilerplate, which is usually the application itself
+    pub app_name: Option<&'a str>,
+    pub app_version: Option<&'a str>,
+}
+
+/// Product tokens that make up ordinary browser user agents and so never
+/// identify the embedding application
+const STANDARD_PRODUCTS: &[&str] = &[
+    "AppleWebKit",
+    "Chrome",
+    "Chromium",
+    "Gecko",
+    "HeadlessChrome",
+    "KHTML",
+    "Mobile",
+    "Mozilla",
+    "Safari",
+    "Version",
+];
+
+fn framework_of(name: &str) -> Option<Framework> {
+    match name {
+        "Electron" => Some(Framework::Electron),
+        "NW.js" | "nwjs" => Some(Framework::NwJs),
+        "CEF" | "CefSharp" => Some(Framework::Cef),
+        _ => None,
+    }
+}
+
+/// Recognizes user agents sent by applications built on Electron, NW.js or
+/// CEF, reporting the framework along with the application's own product
+/// token when it has one. Plain browsers return `None`, however unusual
+/// their other tokens are.
+///
+/// ```rust
+/// # use uaparser::extras::{detect_embedded_app, Framework};
+/// let app = detect_embedded_app(
+///     "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Code/1.85.1 Chrome/114.0.5735.289 Electron/25.9.7 Safari/537.36",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(app.framework, Framework::Electron);
+/// assert_eq!(app.app_name, Some("Code"));
+/// ```
+#[must_use]
+pub fn detect_embedded_app(user_agent: &str) -> Option<EmbeddedApp<'_>> {
+    let products: Vec<(&str, Option<&str>)> = tokenize(user_agent)
+        .into_iter()
+        .filter_map(|token| match token {
+            UaToken::Product { name, version } => Some((name, version)),
+            UaToken::Comment { .. } => None,
+        })
+        .collect();
+
+    let (framework, framework_version) = products
+        .iter()
+        .find_map(|&(name, version)| framework_of(name).map(|x| (x, version)))?;
+
+    let app = products.iter().find(|&&(name, version)| {
+        version.is_some()
+            && framework_of(name).is_none()
+            && !STANDARD_PRODUCTS.contains(&name)
+    });
+
+    Some(EmbeddedApp {
+        framework,
+        framework_version,
+        app_name: app.map(|&(name, _)| name),
+        app_version: app.and_then(|&(_, version)| version),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slack() {
+        let app = detect_embedded_app(
+            "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 \
+             (KHTML, like Gecko) Slack/4.35.126 Chrome/116.0.5845.188 \
+             Electron/26.2.4 Safari/537.36 Sonic Slack_SSB/4.35.126",
+        );
+
+        assert_eq!(
+            app,
+            Some(EmbeddedApp {
+                framework: Framework::Electron,
+                framework_version: Some("26.2.4"),
+                app_name: Some("Slack"),
+                app_version: Some("4.35.126"),
+            })
+        );
+    }
+
+    #[test]
+    fn vs_code() {
+        let app = detect_embedded_app(
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+             (KHTML, like Gecko) Code/1.85.1 Chrome/114.0.5735.289 Electron/25.9.7 \
+             Safari/537.36",
+        )
+        .expect("VS Code is an Electron app");
+
+        assert_eq!(app.framework, Framework::Electron);
+        assert_eq!(app.framework_version, Some("25.9.7"));
+        assert_eq!(app.app_name, Some("Code"));
+        assert_eq!(app.app_version, Some("1.85.1"));
+    }
+
+    #[test]
+    fn plain_browsers() {
+        assert_eq!(
+            detect_embedded_app(
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36"
+            ),
+            None
+        );
+        assert_eq!(
+            detect_embedded_app(
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36 Edg/120.0.2210.91"
+            ),
+            None
+        );
+        assert_eq!(
+            detect_embedded_app(
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/119.0.0.0 Safari/537.36 OPR/105.0.0.0"
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use crate::tokens::{tokenize, UaToken};
+
+/// Automation tools and headless browsers that identify themselves in the
+/// user agent string
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum HeadlessTool {
+    HeadlessChrome,
+    HeadlessFirefox,
+    PhantomJS,
+    SlimerJS,
+    Puppeteer,
+    Playwright,
+}
+
+/// The headless tool found in a user agent, along with its version when the
+/// token carries one
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct HeadlessInfo<'a> {
+    pub tool: HeadlessTool,
+    pub version: Option<&'a str>,
+}
+
+/// Detects headless browsers and browser automation tools. When several
+/// markers are present, automation frameworks (Puppeteer, Playwright) are
+/// reported ahead of the browser they drive.
+///
+/// This only looks for tokens these tools put in the user agent; it doesn't
+/// change what `UserAgentParser` reports for the same string, and automation
+/// that sends an unmodified browser user agent (such as Electron test
+/// runners) can't be told apart from ordinary traffic this way.
+///
+/// ```rust
+/// # use uaparser::extras::{detect_headless, HeadlessTool};
+/// let headless = detect_headless(
+///     "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) HeadlessChrome/120.0.6099.71 Safari/537.36",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(headless.tool, HeadlessTool::HeadlessChrome);
+/// assert_eq!(headless.version, Some("120.0.6099.71"));
+/// ```
+#[must_use]
+pub fn detect_headless(user_agent: &str) -> Option<HeadlessInfo<'_>> {
+    let products: Vec<(&str, Option<&str>)> = tokenize(user_agent)
+        .into_iter()
+        .filter_map(|token| match token {
+            UaToken::Product { name, version } => Some((name, version)),
+            UaToken::Comment { .. } => None,
+        })
+        .collect();
+
+    let find = |tool: HeadlessTool, wanted: &str| {
+        products.iter().find_map(|&(name, version)| {
+            name.eq_ignore_ascii_case(wanted)
+                .then_some(HeadlessInfo { tool, version })
+        })
+    };
+
+    find(HeadlessTool::Puppeteer, "Puppeteer")
+        .or_else(|| find(HeadlessTool::Playwright, "Playwright"))
+        .or_else(|| find(HeadlessTool::PhantomJS, "PhantomJS"))
+        .or_else(|| find(HeadlessTool::SlimerJS, "SlimerJS"))
+        .or_else(|| find(HeadlessTool::HeadlessChrome, "HeadlessChrome"))
+        .or_else(|| {
+            let headless = products
+                .iter()
+                .any(|&(name, _)| name.eq_ignore_ascii_case("Headless"));
+            let firefox = find(HeadlessTool::HeadlessFirefox, "Firefox");
+            firefox.filter(|_| headless)
+        })
+}
+
+/// Whether a user agent belongs to a headless browser or automation tool.
+/// See `detect_headless` for details.
+#[must_use]
+pub fn is_headless(user_agent: &str) -> bool {
+    detect_headless(user_agent).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, UserAgentParser};
+
+    const HEADLESS_CHROME: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 \
+                                   (KHTML, like Gecko) HeadlessChrome/120.0.6099.71 \
+                                   Safari/537.36";
+
+    #[test]
+    fn headless_chrome() {
+        assert_eq!(
+            detect_headless(HEADLESS_CHROME),
+            Some(HeadlessInfo {
+                tool: HeadlessTool::HeadlessChrome,
+                version: Some("120.0.6099.71"),
+            })
+        );
+    }
+
+    #[test]
+    fn composes_with_parse() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let user_agent = parser.parse_user_agent(HEADLESS_CHROME);
+
+        assert!(is_headless(HEADLESS_CHROME));
+        assert_eq!(user_agent.family, "HeadlessChrome");
+        assert_eq!(user_agent.major.as_deref(), Some("120"));
+    }
+
+    #[test]
+    fn other_tools() {
+        let phantom = detect_headless(
+            "Mozilla/5.0 (Unknown; Linux x86_64) AppleWebKit/538.1 (KHTML, like Gecko) \
+             PhantomJS/2.1.1 Safari/538.1",
+        );
+        assert_eq!(phantom.map(|x| x.tool), Some(HeadlessTool::PhantomJS));
+        assert_eq!(phantom.and_then(|x| x.version), Some("2.1.1"));
+
+        let firefox = detect_headless(
+            "Mozilla/5.0 (X11; Linux x86_64; rv:121.0) Gecko/20100101 Firefox/121.0 \
+             Headless",
+        );
+        assert_eq!(firefox.map(|x| x.tool), Some(HeadlessTool::HeadlessFirefox));
+        assert_eq!(firefox.and_then(|x| x.version), Some("121.0"));
+
+        let user_agent = format!("{HEADLESS_CHROME} Puppeteer/21.6.1");
+        let puppeteer = detect_headless(&user_agent);
+        assert_eq!(puppeteer.map(|x| x.tool), Some(HeadlessTool::Puppeteer));
+    }
+
+    #[test]
+    fn normal_chrome() {
+        assert!(!is_headless(
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+             (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36"
+        ));
+        assert!(!is_headless(
+            "Mozilla/5.0 (X11; Linux x86_64; rv:121.0) Gecko/20100101 Firefox/121.0"
+        ));
+    }
+}
@@ -0,0 +1,115 @@
+use crate::tokens::{tokenize, UaToken};
+
+mod table;
+
+/// An HTTP library or command line tool recognized from its user agent
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct HttpTool<'a> {
+    pub name: &'static str,
+    pub version: Option<&'a str>,
+}
+
+/// Classifies user agents sent by HTTP libraries and command line tools
+/// (curl, python-requests, Go's net/http, `OkHttp` and friends), which the
+/// stock rules often leave as `Other`. Browser user agents, which start with a
+/// `Mozilla/` token, are never classified as tools.
+///
+/// ```rust
+/// # use uaparser::extras::classify_tool;
+/// let tool = classify_tool("python-requests/2.31.0").unwrap();
+///
+/// assert_eq!(tool.name, "Python Requests");
+/// assert_eq!(tool.version, Some("2.31.0"));
+/// ```
+#[must_use]
+pub fn classify_tool(user_agent: &str) -> Option<HttpTool<'_>> {
+    let mut products = tokenize(user_agent)
+        .into_iter()
+        .filter_map(|token| match token {
+            UaToken::Product { name, version } => Some((name, version)),
+            UaToken::Comment { .. } => None,
+        })
+        .peekable();
+
+    if products.peek().is_some_and(|&(name, _)| name == "Mozilla") {
+        return None;
+    }
+
+    products.find_map(|(name, version)| {
+        table::TOOLS
+            .iter()
+            .find(|entry| entry.token.eq_ignore_ascii_case(name))
+            .map(|entry| HttpTool {
+                name: entry.name,
+                version,
+            })
+    })
+}
+
+/// Whether a user agent family reported by the parser is one of the known
+/// HTTP libraries or tools
+pub(crate) fn is_tool_family(family: &str) -> bool {
+    table::TOOLS
+        .iter()
+        .any(|entry| entry.family.eq_ignore_ascii_case(family))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, UserAgentParser};
+
+    #[test]
+    fn classifies_tools() {
+        let cases = [
+            ("curl/8.4.0", "curl", "8.4.0"),
+            ("python-requests/2.31.0", "Python Requests", "2.31.0"),
+            ("Go-http-client/2.0", "Go-http-client", "2.0"),
+            ("okhttp/4.12.0", "OkHttp", "4.12.0"),
+            ("Python/3.11 aiohttp/3.9.1", "Python aiohttp", "3.9.1"),
+            (
+                "Apache-HttpClient/4.5.13 (Java/11.0.16)",
+                "Apache HttpClient",
+                "4.5.13",
+            ),
+        ];
+
+        for (user_agent, name, version) in cases {
+            assert_eq!(
+                classify_tool(user_agent),
+                Some(HttpTool {
+                    name,
+                    version: Some(version),
+                }),
+                "{user_agent}"
+            );
+        }
+    }
+
+    #[test]
+    fn browsers_are_not_tools() {
+        assert_eq!(
+            classify_tool(
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36"
+            ),
+            None
+        );
+        assert_eq!(classify_tool(""), None);
+    }
+
+    #[test]
+    fn client_is_http_tool() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+
+        assert!(parser.parse("curl/8.4.0").is_http_tool());
+        assert!(parser.parse("python-requests/2.31.0").is_http_tool());
+        assert!(!parser
+            .parse(
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36"
+            )
+            .is_http_tool());
+    }
+}
@@ -0,0 +1,55 @@
+/// An HTTP library or command line tool, identified by the product token it
+/// sends in its user agent
+pub(super) struct ToolEntry {
+    /// The product token, matched case-insensitively
+    pub token: &'static str,
+    /// The name the tool is reported under
+    pub name: &'static str,
+    /// The family uap-core reports for the tool's user agent
+    pub family: &'static str,
+}
+
+const fn tool(
+    token: &'static str,
+    name: &'static str,
+    family: &'static str,
+) -> ToolEntry {
+    ToolEntry {
+        token,
+        name,
+        family,
+    }
+}
+
+pub(super) static TOOLS: &[ToolEntry] = &[
+    tool("curl", "curl", "curl"),
+    tool("Wget", "Wget", "Wget"),
+    tool("wget2", "Wget2", "wget2"),
+    tool("HTTPie", "HTTPie", "HTTPie"),
+    tool("PostmanRuntime", "Postman", "PostmanRuntime"),
+    tool("insomnia", "Insomnia", "insomnia"),
+    tool("python-requests", "Python Requests", "Python Requests"),
+    tool("Python-urllib", "Python urllib", "Python-urllib"),
+    tool("python-urllib3", "Python urllib3", "python-urllib3"),
+    tool("python-httpx", "Python HTTPX", "python-httpx"),
+    tool("aiohttp", "Python aiohttp", "Python aiohttp"),
+    tool("Go-http-client", "Go-http-client", "Go-http-client"),
+    tool("go-resty", "Resty", "go-resty"),
+    tool("okhttp", "OkHttp", "okhttp"),
+    tool(
+        "Apache-HttpClient",
+        "Apache HttpClient",
+        "Apache-HttpClient",
+    ),
+    tool("Java", "Java", "Java"),
+    tool("axios", "axios", "axios"),
+    tool("node-fetch", "node-fetch", "node-fetch"),
+    tool("undici", "undici", "undici"),
+    tool("got", "got", "got"),
+    tool("GuzzleHttp", "Guzzle", "GuzzleHttp"),
+    tool("libwww-perl", "libwww-perl", "libwww-perl"),
+    tool("Faraday", "Faraday", "Faraday"),
+    tool("Dart", "Dart", "Dart"),
+    tool("RestSharp", "RestSharp", "RestSharp"),
+    tool("reqwest", "reqwest", "reqwest"),
+];
@@ -0,0 +1,287 @@
+use std::borrow::Cow;
+
+use crate::{
+    tokens::{tokenize, UaToken},
+    Device, OS,
+};
+
+/// App names for the `FBAN` and `FB_IAB` values of Meta's apps
+const FACEBOOK_APPS: &[(&str, &str)] = &[
+    ("FB4A", "Facebook"),
+    ("FBIOS", "Facebook"),
+    ("FBAN", "Facebook"),
+    ("MessengerForiOS", "Messenger"),
+    ("MessengerLiteForiOS", "Messenger"),
+    ("Orca-Android", "Messenger"),
+];
+
+/// The Facebook or Instagram app a page was opened in, along with the device
+/// and OS details the app appends to the browser's user agent, borrowed from
+/// it
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct InAppBrowser<'a> {
+    /// `Facebook`, `Messenger` or `Instagram`, or the raw `FBAN` value of
+    /// other Meta apps
+    pub app_name: &'a str,
+    pub app_version: Option<&'a str>,
+    /// The device model, such as `iPhone14,5` or `SM-S901B`
+    pub device_model: Option<&'a str>,
+    pub device_brand: Option<&'a str>,
+    /// `iOS` or `Android`
+    pub os_name: Option<&'a str>,
+    /// The OS version, as the app wrote it, such as `17.1.2` or `17_1_1`
+    pub os_version: Option<&'a str>,
+}
+
+impl<'a> InAppBrowser<'a> {
+    /// The device details as a `Device`, such as to fill in or override the
+    /// generic `iPhone` model of a parsed result with `Device::merge`
+    #[must_use]
+    pub fn device(&self) -> Device<'a> {
+        let brand = self.device_brand.or_else(|| {
+            self.device_model
+                .filter(|model| {
+                    ["iPhone", "iPad", "iPod"]
+                        .iter()
+                        .any(|x| model.starts_with(x))
+                })
+                .map(|_| "Apple")
+        });
+        Device {
+            family: self
+                .device_model
+                .map_or(Cow::Borrowed("Other"), Cow::Borrowed),
+            brand: brand.map(Cow::Borrowed),
+            model: self.device_model.map(Cow::Borrowed),
+        }
+    }
+
+    /// The OS details as an `OS`, for `OS::merge`
+    #[must_use]
+    pub fn os(&self) -> OS<'a> {
+        let mut version = self
+            .os_version
+            .into_iter()
+            .flat_map(|x| x.split(['.', '_']));
+        OS {
+            family: self.os_name.map_or(Cow::Borrowed("Other"), Cow::Borrowed),
+            major: version.next().map(Cow::Borrowed),
+            minor: version.next().map(Cow::Borrowed),
+            patch: version.next().map(Cow::Borrowed),
+            patch_minor: version.next().map(Cow::Borrowed),
+        }
+    }
+}
+
+/// Recognizes the Facebook and Instagram in-app browsers, from the
+/// `[FBAN/...;FBAV/...]` block Facebook's apps append to the user agent and
+/// the `Instagram <version> (...)` part Instagram appends. The regex rules
+/// may name the browser after the app, but drop the app's version and the
+/// device details it reports, which this reads instead.
+///
+/// ```rust
+/// # use uaparser::extras::in_app_browser;
+/// let app = in_app_browser(
+///     "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1_2 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Mobile/15E148 [FBAN/FBIOS;FBAV/442.0.0.38.109;FBDV/iPhone14,5;FBMD/iPhone;FBSN/iOS;FBSV/17.1.2;FBSS/3;FBID/phone;FBLC/en_US;FBOP/5]",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(app.app_name, "Facebook");
+/// assert_eq!(app.app_version, Some("442.0.0.38.109"));
+/// assert_eq!(app.device_model, Some("iPhone14,5"));
+/// ```
+#[must_use]
+pub fn in_app_browser(user_agent: &str) -> Option<InAppBrowser<'_>> {
+    instagram(user_agent).or_else(|| facebook(user_agent))
+}
+
+fn facebook(user_agent: &str) -> Option<InAppBrowser<'_>> {
+    let start = user_agent
+        .find("[FBAN/")
+        .or_else(|| user_agent.find("[FB_IAB/"))?
+        + 1;
+    let block = &user_agent[start..];
+    let block = &block[..block.find(']').unwrap_or(block.len())];
+
+    let mut app = InAppBrowser::default();
+    let mut manufacturer = None;
+    for field in block.split(';') {
+        let Some((key, value)) = field.split_once('/') else {
+            continue;
+        };
+        let value = Some(value).filter(|value| !value.is_empty());
+        match key {
+            "FBAN" | "FB_IAB" => {
+                let id = value.unwrap_or_default();
+                app.app_name = FACEBOOK_APPS
+                    .iter()
+                    .find(|&&(known, _)| known == id)
+                    .map_or(id, |&(_, name)| name);
+                if id.ends_with("Android") || id == "FB4A" {
+                    app.os_name = app.os_name.or(Some("Android"));
+                }
+            }
+            "FBAV" => app.app_version = value,
+            "FBDV" => app.device_model = value,
+            "FBBD" => app.device_brand = value,
+            "FBMF" => manufacturer = value,
+            "FBSN" => app.os_name = value,
+            "FBSV" => app.os_version = value,
+            _ => {}
+        }
+    }
+    app.device_brand = app.device_brand.or(manufacturer);
+    Some(app).filter(|app| !app.app_name.is_empty())
+}
+
+fn instagram(user_agent: &str) -> Option<InAppBrowser<'_>> {
+    let tokens = tokenize(user_agent);
+    let start = tokens.iter().position(|token| {
+        matches!(
+            token,
+            UaToken::Product {
+                name: "Instagram",
+                version: None
+            }
+        )
+    })?;
+    let mut rest = tokens[start + 1..].iter();
+
+    let mut app = InAppBrowser {
+        app_name: "Instagram",
+        ..InAppBrowser::default()
+    };
+    match rest.next() {
+        Some(UaToken::Product {
+            name,
+            version: None,
+        }) if name.starts_with(|c: char| c.is_ascii_digit()) => {
+            app.app_version = Some(name);
+        }
+        _ => return Some(app),
+    }
+    let mut next = rest.next();
+    if let Some(UaToken::Product {
+        name: "Android", ..
+    }) = next
+    {
+        app.os_name = Some("Android");
+        next = rest.next();
+    }
+    let Some(UaToken::Comment { parts }) = next else {
+        return Some(app);
+    };
+
+    if app.os_name.is_some() {
+        // `(33/13; 420dpi; 1080x2400; samsung; SM-S901B; ...)`: the API level
+        // and release, screen density and size, brand and model
+        app.os_version = parts
+            .first()
+            .and_then(|part| part.split_once('/'))
+            .map(|(_, release)| release);
+        app.device_brand = parts.get(3).copied();
+        app.device_model = parts.get(4).copied();
+    } else {
+        // `(iPhone14,2; iOS 17_1_1; en_US; ...)`
+        app.device_model = parts.first().copied();
+        if let Some((name, version)) = parts.get(1).and_then(|part| part.split_once(' '))
+        {
+            app.os_name = Some(name);
+            app.os_version = Some(version);
+        }
+    }
+    Some(app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MergeStrategy, Parser, UserAgentParser};
+
+    const FACEBOOK_IOS: &str =
+        "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1_2 like Mac OS X) \
+         AppleWebKit/605.1.15 (KHTML, like Gecko) Mobile/15E148 \
+         [FBAN/FBIOS;FBAV/442.0.0.38.109;FBBV/546003385;FBDV/iPhone14,5;FBMD/iPhone;\
+         FBSN/iOS;FBSV/17.1.2;FBSS/3;FBID/phone;FBLC/en_US;FBOP/5;FBRV/548193524]";
+
+    #[test]
+    fn facebook() {
+        assert_eq!(
+            in_app_browser(FACEBOOK_IOS),
+            Some(InAppBrowser {
+                app_name: "Facebook",
+                app_version: Some("442.0.0.38.109"),
+                device_model: Some("iPhone14,5"),
+                device_brand: None,
+                os_name: Some("iOS"),
+                os_version: Some("17.1.2"),
+            })
+        );
+
+        let android = in_app_browser(
+            "Mozilla/5.0 (Linux; Android 13; SM-S901B Build/TP1A.220624.014; wv) \
+             AppleWebKit/537.36 (KHTML, like Gecko) Version/4.0 Chrome/119.0.6045.193 \
+             Mobile Safari/537.36 [FB_IAB/FB4A;FBAV/443.0.0.32.118;]",
+        )
+        .expect("No in-app browser");
+        assert_eq!(android.app_name, "Facebook");
+        assert_eq!(android.app_version, Some("443.0.0.32.118"));
+        assert_eq!(android.os_name, Some("Android"));
+        assert_eq!(android.device_model, None);
+    }
+
+    #[test]
+    fn instagram() {
+        let ios = in_app_browser(
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1_1 like Mac OS X) \
+             AppleWebKit/605.1.15 (KHTML, like Gecko) Mobile/15E148 \
+             Instagram 312.1.0.34.111 (iPhone14,2; iOS 17_1_1; en_US; en; scale=3.00; \
+             1170x2532; 548339486)",
+        )
+        .expect("No in-app browser");
+        assert_eq!(ios.app_name, "Instagram");
+        assert_eq!(ios.app_version, Some("312.1.0.34.111"));
+        assert_eq!(ios.device_model, Some("iPhone14,2"));
+        assert_eq!(ios.os().major.as_deref(), Some("17"));
+        assert_eq!(ios.os().patch.as_deref(), Some("1"));
+
+        let android = in_app_browser(
+            "Mozilla/5.0 (Linux; Android 13; SM-S901B Build/TP1A.220624.014; wv) \
+             AppleWebKit/537.36 (KHTML, like Gecko) Version/4.0 Chrome/119.0.6045.193 \
+             Mobile Safari/537.36 Instagram 309.0.0.40.113 Android (33/13; 420dpi; \
+             1080x2400; samsung; SM-S901B; r0s; s5e9925; en_US; 541635890)",
+        )
+        .expect("No in-app browser");
+        assert_eq!(android.app_version, Some("309.0.0.40.113"));
+        assert_eq!(android.device().brand.as_deref(), Some("samsung"));
+        assert_eq!(android.device().model.as_deref(), Some("SM-S901B"));
+        assert_eq!(android.os().family, "Android");
+        assert_eq!(android.os().major.as_deref(), Some("13"));
+    }
+
+    #[test]
+    fn device_hints_merge_into_parsed_results() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let app = in_app_browser(FACEBOOK_IOS).expect("No in-app browser");
+        let device = parser
+            .parse_device(FACEBOOK_IOS)
+            .merge(app.device(), MergeStrategy::PreferOther);
+
+        assert_eq!(device.brand.as_deref(), Some("Apple"));
+        assert_eq!(device.model.as_deref(), Some("iPhone14,5"));
+    }
+
+    #[test]
+    fn plain_browsers() {
+        assert_eq!(
+            in_app_browser(
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1 like Mac OS X) \
+                 AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1 Mobile/15E148 \
+                 Safari/604.1"
+            ),
+            None
+        );
+        assert_eq!(in_app_browser("[FBAN/;FBAV/1.0]"), None);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::tokens::{tokenize, UaToken};
+
+/// A language tag found in a user agent string, borrowed from it
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Locale<'a> {
+    pub language: &'a str,
+    pub script: Option<&'a str>,
+    pub region: Option<&'a str>,
+}
+
+/// Extracts the locale embedded in a user agent, if any. The following
+/// placements are recognized, in order of preference:
+///
+/// - a `Language/xx-YY` product token, as sent by some in-app browsers
+/// - the `FBLC/xx_YY` field of a Facebook in-app browser block
+/// - a `xx-YY` part inside a comment, as in legacy Gecko and Android user
+///   agents (`Linux; U; Android 4.0.3; de-de; ...`)
+///
+/// Matching is conservative: a comment part only counts when it has both a
+/// language and a region or script subtag, so tokens like `wv`, `U` or
+/// `rv:109.0` are never mistaken for a locale.
+///
+/// ```rust
+/// # use uaparser::extras::extract_locale;
+/// let locale = extract_locale("Mozilla/5.0 (Windows; U; Windows NT 5.1; en-US; rv:1.9.2.13) Gecko/20101203 Firefox/3.6.13")
+///     .unwrap();
+///
+/// assert_eq!(locale.language, "en");
+/// assert_eq!(locale.region, Some("US"));
+/// ```
+#[must_use]
+pub fn extract_locale(user_agent: &str) -> Option<Locale<'_>> {
+    let tokens = tokenize(user_agent);
+
+    let language_token = tokens.iter().find_map(|token| match token {
+        UaToken::Product {
+            name: "Language",
+            version: Some(tag),
+        } => parse_tag(tag),
+        _ => None,
+    });
+
+    language_token
+        .or_else(|| facebook_locale(user_agent))
+        .or_else(|| {
+            tokens.iter().find_map(|token| match token {
+                UaToken::Comment { parts } => parts
+                    .iter()
+                    .filter_map(|part| parse_tag(part))
+                    .find(|locale| locale.script.is_some() || locale.region.is_some()),
+                UaToken::Product { .. } => None,
+            })
+        })
+}
+
+fn facebook_locale(user_agent: &str) -> Option<Locale<'_>> {
+    let start = user_agent.find("FBLC/")? + "FBLC/".len();
+    let rest = &user_agent[start..];
+    let end = rest.find([';', ']']).unwrap_or(rest.len());
+    parse_tag(&rest[..end])
+}
+
+/// Parses a `language[-Script][-REGION]` tag using either `-` or `_` as the
+/// separator
+pub(super) fn parse_tag(tag: &str) -> Option<Locale<'_>> {
+    let mut subtags = tag.split(['-', '_']);
+
+    let language = subtags.next().filter(|x| is_alpha(x, 2..=3))?;
+    let mut locale = Locale {
+        language,
+        script: None,
+        region: None,
+    };
+
+    let mut next = subtags.next();
+    if let Some(script) = next.filter(|x| is_alpha(x, 4..=4)) {
+        locale.script = Some(script);
+        next = subtags.next();
+    }
+    if let Some(region) = next {
+        if !is_alpha(region, 2..=2) {
+            return None;
+        }
+        locale.region = Some(region);
+        next = subtags.next();
+    }
+
+    if next.is_some() {
+        None
+    } else {
+        Some(locale)
+    }
+}
+
+fn is_alpha(subtag: &str, length: std::ops::RangeInclusive<usize>) -> bool {
+    length.contains(&subtag.len()) && subtag.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn facebook_in_app_browser() {
+        let locale = extract_locale(
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 14_4 like Mac OS X) \
+             AppleWebKit/605.1.15 (KHTML, like Gecko) Mobile/15E148 \
+             [FBAN/FBIOS;FBDV/iPhone12,1;FBMD/iPhone;FBSN/iOS;FBSV/14.4;FBSS/2;\
+             FBID/phone;FBLC/en_GB;FBOP/5]",
+        );
+
+        assert_eq!(
+            locale,
+            Some(Locale {
+                language: "en",
+                script: None,
+                region: Some("GB"),
+            })
+        );
+    }
+
+    #[test]
+    fn language_token() {
+        let locale = extract_locale(
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 16_0 like Mac OS X) Mobile/15E148 \
+             Language/zh-Hans",
+        );
+
+        assert_eq!(
+            locale,
+            Some(Locale {
+                language: "zh",
+                script: Some("Hans"),
+                region: None,
+            })
+        );
+    }
+
+    #[test]
+    fn old_firefox() {
+        let locale = extract_locale(
+            "Mozilla/5.0 (X11; U; Linux i686; de-de; rv:1.9.0.11) Gecko/2009060309 \
+             Ubuntu/9.04 (jaunty) Firefox/3.0.11",
+        );
+
+        assert_eq!(
+            locale,
+            Some(Locale {
+                language: "de",
+                script: None,
+                region: Some("de"),
+            })
+        );
+    }
+
+    #[test]
+    fn modern_browsers_have_no_locale() {
+        assert_eq!(
+            extract_locale(
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36"
+            ),
+            None
+        );
+        assert_eq!(
+            extract_locale(
+                "Mozilla/5.0 (X11; Linux x86_64; rv:109.0) Gecko/20100101 Firefox/115.0"
+            ),
+            None
+        );
+        assert_eq!(
+            extract_locale(
+                "Mozilla/5.0 (Linux; Android 13; Pixel 7; wv) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Version/4.0 Chrome/120.0.0.0 Mobile Safari/537.36"
+            ),
+            None
+        );
+    }
+}
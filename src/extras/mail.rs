@@ -0,0 +1,195 @@
+use crate::tokens::{tokenize, UaToken};
+
+/// The kind of non-browser software that opened a link from an email
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MailContext<'a> {
+    /// A mail client, such as Outlook or Thunderbird, fetching content or
+    /// opening a link on behalf of its user
+    MailClient {
+        name: &'static str,
+        version: Option<&'a str>,
+    },
+    /// An image proxy or security scanner fetching a link without a human
+    /// involved
+    LinkScanner { name: &'static str },
+}
+
+/// Substrings identifying link scanners and image proxies, checked first
+/// because several of them embed an otherwise ordinary browser user agent
+const LINK_SCANNERS: &[(&str, &str)] = &[
+    ("GoogleImageProxy", "GoogleImageProxy"),
+    ("YahooMailProxy", "YahooMailProxy"),
+    ("Microsoft Office Protocol Discovery", "Microsoft Office"),
+    ("Microsoft Office Existence Discovery", "Microsoft Office"),
+    ("Proofpoint", "Proofpoint"),
+    ("Mimecast", "Mimecast"),
+    ("Barracuda", "Barracuda"),
+];
+
+/// Substrings identifying mail clients, each followed by the client's version
+/// after a `/` or a space. More specific markers come first.
+const MAIL_CLIENTS: &[(&str, &str)] = &[
+    ("Microsoft Outlook", "Outlook"),
+    ("Outlook-iOS", "Outlook for iOS"),
+    ("Outlook-Android", "Outlook for Android"),
+    ("Microsoft Office", "Microsoft Office"),
+    ("Thunderbird", "Thunderbird"),
+    ("eM Client", "eM Client"),
+    ("Airmail", "Airmail"),
+];
+
+/// Classifies traffic from mail clients and from the link scanners and image
+/// proxies that mail providers run. Matching is conservative: a user agent
+/// only classifies when it carries one of the known markers, so a plain
+/// Safari user agent returns `None` even though Apple Mail uses the same
+/// engine.
+///
+/// Apple Mail itself is recognized by its distinctive shape: a `WebKit`
+/// user agent on macOS that ends after `(KHTML, like Gecko)` without any browser
+/// product token.
+///
+/// ```rust
+/// # use uaparser::extras::{classify_mail_context, MailContext};
+/// assert_eq!(
+///     classify_mail_context("Mozilla/5.0 (X11; Linux x86_64; rv:115.0) Gecko/20100101 Thunderbird/115.5.0"),
+///     Some(MailContext::MailClient { name: "Thunderbird", version: Some("115.5.0") }),
+/// );
+/// ```
+#[must_use]
+pub fn classify_mail_context(user_agent: &str) -> Option<MailContext<'_>> {
+    if let Some(&(_, name)) = LINK_SCANNERS
+        .iter()
+        .find(|(marker, _)| user_agent.contains(marker))
+    {
+        return Some(MailContext::LinkScanner { name });
+    }
+
+    MAIL_CLIENTS
+        .iter()
+        .find_map(|&(marker, name)| {
+            let start = user_agent.find(marker)? + marker.len();
+            Some(MailContext::MailClient {
+                name,
+                version: version_after(&user_agent[start..]),
+            })
+        })
+        .or_else(|| {
+            is_apple_mail(user_agent).then_some(MailContext::MailClient {
+                name: "Apple Mail",
+                version: None,
+            })
+        })
+}
+
+/// Reads a version directly following a marker, separated by `/` or a space
+fn version_after(rest: &str) -> Option<&str> {
+    let rest = rest.strip_prefix(['/', ' '])?;
+    let end = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '.'))
+        .unwrap_or(rest.len());
+    let version = &rest[..end];
+
+    version
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then_some(version)
+}
+
+fn is_apple_mail(user_agent: &str) -> bool {
+    let tokens = tokenize(user_agent);
+    let products: Vec<&str> = tokens
+        .iter()
+        .filter_map(|token| match token {
+            UaToken::Product { name, .. } => Some(*name),
+            UaToken::Comment { .. } => None,
+        })
+        .collect();
+    let macintosh = tokens.iter().any(|token| {
+        matches!(token, UaToken::Comment { parts } if parts.first() == Some(&"Macintosh"))
+    });
+
+    macintosh && products == ["Mozilla", "AppleWebKit"]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thunderbird() {
+        assert_eq!(
+            classify_mail_context(
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:115.0) Gecko/20100101 \
+                 Thunderbird/115.5.0"
+            ),
+            Some(MailContext::MailClient {
+                name: "Thunderbird",
+                version: Some("115.5.0"),
+            })
+        );
+    }
+
+    #[test]
+    fn outlook() {
+        assert_eq!(
+            classify_mail_context("Outlook-iOS/723.4027091.prod.iphone (4.2.0)"),
+            Some(MailContext::MailClient {
+                name: "Outlook for iOS",
+                version: Some("723.4027091.prod.iphone"),
+            })
+        );
+        assert_eq!(
+            classify_mail_context(
+                "Microsoft Office/16.0 (Windows NT 10.0; Microsoft Outlook 16.0.17029; Pro)"
+            ),
+            Some(MailContext::MailClient {
+                name: "Outlook",
+                version: Some("16.0.17029"),
+            })
+        );
+    }
+
+    #[test]
+    fn google_image_proxy() {
+        assert_eq!(
+            classify_mail_context(
+                "Mozilla/5.0 (Windows NT 5.1; rv:11.0) Gecko Firefox/11.0 \
+                 (via ggpht.com GoogleImageProxy)"
+            ),
+            Some(MailContext::LinkScanner {
+                name: "GoogleImageProxy",
+            })
+        );
+    }
+
+    #[test]
+    fn apple_mail() {
+        assert_eq!(
+            classify_mail_context(
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 \
+                 (KHTML, like Gecko)"
+            ),
+            Some(MailContext::MailClient {
+                name: "Apple Mail",
+                version: None,
+            })
+        );
+    }
+
+    #[test]
+    fn browsers() {
+        assert_eq!(
+            classify_mail_context(
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36"
+            ),
+            None
+        );
+        assert_eq!(
+            classify_mail_context(
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 \
+                 (KHTML, like Gecko) Version/17.1 Safari/605.1.15"
+            ),
+            None
+        );
+    }
+}
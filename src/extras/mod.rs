@@ -0,0 +1,29 @@
+//! Supplemental helpers that pull extra details out of a raw user agent
+//! string. These complement the rule-based classification done by
+//! `UserAgentParser` rather than replacing it.
+
+mod android;
+mod apple;
+mod cpu;
+mod darwin;
+mod embedded_app;
+mod headless;
+mod http_tool;
+mod in_app;
+mod locale;
+mod mail;
+mod webview;
+
+pub use android::{android_details, AndroidDetails};
+pub use apple::extract_apple_identifier;
+pub use cpu::{cpu_arch, CpuArch};
+pub use darwin::{darwin_os, darwin_release, DarwinPlatform, DarwinRelease};
+pub use embedded_app::{detect_embedded_app, EmbeddedApp, Framework};
+pub use headless::{detect_headless, is_headless, HeadlessInfo, HeadlessTool};
+pub use http_tool::{classify_tool, HttpTool};
+pub use in_app::{in_app_browser, InAppBrowser};
+pub use locale::{extract_locale, Locale};
+pub use mail::{classify_mail_context, MailContext};
+pub use webview::is_android_webview;
+
+pub(crate) use http_tool::is_tool_family;
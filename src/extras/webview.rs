@@ -0,0 +1,89 @@
+use crate::tokens::{tokenize, UaToken};
+
+/// Whether a user agent was sent by an Android `WebView` rather than a full
+/// browser. This recognizes the `wv` comment part added since Android 5.0
+/// and the older `Version/4.0 ... Chrome/...` signature, which real Chrome
+/// (including Custom Tabs) and Samsung Internet never send.
+///
+/// This works on the raw string because the parsed `Client` doesn't keep the
+/// tokens needed to tell the two apart.
+///
+/// ```rust
+/// # use uaparser::extras::is_android_webview;
+/// assert!(is_android_webview(
+///     "Mozilla/5.0 (Linux; Android 13; Pixel 7 Build/TQ3A.230901.001; wv) AppleWebKit/537.36 (KHTML, like Gecko) Version/4.0 Chrome/120.0.6099.144 Mobile Safari/537.36",
+/// ));
+/// ```
+#[must_use]
+pub fn is_android_webview(user_agent: &str) -> bool {
+    let tokens = tokenize(user_agent);
+
+    let mut android = false;
+    let mut wv = false;
+    for token in &tokens {
+        if let UaToken::Comment { parts } = token {
+            android |= parts.iter().any(|part| part.starts_with("Android"));
+            wv |= parts.contains(&"wv");
+        }
+    }
+
+    if !android {
+        return false;
+    }
+
+    let has_product = |wanted: &str| {
+        tokens.iter().any(
+            |token| matches!(token, UaToken::Product { name, .. } if *name == wanted),
+        )
+    };
+
+    wv || (has_product("Version") && has_product("Chrome"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn webview() {
+        assert!(is_android_webview(
+            "Mozilla/5.0 (Linux; Android 13; Pixel 7 Build/TQ3A.230901.001; wv) \
+             AppleWebKit/537.36 (KHTML, like Gecko) Version/4.0 Chrome/120.0.6099.144 \
+             Mobile Safari/537.36"
+        ));
+    }
+
+    #[test]
+    fn legacy_webview() {
+        assert!(is_android_webview(
+            "Mozilla/5.0 (Linux; Android 4.4; Nexus 5 Build/KRT16M) AppleWebKit/537.36 \
+             (KHTML, like Gecko) Version/4.0 Chrome/30.0.0.0 Mobile Safari/537.36"
+        ));
+    }
+
+    #[test]
+    fn chrome_mobile() {
+        assert!(!is_android_webview(
+            "Mozilla/5.0 (Linux; Android 10; K) AppleWebKit/537.36 \
+             (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36"
+        ));
+    }
+
+    #[test]
+    fn samsung_internet() {
+        assert!(!is_android_webview(
+            "Mozilla/5.0 (Linux; Android 13; SAMSUNG SM-S901B) AppleWebKit/537.36 \
+             (KHTML, like Gecko) SamsungBrowser/23.0 Chrome/115.0.0.0 Mobile \
+             Safari/537.36"
+        ));
+    }
+
+    #[test]
+    fn ios_safari() {
+        assert!(!is_android_webview(
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1 like Mac OS X) \
+             AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1 Mobile/15E148 \
+             Safari/604.1"
+        ));
+    }
+}
@@ -0,0 +1,99 @@
+//! Names the stock uap-core rules give families and brands, for comparing
+//! parse results without typos such as `"Mac OSX"`.
+//!
+//! Apart from the `OTHER` and `SPIDER` sentinels, the constants are generated
+//! from `src/core/regexes.yaml` by `scripts/generate_families.py`, which
+//! fails if the rules stop producing one of them; rerun it after updating
+//! the rules rather than editing the table by hand. Custom rules can name
+//! families anything, so these only hold for the stock ones.
+//!
+//! ```rust
+//! # use uaparser::*;
+//! let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+//! let client = parser.parse("Mozilla/5.0 (X11; Ubuntu; Linux x86_64; rv:121.0) Gecko/20100101 Firefox/121.0");
+//!
+//! assert_eq!(client.user_agent.family, families::FIREFOX);
+//! assert!(client.is_other_device());
+//! ```
+
+mod table;
+
+pub use table::*;
+
+/// The family given to whatever no rule matched, for user agents, OSes and
+/// devices alike
+pub const OTHER: &str = "Other";
+
+/// The device family and brand the rules give crawlers
+pub const SPIDER: &str = "Spider";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, UserAgentParser};
+
+    #[test]
+    fn constants_match_parse_results() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let cases = [
+            (
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+                CHROME,
+                WINDOWS,
+                OTHER,
+                None,
+            ),
+            (
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 \
+                 (KHTML, like Gecko) Version/17.1 Safari/605.1.15",
+                SAFARI,
+                MAC_OS_X,
+                "Mac",
+                Some(APPLE),
+            ),
+            (
+                "Mozilla/5.0 (Linux; Android 13; SM-S901B) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/120.0.6099.144 Mobile Safari/537.36",
+                CHROME_MOBILE,
+                ANDROID,
+                "Samsung SM-S901B",
+                Some(SAMSUNG),
+            ),
+            (
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1_2 like Mac OS X) \
+                 AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1 Mobile/15E148 \
+                 Safari/604.1",
+                MOBILE_SAFARI,
+                IOS,
+                "iPhone",
+                Some(APPLE),
+            ),
+            (
+                "Mozilla/5.0 (X11; Ubuntu; Linux x86_64; rv:121.0) Gecko/20100101 \
+                 Firefox/121.0",
+                FIREFOX,
+                "Ubuntu",
+                OTHER,
+                None,
+            ),
+            (
+                "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)",
+                "Googlebot",
+                OTHER,
+                SPIDER,
+                Some(SPIDER),
+            ),
+            ("totally-unknown-agent", OTHER, OTHER, OTHER, None),
+        ];
+
+        for (user_agent, ua_family, os_family, device_family, brand) in cases {
+            let client = parser.parse(user_agent);
+            assert_eq!(client.user_agent.family, ua_family, "{user_agent}");
+            assert_eq!(client.os.family, os_family, "{user_agent}");
+            assert_eq!(client.device.family, device_family, "{user_agent}");
+            assert_eq!(client.device.brand.as_deref(), brand, "{user_agent}");
+        }
+    }
+}
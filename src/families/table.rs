@@ -0,0 +1,54 @@
+// @generated by scripts/generate_families.py from src/core/regexes.yaml.
+// Do not edit by hand.
+
+// User agent families
+pub const CHROME: &str = "Chrome";
+pub const CHROME_MOBILE: &str = "Chrome Mobile";
+pub const CHROME_MOBILE_IOS: &str = "Chrome Mobile iOS";
+pub const CHROME_MOBILE_WEBVIEW: &str = "Chrome Mobile WebView";
+pub const EDGE: &str = "Edge";
+pub const EDGE_MOBILE: &str = "Edge Mobile";
+pub const FACEBOOK: &str = "Facebook";
+pub const FIREFOX: &str = "Firefox";
+pub const FIREFOX_MOBILE: &str = "Firefox Mobile";
+pub const FIREFOX_IOS: &str = "Firefox iOS";
+pub const IE: &str = "IE";
+pub const IE_MOBILE: &str = "IE Mobile";
+pub const MOBILE_SAFARI: &str = "Mobile Safari";
+pub const MOBILE_SAFARI_UI_WKWEBVIEW: &str = "Mobile Safari UI/WKWebView";
+pub const OPERA: &str = "Opera";
+pub const OPERA_MOBILE: &str = "Opera Mobile";
+pub const SAFARI: &str = "Safari";
+pub const SAMSUNG_INTERNET: &str = "Samsung Internet";
+pub const UC_BROWSER: &str = "UC Browser";
+pub const YANDEX_BROWSER: &str = "Yandex Browser";
+
+// OS families
+pub const ANDROID: &str = "Android";
+pub const CHROME_OS: &str = "Chrome OS";
+pub const FREEBSD: &str = "FreeBSD";
+pub const IOS: &str = "iOS";
+pub const KAIOS: &str = "KaiOS";
+pub const LINUX: &str = "Linux";
+pub const MAC_OS_X: &str = "Mac OS X";
+pub const WINDOWS: &str = "Windows";
+pub const WINDOWS_PHONE: &str = "Windows Phone";
+
+// Device brands
+pub const AMAZON: &str = "Amazon";
+pub const APPLE: &str = "Apple";
+pub const ASUS: &str = "Asus";
+pub const GENERIC: &str = "Generic";
+pub const GENERIC_ANDROID: &str = "Generic_Android";
+pub const GOOGLE: &str = "Google";
+pub const HTC: &str = "HTC";
+pub const HUAWEI: &str = "Huawei";
+pub const LENOVO: &str = "Lenovo";
+pub const LG: &str = "LG";
+pub const MOTOROLA: &str = "Motorola";
+pub const NOKIA: &str = "Nokia";
+pub const ONEPLUS: &str = "OnePlus";
+pub const OPPO: &str = "Oppo";
+pub const SAMSUNG: &str = "Samsung";
+pub const SONY: &str = "Sony";
+pub const ZTE: &str = "ZTE";
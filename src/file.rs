@@ -1,35 +1,417 @@
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+use std::{borrow::Cow, collections::BTreeMap, fmt};
+
+use serde_yaml::{Mapping, Value};
+
 use super::*;
 
-#[derive(Debug, Deserialize)]
+/// A rule file. Missing or `null` sections are read as empty, so overlays
+/// only need the sections they add rules to.
+///
+/// Entries can share fields through anchors and `<<` merge keys, with the
+/// entry's own keys overriding merged ones and earlier mappings of a merged
+/// sequence overriding later ones. Errors in files with merge keys have no
+/// line or column, and strict mode rejects a top level key only holding
+/// anchors, so define those on an entry instead.
+///
+/// Unknown keys of an entry are kept in its `extras` and can be read back from
+/// the matcher compiled from it. Strict mode still rejects them, as it can't
+/// tell an annotation from a misspelled replacement. An entry's example
+/// `tests` are kept in its `extras` too, and checked by `validate`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct RegexFile {
+    #[serde(default, deserialize_with = "empty_if_null")]
     pub user_agent_parsers: Vec<UserAgentParserEntry>,
+    #[serde(default, deserialize_with = "empty_if_null")]
     pub os_parsers: Vec<OSParserEntry>,
+    #[serde(default, deserialize_with = "empty_if_null")]
     pub device_parsers: Vec<DeviceParserEntry>,
 }
 
-#[derive(Debug, Deserialize)]
+impl RegexFile {
+    /// Writes the file as YAML in the layout of uap-core's `regexes.yaml`,
+    /// leaving out unset keys
+    pub fn to_yaml_string(&self) -> Result<String, YamlError> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+
+    /// Like `to_yaml_string`, writing to `writer`
+    pub fn to_yaml_writer(&self, writer: impl std::io::Write) -> Result<(), YamlError> {
+        Ok(serde_yaml::to_writer(writer, self)?)
+    }
+}
+
+/// Why a rule file couldn't be read, with its position when known
+#[derive(Debug)]
+pub struct YamlError {
+    error: serde_yaml::Error,
+    entry: Option<EntryLocation>,
+}
+
+/// The rule entry that failed to deserialize
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EntryLocation {
+    pub section: &'static str,
+    pub index: usize,
+    /// The entry's `regex`, when it is a string
+    pub regex: Option<String>,
+}
+
+impl YamlError {
+    /// The 1-based line the error was found on
+    #[must_use]
+    pub fn line(&self) -> Option<usize> {
+        self.error.location().map(|location| location.line())
+    }
+
+    /// The 1-based column the error was found at
+    #[must_use]
+    pub fn column(&self) -> Option<usize> {
+        self.error.location().map(|location| location.column())
+    }
+
+    /// The entry that failed, found by checking each entry separately once
+    /// the whole file has failed. Missing for syntax errors and problems
+    /// outside the rule entries.
+    #[must_use]
+    pub fn entry(&self) -> Option<&EntryLocation> {
+        self.entry.as_ref()
+    }
+
+    #[must_use]
+    pub fn inner(&self) -> &serde_yaml::Error {
+        &self.error
+    }
+}
+
+impl YamlError {
+    pub(crate) fn at_entry(error: serde_yaml::Error, entry: EntryLocation) -> YamlError {
+        YamlError {
+            error,
+            entry: Some(entry),
+        }
+    }
+}
+
+impl From<serde_yaml::Error> for YamlError {
+    fn from(error: serde_yaml::Error) -> Self {
+        YamlError { error, entry: None }
+    }
+}
+
+/// The `serde_yaml` message already names the field path, line and column, so
+/// only the regex of the failing entry is added
+impl fmt::Display for YamlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+        if let Some(EntryLocation {
+            regex: Some(regex), ..
+        }) = &self.entry
+        {
+            write!(f, " (entry regex `{regex}`)")?;
+        }
+        Ok(())
+    }
+}
+
+impl RegexFile {
+    /// Deserializes a rule file, pinpointing the failing entry on error
+    pub fn from_slice(bytes: &[u8]) -> Result<RegexFile, YamlError> {
+        // Only go through `Value`, losing error locations, when merging
+        let file = if bytes.windows(2).any(|window| window == b"<<") {
+            read_value(bytes).and_then(serde_yaml::from_value)
+        } else {
+            serde_yaml::from_slice(bytes)
+        };
+        file.map_err(|error| YamlError {
+            entry: find_failing_entry(bytes),
+            error,
+        })
+    }
+}
+
+/// Reads YAML with its `<<` merge keys applied
+pub(crate) fn read_value(bytes: &[u8]) -> Result<Value, serde_yaml::Error> {
+    let mut value = serde_yaml::from_slice(bytes)?;
+    apply_merge(&mut value);
+    Ok(value)
+}
+
+/// Resolves merge keys in place, which `serde_yaml` leaves as ordinary keys.
+/// Aliases are already copies of their anchors by now. A `<<` holding
+/// anything but a mapping or a sequence of mappings is kept, for strict mode
+/// to report.
+fn apply_merge(value: &mut Value) {
+    match value {
+        Value::Mapping(mapping) => {
+            for (_, nested) in mapping.iter_mut() {
+                apply_merge(nested);
+            }
+            let key = Value::from("<<");
+            match mapping.remove(&key) {
+                Some(Value::Mapping(source)) => merge_into(mapping, source),
+                Some(Value::Sequence(sources))
+                    if sources.iter().all(|source| source.as_mapping().is_some()) =>
+                {
+                    for source in sources {
+                        if let Value::Mapping(source) = source {
+                            merge_into(mapping, source);
+                        }
+                    }
+                }
+                Some(merged) => {
+                    mapping.insert(key, merged);
+                }
+                None => {}
+            }
+        }
+        Value::Sequence(sequence) => sequence.iter_mut().for_each(apply_merge),
+        _ => {}
+    }
+}
+
+fn merge_into(mapping: &mut Mapping, source: Mapping) {
+    for (key, value) in source {
+        mapping.entry(key).or_insert(value);
+    }
+}
+
+/// Deserializes each entry on its own to find the first that fails
+fn find_failing_entry(bytes: &[u8]) -> Option<EntryLocation> {
+    fn check<T: serde::de::DeserializeOwned>(entry: &Value) -> bool {
+        serde_yaml::from_value::<T>(entry.clone()).is_ok()
+    }
+
+    type Check = fn(&Value) -> bool;
+
+    let file = read_value(bytes).ok()?;
+    let checks: [(&str, Check); 3] = [
+        ("user_agent_parsers", check::<UserAgentParserEntry>),
+        ("os_parsers", check::<OSParserEntry>),
+        ("device_parsers", check::<DeviceParserEntry>),
+    ];
+
+    checks.iter().find_map(|&(section, check)| {
+        let entries = file.get(section)?.as_sequence()?;
+        let index = entries.iter().position(|entry| !check(entry))?;
+        let regex = entries[index]
+            .get("regex")
+            .and_then(Value::as_str)
+            .map(str::to_owned);
+        Some(EntryLocation {
+            section,
+            index,
+            regex,
+        })
+    })
+}
+
+fn empty_if_null<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::Deserialize<'de>,
+{
+    let entries: Option<Vec<T>> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(entries.unwrap_or_default())
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct UserAgentParserEntry {
     pub regex: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub family_replacement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub v1_replacement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub v2_replacement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub v3_replacement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub v4_replacement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<MatchQuality>,
+    /// Keys this crate doesn't know, such as annotations for the caller's own
+    /// use, carried onto the compiled matcher
+    #[serde(flatten)]
+    pub extras: BTreeMap<String, Value>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct OSParserEntry {
     pub regex: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub os_replacement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub os_v1_replacement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub os_v2_replacement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub os_v3_replacement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<MatchQuality>,
+    /// Keys this crate doesn't know, such as annotations for the caller's own
+    /// use, carried onto the compiled matcher
+    #[serde(flatten)]
+    pub extras: BTreeMap<String, Value>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct DeviceParserEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub regex_flag: Option<String>,
     pub regex: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub device_replacement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub brand_replacement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub model_replacement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<MatchQuality>,
+    /// Keys this crate doesn't know, such as annotations for the caller's own
+    /// use, carried onto the compiled matcher
+    #[serde(flatten)]
+    pub extras: BTreeMap<String, Value>,
+}
+
+impl DeviceParserEntry {
+    /// The regex with its `regex_flag`, such as `i`, applied as an inline
+    /// flag group
+    pub(crate) fn flagged_regex(&self) -> Cow<'_, str> {
+        match self.regex_flag.as_deref() {
+            None | Some("") => Cow::Borrowed(&self.regex),
+            Some(flag) => Cow::Owned(format!("(?{flag}){}", self.regex)),
+        }
+    }
+}
+
+const SECTIONS: [(&str, &[&str]); 3] = [
+    (
+        "user_agent_parsers",
+        &[
+            "regex",
+            "family_replacement",
+            "v1_replacement",
+            "v2_replacement",
+            "v3_replacement",
+            "v4_replacement",
+            "priority",
+            "quality",
+            "tests",
+        ],
+    ),
+    (
+        "os_parsers",
+        &[
+            "regex",
+            "os_replacement",
+            "os_v1_replacement",
+            "os_v2_replacement",
+            "os_v3_replacement",
+            "priority",
+            "quality",
+            "tests",
+        ],
+    ),
+    (
+        "device_parsers",
+        &[
+            "regex_flag",
+            "regex",
+            "device_replacement",
+            "brand_replacement",
+            "model_replacement",
+            "priority",
+            "quality",
+            "tests",
+        ],
+    ),
+];
+
+/// A problem found in a rule file by strict validation
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ValidationError {
+    /// A top level key other than the three rule sections
+    UnknownSection(String),
+    /// A key the entries of `section` don't have, such as a misspelled
+    /// replacement
+    UnknownField {
+        section: &'static str,
+        index: usize,
+        key: String,
+    },
+    MissingField {
+        section: &'static str,
+        index: usize,
+        key: &'static str,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::UnknownSection(key) => write!(f, "unknown section `{key}`"),
+            ValidationError::UnknownField {
+                section,
+                index,
+                key,
+            } => write!(f, "{section}[{index}]: unknown field `{key}`"),
+            ValidationError::MissingField {
+                section,
+                index,
+                key,
+            } => write!(f, "{section}[{index}]: missing field `{key}`"),
+        }
+    }
+}
+
+/// Checks that a rule file only uses the keys `RegexFile` knows about and
+/// that every entry has a `regex`. Anything with the wrong shape is left for
+/// deserialization to report.
+pub(crate) fn validate_strict(file: &Value) -> Result<(), ValidationError> {
+    let Some(file) = file.as_mapping() else {
+        return Ok(());
+    };
+
+    for (key, section) in file {
+        let key = key.as_str().unwrap_or_default();
+        let Some(&(name, fields)) = SECTIONS.iter().find(|(name, _)| *name == key) else {
+            return Err(ValidationError::UnknownSection(key.to_owned()));
+        };
+        let Some(entries) = section.as_sequence() else {
+            continue;
+        };
+
+        for (index, entry) in entries.iter().enumerate() {
+            let Some(entry) = entry.as_mapping() else {
+                continue;
+            };
+            for (key, _) in entry {
+                let key = key.as_str().unwrap_or_default();
+                if !fields.contains(&key) {
+                    return Err(ValidationError::UnknownField {
+                        section: name,
+                        index,
+                        key: key.to_owned(),
+                    });
+                }
+            }
+            if !entry.contains_key(&Value::from("regex")) {
+                return Err(ValidationError::MissingField {
+                    section: name,
+                    index,
+                    key: "regex",
+                });
+            }
+        }
+    }
+
+    Ok(())
 }
@@ -0,0 +1,141 @@
+//! Conversions between the forms a rule set comes in: uap-core style YAML,
+//! the same structure as JSON with the `json` feature, and the compiled
+//! cache of `UserAgentParser::to_cache_bytes`. Keys are written in a fixed
+//! order, so converted files diff cleanly.
+//!
+//! ```rust
+//! # use uaparser::{convert, InputFormat, OutputFormat, Parser, UserAgentParser};
+//! let yaml = std::fs::read("./src/core/regexes.yaml").unwrap();
+//! let cache = convert(&yaml, InputFormat::Yaml, OutputFormat::Cache).unwrap();
+//! let parser = UserAgentParser::from_cache_bytes(&cache).unwrap();
+//!
+//! assert_eq!(parser.parse("curl/8.4.0").user_agent.family, "curl");
+//! ```
+
+use derive_more::{Display, From};
+
+use super::{RegexFile, UserAgentParser, YamlError};
+
+#[derive(Debug, Display, From)]
+pub enum Error {
+    Yaml(YamlError),
+    #[cfg(feature = "json")]
+    Json(serde_json::Error),
+    Parser(super::Error),
+}
+
+/// The form of the rules given to `convert`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum InputFormat {
+    Yaml,
+    #[cfg(feature = "json")]
+    Json,
+    /// A cache written by `UserAgentParser::to_cache_bytes`
+    Cache,
+}
+
+/// The form `convert` writes the rules in
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum OutputFormat {
+    Yaml,
+    #[cfg(feature = "json")]
+    Json,
+    /// The cache read by `UserAgentParser::from_cache_bytes`, compiling the
+    /// rules with the default parser settings
+    Cache,
+}
+
+impl RegexFile {
+    /// Reads a rule file in the YAML layout of uap-core's `regexes.yaml`
+    pub fn from_yaml_bytes(bytes: &[u8]) -> Result<RegexFile, YamlError> {
+        RegexFile::from_slice(bytes)
+    }
+
+    /// Reads a rule file written as JSON, with the same keys as the YAML
+    #[cfg(feature = "json")]
+    pub fn from_json_bytes(bytes: &[u8]) -> Result<RegexFile, serde_json::Error> {
+        serde_json::from_slice(bytes)
+    }
+
+    /// Writes the file as pretty-printed JSON, leaving out unset keys
+    #[cfg(feature = "json")]
+    pub fn to_json_string(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Converts a rule set from one format to another. Converting a cache back
+/// to a rule file goes through `UserAgentParser::to_regex_file`.
+pub fn convert(
+    input: &[u8],
+    from: InputFormat,
+    to: OutputFormat,
+) -> Result<Vec<u8>, Error> {
+    let file = match from {
+        InputFormat::Yaml => RegexFile::from_yaml_bytes(input)?,
+        #[cfg(feature = "json")]
+        InputFormat::Json => RegexFile::from_json_bytes(input)?,
+        InputFormat::Cache => UserAgentParser::from_cache_bytes(input)?.to_regex_file().0,
+    };
+
+    match to {
+        OutputFormat::Yaml => Ok(file.to_yaml_string()?.into_bytes()),
+        #[cfg(feature = "json")]
+        OutputFormat::Json => Ok(file.to_json_string()?.into_bytes()),
+        OutputFormat::Cache => Ok(UserAgentParser::try_from(file)?.to_cache_bytes()?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    const REGEXES: &[u8] = include_bytes!("core/regexes.yaml");
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn yaml_json_yaml_round_trips() {
+        let json = convert(REGEXES, InputFormat::Yaml, OutputFormat::Json)
+            .expect("Conversion to JSON failed");
+        let yaml = convert(&json, InputFormat::Json, OutputFormat::Yaml)
+            .expect("Conversion to YAML failed");
+
+        let original = RegexFile::from_yaml_bytes(REGEXES).expect("Reading failed");
+        let converted = RegexFile::from_yaml_bytes(&yaml).expect("Reading failed");
+        assert_eq!(
+            converted.user_agent_parsers.len(),
+            original.user_agent_parsers.len()
+        );
+        assert_eq!(converted.os_parsers.len(), original.os_parsers.len());
+        assert_eq!(
+            converted.device_parsers.len(),
+            original.device_parsers.len()
+        );
+        assert_eq!(
+            converted.to_yaml_string().expect("Serialization failed"),
+            original.to_yaml_string().expect("Serialization failed")
+        );
+    }
+
+    #[test]
+    fn caches_convert_both_ways() {
+        let cache = convert(REGEXES, InputFormat::Yaml, OutputFormat::Cache)
+            .expect("Conversion to a cache failed");
+        let yaml = convert(&cache, InputFormat::Cache, OutputFormat::Yaml)
+            .expect("Conversion to YAML failed");
+
+        let original =
+            UserAgentParser::from_bytes(REGEXES).expect("Parser creation failed");
+        let converted =
+            UserAgentParser::from_bytes(&yaml).expect("Parser creation failed");
+        let user_agent = "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 \
+                          (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36";
+        assert_eq!(converted.parse(user_agent), original.parse(user_agent));
+
+        assert!(matches!(
+            convert(b"not a cache", InputFormat::Cache, OutputFormat::Yaml),
+            Err(Error::Parser(crate::Error::IncompatibleCache(_)))
+        ));
+    }
+}
@@ -0,0 +1,503 @@
+//! Composes realistic user agents from templates for the major browsers and
+//! platforms, along with the fields each should parse to, for load tests
+//! that need varied user agents without collecting real ones. Generators
+//! with the same seed and settings produce the same user agents.
+//!
+//! Every template parses as expected with the bundled rules. The expectations
+//! leave out what the templates can't pin down: the device of Firefox on
+//! Android, which leaves it out, and the Windows version past the major one,
+//! which every browser freezes at `10.0`.
+//!
+//! ```rust
+//! # use uaparser::*;
+//! # use uaparser::generate::UaGenerator;
+//! let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+//!
+//! for generated in UaGenerator::new(7).chrome_versions(110..=120).take(100) {
+//!     generated
+//!         .expected
+//!         .assert_matches_for(&generated.user_agent, &parser.parse(&generated.user_agent));
+//! }
+//! ```
+
+use std::ops::RangeInclusive;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::testing::ExpectedClient;
+
+/// A generated user agent and the fields it should parse to
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GeneratedUa {
+    pub user_agent: String,
+    pub expected: ExpectedClient,
+}
+
+/// The browsers user agents are generated for
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Browser {
+    Chrome,
+    Firefox,
+    Safari,
+}
+
+/// The platforms user agents are generated for
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Platform {
+    Windows,
+    MacOs,
+    Android,
+    Ios,
+}
+
+/// The browser and platform pairs with a template. Safari only runs on Apple
+/// platforms.
+const TEMPLATES: [(Browser, Platform); 10] = [
+    (Browser::Chrome, Platform::Windows),
+    (Browser::Chrome, Platform::MacOs),
+    (Browser::Chrome, Platform::Android),
+    (Browser::Chrome, Platform::Ios),
+    (Browser::Firefox, Platform::Windows),
+    (Browser::Firefox, Platform::MacOs),
+    (Browser::Firefox, Platform::Android),
+    (Browser::Firefox, Platform::Ios),
+    (Browser::Safari, Platform::MacOs),
+    (Browser::Safari, Platform::Ios),
+];
+
+/// Android devices as they appear in user agents, with the device family,
+/// brand and model they parse to
+const ANDROID_DEVICES: [(&str, &str, &str, &str); 4] = [
+    ("Pixel 8", "Pixel 8", "Google", "Pixel 8"),
+    ("Pixel 7a", "Pixel 7a", "Google", "Pixel 7a"),
+    ("SM-S911B", "Samsung SM-S911B", "Samsung", "SM-S911B"),
+    ("SM-A546B", "Samsung SM-A546B", "Samsung", "SM-A546B"),
+];
+
+/// Crawlers with their fixed user agents and the user agent family and
+/// version they parse to
+const BOTS: [(&str, &str, &str, &str); 3] = [
+    (
+        "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)",
+        "Googlebot",
+        "2",
+        "1",
+    ),
+    (
+        "Mozilla/5.0 (compatible; bingbot/2.0; +http://www.bing.com/bingbot.htm)",
+        "bingbot",
+        "2",
+        "0",
+    ),
+    (
+        "Mozilla/5.0 (compatible; YandexBot/3.0; +http://yandex.com/bots)",
+        "YandexBot",
+        "3",
+        "0",
+    ),
+];
+
+/// Generates user agents, as an endless iterator or one at a time
+///
+/// ```rust
+/// # use uaparser::generate::{Browser, Platform, UaGenerator};
+/// let mut generator = UaGenerator::new(42).firefox_versions(121..=121);
+/// let generated = generator.generate_for(Browser::Firefox, Platform::Windows).unwrap();
+///
+/// assert_eq!(
+///     generated.user_agent,
+///     "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:121.0) Gecko/20100101 Firefox/121.0"
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct UaGenerator {
+    rng: StdRng,
+    chrome: RangeInclusive<u32>,
+    firefox: RangeInclusive<u32>,
+    safari: RangeInclusive<u32>,
+    android: RangeInclusive<u32>,
+    ios: RangeInclusive<u32>,
+    bot_share: f64,
+}
+
+impl UaGenerator {
+    /// A generator seeded with `seed`, covering the browser and OS versions
+    /// of recent years, with one user agent in twenty from a crawler
+    #[must_use]
+    pub fn new(seed: u64) -> UaGenerator {
+        UaGenerator {
+            rng: StdRng::seed_from_u64(seed),
+            chrome: 100..=124,
+            firefox: 100..=125,
+            safari: 15..=17,
+            android: 10..=14,
+            ios: 15..=17,
+            bot_share: 0.05,
+        }
+    }
+
+    /// Sets the Chrome major versions to pick from
+    #[must_use]
+    pub fn chrome_versions(mut self, majors: RangeInclusive<u32>) -> Self {
+        self.chrome = majors;
+        self
+    }
+
+    /// Sets the Firefox major versions to pick from
+    #[must_use]
+    pub fn firefox_versions(mut self, majors: RangeInclusive<u32>) -> Self {
+        self.firefox = majors;
+        self
+    }
+
+    /// Sets the desktop Safari major versions to pick from. Safari on iOS
+    /// has the version of iOS.
+    #[must_use]
+    pub fn safari_versions(mut self, majors: RangeInclusive<u32>) -> Self {
+        self.safari = majors;
+        self
+    }
+
+    /// Sets the Android major versions to pick from
+    #[must_use]
+    pub fn android_versions(mut self, majors: RangeInclusive<u32>) -> Self {
+        self.android = majors;
+        self
+    }
+
+    /// Sets the iOS major versions to pick from
+    #[must_use]
+    pub fn ios_versions(mut self, majors: RangeInclusive<u32>) -> Self {
+        self.ios = majors;
+        self
+    }
+
+    /// Sets the share of user agents from crawlers, between 0 and 1
+    #[must_use]
+    pub fn bot_share(mut self, share: f64) -> Self {
+        self.bot_share = share.clamp(0.0, 1.0);
+        self
+    }
+
+    /// A user agent from a crawler or one of the browser templates, picked
+    /// at random
+    pub fn generate(&mut self) -> GeneratedUa {
+        if self.rng.gen_bool(self.bot_share) {
+            return self.generate_bot();
+        }
+        let (browser, platform) = TEMPLATES[self.rng.gen_range(0..TEMPLATES.len())];
+        self.template(browser, platform)
+    }
+
+    /// A user agent of `browser` on `platform`, `None` for Safari on
+    /// Windows and Android
+    pub fn generate_for(
+        &mut self,
+        browser: Browser,
+        platform: Platform,
+    ) -> Option<GeneratedUa> {
+        if !TEMPLATES.contains(&(browser, platform)) {
+            return None;
+        }
+        Some(self.template(browser, platform))
+    }
+
+    /// A crawler's user agent, which parses to the `Spider` device
+    pub fn generate_bot(&mut self) -> GeneratedUa {
+        let (user_agent, family, major, minor) = BOTS[self.rng.gen_range(0..BOTS.len())];
+        GeneratedUa {
+            user_agent: user_agent.to_owned(),
+            expected: ExpectedClient::new()
+                .ua_family(family)
+                .ua_major(major)
+                .ua_minor(minor)
+                .device_family("Spider"),
+        }
+    }
+
+    fn template(&mut self, browser: Browser, platform: Platform) -> GeneratedUa {
+        let (user_agent, expected) = match browser {
+            Browser::Chrome => self.chrome(platform),
+            Browser::Firefox => self.firefox(platform),
+            Browser::Safari => self.safari(platform),
+        };
+        GeneratedUa {
+            user_agent,
+            expected,
+        }
+    }
+
+    fn chrome(&mut self, platform: Platform) -> (String, ExpectedClient) {
+        if platform == Platform::Ios {
+            let (os, expected) = self.ios();
+            let major = self.major(self.chrome.clone());
+            let build = self.rng.gen_range(1_000..7_000);
+            let patch = self.rng.gen_range(0..200);
+            return (
+                format!(
+                    "Mozilla/5.0 ({os}) AppleWebKit/605.1.15 (KHTML, like Gecko) \
+                     CriOS/{major}.0.{build}.{patch} Mobile/15E148 Safari/604.1"
+                ),
+                expected
+                    .ua_family("Chrome Mobile iOS")
+                    .ua_major(major.as_str())
+                    .ua_minor("0")
+                    .ua_patch(build.to_string().as_str()),
+            );
+        }
+
+        let (os, expected, mobile, family) = match platform {
+            Platform::Android => {
+                let (os, expected) = self.android_device();
+                (os, expected, "Mobile ", "Chrome Mobile")
+            }
+            Platform::MacOs => {
+                let (os, expected) = mac_webkit();
+                (os, expected, "", "Chrome")
+            }
+            _ => {
+                let (os, expected) = windows();
+                (os, expected, "", "Chrome")
+            }
+        };
+        // Chrome reduces its version to the major one
+        let major = self.major(self.chrome.clone());
+        (
+            format!(
+                "Mozilla/5.0 ({os}) AppleWebKit/537.36 (KHTML, like Gecko) \
+                 Chrome/{major}.0.0.0 {mobile}Safari/537.36"
+            ),
+            expected
+                .ua_family(family)
+                .ua_major(major.as_str())
+                .ua_minor("0")
+                .ua_patch("0"),
+        )
+    }
+
+    fn firefox(&mut self, platform: Platform) -> (String, ExpectedClient) {
+        match platform {
+            Platform::Ios => {
+                let (os, expected) = self.ios();
+                let major = self.major(self.firefox.clone());
+                let minor = self.rng.gen_range(0..3).to_string();
+                (
+                    format!(
+                        "Mozilla/5.0 ({os}) AppleWebKit/605.1.15 (KHTML, like Gecko) \
+                         FxiOS/{major}.{minor} Mobile/15E148 Safari/605.1.15"
+                    ),
+                    expected
+                        .ua_family("Firefox iOS")
+                        .ua_major(major.as_str())
+                        .ua_minor(minor.as_str()),
+                )
+            }
+            Platform::Android => {
+                // Firefox leaves the device out on Android
+                let android = self.rng.gen_range(self.android.clone()).to_string();
+                let major = self.major(self.firefox.clone());
+                (
+                    format!(
+                        "Mozilla/5.0 (Android {android}; Mobile; rv:{major}.0) \
+                         Gecko/{major}.0 Firefox/{major}.0"
+                    ),
+                    ExpectedClient::new()
+                        .os_family("Android")
+                        .os_major(android.as_str())
+                        .ua_family("Firefox Mobile")
+                        .ua_major(major.as_str())
+                        .ua_minor("0"),
+                )
+            }
+            _ => {
+                let major = self.major(self.firefox.clone());
+                let (os, expected) = if platform == Platform::MacOs {
+                    // Firefox gives only two parts of the macOS version
+                    (
+                        "Macintosh; Intel Mac OS X 10.15".to_owned(),
+                        mac().os_patch(None),
+                    )
+                } else {
+                    windows()
+                };
+                (
+                    format!(
+                        "Mozilla/5.0 ({os}; rv:{major}.0) Gecko/20100101 Firefox/{major}.0"
+                    ),
+                    expected
+                        .ua_family("Firefox")
+                        .ua_major(major.as_str())
+                        .ua_minor("0"),
+                )
+            }
+        }
+    }
+
+    fn safari(&mut self, platform: Platform) -> (String, ExpectedClient) {
+        // Safari on iOS has the version of iOS
+        let majors = if platform == Platform::Ios {
+            self.ios.clone()
+        } else {
+            self.safari.clone()
+        };
+        let (major, minor) = (self.rng.gen_range(majors), self.rng.gen_range(0..5));
+        let (os, expected, mobile, family) = if platform == Platform::Ios {
+            let (os, expected) = ios(major, minor);
+            (os, expected, "Mobile/15E148 ", "Mobile Safari")
+        } else {
+            let (os, expected) = mac_webkit();
+            (os, expected, "", "Safari")
+        };
+        (
+            format!(
+                "Mozilla/5.0 ({os}) AppleWebKit/605.1.15 (KHTML, like Gecko) \
+                 Version/{major}.{minor} {mobile}Safari/604.1"
+            ),
+            expected
+                .ua_family(family)
+                .ua_major(major.to_string().as_str())
+                .ua_minor(minor.to_string().as_str()),
+        )
+    }
+
+    fn major(&mut self, majors: RangeInclusive<u32>) -> String {
+        self.rng.gen_range(majors).to_string()
+    }
+
+    fn ios(&mut self) -> (String, ExpectedClient) {
+        let major = self.rng.gen_range(self.ios.clone());
+        let minor = self.rng.gen_range(0..5);
+        ios(major, minor)
+    }
+
+    fn android_device(&mut self) -> (String, ExpectedClient) {
+        let android = self.rng.gen_range(self.android.clone()).to_string();
+        let (token, family, brand, model) =
+            ANDROID_DEVICES[self.rng.gen_range(0..ANDROID_DEVICES.len())];
+        (
+            format!("Linux; Android {android}; {token}"),
+            ExpectedClient::new()
+                .os_family("Android")
+                .os_major(android.as_str())
+                .device_family(family)
+                .device_brand(brand)
+                .device_model(model),
+        )
+    }
+}
+
+impl Iterator for UaGenerator {
+    type Item = GeneratedUa;
+
+    fn next(&mut self) -> Option<GeneratedUa> {
+        Some(self.generate())
+    }
+}
+
+fn windows() -> (String, ExpectedClient) {
+    (
+        "Windows NT 10.0; Win64; x64".to_owned(),
+        ExpectedClient::new()
+            .os_family("Windows")
+            .os_major("10")
+            .device_family("Other"),
+    )
+}
+
+/// A Mac, whose browsers freeze the macOS version at 10.15
+fn mac() -> ExpectedClient {
+    ExpectedClient::new()
+        .os_family("Mac OS X")
+        .os_major("10")
+        .os_minor("15")
+        .device_family("Mac")
+        .device_brand("Apple")
+        .device_model("Mac")
+}
+
+fn mac_webkit() -> (String, ExpectedClient) {
+    (
+        "Macintosh; Intel Mac OS X 10_15_7".to_owned(),
+        mac().os_patch("7"),
+    )
+}
+
+fn ios(major: u32, minor: u32) -> (String, ExpectedClient) {
+    (
+        format!("iPhone; CPU iPhone OS {major}_{minor} like Mac OS X"),
+        ExpectedClient::new()
+            .os_family("iOS")
+            .os_major(major.to_string().as_str())
+            .os_minor(minor.to_string().as_str())
+            .device_family("iPhone")
+            .device_brand("Apple")
+            .device_model("iPhone"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, UserAgentParser};
+
+    /// Every template parses as expected with the bundled rules, so the bar
+    /// only leaves room for rule changes to be investigated rather than
+    /// failing outright
+    #[test]
+    fn parser_classifies_generated_user_agents() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let generated: Vec<_> = UaGenerator::new(2024).take(2_000).collect();
+
+        let mismatched: Vec<_> = generated
+            .iter()
+            .filter(|generated| {
+                !generated
+                    .expected
+                    .mismatches(&parser.parse(&generated.user_agent))
+                    .is_empty()
+            })
+            .map(|generated| generated.user_agent.as_str())
+            .collect();
+        assert!(
+            mismatched.len() * 100 <= generated.len(),
+            "{} mismatched, such as {:?}",
+            mismatched.len(),
+            &mismatched[..mismatched.len().min(5)]
+        );
+    }
+
+    #[test]
+    fn seeds_reproduce_user_agents() {
+        let first: Vec<_> = UaGenerator::new(1).take(50).collect();
+        assert_eq!(UaGenerator::new(1).take(50).collect::<Vec<_>>(), first);
+        assert_ne!(UaGenerator::new(2).take(50).collect::<Vec<_>>(), first);
+
+        let mut generator = UaGenerator::new(1).chrome_versions(99..=99).bot_share(0.0);
+        for platform in [
+            Platform::Windows,
+            Platform::MacOs,
+            Platform::Android,
+            Platform::Ios,
+        ] {
+            let generated = generator
+                .generate_for(Browser::Chrome, platform)
+                .expect("Chrome runs everywhere");
+            assert!(
+                generated.user_agent.contains("Chrome/99.0.0.0")
+                    || generated.user_agent.contains("CriOS/99.0."),
+                "{}",
+                generated.user_agent
+            );
+        }
+        assert_eq!(
+            generator.generate_for(Browser::Safari, Platform::Android),
+            None
+        );
+        assert!(UaGenerator::new(1)
+            .bot_share(1.0)
+            .take(10)
+            .all(|generated| generated.user_agent.contains("bot")
+                || generated.user_agent.contains("Bot")));
+    }
+}
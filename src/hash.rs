@@ -0,0 +1,257 @@
+//! Stable, anonymized keys for user agents, for joining events without
+//! storing the raw strings.
+//!
+//! Keys are `BLAKE2b` with a 16 byte output, keyed with the caller's salt. The
+//! normalization and encoding described on each function are part of this
+//! crate's stability guarantee: the same input and salt produce the same key
+//! across crate versions, and any change to them is a breaking change.
+
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+use std::{convert::TryFrom, sync::LazyLock};
+
+use blake2::{
+    digest::{consts::U16, Digest, InvalidLength, KeyInit, Mac},
+    Blake2b, Blake2b512, Blake2bMac,
+};
+use regex::Regex;
+
+use crate::Client;
+
+// The pattern is a constant that `drops_build_numbers` compiles, so the
+// `expect` is unreachable
+#[allow(clippy::expect_used)]
+static BUILD_NUMBERS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(/\d+\.\d+)(?:\.\d+)+").expect("build number pattern is valid")
+});
+
+/// Controls how a user agent is normalized before hashing
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct KeyOptions {
+    /// Truncates product versions after their second component, so that
+    /// `Chrome/120.0.6099.144` and `Chrome/120.0.6099.199` share a key
+    pub drop_build_numbers: bool,
+}
+
+/// Returns a stable key for a user agent under the default `KeyOptions`.
+/// The user agent is trimmed and runs of whitespace collapse to one space.
+///
+/// ```rust
+/// # use uaparser::hash::ua_key;
+/// assert_eq!(ua_key("  curl/8.4.0 ", b"salt"), ua_key("curl/8.4.0", b"salt"));
+/// assert_ne!(ua_key("curl/8.4.0", b"salt"), ua_key("curl/8.4.0", b"pepper"));
+/// ```
+#[must_use]
+pub fn ua_key(user_agent: &str, salt: &[u8]) -> [u8; 16] {
+    ua_key_with_options(user_agent, salt, KeyOptions::default())
+}
+
+/// Like `ua_key`, with control over normalization
+#[must_use]
+pub fn ua_key_with_options(
+    user_agent: &str,
+    salt: &[u8],
+    options: KeyOptions,
+) -> [u8; 16] {
+    let mut normalized = user_agent.split_whitespace().collect::<Vec<_>>().join(" ");
+    if options.drop_build_numbers {
+        normalized = BUILD_NUMBERS.replace_all(&normalized, "$1").into_owned();
+    }
+
+    keyed_hash(salt, normalized.as_bytes())
+}
+
+/// Hashes only the parsed fields of a client, in the order user agent
+/// (family, major, minor, patch, patch minor), OS (family, major, minor,
+/// patch, patch minor) and device (family, brand, model). Each field is
+/// encoded as a `0` byte when missing, or a `1` byte, its length as a little
+/// endian `u32` and its UTF-8 bytes.
+///
+/// The fields are listed here rather than taken from `Client::flatten`, so
+/// that changes to the tabular outputs never change the keys.
+pub(crate) fn classification_key(client: &Client<'_>) -> [u8; 16] {
+    let user_agent = &client.user_agent;
+    let os = &client.os;
+    let device = &client.device;
+    let fields = [
+        Some(&*user_agent.family),
+        user_agent.major.as_deref(),
+        user_agent.minor.as_deref(),
+        user_agent.patch.as_deref(),
+        user_agent.patch_minor.as_deref(),
+        Some(&*os.family),
+        os.major.as_deref(),
+        os.minor.as_deref(),
+        os.patch.as_deref(),
+        os.patch_minor.as_deref(),
+        Some(&*device.family),
+        device.brand.as_deref(),
+        device.model.as_deref(),
+    ];
+
+    let mut encoded = Vec::new();
+    for field in &fields {
+        match field {
+            None => encoded.push(0),
+            Some(value) => {
+                let len = u32::try_from(value.len()).unwrap_or(u32::MAX);
+                encoded.push(1);
+                encoded.extend_from_slice(&len.to_le_bytes());
+                encoded.extend_from_slice(value.as_bytes());
+            }
+        }
+    }
+
+    keyed_hash(&[], &encoded)
+}
+
+/// `BLAKE2b-128` of `data`, keyed with `salt`. An empty salt gives the unkeyed
+/// hash, and salts over 64 bytes are first reduced with `BLAKE2b-512`.
+fn keyed_hash(salt: &[u8], data: &[u8]) -> [u8; 16] {
+    if salt.is_empty() {
+        return Blake2b::<U16>::digest(data).into();
+    }
+
+    let mut mac = match <Blake2bMac<U16> as KeyInit>::new_from_slice(salt) {
+        Ok(mac) => mac,
+        // Only keys over 64 bytes are rejected, and the reduced key is
+        // exactly 64 bytes, which the key type guarantees
+        Err(InvalidLength) => {
+            <Blake2bMac<U16> as KeyInit>::new(&Blake2b512::digest(salt))
+        }
+    };
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Device, UserAgent, OS};
+    use std::borrow::Cow;
+
+    const CHROME: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                          (KHTML, like Gecko) Chrome/120.0.6099.144 Safari/537.36";
+
+    #[test]
+    fn normalizes_whitespace() {
+        let padded = format!("\t {}  \n", CHROME.replace(") ", ")   "));
+
+        assert_eq!(ua_key(&padded, b"salt"), ua_key(CHROME, b"salt"));
+    }
+
+    #[test]
+    fn salts_change_keys() {
+        assert_ne!(ua_key(CHROME, b"salt"), ua_key(CHROME, b"pepper"));
+        assert_ne!(ua_key(CHROME, b""), ua_key(CHROME, b"salt"));
+        assert_ne!(ua_key(CHROME, &[7; 65]), ua_key(CHROME, &[7; 64]));
+        assert_eq!(
+            ua_key(CHROME, &[7; 65]),
+            ua_key(CHROME, &Blake2b512::digest([7; 65]))
+        );
+    }
+
+    #[test]
+    fn drops_build_numbers() {
+        let options = KeyOptions {
+            drop_build_numbers: true,
+        };
+        let newer = CHROME.replace("6099.144", "6099.199");
+
+        assert_ne!(ua_key(&newer, b"salt"), ua_key(CHROME, b"salt"));
+        assert_eq!(
+            ua_key_with_options(&newer, b"salt", options),
+            ua_key_with_options(CHROME, b"salt", options)
+        );
+    }
+
+    #[test]
+    fn keys_are_stable() {
+        // Pinned so that an accidental change to the normalization or the
+        // hash shows up here rather than in someone's joined tables
+        assert_eq!(
+            ua_key("curl/8.4.0", b"salt"),
+            [
+                0x3d, 0x0d, 0xa1, 0x25, 0x80, 0xa9, 0x24, 0x69, 0xe5, 0x4a, 0x45, 0x64,
+                0xdf, 0xad, 0xe2, 0x7d
+            ]
+        );
+    }
+
+    #[test]
+    fn classification_keys() {
+        let client = Client {
+            device: Device::default(),
+            os: OS {
+                family: Cow::Borrowed("Windows"),
+                major: Some(Cow::Borrowed("10")),
+                ..OS::default()
+            },
+            user_agent: UserAgent {
+                family: Cow::Borrowed("Chrome"),
+                major: Some(Cow::Borrowed("120")),
+                ..UserAgent::default()
+            },
+        };
+        let owned = Client {
+            os: OS {
+                family: Cow::Owned("Windows".to_owned()),
+                ..client.os.clone()
+            },
+            ..client.clone()
+        };
+        let other = Client {
+            os: OS {
+                major: None,
+                minor: Some(Cow::Borrowed("10")),
+                ..client.os.clone()
+            },
+            ..client.clone()
+        };
+
+        let patched = Client {
+            user_agent: UserAgent {
+                patch_minor: Some(Cow::Borrowed("144")),
+                ..client.user_agent.clone()
+            },
+            ..client.clone()
+        };
+
+        assert_eq!(client.classification_key(), owned.classification_key());
+        assert_ne!(client.classification_key(), other.classification_key());
+        assert_ne!(client.classification_key(), patched.classification_key());
+    }
+
+    #[test]
+    fn classification_keys_are_stable() {
+        let client = Client {
+            device: Device {
+                family: Cow::Borrowed("iPhone"),
+                brand: Some(Cow::Borrowed("Apple")),
+                model: Some(Cow::Borrowed("iPhone")),
+            },
+            os: OS {
+                family: Cow::Borrowed("iOS"),
+                major: Some(Cow::Borrowed("17")),
+                minor: Some(Cow::Borrowed("2")),
+                ..OS::default()
+            },
+            user_agent: UserAgent {
+                family: Cow::Borrowed("Mobile Safari"),
+                major: Some(Cow::Borrowed("17")),
+                minor: Some(Cow::Borrowed("2")),
+                ..UserAgent::default()
+            },
+        };
+
+        // Pinned so that a change to the field order or encoding shows up
+        // here, as for `ua_key`
+        assert_eq!(
+            client.classification_key(),
+            [
+                0xf0, 0xbf, 0x9b, 0xe6, 0xa6, 0xc2, 0xdf, 0x8f, 0xfe, 0xd1, 0xe6, 0x1e,
+                0x6b, 0x4f, 0x86, 0xf5
+            ]
+        );
+    }
+}
@@ -0,0 +1,496 @@
+//! Handles `User-Agent` header values that proxies folded together from
+//! duplicate headers, such as `curl/8.4.0, Mozilla/5.0 (...)`, which parse as
+//! junk when matched whole.
+//!
+//! ```rust
+//! # use uaparser::headers::{split_ua_values, HeaderValuePolicy};
+//! let folded = "curl/8.4.0, Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7)";
+//!
+//! assert_eq!(
+//!     split_ua_values(folded),
+//!     ["curl/8.4.0", "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7)"]
+//! );
+//! assert_eq!(HeaderValuePolicy::UseFirst.select(folded), "curl/8.4.0");
+//! ```
+
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+use super::{Client, Deserialize, Serialize};
+
+/// Friendly names of apps commonly seen embedding Android web views
+const APP_NAMES: &[(&str, &str)] = &[
+    ("com.facebook.katana", "Facebook"),
+    ("com.google.android.gm", "Gmail"),
+    ("com.instagram.android", "Instagram"),
+    ("com.twitter.android", "Twitter"),
+];
+
+/// The Android app whose web view sent a request, from the package name
+/// web views put in the `X-Requested-With` header
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct AppContext<'a> {
+    /// The package name, such as `com.twitter.android`
+    pub package: &'a str,
+    /// The app's name, for the few well-known packages
+    pub name: Option<&'static str>,
+}
+
+/// A parsed `Client` along with the app that embedded the browser, when the
+/// headers named one
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct HeaderClient<'a> {
+    pub client: Client<'a>,
+    pub app: Option<AppContext<'a>>,
+    /// The name of the header the user agent was read from, as sent, such
+    /// as `X-Original-User-Agent` when a `UaHeaderPolicy` preferred it.
+    /// `None` when no header of the policy was present.
+    pub source: Option<&'a str>,
+}
+
+/// The headers a user agent is read from, in order of preference, for
+/// proxies that replace the `User-Agent` with their own and keep the
+/// client's in a header such as `X-Original-User-Agent`. Names are matched
+/// in any case. The default reads only the `User-Agent`.
+///
+/// ```rust
+/// # use uaparser::headers::UaHeaderPolicy;
+/// let policy = UaHeaderPolicy::new(["X-Original-User-Agent", "User-Agent"]);
+/// let headers = [
+///     ("User-Agent", "CorpProxy/2.1"),
+///     ("X-Original-User-Agent", "curl/8.4.0"),
+/// ];
+///
+/// assert_eq!(
+///     policy.find(headers.iter().copied()),
+///     Some(("X-Original-User-Agent", "curl/8.4.0"))
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct UaHeaderPolicy(Vec<String>);
+
+impl Default for UaHeaderPolicy {
+    fn default() -> Self {
+        UaHeaderPolicy(vec!["User-Agent".to_owned()])
+    }
+}
+
+impl UaHeaderPolicy {
+    /// Reads the first of `names` that a request has, so `User-Agent` has to
+    /// be listed for it to be read at all
+    pub fn new(names: impl IntoIterator<Item = impl Into<String>>) -> UaHeaderPolicy {
+        UaHeaderPolicy(names.into_iter().map(Into::into).collect())
+    }
+
+    #[must_use]
+    pub fn names(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Finds the user agent among `(name, value)` header pairs, returning
+    /// the name of the header it came from along with it. Each value is
+    /// read as `find_user_agent` reads the `User-Agent`, taking the first
+    /// of any folded user agents, and headers left blank are passed over
+    /// for the next name. Of several headers with one name, the first wins.
+    pub fn find<'a>(
+        &self,
+        headers: impl Iterator<Item = (&'a str, &'a str)>,
+    ) -> Option<(&'a str, &'a str)> {
+        self.find_with(headers, HeaderValuePolicy::UseFirst)
+    }
+
+    /// Like `find`, with `value_policy` choosing among the user agents of
+    /// the preferred header, both those folded into one value and those
+    /// sent in several headers of that name
+    pub fn find_with<'a>(
+        &self,
+        headers: impl Iterator<Item = (&'a str, &'a str)>,
+        value_policy: HeaderValuePolicy,
+    ) -> Option<(&'a str, &'a str)> {
+        let mut found: Option<(usize, Vec<(&'a str, &'a str)>)> = None;
+        for (name, value) in headers {
+            let Some(rank) = self
+                .0
+                .iter()
+                .position(|preferred| name.trim().eq_ignore_ascii_case(preferred.trim()))
+            else {
+                continue;
+            };
+            if matches!(found, Some((best, _)) if best < rank) {
+                continue;
+            }
+            let values = split_ua_values(value);
+            if values.is_empty() {
+                continue;
+            }
+            let values = values.into_iter().map(|value| (name.trim(), value));
+            match &mut found {
+                Some((best, found_values)) if *best == rank => {
+                    found_values.extend(values);
+                }
+                _ => found = Some((rank, values.collect())),
+            }
+        }
+        let (_, values) = found?;
+        let index = value_policy.pick(values.iter().map(|(_, value)| *value))?;
+        Some(values[index])
+    }
+}
+
+/// Headers in which proxy browsers and transcoders pass on the user agent
+/// of the handset, in order of precedence. Opera Mini's names the phone the
+/// browser runs on, while `Device-Stock-UA` is the handset's own browser as
+/// reported by a transcoding proxy, so the browser's own header wins.
+const DEVICE_UA_HEADERS: &[&str] = &[
+    "x-operamini-phone-ua",
+    "x-ucbrowser-device-ua",
+    "device-stock-ua",
+];
+
+/// How `UserAgentParser::parse_header_pairs` uses the original device's
+/// user agent, as found by `effective_device_ua`
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum DeviceUaOverride {
+    /// Parses the `User-Agent` alone
+    #[default]
+    Ignore,
+    /// Takes the device from the original user agent, and the browser and
+    /// OS from the `User-Agent`
+    Device,
+    /// Parses the original user agent in place of the `User-Agent`
+    Full,
+}
+
+/// Which of several folded user agents to parse
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum HeaderValuePolicy {
+    /// The first header, usually the one set by the client itself
+    #[default]
+    UseFirst,
+    /// The last header, usually the one added closest to this service
+    UseLast,
+    /// The longest value, which tends to be the most specific, with ties
+    /// going to the first
+    UseLongest,
+}
+
+impl HeaderValuePolicy {
+    /// Picks one user agent from a header value, which is returned trimmed
+    /// when it holds a single user agent
+    #[must_use]
+    pub fn select(self, value: &str) -> &str {
+        let values = split_ua_values(value);
+        self.pick(values.iter().copied())
+            .map_or("", |index| values[index])
+    }
+
+    /// The index of the chosen user agent among `values`
+    fn pick<'a>(
+        self,
+        values: impl DoubleEndedIterator<Item = &'a str> + ExactSizeIterator,
+    ) -> Option<usize> {
+        let mut values = values.enumerate();
+        let picked = match self {
+            HeaderValuePolicy::UseFirst => values.next(),
+            HeaderValuePolicy::UseLast => values.next_back(),
+            HeaderValuePolicy::UseLongest => {
+                values.rev().max_by_key(|(_, value)| value.len())
+            }
+        };
+        picked.map(|(index, _)| index)
+    }
+}
+
+/// Finds the user agent among `(name, value)` header pairs, matching the
+/// name in any case. When there are several `User-Agent` headers, or one
+/// holding folded values, the first user agent wins.
+pub fn find_user_agent<'a>(
+    mut headers: impl Iterator<Item = (&'a str, &'a str)>,
+) -> Option<&'a str> {
+    headers
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("user-agent"))
+        .map(|(_, value)| HeaderValuePolicy::UseFirst.select(value))
+}
+
+/// Finds the user agent of the handset behind a proxy browser or
+/// transcoder among `(name, value)` header pairs, such as Opera Mini's
+/// `X-OperaMini-Phone-UA`, which describes the device while the
+/// `User-Agent` describes the proxy. Headers are matched by name in any
+/// case, and blank values are skipped.
+///
+/// When several of these headers are present, they take precedence in this
+/// order, whatever the order they were sent in:
+///
+/// 1. `X-OperaMini-Phone-UA`, from Opera Mini
+/// 2. `X-UCBrowser-Device-UA`, from UC Browser
+/// 3. `Device-Stock-UA`, from transcoding proxies
+///
+/// ```rust
+/// # use uaparser::headers::effective_device_ua;
+/// let headers = [
+///     ("User-Agent", "Opera/9.80 (J2ME/MIDP; Opera Mini/9.80 (S60; SymbOS; Opera Mobi/23.348; U; en) Presto/2.5.25 Version/10.54"),
+///     ("X-OperaMini-Phone-UA", "NokiaN97/21.1.107 (SymbianOS/9.4; Series60/5.0 Mozilla/5.0; Profile/MIDP-2.1)"),
+/// ];
+///
+/// assert_eq!(
+///     effective_device_ua(headers.iter().copied()),
+///     Some("NokiaN97/21.1.107 (SymbianOS/9.4; Series60/5.0 Mozilla/5.0; Profile/MIDP-2.1)")
+/// );
+/// ```
+pub fn effective_device_ua<'a>(
+    headers: impl Iterator<Item = (&'a str, &'a str)>,
+) -> Option<&'a str> {
+    headers
+        .filter_map(|(name, value)| {
+            let name = name.trim();
+            let rank = DEVICE_UA_HEADERS
+                .iter()
+                .position(|header| name.eq_ignore_ascii_case(header))?;
+            let value = value.trim();
+            (!value.is_empty()).then_some((rank, value))
+        })
+        .min_by_key(|&(rank, _)| rank)
+        .map(|(_, value)| value)
+}
+
+/// Finds the app context among `(name, value)` header pairs, from the first
+/// `X-Requested-With` header. `None` for the `XMLHttpRequest` value that
+/// JavaScript libraries send, and for anything else that isn't a package name.
+pub fn app_context<'a>(
+    mut headers: impl Iterator<Item = (&'a str, &'a str)>,
+) -> Option<AppContext<'a>> {
+    let (_, package) =
+        headers.find(|(name, _)| name.trim().eq_ignore_ascii_case("x-requested-with"))?;
+    let package = package.trim();
+    let is_package = package.contains('.')
+        && package
+            .split('.')
+            .all(|part| !part.is_empty() && part.bytes().all(is_package_byte));
+    if !is_package {
+        return None;
+    }
+
+    Some(AppContext {
+        package,
+        name: APP_NAMES
+            .iter()
+            .find(|(known, _)| *known == package)
+            .map(|(_, name)| *name),
+    })
+}
+
+/// Splits a header value into the user agents folded into it, trimmed and
+/// without empty ones. A comma only separates user agents outside of a
+/// parenthesized comment and when a `product/version` token follows it, so
+/// the commas in comments like `(KHTML, like Gecko)` are left alone.
+#[must_use]
+pub fn split_ua_values(value: &str) -> Vec<&str> {
+    let mut values = Vec::new();
+    let mut depth = 0_usize;
+    let mut start = 0;
+
+    for (index, byte) in value.bytes().enumerate() {
+        match byte {
+            b'(' => depth += 1,
+            b')' => depth = depth.saturating_sub(1),
+            b',' if depth == 0 && starts_with_product(&value[index + 1..]) => {
+                values.push(&value[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    values.push(&value[start..]);
+
+    values
+        .into_iter()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+/// Whether `rest` starts, after any whitespace, with a product token followed
+/// by a slash
+fn starts_with_product(rest: &str) -> bool {
+    let rest = rest.trim_start();
+    let token_len = rest.bytes().take_while(|&byte| is_token_byte(byte)).count();
+    token_len > 0 && rest.as_bytes().get(token_len) == Some(&b'/')
+}
+
+fn is_package_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+/// The token characters of RFC 9110, which product names are made of
+fn is_token_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAC_SAFARI: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) \
+                              AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.2 Safari/605.1.15";
+
+    #[test]
+    fn splits_folded_values() {
+        let folded = format!("curl/8.4.0, {MAC_SAFARI} ,okhttp/4.12.0");
+
+        assert_eq!(
+            split_ua_values(&folded),
+            ["curl/8.4.0", MAC_SAFARI, "okhttp/4.12.0"]
+        );
+        assert_eq!(HeaderValuePolicy::UseFirst.select(&folded), "curl/8.4.0");
+        assert_eq!(HeaderValuePolicy::UseLast.select(&folded), "okhttp/4.12.0");
+        assert_eq!(HeaderValuePolicy::UseLongest.select(&folded), MAC_SAFARI);
+    }
+
+    #[test]
+    fn keeps_commas_inside_comments() {
+        assert_eq!(split_ua_values(MAC_SAFARI), [MAC_SAFARI]);
+        assert_eq!(
+            split_ua_values("MyApp/1.0 (build 7, Foo/2.0), beta"),
+            ["MyApp/1.0 (build 7, Foo/2.0), beta"]
+        );
+    }
+
+    #[test]
+    fn finds_the_first_user_agent() {
+        let headers = [
+            ("Accept", "*/*"),
+            ("USER-AGENT", "curl/8.4.0, okhttp/4.12.0"),
+            ("user-agent", "Wget/1.21"),
+        ];
+
+        assert_eq!(find_user_agent(headers.iter().copied()), Some("curl/8.4.0"));
+        assert_eq!(find_user_agent(headers[..1].iter().copied()), None);
+    }
+
+    #[test]
+    fn device_ua_headers_by_precedence() {
+        let headers = [
+            ("Device-Stock-UA", "Stock/1.0"),
+            ("x-operamini-phone-ua", " Phone/2.0 "),
+            ("X-OperaMini-Phone-UA", "Phone/3.0"),
+        ];
+
+        assert_eq!(
+            effective_device_ua(headers.iter().copied()),
+            Some("Phone/2.0")
+        );
+        assert_eq!(
+            effective_device_ua(headers[..1].iter().copied()),
+            Some("Stock/1.0")
+        );
+        assert_eq!(
+            effective_device_ua([("X-OperaMini-Phone-UA", " ")].iter().copied()),
+            None
+        );
+        assert_eq!(effective_device_ua(std::iter::empty()), None);
+    }
+
+    #[test]
+    fn ua_header_policy_prefers_listed_order() {
+        let policy = UaHeaderPolicy::new(["X-Original-User-Agent", "User-Agent"]);
+        let headers = [
+            ("User-Agent", "CorpProxy/2.1"),
+            ("x-original-user-agent", " "),
+            ("X-ORIGINAL-USER-AGENT", "curl/8.4.0, Wget/1.21"),
+        ];
+
+        assert_eq!(
+            policy.find(headers.iter().copied()),
+            Some(("X-ORIGINAL-USER-AGENT", "curl/8.4.0"))
+        );
+        assert_eq!(
+            policy.find(headers[..2].iter().copied()),
+            Some(("User-Agent", "CorpProxy/2.1"))
+        );
+        assert_eq!(
+            UaHeaderPolicy::default().find(headers.iter().copied()),
+            Some(("User-Agent", "CorpProxy/2.1"))
+        );
+        assert_eq!(
+            UaHeaderPolicy::new(["X-Device-User-Agent"]).find(headers.iter().copied()),
+            None
+        );
+    }
+
+    #[test]
+    fn ua_header_policy_chooses_among_values() {
+        let policy = UaHeaderPolicy::default();
+        let headers = [
+            ("User-Agent", "curl/8.4.0, Wget/1.21"),
+            ("Accept", "*/*"),
+            ("user-agent", MAC_SAFARI),
+        ];
+
+        let find = |value_policy| policy.find_with(headers.iter().copied(), value_policy);
+        assert_eq!(
+            find(HeaderValuePolicy::UseFirst),
+            Some(("User-Agent", "curl/8.4.0"))
+        );
+        assert_eq!(
+            find(HeaderValuePolicy::UseLast),
+            Some(("user-agent", MAC_SAFARI))
+        );
+        assert_eq!(
+            find(HeaderValuePolicy::UseLongest),
+            Some(("user-agent", MAC_SAFARI))
+        );
+        assert_eq!(
+            policy.find_with(headers[..2].iter().copied(), HeaderValuePolicy::UseLast),
+            Some(("User-Agent", "Wget/1.21"))
+        );
+    }
+
+    #[test]
+    fn app_context_from_package_names() {
+        let headers = [
+            ("User-Agent", MAC_SAFARI),
+            ("X-Requested-With", " com.twitter.android "),
+        ];
+        assert_eq!(
+            app_context(headers.iter().copied()),
+            Some(AppContext {
+                package: "com.twitter.android",
+                name: Some("Twitter"),
+            })
+        );
+
+        let headers = [("x-requested-with", "com.example.shop_app")];
+        assert_eq!(
+            app_context(headers.iter().copied()),
+            Some(AppContext {
+                package: "com.example.shop_app",
+                name: None,
+            })
+        );
+    }
+
+    #[test]
+    fn no_app_context_without_a_package() {
+        let ajax = [("X-Requested-With", "XMLHttpRequest")];
+        assert_eq!(app_context(ajax.iter().copied()), None);
+        assert_eq!(
+            app_context([("User-Agent", MAC_SAFARI)].iter().copied()),
+            None
+        );
+        assert_eq!(app_context(std::iter::empty()), None);
+    }
+
+    #[test]
+    fn single_values_are_trimmed() {
+        assert_eq!(
+            split_ua_values(" Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) "),
+            ["Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7)"]
+        );
+        assert!(split_ua_values("  ").is_empty());
+        assert_eq!(HeaderValuePolicy::UseLongest.select(""), "");
+        assert_eq!(
+            HeaderValuePolicy::UseLongest.select("Foo/1, Bar/2"),
+            "Foo/1"
+        );
+    }
+}
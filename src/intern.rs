@@ -0,0 +1,237 @@
+//! Shares one allocation between equal parse results, for long runs in
+//! which millions of user agents parse to a few thousand distinct clients.
+//! Each distinct `Client` is kept once, behind an `Arc`, instead of as a
+//! fresh set of strings per parse.
+//!
+//! ```rust
+//! # use std::sync::Arc;
+//! # use uaparser::*;
+//! # use uaparser::intern::InterningParser;
+//! let parser = Arc::new(UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap());
+//! let interning = InterningParser::new(parser, 10_000);
+//!
+//! let first = interning.parse_interned("curl/8.4.0");
+//! let second = interning.parse_interned("curl/8.4.0");
+//! assert!(Arc::ptr_eq(&first, &second));
+//! assert_eq!(interning.interner().len(), 1);
+//! ```
+
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    hash::BuildHasher,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{Client, Parser, UserAgentParser};
+
+/// A pool of distinct clients, holding at most `capacity`. Clients are told
+/// apart by their `Eq` and `Hash` implementations, so two clients share an
+/// `Arc` exactly when they are equal.
+///
+/// When the pool is full, the clients no one else holds an `Arc` to any
+/// more are evicted. When every client is still in use, new clients are
+/// returned in an `Arc` of their own without being pooled, and eviction
+/// isn't tried again for as many new clients as the capacity, so that the
+/// scan for unused clients costs a constant amount per new client.
+#[derive(Debug)]
+pub struct ClientInterner {
+    capacity: usize,
+    hasher: RandomState,
+    pool: Mutex<Pool>,
+}
+
+#[derive(Debug, Default)]
+struct Pool {
+    /// The clients by hash, with the rare collisions side by side
+    clients: HashMap<u64, Vec<Arc<Client<'static>>>>,
+    len: usize,
+    /// New clients to go before trying to evict again, after an eviction
+    /// found every client in use
+    eviction_backoff: usize,
+}
+
+impl ClientInterner {
+    #[must_use]
+    pub fn new(capacity: usize) -> ClientInterner {
+        ClientInterner {
+            capacity,
+            hasher: RandomState::new(),
+            pool: Mutex::default(),
+        }
+    }
+
+    /// The pooled client equal to `client`, which is copied into the pool
+    /// when there is none. Only a client not seen before is copied.
+    pub fn intern(&self, client: &Client<'_>) -> Arc<Client<'static>> {
+        let hash = self.hasher.hash_one(client);
+        let mut pool = self.pool();
+        if let Some(pooled) = pool
+            .clients
+            .get(&hash)
+            .and_then(|bucket| bucket.iter().find(|pooled| ***pooled == *client))
+        {
+            return Arc::clone(pooled);
+        }
+
+        let interned = Arc::new(client.clone().into_owned());
+        if pool.len >= self.capacity {
+            if pool.eviction_backoff > 0 {
+                pool.eviction_backoff -= 1;
+            } else {
+                pool.evict_unused();
+                if pool.len >= self.capacity {
+                    pool.eviction_backoff = self.capacity;
+                }
+            }
+        }
+        if pool.len < self.capacity {
+            pool.clients
+                .entry(hash)
+                .or_default()
+                .push(Arc::clone(&interned));
+            pool.len += 1;
+        }
+        interned
+    }
+
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of distinct clients pooled
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.pool().len
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn pool(&self) -> MutexGuard<'_, Pool> {
+        self.pool.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Pool {
+    /// Drops the clients only the pool holds
+    fn evict_unused(&mut self) {
+        self.clients.retain(|_, bucket| {
+            bucket.retain(|client| Arc::strong_count(client) > 1);
+            !bucket.is_empty()
+        });
+        self.len = self.clients.values().map(Vec::len).sum();
+    }
+}
+
+/// Wraps a shared `UserAgentParser` with a `ClientInterner`, so equal parse
+/// results come back as the same `Arc`
+#[derive(Debug)]
+pub struct InterningParser {
+    parser: Arc<UserAgentParser>,
+    interner: ClientInterner,
+}
+
+impl InterningParser {
+    /// Pools at most `capacity` distinct clients
+    #[must_use]
+    pub fn new(parser: Arc<UserAgentParser>, capacity: usize) -> InterningParser {
+        InterningParser {
+            parser,
+            interner: ClientInterner::new(capacity),
+        }
+    }
+
+    /// Parses `user_agent` as `parse` does, returning the pooled client
+    /// equal to the result
+    pub fn parse_interned(&self, user_agent: &str) -> Arc<Client<'static>> {
+        self.interner.intern(&self.parser.parse(user_agent))
+    }
+
+    #[must_use]
+    pub fn parser(&self) -> &Arc<UserAgentParser> {
+        &self.parser
+    }
+
+    #[must_use]
+    pub fn interner(&self) -> &ClientInterner {
+        &self.interner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CORPUS: &str = include_str!("../tests/fixtures/traffic.txt");
+
+    #[test]
+    fn skewed_traffic_shares_clients() {
+        let parser = Arc::new(
+            UserAgentParser::from_yaml("./src/core/regexes.yaml")
+                .expect("Parser creation failed"),
+        );
+        let interning = InterningParser::new(parser, 1_000);
+        let lines: Vec<&str> = CORPUS.lines().collect();
+        // The first user agent makes up half the traffic
+        let corpus: Vec<&str> = (0..2_000)
+            .map(|n| {
+                if n % 2 == 0 {
+                    lines[0]
+                } else {
+                    lines[n % lines.len()]
+                }
+            })
+            .collect();
+
+        let clients: Vec<_> = corpus
+            .iter()
+            .map(|user_agent| interning.parse_interned(user_agent))
+            .collect();
+
+        assert!(interning.interner().len() <= 13);
+        assert!(interning.interner().len() * 100 < clients.len());
+        for (user_agent, client) in corpus.iter().zip(&clients) {
+            assert_eq!(**client, interning.parser().parse(user_agent));
+        }
+        assert!(Arc::ptr_eq(&clients[0], &clients[2]));
+        let again = interning.parse_interned(lines[5]);
+        assert!(Arc::ptr_eq(&again, &clients[5]));
+    }
+
+    #[test]
+    fn full_pools_evict_unused_clients() {
+        let interner = ClientInterner::new(2);
+        let client = |family: &str| {
+            let mut client = Client::default();
+            client.user_agent.family = family.to_owned().into();
+            client
+        };
+
+        let held = interner.intern(&client("a"));
+        drop(interner.intern(&client("b")));
+        assert_eq!(interner.len(), 2);
+
+        let c = interner.intern(&client("c"));
+        assert_eq!(interner.len(), 2);
+        assert!(Arc::ptr_eq(&interner.intern(&client("a")), &held));
+
+        // Every pooled client is in use, so `d` isn't pooled
+        let d = interner.intern(&client("d"));
+        assert_eq!(interner.len(), 2);
+        assert!(!Arc::ptr_eq(&interner.intern(&client("d")), &d));
+        assert!(Arc::ptr_eq(&interner.intern(&client("c")), &c));
+
+        // Eviction waits out its backoff before finding `c` unused
+        drop(c);
+        let e = interner.intern(&client("e"));
+        assert!(!Arc::ptr_eq(&interner.intern(&client("e")), &e));
+        let f = interner.intern(&client("f"));
+        assert!(Arc::ptr_eq(&interner.intern(&client("f")), &f));
+        assert!(Arc::ptr_eq(&interner.intern(&client("a")), &held));
+    }
+}
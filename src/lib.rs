@@ -18,6 +18,16 @@
 //! assert_eq!(client.os, os);
 //! assert_eq!(client.user_agent, user_agent);
 //! ```
+//!
+//! On WebAssembly targets there is no filesystem to load rules from, so
+//! `from_yaml`, `from_file`, `from_cache_path_mmap` and their builder
+//! counterparts are left out, and parsers are built with `from_bytes`,
+//! `try_from` or `from_cache_bytes` from rules embedded with
+//! `include_bytes!`. `examples/wasm.rs` shows the supported surface. Every
+//! feature except `rocket`, `warp` and `proptest` builds for
+//! `wasm32-unknown-unknown`. Neither target can spawn the thread
+//! `BackgroundParser` compiles on, and `wasm32-unknown-unknown` has no clock
+//! for `on_slow_parse` hooks to time parses with.
 
 #![deny(clippy::all)]
 #![deny(clippy::pedantic)]
@@ -27,20 +37,116 @@
 
 use serde_derive::{Deserialize, Serialize};
 
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "test-util")]
+pub mod bench_support;
+pub mod bots;
+mod brand;
 mod client;
+pub mod client_hints;
+#[cfg(feature = "csv")]
+pub mod csv;
 mod device;
+mod device_names;
+#[cfg(feature = "apple-devices")]
+pub mod devices;
+pub mod diff;
+#[cfg(feature = "json")]
+pub mod enrich;
+pub mod extras;
+pub mod families;
 mod file;
+pub mod formats;
+#[cfg(feature = "test-util")]
+pub mod generate;
+#[cfg(feature = "hash")]
+pub mod hash;
+pub mod headers;
+pub mod intern;
+pub mod logs;
+mod merge;
+pub mod normalize;
 mod os;
+pub mod otel;
+pub mod parse_cache;
 mod parser;
+pub mod policy;
+mod post_process;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+pub mod report;
+#[cfg(feature = "rocket")]
+pub mod rocket;
+pub mod sanitize;
+#[cfg(feature = "schemars")]
+pub mod schema;
+mod spans;
+pub mod stats;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+mod synthetic;
+#[cfg(feature = "test-util")]
+pub mod testing;
+pub mod tokens;
+pub mod uaparser_js;
 mod user_agent;
-
-pub use parser::{Error, UserAgentParser};
-
+#[cfg(feature = "valuable")]
+mod valuable;
+mod version;
+#[cfg(feature = "warp")]
+pub mod warp;
+
+pub use parser::{
+    validate, AdaptiveParser, ApplyReport, BackgroundParser, CacheMismatch, Categories,
+    ClientBuf, ClientMetadata, DeltaMismatch, DeviceMatcher, DeviceSubParser, Error,
+    ExportWarning, FamilyOrigin, InlineTestMiss, InlineTestReport, InlineTestResult,
+    LazyClient, MatchQuality, MatchStrategies, MatchStrategy, OSMatcher, OSSubParser,
+    ParserStats, PatternPreprocessor, Position, PreprocessMode, PruneReport, ReorderMode,
+    RuleCounts, RuleSummary, ScoredClient, SelfTestCase, SelfTestFailure, SelfTestMiss,
+    SharedBaseParser, SlowParseEvent, SlowParseHook, SourceInfo, SubParsers,
+    TenantParser, ThreadCacheStats, ThreadLocalCachingParser, UserAgentMatcher,
+    UserAgentParser, UserAgentParserBuilder, UserAgentSubParser, WhileCompiling,
+    CACHE_FORMAT_VERSION, SELF_TEST_CASES,
+};
+
+#[cfg(feature = "rkyv")]
+pub use parser::{ArchivedRules, Rules};
+
+pub use brand::BrandNormalizer;
 pub use client::Client;
 pub use device::Device;
+pub use device_names::{DeviceNameResolver, Error as DeviceNameError};
+pub use file::{EntryLocation, RegexFile, ValidationError, YamlError};
+pub use formats::{convert, InputFormat, OutputFormat};
+pub use merge::{MergeStrategy, Side};
+pub use normalize::InputNormalization;
 pub use os::OS;
+pub use post_process::{CapitalizeBrand, FamilyAliases, PostProcessor, PostProcessors};
+pub use spans::{ClientSpans, DeviceSpans, OSSpans, UserAgentSpans};
 pub use user_agent::UserAgent;
-
+#[cfg(feature = "semver")]
+pub use version::coerce_semver;
+pub use version::Version;
+
+/// Parses user agents into their parts. The trait is object safe, so
+/// parsers can be passed around as `Box<dyn Parser>` or `Arc<dyn Parser>`,
+/// and it is implemented for references, `Box` and `Arc` of any parser.
+///
+/// ```rust
+/// # use uaparser::*;
+/// # use std::sync::Arc;
+/// fn family<P: Parser>(parser: P, user_agent: &str) -> String {
+///     parser.parse_user_agent(user_agent).family.into_owned()
+/// }
+///
+/// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+/// assert_eq!(family(&parser, "curl/8.4.0"), "curl");
+///
+/// let boxed: Box<dyn Parser> = Box::new(parser);
+/// assert_eq!(family(Arc::new(boxed), "curl/8.4.0"), "curl");
+/// ```
 pub trait Parser {
     fn parse<'a>(&self, user_agent: &'a str) -> Client<'a>;
     fn parse_device<'a>(&self, user_agent: &'a str) -> Device<'a>;
@@ -48,7 +154,118 @@ pub trait Parser {
     fn parse_user_agent<'a>(&self, user_agent: &'a str) -> UserAgent<'a>;
 }
 
-pub(crate) trait SubParser<'a> {
+macro_rules! forward_parser {
+    ($($wrapper:ty),*) => {$(
+        impl<P: Parser + ?Sized> Parser for $wrapper {
+            fn parse<'a>(&self, user_agent: &'a str) -> Client<'a> {
+                (**self).parse(user_agent)
+            }
+
+            fn parse_device<'a>(&self, user_agent: &'a str) -> Device<'a> {
+                (**self).parse_device(user_agent)
+            }
+
+            fn parse_os<'a>(&self, user_agent: &'a str) -> OS<'a> {
+                (**self).parse_os(user_agent)
+            }
+
+            fn parse_user_agent<'a>(&self, user_agent: &'a str) -> UserAgent<'a> {
+                (**self).parse_user_agent(user_agent)
+            }
+        }
+    )*};
+}
+
+forward_parser!(&P, Box<P>, std::sync::Arc<P>);
+
+/// Like `Parser`, but with results that own their text and so don't borrow
+/// the user agent. Prefer `Parser`, which avoids copying; use this trait when
+/// results must outlive the user agent, such as when they are sent through a
+/// channel or returned from behind a trait object.
+///
+/// Every parser in this crate implements it. `parse_cache::CachedParser`
+/// hands out the clients its cache gives as they are, and
+/// `ThreadLocalCachingParser` copies its cached results, while the others
+/// copy what their `Parser` results borrow. Other `Parser`s can implement it the same way,
+/// with `into_owned`.
+///
+/// ```rust
+/// # use uaparser::*;
+/// let parser: Box<dyn OwnedParser + Send + Sync> =
+///     Box::new(UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap());
+/// let client = {
+///     let user_agent = String::from("curl/8.4.0");
+///     parser.parse_owned(&user_agent)
+/// };
+///
+/// assert_eq!(client.user_agent.family, "curl");
+/// ```
+pub trait OwnedParser {
+    fn parse_owned(&self, user_agent: &str) -> Client<'static>;
+    fn parse_device_owned(&self, user_agent: &str) -> Device<'static>;
+    fn parse_os_owned(&self, user_agent: &str) -> OS<'static>;
+    fn parse_user_agent_owned(&self, user_agent: &str) -> UserAgent<'static>;
+}
+
+macro_rules! forward_owned_parser {
+    ($($wrapper:ty),*) => {$(
+        impl<P: OwnedParser + ?Sized> OwnedParser for $wrapper {
+            fn parse_owned(&self, user_agent: &str) -> Client<'static> {
+                (**self).parse_owned(user_agent)
+            }
+
+            fn parse_device_owned(&self, user_agent: &str) -> Device<'static> {
+                (**self).parse_device_owned(user_agent)
+            }
+
+            fn parse_os_owned(&self, user_agent: &str) -> OS<'static> {
+                (**self).parse_os_owned(user_agent)
+            }
+
+            fn parse_user_agent_owned(&self, user_agent: &str) -> UserAgent<'static> {
+                (**self).parse_user_agent_owned(user_agent)
+            }
+        }
+    )*};
+}
+
+forward_owned_parser!(&P, Box<P>, std::sync::Arc<P>);
+
+/// Implements `OwnedParser` by copying the results of `Parser`
+macro_rules! owned_by_copying {
+    ($($parser:ty),*) => {$(
+        impl OwnedParser for $parser {
+            fn parse_owned(&self, user_agent: &str) -> Client<'static> {
+                self.parse(user_agent).into_owned()
+            }
+
+            fn parse_device_owned(&self, user_agent: &str) -> Device<'static> {
+                self.parse_device(user_agent).into_owned()
+            }
+
+            fn parse_os_owned(&self, user_agent: &str) -> OS<'static> {
+                self.parse_os(user_agent).into_owned()
+            }
+
+            fn parse_user_agent_owned(&self, user_agent: &str) -> UserAgent<'static> {
+                self.parse_user_agent(user_agent).into_owned()
+            }
+        }
+    )*};
+}
+
+owned_by_copying!(
+    UserAgentParser,
+    AdaptiveParser,
+    BackgroundParser,
+    SharedBaseParser,
+    TenantParser
+);
+
+/// Matches a user agent for one category, as the regex matchers do. Custom
+/// implementations can be added to a parser with
+/// `UserAgentParser::register_device_subparser` and its siblings.
+pub trait SubParser<'a> {
     type Item;
     fn try_parse(&self, text: &'a str) -> Option<Self::Item>;
 }
@@ -272,6 +489,71 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parser_wrappers_forward() {
+        fn classify<P: Parser>(parser: P, ua: &str) -> (String, String, String) {
+            let client = parser.parse(ua);
+            assert_eq!(parser.parse_device(ua), client.device);
+            assert_eq!(parser.parse_os(ua), client.os);
+            assert_eq!(parser.parse_user_agent(ua), client.user_agent);
+            (
+                client.device.family.into_owned(),
+                client.os.family.into_owned(),
+                client.user_agent.family.into_owned(),
+            )
+        }
+
+        let ua = "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 \
+                  (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36";
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let expected = classify(&parser, ua);
+        assert_eq!(expected.2, "Chrome Mobile");
+
+        let arc = std::sync::Arc::new(parser);
+        assert_eq!(classify(&*arc, ua), expected);
+        assert_eq!(classify(std::sync::Arc::clone(&arc), ua), expected);
+        let parser = std::sync::Arc::try_unwrap(arc).expect("No other references");
+
+        let boxed: Box<dyn Parser> = Box::new(parser);
+        assert_eq!(classify(&boxed, ua), expected);
+        assert_eq!(classify(&*boxed, ua), expected);
+        assert_eq!(classify(boxed, ua), expected);
+    }
+
+    #[test]
+    fn owned_parsers_cross_threads() {
+        struct Plugin {
+            parser: Box<dyn OwnedParser + Send + Sync>,
+        }
+
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let expected = parser.parse("curl/8.4.0").into_owned();
+        let plugin = std::sync::Arc::new(Plugin {
+            parser: Box::new(parser),
+        });
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let worker = {
+            let plugin = std::sync::Arc::clone(&plugin);
+            std::thread::spawn(move || {
+                let user_agent = String::from("curl/8.4.0");
+                sender
+                    .send(plugin.parser.parse_owned(&user_agent))
+                    .expect("Receiver dropped");
+            })
+        };
+        worker.join().expect("Worker panicked");
+
+        let client = receiver.recv().expect("Sender dropped");
+        assert_eq!(client, expected);
+        assert_eq!(
+            plugin.parser.parse_user_agent_owned("curl/8.4.0"),
+            expected.user_agent
+        );
+    }
+
     fn print_failure<T: Debug, F: Debug>(got: &T, expected: &F) {
         println!(
             r" --- Failed Test Case ----
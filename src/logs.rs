@@ -0,0 +1,244 @@
+//! Splits access log lines in the NCSA common and combined formats, as
+//! written by Apache and nginx, so the user agent can be parsed.
+//!
+//! ```rust
+//! # use uaparser::logs::parse_combined_line;
+//! let record = parse_combined_line(
+//!     r#"203.0.113.7 - - [10/Oct/2023:13:55:36 +0000] "GET / HTTP/1.1" 200 2326 "-" "curl/8.4.0""#,
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(record.status, 200);
+//! assert_eq!(record.referer, None);
+//! assert_eq!(record.user_agent, Some("curl/8.4.0"));
+//! ```
+
+use crate::{Client, Parser, UserAgentParser};
+
+/// The fields of a single access log line. Quoted fields are borrowed as
+/// written, so any `\"` escapes inside them are kept, and `-` placeholders
+/// become `None`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CombinedLogRecord<'a> {
+    pub remote_host: &'a str,
+    pub ident: Option<&'a str>,
+    pub user: Option<&'a str>,
+    /// The timestamp without its surrounding brackets
+    pub time: &'a str,
+    pub request: Option<&'a str>,
+    pub status: u16,
+    pub bytes: Option<u64>,
+    /// Missing in the common log format
+    pub referer: Option<&'a str>,
+    /// Missing in the common log format
+    pub user_agent: Option<&'a str>,
+}
+
+/// Splits a common or combined format log line into its fields, returning
+/// `None` for lines that don't follow the format. Fields after the user
+/// agent, which some servers are configured to add, are ignored.
+#[must_use]
+pub fn parse_combined_line(line: &str) -> Option<CombinedLogRecord<'_>> {
+    let mut fields = Fields { rest: line.trim() };
+
+    let remote_host = fields.token()?;
+    let ident = placeholder(fields.token()?);
+    let user = placeholder(fields.token()?);
+    let time = fields.bracketed()?;
+    let request = placeholder(fields.quoted()?);
+    let status = fields.token()?.parse().ok()?;
+    let bytes = match fields.token()? {
+        "-" => None,
+        bytes => Some(bytes.parse().ok()?),
+    };
+
+    let (referer, user_agent) = if fields.rest.is_empty() {
+        (None, None)
+    } else {
+        (placeholder(fields.quoted()?), placeholder(fields.quoted()?))
+    };
+
+    Some(CombinedLogRecord {
+        remote_host,
+        ident,
+        user,
+        time,
+        request,
+        status,
+        bytes,
+        referer,
+        user_agent,
+    })
+}
+
+/// Splits a log line and parses its user agent. Lines without a user agent
+/// classify as `Other`, like an empty user agent string.
+#[must_use]
+pub fn classify_log_line<'a>(
+    parser: &UserAgentParser,
+    line: &'a str,
+) -> Option<(CombinedLogRecord<'a>, Client<'a>)> {
+    let record = parse_combined_line(line)?;
+    let client = parser.parse(record.user_agent.unwrap_or_default());
+    Some((record, client))
+}
+
+fn placeholder(field: &str) -> Option<&str> {
+    (field != "-").then_some(field)
+}
+
+struct Fields<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Fields<'a> {
+    /// Reads up to the next space
+    fn token(&mut self) -> Option<&'a str> {
+        let end = self.rest.find(' ').unwrap_or(self.rest.len());
+        let token = &self.rest[..end];
+        self.advance(end);
+        (!token.is_empty()).then_some(token)
+    }
+
+    /// Reads a `[...]` field, returning its contents
+    fn bracketed(&mut self) -> Option<&'a str> {
+        let inner = self.rest.strip_prefix('[')?;
+        let end = inner.find(']')?;
+        let field = &inner[..end];
+        self.advance(end + 2);
+        Some(field)
+    }
+
+    /// Reads a `"..."` field in which `\` escapes the next character,
+    /// returning its contents with the escapes left in place
+    fn quoted(&mut self) -> Option<&'a str> {
+        let inner = self.rest.strip_prefix('"')?;
+        let mut escaped = false;
+        let end = inner.char_indices().find_map(|(index, c)| {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => return Some(index),
+                _ => {}
+            }
+            None
+        })?;
+        let field = &inner[..end];
+        self.advance(end + 2);
+        Some(field)
+    }
+
+    /// Skips `len` bytes and the spaces following them
+    fn advance(&mut self, len: usize) {
+        self.rest = self
+            .rest
+            .get(len..)
+            .unwrap_or_default()
+            .trim_start_matches(' ');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_combined_lines() {
+        let record = parse_combined_line(
+            r#"198.51.100.23 - frank [10/Oct/2023:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326 "http://www.example.com/start.html" "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36""#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            record,
+            CombinedLogRecord {
+                remote_host: "198.51.100.23",
+                ident: None,
+                user: Some("frank"),
+                time: "10/Oct/2023:13:55:36 -0700",
+                request: Some("GET /apache_pb.gif HTTP/1.0"),
+                status: 200,
+                bytes: Some(2326),
+                referer: Some("http://www.example.com/start.html"),
+                user_agent: Some(
+                    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                     (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36"
+                ),
+            }
+        );
+    }
+
+    #[test]
+    fn handles_escaped_quotes() {
+        let record = parse_combined_line(
+            r#"192.0.2.1 - - [11/Oct/2023:08:01:02 +0000] "GET /search?q=\"rust\" HTTP/1.1" 304 - "https://example.com/?q=\"a\"" "Mozilla/5.0 (compatible; \"Quoted\" Bot/1.2; +https://example.com/bot)" "10.0.0.1""#,
+        )
+        .unwrap();
+
+        assert_eq!(record.request, Some(r#"GET /search?q=\"rust\" HTTP/1.1"#));
+        assert_eq!(record.bytes, None);
+        assert_eq!(record.referer, Some(r#"https://example.com/?q=\"a\""#));
+        assert_eq!(
+            record.user_agent,
+            Some(
+                r#"Mozilla/5.0 (compatible; \"Quoted\" Bot/1.2; +https://example.com/bot)"#
+            )
+        );
+    }
+
+    #[test]
+    fn handles_placeholders_and_common_format() {
+        let missing = parse_combined_line(
+            r#"192.0.2.1 - - [11/Oct/2023:08:01:02 +0000] "-" 400 0 "-" "-""#,
+        )
+        .unwrap();
+        assert_eq!(missing.request, None);
+        assert_eq!(missing.referer, None);
+        assert_eq!(missing.user_agent, None);
+
+        let common = parse_combined_line(
+            r#"192.0.2.1 - - [11/Oct/2023:08:01:02 +0000] "GET / HTTP/1.1" 200 512"#,
+        )
+        .unwrap();
+        assert_eq!(common.bytes, Some(512));
+        assert_eq!(common.user_agent, None);
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        for line in [
+            "",
+            "192.0.2.1",
+            r#"192.0.2.1 - - 11/Oct/2023 "GET / HTTP/1.1" 200 512"#,
+            r#"192.0.2.1 - - [11/Oct/2023:08:01:02 +0000] "GET / HTTP/1.1 200 512"#,
+            r#"192.0.2.1 - - [11/Oct/2023:08:01:02 +0000] "GET / HTTP/1.1" OK 512"#,
+            r#"192.0.2.1 - - [11/Oct/2023:08:01:02 +0000] "GET / HTTP/1.1" 200 512 "-" "unterminated"#,
+            r#"192.0.2.1 - - [11/Oct/2023:08:01:02 +0000] "GET / HTTP/1.1" 200 512 "-""#,
+        ] {
+            assert_eq!(parse_combined_line(line), None, "{line}");
+        }
+    }
+
+    #[test]
+    fn classifies_lines() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+
+        let (record, client) = classify_log_line(
+            &parser,
+            r#"192.0.2.1 - - [11/Oct/2023:08:01:02 +0000] "GET / HTTP/1.1" 200 512 "-" "curl/8.4.0""#,
+        )
+        .unwrap();
+        assert_eq!(record.status, 200);
+        assert_eq!(client.user_agent.family, "curl");
+
+        let (_, client) = classify_log_line(
+            &parser,
+            r#"192.0.2.1 - - [11/Oct/2023:08:01:02 +0000] "GET / HTTP/1.1" 200 512 "-" "-""#,
+        )
+        .unwrap();
+        assert_eq!(client.user_agent.family, "Other");
+
+        assert!(classify_log_line(&parser, "not a log line").is_none());
+    }
+}
@@ -0,0 +1,277 @@
+use std::borrow::Cow;
+
+use super::{Client, Device, UserAgent, OS};
+
+/// Decides, for each field, which of two results `merge` takes it from
+#[derive(Clone, Copy, Debug)]
+pub enum MergeStrategy {
+    /// Keeps the set fields of `self` and fills its unset ones from `other`
+    PreferSelf,
+    /// Keeps the set fields of `other` and fills its unset ones from `self`
+    PreferOther,
+    /// Asks the function for every field, passing its flattened key, such
+    /// as `os_major`, then the values of `self` and `other`, which are
+    /// `None` when unset
+    FieldWise(fn(&'static str, Option<&str>, Option<&str>) -> Side),
+}
+
+/// The result a `MergeStrategy::FieldWise` function picks a field from
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Side {
+    /// The result `merge` was called on
+    This,
+    /// The result passed to `merge`
+    Other,
+}
+
+/// A field of a result, which may be unset
+trait Field {
+    /// The value, `None` when unset
+    fn value(&self) -> Option<&str>;
+}
+
+impl Field for Cow<'_, str> {
+    fn value(&self) -> Option<&str> {
+        Some(self.as_ref()).filter(|family| !family.is_empty() && *family != "Other")
+    }
+}
+
+impl Field for Option<Cow<'_, str>> {
+    fn value(&self) -> Option<&str> {
+        self.as_deref().filter(|value| !value.is_empty())
+    }
+}
+
+fn merge_field<F: Field>(
+    key: &'static str,
+    this: F,
+    other: F,
+    strategy: MergeStrategy,
+) -> F {
+    let take_other = match strategy {
+        MergeStrategy::PreferSelf => this.value().is_none() && other.value().is_some(),
+        MergeStrategy::PreferOther => other.value().is_some() || this.value().is_none(),
+        MergeStrategy::FieldWise(pick) => {
+            pick(key, this.value(), other.value()) == Side::Other
+        }
+    };
+    if take_other {
+        other
+    } else {
+        this
+    }
+}
+
+impl<'a> Client<'a> {
+    /// Combines this result with `other` as `strategy` decides, field by
+    /// field, such as to fill in what a fallback parser or client hints
+    /// know. A field counts as unset when it holds what an unmatched parse
+    /// gives: `Other` or an empty string for families, and `None` or an empty
+    /// string for everything else.
+    ///
+    /// The result borrows from both inputs, so it lives as long as the
+    /// shorter lived of the two; call `into_owned` on either first to avoid
+    /// that.
+    ///
+    /// ```rust
+    /// # use std::borrow::Cow;
+    /// # use uaparser::*;
+    /// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+    /// let parsed = parser.parse("Mozilla/5.0 (Linux; Android 14) Chrome/120.0.0.0 Mobile");
+    /// let hinted = Client {
+    ///     device: Device {
+    ///         model: Some(Cow::Borrowed("Pixel 8")),
+    ///         ..Device::default()
+    ///     },
+    ///     ..Client::default()
+    /// };
+    ///
+    /// let merged = hinted.merge(parsed, MergeStrategy::PreferSelf);
+    /// assert_eq!(merged.device.model.as_deref(), Some("Pixel 8"));
+    /// assert_eq!(merged.os.family, "Android");
+    /// ```
+    #[must_use]
+    pub fn merge(self, other: Client<'a>, strategy: MergeStrategy) -> Client<'a> {
+        Client {
+            device: self.device.merge(other.device, strategy),
+            os: self.os.merge(other.os, strategy),
+            user_agent: self.user_agent.merge(other.user_agent, strategy),
+        }
+    }
+}
+
+impl<'a> Device<'a> {
+    /// Combines the device fields as `Client::merge` does
+    #[must_use]
+    pub fn merge(self, other: Device<'a>, strategy: MergeStrategy) -> Device<'a> {
+        Device {
+            family: merge_field("device_family", self.family, other.family, strategy),
+            brand: merge_field("device_brand", self.brand, other.brand, strategy),
+            model: merge_field("device_model", self.model, other.model, strategy),
+        }
+    }
+}
+
+impl<'a> OS<'a> {
+    /// Combines the OS fields as `Client::merge` does
+    #[must_use]
+    pub fn merge(self, other: OS<'a>, strategy: MergeStrategy) -> OS<'a> {
+        OS {
+            family: merge_field("os_family", self.family, other.family, strategy),
+            major: merge_field("os_major", self.major, other.major, strategy),
+            minor: merge_field("os_minor", self.minor, other.minor, strategy),
+            patch: merge_field("os_patch", self.patch, other.patch, strategy),
+            patch_minor: merge_field(
+                "os_patch_minor",
+                self.patch_minor,
+                other.patch_minor,
+                strategy,
+            ),
+        }
+    }
+}
+
+impl<'a> UserAgent<'a> {
+    /// Combines the user agent fields as `Client::merge` does
+    #[must_use]
+    pub fn merge(self, other: UserAgent<'a>, strategy: MergeStrategy) -> UserAgent<'a> {
+        UserAgent {
+            family: merge_field("ua_family", self.family, other.family, strategy),
+            major: merge_field("ua_major", self.major, other.major, strategy),
+            minor: merge_field("ua_minor", self.minor, other.minor, strategy),
+            patch: merge_field("ua_patch", self.patch, other.patch, strategy),
+            patch_minor: merge_field(
+                "ua_patch_minor",
+                self.patch_minor,
+                other.patch_minor,
+                strategy,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cow(value: &str) -> Cow<'_, str> {
+        Cow::Borrowed(value)
+    }
+
+    /// A fully populated client, so merging it has no unset fields to fill
+    fn full(family: &str) -> Client<'_> {
+        Client {
+            device: Device {
+                family: cow(family),
+                brand: Some(cow(family)),
+                model: Some(cow(family)),
+            },
+            os: OS {
+                family: cow(family),
+                major: Some(cow("1")),
+                minor: Some(cow("2")),
+                patch: Some(cow("3")),
+                patch_minor: Some(cow("4")),
+            },
+            user_agent: UserAgent {
+                family: cow(family),
+                major: Some(cow("5")),
+                minor: Some(cow("6")),
+                patch: Some(cow("7")),
+                patch_minor: Some(cow("8")),
+            },
+        }
+    }
+
+    fn partial() -> Client<'static> {
+        Client {
+            device: Device {
+                family: Cow::Borrowed("Pixel 8"),
+                brand: Some(cow("")),
+                model: None,
+            },
+            os: OS {
+                major: Some(cow("14")),
+                ..OS::default()
+            },
+            user_agent: UserAgent {
+                family: Cow::Borrowed(""),
+                ..UserAgent::default()
+            },
+        }
+    }
+
+    #[test]
+    fn prefer_self_fills_unset_fields() {
+        let merged = partial().merge(full("Acme"), MergeStrategy::PreferSelf);
+        assert_eq!(merged.device.family, "Pixel 8");
+        assert_eq!(merged.device.brand.as_deref(), Some("Acme"));
+        assert_eq!(merged.os.family, "Acme");
+        assert_eq!(merged.os.major.as_deref(), Some("14"));
+        assert_eq!(merged.os.minor.as_deref(), Some("2"));
+        assert_eq!(merged.user_agent, full("Acme").user_agent);
+
+        assert_eq!(
+            full("Acme").merge(full("Other Co"), MergeStrategy::PreferSelf),
+            full("Acme")
+        );
+        assert_eq!(
+            Client::default().merge(Client::default(), MergeStrategy::PreferSelf),
+            Client::default()
+        );
+    }
+
+    #[test]
+    fn prefer_other_keeps_set_fields_of_other() {
+        let merged = full("Acme").merge(partial(), MergeStrategy::PreferOther);
+        assert_eq!(merged.device.family, "Pixel 8");
+        assert_eq!(merged.device.brand.as_deref(), Some("Acme"));
+        assert_eq!(merged.os.major.as_deref(), Some("14"));
+        assert_eq!(merged.os.minor.as_deref(), Some("2"));
+
+        assert_eq!(
+            full("Acme").merge(full("Other Co"), MergeStrategy::PreferOther),
+            full("Other Co")
+        );
+        assert_eq!(
+            Client::default().merge(Client::default(), MergeStrategy::PreferOther),
+            Client::default()
+        );
+        assert_eq!(
+            full("Acme").merge(Client::default(), MergeStrategy::PreferOther),
+            full("Acme")
+        );
+    }
+
+    #[test]
+    fn field_wise_asks_for_every_field() {
+        fn os_from_other(
+            key: &'static str,
+            this: Option<&str>,
+            other: Option<&str>,
+        ) -> Side {
+            assert!(this.is_none() || this != other);
+            if key.starts_with("os_") {
+                Side::Other
+            } else {
+                Side::This
+            }
+        }
+
+        let merged =
+            full("Acme").merge(partial(), MergeStrategy::FieldWise(os_from_other));
+        assert_eq!(merged.device, full("Acme").device);
+        assert_eq!(merged.os, partial().os);
+        assert_eq!(merged.user_agent, full("Acme").user_agent);
+
+        let owned = String::from("Borrowed");
+        let merged = full(&owned).merge(
+            Client::default(),
+            MergeStrategy::FieldWise(|_, this, _| {
+                assert!(this.is_some());
+                Side::Other
+            }),
+        );
+        assert_eq!(merged, Client::default());
+    }
+}
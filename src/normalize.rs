@@ -0,0 +1,240 @@
+//! Cleans up user agent strings before the rules see them, for pipelines that
+//! deliver them with stray control characters such as embedded NULs or the
+//! CR/LF of a header splitting attempt, or percent-encoded along the way, and
+//! pulls user agents out of pasted header lines.
+//!
+//! ```rust
+//! # use uaparser::normalize::{maybe_percent_decode, InputNormalization};
+//! let normalized = InputNormalization::StripControls.apply("curl/8.4.0\r\nX-Injected: 1");
+//! assert_eq!(normalized, "curl/8.4.0X-Injected: 1");
+//!
+//! let decoded = maybe_percent_decode("Mozilla%2F5.0%20(X11%3B%20Linux%20x86_64)");
+//! assert_eq!(decoded, "Mozilla/5.0 (X11; Linux x86_64)");
+//! ```
+
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+use std::borrow::Cow;
+
+use super::{Deserialize, Serialize};
+
+/// What a parser does to each user agent before matching it, set with
+/// `UserAgentParserBuilder::normalize_input`
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum InputNormalization {
+    /// Match the user agent as given
+    #[default]
+    Off,
+    /// Remove C0 and C1 control characters, including tabs and line breaks
+    StripControls,
+    /// Remove control characters, then leading and trailing whitespace
+    StripControlsAndTrim,
+}
+
+impl InputNormalization {
+    /// Normalizes `user_agent`, borrowing it when nothing had to change
+    #[must_use]
+    pub fn apply(self, user_agent: &str) -> Cow<'_, str> {
+        match self {
+            InputNormalization::Off => Cow::Borrowed(user_agent),
+            InputNormalization::StripControls => strip_controls(user_agent),
+            InputNormalization::StripControlsAndTrim => {
+                match strip_controls(user_agent) {
+                    Cow::Borrowed(stripped) => Cow::Borrowed(stripped.trim()),
+                    Cow::Owned(stripped) if stripped.trim().len() == stripped.len() => {
+                        Cow::Owned(stripped)
+                    }
+                    Cow::Owned(stripped) => Cow::Owned(stripped.trim().to_owned()),
+                }
+            }
+        }
+    }
+}
+
+/// The fewest `%xx` escapes a percent-encoded user agent is expected to have
+const MIN_ESCAPES: usize = 2;
+
+/// Escapes must account for at least one in this many bytes, which the odd
+/// literal `%` in a real user agent never comes close to
+const MAX_BYTES_PER_ESCAPE: usize = 16;
+
+/// Decodes `user_agent` when it looks percent-encoded, going by how densely
+/// it is packed with `%xx` escapes. Anything else, including strings with a
+/// malformed escape or that don't decode to UTF-8, is borrowed unchanged.
+#[must_use]
+pub fn maybe_percent_decode(user_agent: &str) -> Cow<'_, str> {
+    let bytes = user_agent.as_bytes();
+    let escapes = bytes
+        .windows(3)
+        .filter(|window| window[0] == b'%' && is_escape(window[1], window[2]))
+        .count();
+    if escapes < MIN_ESCAPES || escapes * MAX_BYTES_PER_ESCAPE < bytes.len() {
+        return Cow::Borrowed(user_agent);
+    }
+
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut rest = bytes;
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte != b'%' {
+            decoded.push(byte);
+            rest = tail;
+            continue;
+        }
+        match tail {
+            [high, low, tail @ ..] if is_escape(*high, *low) => {
+                decoded.push(hex(*high) << 4 | hex(*low));
+                rest = tail;
+            }
+            _ => return Cow::Borrowed(user_agent),
+        }
+    }
+
+    String::from_utf8(decoded).map_or(Cow::Borrowed(user_agent), Cow::Owned)
+}
+
+fn is_escape(high: u8, low: u8) -> bool {
+    high.is_ascii_hexdigit() && low.is_ascii_hexdigit()
+}
+
+fn hex(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'a'..=b'f' => digit - b'a' + 10,
+        _ => digit - b'A' + 10,
+    }
+}
+
+/// Header name prefixes stripped by `extract_ua`, compared in lowercase
+const HEADER_PREFIXES: [&str; 3] = ["user-agent:", "\"user-agent\":", "'user-agent':"];
+
+/// Pulls the user agent out of text pasted from a HAR file, a packet capture
+/// or a log, stripping a leading `User-Agent:` header name in any case,
+/// surrounding quotes and whitespace. Clean user agents are returned as is,
+/// so it's safe to apply to any input.
+///
+/// ```rust
+/// # use uaparser::normalize::extract_ua;
+/// assert_eq!(extract_ua("User-Agent: \"curl/8.4.0\"\r\n"), "curl/8.4.0");
+/// assert_eq!(extract_ua("curl/8.4.0"), "curl/8.4.0");
+/// ```
+#[must_use]
+pub fn extract_ua(input: &str) -> &str {
+    let input = input.trim();
+    let value = strip_header_name(input).unwrap_or_else(|| {
+        let unquoted = unquote(input);
+        strip_header_name(unquoted).unwrap_or(unquoted)
+    });
+    unquote(value)
+}
+
+/// The rest of `line` after a leading header name, if it has one
+fn strip_header_name(line: &str) -> Option<&str> {
+    HEADER_PREFIXES.iter().find_map(|prefix| {
+        let start = line.get(..prefix.len())?;
+        start
+            .eq_ignore_ascii_case(prefix)
+            .then(|| line[prefix.len()..].trim())
+    })
+}
+
+/// Removes one pair of matching single or double quotes around `value`, and
+/// any whitespace inside them
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return value[1..value.len() - 1].trim();
+        }
+    }
+    value
+}
+
+fn strip_controls(user_agent: &str) -> Cow<'_, str> {
+    if user_agent.chars().any(char::is_control) {
+        Cow::Owned(user_agent.chars().filter(|c| !c.is_control()).collect())
+    } else {
+        Cow::Borrowed(user_agent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_controls() {
+        let strip = InputNormalization::StripControls;
+
+        assert_eq!(strip.apply("Foo/1.0\0 (Bar\u{85})\r\n"), "Foo/1.0 (Bar)");
+        assert_eq!(strip.apply("\t Foo/1.0 "), " Foo/1.0 ");
+        assert_eq!(
+            InputNormalization::StripControlsAndTrim.apply("\t Foo/1.0 \r\n"),
+            "Foo/1.0"
+        );
+        assert_eq!(InputNormalization::Off.apply("Foo\r\n"), "Foo\r\n");
+    }
+
+    #[test]
+    fn borrows_clean_input() {
+        let user_agent = " Foo/1.0 (Bar; é) ";
+
+        for normalization in [
+            InputNormalization::Off,
+            InputNormalization::StripControls,
+            InputNormalization::StripControlsAndTrim,
+        ] {
+            assert!(matches!(normalization.apply(user_agent), Cow::Borrowed(_)));
+        }
+        assert!(matches!(
+            InputNormalization::StripControlsAndTrim.apply(user_agent),
+            Cow::Borrowed("Foo/1.0 (Bar; é)")
+        ));
+    }
+
+    #[test]
+    fn decodes_dense_escapes() {
+        assert_eq!(
+            maybe_percent_decode("curl%2F8.4.0%20%28%E2%9C%93%29"),
+            "curl/8.4.0 (\u{2713})"
+        );
+        assert!(matches!(
+            maybe_percent_decode("curl%2F8.4.0%20%28%FF%29"),
+            Cow::Borrowed("curl%2F8.4.0%20%28%FF%29")
+        ));
+        assert!(matches!(
+            maybe_percent_decode("curl%2F8.4.0%20%2"),
+            Cow::Borrowed("curl%2F8.4.0%20%2")
+        ));
+    }
+
+    #[test]
+    fn leaves_normal_user_agents_alone() {
+        for user_agent in [
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+             (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+            "MyApp/2.1 (battery 100%; charging 50%25)",
+            "%20",
+        ] {
+            let decoded = maybe_percent_decode(user_agent);
+            assert!(matches!(decoded, Cow::Borrowed(_)));
+            assert_eq!(decoded.as_bytes(), user_agent.as_bytes());
+        }
+    }
+
+    #[test]
+    fn extracts_pasted_user_agents() {
+        for input in [
+            "curl/8.4.0",
+            "  curl/8.4.0\r\n",
+            "User-Agent: curl/8.4.0",
+            "user-agent:curl/8.4.0",
+            "USER-AGENT: \"curl/8.4.0\"",
+            "'curl/8.4.0'",
+            "\"User-Agent: curl/8.4.0\"",
+            "\"user-agent\": \"curl/8.4.0\"",
+        ] {
+            assert_eq!(extract_ua(input), "curl/8.4.0", "{input:?}");
+        }
+        assert_eq!(extract_ua("\""), "\"");
+        assert_eq!(extract_ua("User-Agent:"), "");
+    }
+}
@@ -1,18 +1,83 @@
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
 use std::borrow::Cow;
 
-use super::{Deserialize, Serialize};
+use super::{version, Deserialize, Serialize, Version};
 
 /// Describes the `Family` as well as the `Major`, `Minor`, `Patch`, and
 /// `PatchMinor` versions of an `OS`
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct OS<'a> {
+    /// The operating system name, `Other` when no rule matched
+    #[serde(borrow)]
     pub family: Cow<'a, str>,
+    /// The major version, as written in the user agent
+    #[serde(borrow, default, deserialize_with = "crate::client::borrowed::option")]
     pub major: Option<Cow<'a, str>>,
+    /// The minor version, as written in the user agent
+    #[serde(borrow, default, deserialize_with = "crate::client::borrowed::option")]
     pub minor: Option<Cow<'a, str>>,
+    /// The patch version, as written in the user agent
+    #[serde(borrow, default, deserialize_with = "crate::client::borrowed::option")]
     pub patch: Option<Cow<'a, str>>,
+    /// The version after the patch version, as written in the user agent
+    #[serde(borrow, default, deserialize_with = "crate::client::borrowed::option")]
     pub patch_minor: Option<Cow<'a, str>>,
 }
 
+impl OS<'_> {
+    /// The present version components joined with dots, such as `14.1.2`,
+    /// with a major version captured as `10_15_7` read as `10.15.7`
+    #[must_use]
+    pub fn version_string(&self) -> Option<String> {
+        version::joined(self.versions())
+    }
+
+    /// The numeric version, `None` when the major version isn't a number
+    #[must_use]
+    pub fn version(&self) -> Option<Version> {
+        version::numeric(self.versions())
+    }
+
+    /// Whether the version satisfies a requirement such as `>=13.4, <15`,
+    /// `None` when the major version isn't a number. Missing components are
+    /// zero, as for `coerce_semver`, so a bare `14` satisfies `>=14.0.0`.
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+    /// let os = parser.parse_os("Mozilla/5.0 (iPhone; CPU iPhone OS 17_1_2 like Mac OS X)");
+    /// let req = semver::VersionReq::parse(">=13.4, <18").unwrap();
+    ///
+    /// assert_eq!(os.satisfies(&req), Some(true));
+    /// ```
+    #[cfg(feature = "semver")]
+    #[must_use]
+    pub fn satisfies(&self, req: &semver::VersionReq) -> Option<bool> {
+        version::satisfies(self.versions(), req)
+    }
+
+    fn versions(&self) -> [&Option<Cow<'_, str>>; 4] {
+        [&self.major, &self.minor, &self.patch, &self.patch_minor]
+    }
+
+    /// Copies any borrowed fields, so the result outlives the user agent
+    /// string and the parser
+    #[must_use]
+    pub fn into_owned(self) -> OS<'static> {
+        OS {
+            family: Cow::Owned(self.family.into_owned()),
+            major: self.major.map(|major| Cow::Owned(major.into_owned())),
+            minor: self.minor.map(|minor| Cow::Owned(minor.into_owned())),
+            patch: self.patch.map(|patch| Cow::Owned(patch.into_owned())),
+            patch_minor: self
+                .patch_minor
+                .map(|patch_minor| Cow::Owned(patch_minor.into_owned())),
+        }
+    }
+}
+
 impl<'a> Default for OS<'a> {
     fn default() -> Self {
         Self {
@@ -24,3 +89,70 @@ impl<'a> Default for OS<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, UserAgentParser};
+
+    #[test]
+    fn version_helpers() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+
+        let android = parser.parse_os(
+            "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 \
+             (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36",
+        );
+        assert_eq!(android.version_string().as_deref(), Some("14"));
+        assert_eq!(android.version(), Some(Version::new(14, 0, 0)));
+
+        let mac = parser.parse_os(
+            "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 \
+             (KHTML, like Gecko) Version/17.1 Safari/605.1.15",
+        );
+        assert_eq!(mac.version_string().as_deref(), Some("10.15.7"));
+        assert_eq!(mac.version(), Some(Version::new(10, 15, 7)));
+
+        let unknown = OS::default();
+        assert_eq!(unknown.version_string(), None);
+        assert_eq!(unknown.version(), None);
+    }
+
+    #[test]
+    fn underscored_major_is_split() {
+        let os = OS {
+            family: Cow::Borrowed("Mac OS X"),
+            major: Some(Cow::Borrowed("10_15_7")),
+            ..OS::default()
+        };
+
+        assert_eq!(os.version_string().as_deref(), Some("10.15.7"));
+        assert_eq!(os.version(), Some(Version::new(10, 15, 7)));
+    }
+
+    #[cfg(feature = "semver")]
+    #[test]
+    fn semver_requirements() {
+        let os = |family, major: &'static str, minor: Option<&'static str>| OS {
+            family: Cow::Borrowed(family),
+            major: Some(Cow::Borrowed(major)),
+            minor: minor.map(Cow::Borrowed),
+            ..OS::default()
+        };
+        let req = |text| semver::VersionReq::parse(text).unwrap();
+
+        let ios = os("iOS", "17", Some("1"));
+        assert_eq!(ios.satisfies(&req(">=17.0, <18")), Some(true));
+        assert_eq!(ios.satisfies(&req(">=17.2")), Some(false));
+
+        let android = os("Android", "9", None);
+        assert_eq!(android.satisfies(&req(">=10")), Some(false));
+        assert_eq!(
+            os("Android", "14", None).satisfies(&req(">=14.0.0")),
+            Some(true)
+        );
+
+        assert_eq!(OS::default().satisfies(&req(">=0")), None);
+    }
+}
@@ -0,0 +1,169 @@
+//! Maps parse results onto the OpenTelemetry semantic convention attributes
+//! for user agents, operating systems and devices.
+//!
+//! ```rust
+//! # use uaparser::*;
+//! let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+//! let client = parser.parse("Mozilla/5.0 (X11; Ubuntu; Linux x86_64; rv:121.0) Gecko/20100101 Firefox/121.0");
+//! let attributes = client.otel_attributes(None);
+//!
+//! assert_eq!(attributes[0], (otel::USER_AGENT_NAME, "Firefox".into()));
+//! ```
+
+use std::borrow::Cow;
+
+use crate::{families::OTHER, Client};
+
+pub const USER_AGENT_ORIGINAL: &str = "user_agent.original";
+pub const USER_AGENT_NAME: &str = "user_agent.name";
+pub const USER_AGENT_VERSION: &str = "user_agent.version";
+pub const OS_TYPE: &str = "os.type";
+pub const OS_NAME: &str = "os.name";
+pub const OS_VERSION: &str = "os.version";
+pub const DEVICE_MANUFACTURER: &str = "device.manufacturer";
+pub const DEVICE_MODEL_IDENTIFIER: &str = "device.model.identifier";
+pub const DEVICE_MODEL_NAME: &str = "device.model.name";
+
+/// uap-core OS families with an `os.type` value, which the convention
+/// spells in lowercase
+const OS_TYPES: &[(&str, &str)] = &[
+    ("Windows", "windows"),
+    ("Mac OS X", "darwin"),
+    ("Linux", "linux"),
+    ("Ubuntu", "linux"),
+    ("Debian", "linux"),
+    ("Fedora", "linux"),
+    ("Red Hat", "linux"),
+    ("SUSE", "linux"),
+    ("Arch Linux", "linux"),
+    ("Chrome OS", "linux"),
+    ("FreeBSD", "freebsd"),
+    ("NetBSD", "netbsd"),
+    ("OpenBSD", "openbsd"),
+    ("DragonFly BSD", "dragonflybsd"),
+    ("Solaris", "solaris"),
+    ("AIX", "aix"),
+    ("HP-UX", "hpux"),
+];
+
+pub(crate) fn attributes<'a>(
+    client: &'a Client<'_>,
+    original: Option<&'a str>,
+) -> Vec<(&'static str, Cow<'a, str>)> {
+    let user_agent = &client.user_agent;
+    let os = &client.os;
+    let device = &client.device;
+    let mut attributes = Vec::new();
+
+    if let Some(original) = original {
+        attributes.push((USER_AGENT_ORIGINAL, Cow::Borrowed(original)));
+    }
+    if user_agent.family != OTHER {
+        attributes.push((USER_AGENT_NAME, Cow::Borrowed(&*user_agent.family)));
+    }
+    if let Some(version) = version(&[
+        &user_agent.major,
+        &user_agent.minor,
+        &user_agent.patch,
+        &user_agent.patch_minor,
+    ]) {
+        attributes.push((USER_AGENT_VERSION, version));
+    }
+
+    if let Some(&(_, os_type)) = OS_TYPES.iter().find(|(family, _)| os.family == *family)
+    {
+        attributes.push((OS_TYPE, Cow::Borrowed(os_type)));
+    }
+    if os.family != OTHER {
+        attributes.push((OS_NAME, Cow::Borrowed(&*os.family)));
+    }
+    if let Some(version) = version(&[&os.major, &os.minor, &os.patch, &os.patch_minor]) {
+        attributes.push((OS_VERSION, version));
+    }
+
+    if let Some(brand) = &device.brand {
+        attributes.push((DEVICE_MANUFACTURER, Cow::Borrowed(&**brand)));
+    }
+    if let Some(model) = &device.model {
+        attributes.push((DEVICE_MODEL_IDENTIFIER, Cow::Borrowed(&**model)));
+    }
+    if device.family != OTHER {
+        attributes.push((DEVICE_MODEL_NAME, Cow::Borrowed(&*device.family)));
+    }
+
+    attributes
+}
+
+/// Joins the leading run of present version parts with dots
+pub(crate) fn version<'a>(parts: &[&'a Option<Cow<'_, str>>]) -> Option<Cow<'a, str>> {
+    let present: Vec<&str> = parts.iter().map_while(|part| part.as_deref()).collect();
+
+    match present.as_slice() {
+        [] => None,
+        [only] => Some(Cow::Borrowed(only)),
+        _ => Some(Cow::Owned(present.join("."))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, UserAgentParser};
+
+    const CHROME_ANDROID: &str = "Mozilla/5.0 (Linux; Android 13; SM-S901B) AppleWebKit/537.36 \
+                                  (KHTML, like Gecko) Chrome/120.0.6099.144 Mobile Safari/537.36";
+
+    #[test]
+    fn chrome_on_android() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let client = parser.parse(CHROME_ANDROID);
+
+        let expected = [
+            (USER_AGENT_NAME, "Chrome Mobile"),
+            (USER_AGENT_VERSION, "120.0.6099.144"),
+            (OS_NAME, "Android"),
+            (OS_VERSION, "13"),
+            (DEVICE_MANUFACTURER, "Samsung"),
+            (DEVICE_MODEL_IDENTIFIER, "SM-S901B"),
+            (DEVICE_MODEL_NAME, "Samsung SM-S901B"),
+        ];
+        let attributes = client.otel_attributes(None);
+        assert_eq!(
+            attributes
+                .iter()
+                .map(|(key, value)| (*key, &**value))
+                .collect::<Vec<_>>(),
+            expected
+        );
+
+        let attributes = client.otel_attributes(Some(CHROME_ANDROID));
+        assert_eq!(attributes.len(), expected.len() + 1);
+        assert_eq!(
+            attributes[0],
+            (USER_AGENT_ORIGINAL, Cow::Borrowed(CHROME_ANDROID))
+        );
+    }
+
+    #[test]
+    fn omits_unknown_fields() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let client = parser.parse(
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+             (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+        );
+
+        assert_eq!(
+            client.otel_attributes(None),
+            [
+                (USER_AGENT_NAME, Cow::Borrowed("Chrome")),
+                (USER_AGENT_VERSION, Cow::Borrowed("120.0.0.0")),
+                (OS_TYPE, Cow::Borrowed("windows")),
+                (OS_NAME, Cow::Borrowed("Windows")),
+                (OS_VERSION, Cow::Borrowed("10")),
+            ]
+        );
+        assert!(parser.parse("").otel_attributes(None).is_empty());
+    }
+}
@@ -0,0 +1,278 @@
+//! Caches parse results outside the parser, such as in a store shared by
+//! many short-lived worker processes, where the in-process caches of
+//! `ThreadLocalCachingParser` don't help. A `ParseCache` stores clients
+//! however it likes, and `CachedParser` consults it around a parser.
+//!
+//! `examples/kv_cache.rs` backs a cache with a key/value store, serializing
+//! clients as JSON, the way a Redis or memcached cache would.
+//!
+//! ```rust
+//! # use std::sync::Arc;
+//! # use uaparser::*;
+//! # use uaparser::parse_cache::{CachedParser, MemoryCache};
+//! let parser = Arc::new(UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap());
+//! let cached = CachedParser::new(parser, MemoryCache::new(10_000));
+//!
+//! assert_eq!(cached.parse("curl/8.4.0").user_agent.family, "curl");
+//! assert_eq!(cached.cache().len(), 1);
+//! ```
+
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use crate::{Client, Device, OwnedParser, Parser, UserAgent, UserAgentParser, OS};
+
+/// A store of parse results keyed by user agent, for `CachedParser`. How
+/// clients are serialized, if at all, is up to the implementation. The
+/// trait is object safe, so a `Box<dyn ParseCache>` can stand in for any.
+///
+/// Neither method can fail: a cache whose backend is unavailable should
+/// treat lookups as misses and drop writes, so that parsing carries on
+/// without it.
+pub trait ParseCache {
+    /// The client cached for `user_agent`, if any
+    fn get(&self, user_agent: &str) -> Option<Client<'static>>;
+    /// Caches the client parsed from `user_agent`
+    fn put(&self, user_agent: &str, client: &Client<'static>);
+}
+
+macro_rules! forward_parse_cache {
+    ($($wrapper:ty),*) => {$(
+        impl<C: ParseCache + ?Sized> ParseCache for $wrapper {
+            fn get(&self, user_agent: &str) -> Option<Client<'static>> {
+                (**self).get(user_agent)
+            }
+
+            fn put(&self, user_agent: &str, client: &Client<'static>) {
+                (**self).put(user_agent, client);
+            }
+        }
+    )*};
+}
+
+forward_parse_cache!(&C, Box<C>, Arc<C>);
+
+/// Wraps a shared `UserAgentParser` with a `ParseCache`, which `parse`
+/// looks user agents up in before parsing and stores the clients it parses
+/// in. The single category methods take their part of a cached client, and
+/// parse directly on a miss without caching anything.
+///
+/// User agents are looked up as given, before the parser normalizes them.
+#[derive(Debug)]
+pub struct CachedParser<C: ParseCache> {
+    parser: Arc<UserAgentParser>,
+    cache: C,
+}
+
+impl<C: ParseCache> CachedParser<C> {
+    #[must_use]
+    pub fn new(parser: Arc<UserAgentParser>, cache: C) -> CachedParser<C> {
+        CachedParser { parser, cache }
+    }
+
+    #[must_use]
+    pub fn parser(&self) -> &Arc<UserAgentParser> {
+        &self.parser
+    }
+
+    #[must_use]
+    pub fn cache(&self) -> &C {
+        &self.cache
+    }
+}
+
+impl<C: ParseCache> Parser for CachedParser<C> {
+    fn parse<'a>(&self, user_agent: &'a str) -> Client<'a> {
+        self.parse_owned(user_agent)
+    }
+
+    fn parse_device<'a>(&self, user_agent: &'a str) -> Device<'a> {
+        match self.cache.get(user_agent) {
+            Some(client) => client.device,
+            None => self.parser.parse_device(user_agent),
+        }
+    }
+
+    fn parse_os<'a>(&self, user_agent: &'a str) -> OS<'a> {
+        match self.cache.get(user_agent) {
+            Some(client) => client.os,
+            None => self.parser.parse_os(user_agent),
+        }
+    }
+
+    fn parse_user_agent<'a>(&self, user_agent: &'a str) -> UserAgent<'a> {
+        match self.cache.get(user_agent) {
+            Some(client) => client.user_agent,
+            None => self.parser.parse_user_agent(user_agent),
+        }
+    }
+}
+
+/// Returns cached clients as the cache gives them, without copying them again
+impl<C: ParseCache> OwnedParser for CachedParser<C> {
+    fn parse_owned(&self, user_agent: &str) -> Client<'static> {
+        if let Some(client) = self.cache.get(user_agent) {
+            return client;
+        }
+        let client = self.parser.parse_owned(user_agent);
+        self.cache.put(user_agent, &client);
+        client
+    }
+
+    fn parse_device_owned(&self, user_agent: &str) -> Device<'static> {
+        match self.cache.get(user_agent) {
+            Some(client) => client.device,
+            None => self.parser.parse_device_owned(user_agent),
+        }
+    }
+
+    fn parse_os_owned(&self, user_agent: &str) -> OS<'static> {
+        match self.cache.get(user_agent) {
+            Some(client) => client.os,
+            None => self.parser.parse_os_owned(user_agent),
+        }
+    }
+
+    fn parse_user_agent_owned(&self, user_agent: &str) -> UserAgent<'static> {
+        match self.cache.get(user_agent) {
+            Some(client) => client.user_agent,
+            None => self.parser.parse_user_agent_owned(user_agent),
+        }
+    }
+}
+
+/// A `ParseCache` in the process's memory, holding at most `max_entries`
+/// clients. Once full, new clients aren't cached, which bounds memory on
+/// unbounded input as `stats::ClientStats` does.
+#[derive(Debug, Default)]
+pub struct MemoryCache {
+    max_entries: usize,
+    entries: Mutex<HashMap<Box<str>, Client<'static>>>,
+}
+
+impl MemoryCache {
+    #[must_use]
+    pub fn new(max_entries: usize) -> MemoryCache {
+        MemoryCache {
+            max_entries,
+            entries: Mutex::default(),
+        }
+    }
+
+    /// The number of clients cached
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries().len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries().is_empty()
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, HashMap<Box<str>, Client<'static>>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl ParseCache for MemoryCache {
+    fn get(&self, user_agent: &str) -> Option<Client<'static>> {
+        self.entries().get(user_agent).cloned()
+    }
+
+    fn put(&self, user_agent: &str, client: &Client<'static>) {
+        let mut entries = self.entries();
+        if entries.len() < self.max_entries || entries.contains_key(user_agent) {
+            entries.insert(user_agent.into(), client.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A cache that misses every lookup and logs its calls, along with the
+    /// parses the parser's post-processor logs
+    #[derive(Clone, Default)]
+    struct Logging(Arc<Mutex<Vec<String>>>);
+
+    impl Logging {
+        fn push(&self, event: String) {
+            self.0.lock().unwrap().push(event);
+        }
+
+        fn take(&self) -> Vec<String> {
+            std::mem::take(&mut self.0.lock().unwrap())
+        }
+    }
+
+    impl ParseCache for Logging {
+        fn get(&self, user_agent: &str) -> Option<Client<'static>> {
+            self.push(format!("get {user_agent}"));
+            None
+        }
+
+        fn put(&self, user_agent: &str, client: &Client<'static>) {
+            self.push(format!("put {user_agent} {}", client.user_agent.family));
+        }
+    }
+
+    fn parser(log: &Logging) -> Arc<UserAgentParser> {
+        let mut parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let log = log.clone();
+        parser.add_post_processor(move |client: &mut Client<'_>| {
+            log.push(format!("parse {}", client.user_agent.family));
+        });
+        Arc::new(parser)
+    }
+
+    #[test]
+    fn looks_up_before_parsing_and_stores_misses() {
+        let log = Logging::default();
+        let cached = CachedParser::new(parser(&log), log.clone());
+
+        assert_eq!(cached.parse("curl/8.4.0").user_agent.family, "curl");
+        assert_eq!(
+            log.take(),
+            ["get curl/8.4.0", "parse curl", "put curl/8.4.0 curl"]
+        );
+
+        assert_eq!(cached.parse_os("curl/8.4.0").family, "Other");
+        assert_eq!(log.take(), ["get curl/8.4.0"]);
+    }
+
+    #[test]
+    fn hits_skip_the_parser() {
+        let log = Logging::default();
+        let cache: Box<dyn ParseCache> = Box::new(MemoryCache::new(1));
+        let cached = CachedParser::new(parser(&log), cache);
+
+        let client = cached.parse("curl/8.4.0");
+        assert_eq!(log.take(), ["parse curl"]);
+        assert_eq!(cached.parse("curl/8.4.0"), client);
+        assert_eq!(cached.parse_user_agent("curl/8.4.0"), client.user_agent);
+        let owned: &dyn OwnedParser = &cached;
+        assert_eq!(owned.parse_owned("curl/8.4.0"), client);
+        assert!(log.take().is_empty());
+    }
+
+    #[test]
+    fn memory_cache_is_bounded() {
+        let cache = MemoryCache::new(2);
+        for family in ["a", "b", "c"] {
+            let mut client = Client::default();
+            client.user_agent.family = family.to_owned().into();
+            cache.put(family, &client);
+        }
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("b").is_some());
+        assert!(cache.get("c").is_none());
+    }
+}
@@ -0,0 +1,529 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, BinaryHeap},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex, PoisonError, RwLock,
+    },
+};
+
+use super::*;
+
+/// How an `AdaptiveParser` treats the result of its reordered rules
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum ReorderMode {
+    /// Checks every match against the rules it was moved ahead of, so the
+    /// result is always the one the original order gives. The check tries
+    /// those rules, so this scans at least as many rules as the original
+    /// order; it exists to learn which rules conflict on live traffic.
+    #[default]
+    Verified,
+    /// Returns the first match in the reordered rules. Rules found to
+    /// conflict while `Verified` are kept in their original order, but
+    /// conflicts never seen can change results, so switch to this only
+    /// after verifying on representative traffic.
+    Trusted,
+}
+
+/// Wraps a `UserAgentParser` and reorders each category's rules by how often
+/// they match, so that skewed traffic tries its common rules first.
+/// Reordering happens on `resort`, and every `resort_every` parses when
+/// set. Categories using `MatchStrategy::MostSpecific` are never reordered.
+///
+/// A `max_matchers_per_category` budget on the wrapped parser limits which
+/// rules are tried rather than how many: the rules it covers in the
+/// original order are tried wherever reordering puts them, and no others,
+/// so the budget gives the same results as it does without reordering.
+///
+/// ```rust
+/// # use uaparser::*;
+/// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+/// let adaptive = AdaptiveParser::new(parser).resort_every(10_000);
+///
+/// assert_eq!(adaptive.parse_user_agent("curl/8.4.0").family, "curl");
+/// assert_eq!(adaptive.stats().user_agent.values().sum::<u64>(), 1);
+/// ```
+#[derive(Debug)]
+pub struct AdaptiveParser {
+    parser: UserAgentParser,
+    trusted: AtomicBool,
+    resort_every: u64,
+    parses: AtomicU64,
+    device: Ranking,
+    os: Ranking,
+    user_agent: Ranking,
+}
+
+/// The live order of one category's rules, which are referred to by their
+/// index in the wrapped parser
+#[derive(Debug)]
+struct Ranking {
+    hits: Vec<AtomicU64>,
+    scanned: AtomicUsize,
+    order: RwLock<Arc<Order>>,
+    /// `(earlier, later)` pairs seen matching the same user agent, which
+    /// must keep their original order
+    pins: Mutex<BTreeSet<(usize, usize)>>,
+}
+
+#[derive(Debug)]
+struct Order {
+    /// The rule index at each position
+    indices: Vec<usize>,
+    /// The position of each rule index
+    positions: Vec<usize>,
+}
+
+impl AdaptiveParser {
+    /// Starts from the rule order of `parser`, in `ReorderMode::Verified`
+    /// and only resorting when asked
+    #[must_use]
+    pub fn new(parser: UserAgentParser) -> AdaptiveParser {
+        AdaptiveParser {
+            device: Ranking::new(parser.device_matchers.len()),
+            os: Ranking::new(parser.os_matchers.len()),
+            user_agent: Ranking::new(parser.user_agent_matchers.len()),
+            parser,
+            trusted: AtomicBool::new(false),
+            resort_every: 0,
+            parses: AtomicU64::new(0),
+        }
+    }
+
+    /// Resorts after every `parses` calls to any parse method, or never
+    /// when `0`
+    #[must_use]
+    pub fn resort_every(mut self, parses: u64) -> Self {
+        self.resort_every = parses;
+        self
+    }
+
+    /// Switches modes, which takes effect from the next parse
+    pub fn set_mode(&self, mode: ReorderMode) {
+        self.trusted
+            .store(mode == ReorderMode::Trusted, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn mode(&self) -> ReorderMode {
+        if self.trusted.load(Ordering::Relaxed) {
+            ReorderMode::Trusted
+        } else {
+            ReorderMode::Verified
+        }
+    }
+
+    /// Orders each category's rules by descending hit count, with ties and
+    /// conflicting rules kept in their original order
+    pub fn resort(&self) {
+        self.device.resort();
+        self.os.resort();
+        self.user_agent.resort();
+    }
+
+    /// The hits of each rule so far, for `UserAgentParser::prune_unhit` or
+    /// for export
+    #[must_use]
+    pub fn stats(&self) -> ParserStats {
+        ParserStats {
+            device: self
+                .device
+                .stats(|index| self.parser.device_matchers[index].regex.as_str()),
+            os: self
+                .os
+                .stats(|index| self.parser.os_matchers[index].regex.as_str()),
+            user_agent: self
+                .user_agent
+                .stats(|index| self.parser.user_agent_matchers[index].regex.as_str()),
+        }
+    }
+
+    /// The number of rules tried so far in each category, verification
+    /// included
+    #[must_use]
+    pub fn scanned(&self) -> RuleCounts {
+        RuleCounts {
+            device: self.device.scanned.load(Ordering::Relaxed),
+            os: self.os.scanned.load(Ordering::Relaxed),
+            user_agent: self.user_agent.scanned.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The wrapped parser, whose rules keep their original order
+    #[must_use]
+    pub fn parser(&self) -> &UserAgentParser {
+        &self.parser
+    }
+
+    fn count_parse(&self) {
+        let parses = self.parses.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.resort_every > 0 && parses % self.resort_every == 0 {
+            self.resort();
+        }
+    }
+
+    fn parse_untimed<'a>(&self, user_agent: &'a str) -> Client<'a> {
+        let mut client = normalized!(
+            self.parser,
+            user_agent,
+            Client {
+                device: self.match_device(user_agent),
+                os: self.match_os(user_agent),
+                user_agent: self.match_user_agent(user_agent),
+            }
+        );
+        self.parser.post_processors.apply(&mut client);
+        client
+    }
+
+    fn match_device<'a>(&self, user_agent: &'a str) -> Device<'a> {
+        if self.parser.match_strategies.device != MatchStrategy::First {
+            return self.parser.match_device(user_agent);
+        }
+        if self.parser.skips(user_agent) {
+            return Device::default();
+        }
+        self.parser
+            .sub_parsers
+            .device
+            .first(
+                || {
+                    self.device.find(
+                        self.mode(),
+                        self.parser.budgeted(&self.parser.device_matchers).len(),
+                        |index| self.parser.device_matchers[index].try_parse(user_agent),
+                    )
+                },
+                |parser| parser.try_parse(user_agent),
+            )
+            .unwrap_or_default()
+    }
+
+    fn match_os<'a>(&self, user_agent: &'a str) -> OS<'a> {
+        if self.parser.match_strategies.os != MatchStrategy::First {
+            return self.parser.match_os(user_agent);
+        }
+        if self.parser.skips(user_agent) {
+            return OS::default();
+        }
+        let os = self
+            .parser
+            .sub_parsers
+            .os
+            .first(
+                || {
+                    self.os.find(
+                        self.mode(),
+                        self.parser.budgeted(&self.parser.os_matchers).len(),
+                        |index| self.parser.os_matchers[index].try_parse(user_agent),
+                    )
+                },
+                |parser| parser.try_parse(user_agent),
+            )
+            .unwrap_or_default();
+        self.parser.darwin_os(user_agent, os)
+    }
+
+    fn match_user_agent<'a>(&self, user_agent: &'a str) -> UserAgent<'a> {
+        if self.parser.match_strategies.user_agent != MatchStrategy::First {
+            return self.parser.match_user_agent(user_agent);
+        }
+        if self.parser.skips(user_agent) {
+            return UserAgent::default();
+        }
+        self.parser
+            .sub_parsers
+            .user_agent
+            .first(
+                || {
+                    self.user_agent.find(
+                        self.mode(),
+                        self.parser.budgeted(&self.parser.user_agent_matchers).len(),
+                        |index| {
+                            self.parser.user_agent_matchers[index].try_parse(user_agent)
+                        },
+                    )
+                },
+                |parser| parser.try_parse(user_agent),
+            )
+            .unwrap_or_default()
+    }
+}
+
+impl Parser for AdaptiveParser {
+    fn parse<'a>(&self, user_agent: &'a str) -> Client<'a> {
+        self.count_parse();
+        let Some(hook) = &self.parser.slow_parse else {
+            return self.parse_untimed(user_agent);
+        };
+        let start = Instant::now();
+        let client = self.parse_untimed(user_agent);
+        hook.observe(start.elapsed(), user_agent, &client);
+        client
+    }
+
+    fn parse_device<'a>(&self, user_agent: &'a str) -> Device<'a> {
+        self.count_parse();
+        normalized!(self.parser, user_agent, self.match_device(user_agent))
+    }
+
+    fn parse_os<'a>(&self, user_agent: &'a str) -> OS<'a> {
+        self.count_parse();
+        normalized!(self.parser, user_agent, self.match_os(user_agent))
+    }
+
+    fn parse_user_agent<'a>(&self, user_agent: &'a str) -> UserAgent<'a> {
+        self.count_parse();
+        normalized!(self.parser, user_agent, self.match_user_agent(user_agent))
+    }
+}
+
+impl Ranking {
+    fn new(len: usize) -> Ranking {
+        Ranking {
+            hits: (0..len).map(|_| AtomicU64::new(0)).collect(),
+            scanned: AtomicUsize::new(0),
+            order: RwLock::new(Arc::new(Order::new((0..len).collect()))),
+            pins: Mutex::default(),
+        }
+    }
+
+    fn current(&self) -> Arc<Order> {
+        Arc::clone(&self.order.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Tries the rules with an index below `budget` in the current order.
+    /// When verifying, the rules with a lower index that come later in the
+    /// order are tried too, and the lowest matching index wins as it would
+    /// without reordering.
+    fn find<T>(
+        &self,
+        mode: ReorderMode,
+        budget: usize,
+        try_parse: impl Fn(usize) -> Option<T>,
+    ) -> Option<T> {
+        let order = self.current();
+        let mut scanned = 0;
+        let found = order
+            .indices
+            .iter()
+            .enumerate()
+            .filter(|&(_, &index)| index < budget)
+            .find_map(|(position, &index)| {
+                scanned += 1;
+                try_parse(index).map(|item| (position, index, item))
+            });
+        let Some((position, mut index, mut item)) = found else {
+            self.scanned.fetch_add(scanned, Ordering::Relaxed);
+            return None;
+        };
+
+        if mode == ReorderMode::Verified {
+            let skipped =
+                (0..index).filter(|&earlier| order.positions[earlier] > position);
+            for earlier in skipped {
+                scanned += 1;
+                if let Some(earlier_item) = try_parse(earlier) {
+                    self.pins
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .insert((earlier, index));
+                    index = earlier;
+                    item = earlier_item;
+                    break;
+                }
+            }
+        }
+        self.scanned.fetch_add(scanned, Ordering::Relaxed);
+        self.hits[index].fetch_add(1, Ordering::Relaxed);
+        Some(item)
+    }
+
+    /// Places the most hit rule whose pinned predecessors are all placed,
+    /// until every rule is
+    fn resort(&self) {
+        let len = self.hits.len();
+        let mut blockers = vec![0_usize; len];
+        let mut unblocks = vec![Vec::new(); len];
+        for &(earlier, later) in self
+            .pins
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+        {
+            blockers[later] += 1;
+            unblocks[earlier].push(later);
+        }
+
+        let hits = |index: usize| self.hits[index].load(Ordering::Relaxed);
+        let mut ready: BinaryHeap<_> = (0..len)
+            .filter(|&index| blockers[index] == 0)
+            .map(|index| (hits(index), Reverse(index)))
+            .collect();
+        let mut indices = Vec::with_capacity(len);
+        while let Some((_, Reverse(index))) = ready.pop() {
+            indices.push(index);
+            for &later in &unblocks[index] {
+                blockers[later] -= 1;
+                if blockers[later] == 0 {
+                    ready.push((hits(later), Reverse(later)));
+                }
+            }
+        }
+
+        *self.order.write().unwrap_or_else(PoisonError::into_inner) =
+            Arc::new(Order::new(indices));
+    }
+
+    fn stats<'p>(&self, pattern: impl Fn(usize) -> &'p str) -> BTreeMap<String, u64> {
+        self.hits
+            .iter()
+            .enumerate()
+            .map(|(index, hits)| {
+                (pattern(index).to_owned(), hits.load(Ordering::Relaxed))
+            })
+            .collect()
+    }
+}
+
+impl Order {
+    fn new(indices: Vec<usize>) -> Order {
+        let mut positions = vec![0; indices.len()];
+        for (position, &index) in indices.iter().enumerate() {
+            positions[index] = position;
+        }
+        Order { indices, positions }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Category;
+
+    fn corpus() -> impl Iterator<Item = &'static str> {
+        Category::ALL
+            .iter()
+            .flat_map(|category| category.user_agents().iter().copied())
+    }
+
+    fn pins(adaptive: &AdaptiveParser) -> usize {
+        [&adaptive.device, &adaptive.os, &adaptive.user_agent]
+            .iter()
+            .map(|ranking| ranking.pins.lock().unwrap().len())
+            .sum()
+    }
+
+    #[test]
+    fn resorting_keeps_classifications() {
+        let regular = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let adaptive = AdaptiveParser::new(
+            UserAgentParser::from_yaml("./src/core/regexes.yaml")
+                .expect("Parser creation failed"),
+        );
+
+        let mut learned = usize::MAX;
+        for _ in 0..10 {
+            for user_agent in corpus() {
+                assert_eq!(adaptive.parse(user_agent), regular.parse(user_agent));
+            }
+            adaptive.resort();
+            if pins(&adaptive) == learned {
+                break;
+            }
+            learned = pins(&adaptive);
+        }
+        assert!(learned > 0);
+
+        adaptive.set_mode(ReorderMode::Trusted);
+        let before = adaptive.scanned();
+        for user_agent in corpus() {
+            assert_eq!(adaptive.parse(user_agent), regular.parse(user_agent));
+        }
+        let after = adaptive.scanned();
+
+        let unordered = AdaptiveParser::new(
+            UserAgentParser::from_yaml("./src/core/regexes.yaml")
+                .expect("Parser creation failed"),
+        );
+        for user_agent in corpus() {
+            unordered.parse(user_agent);
+        }
+        assert!(after.user_agent - before.user_agent < unordered.scanned().user_agent);
+        assert!(after.device - before.device < unordered.scanned().device);
+    }
+
+    #[test]
+    fn resorts_periodically_and_counts_hits() {
+        let parser = UserAgentParser::from_bytes(
+            br"
+user_agent_parsers:
+  - regex: '(Cold)/(\d+)'
+  - regex: '(Hot)/(\d+)'
+",
+        )
+        .expect("Parser creation failed");
+        let adaptive = AdaptiveParser::new(parser).resort_every(3);
+
+        for user_agent in ["Hot/1", "Hot/2", "Cold/1"] {
+            adaptive.parse_user_agent(user_agent);
+        }
+        assert_eq!(adaptive.user_agent.current().indices, [1, 0]);
+        assert_eq!(adaptive.stats().user_agent[r"(Hot)/(\d+)"], 2);
+        assert_eq!(adaptive.parse_user_agent("Cold/3").family, "Cold");
+        assert_eq!(
+            adaptive.parser().user_agent_matchers()[0].pattern(),
+            r"(Cold)/(\d+)"
+        );
+    }
+
+    #[test]
+    fn verification_pins_conflicting_rules() {
+        let parser = UserAgentParser::from_bytes(
+            br"
+user_agent_parsers:
+  - regex: '(Edge)/(\d+)'
+  - regex: '(Chrome)/(\d+)'
+",
+        )
+        .expect("Parser creation failed");
+        let adaptive = AdaptiveParser::new(parser);
+        for _ in 0..3 {
+            adaptive.parse_user_agent("Chrome/120");
+        }
+        adaptive.resort();
+        assert_eq!(adaptive.user_agent.current().indices, [1, 0]);
+
+        let both = "Chrome/120 Edge/120";
+        assert_eq!(adaptive.parse_user_agent(both).family, "Edge");
+        adaptive.resort();
+        assert_eq!(adaptive.user_agent.current().indices, [0, 1]);
+
+        adaptive.set_mode(ReorderMode::Trusted);
+        assert_eq!(adaptive.parse_user_agent(both).family, "Edge");
+    }
+
+    #[test]
+    fn budgets_cover_the_original_first_rules() {
+        let parser = UserAgentParser::builder()
+            .max_matchers_per_category(1)
+            .build_from_bytes(
+                br"
+user_agent_parsers:
+  - regex: '(Cold)/(\d+)'
+  - regex: '(Hot)/(\d+)'
+",
+            )
+            .expect("Parser creation failed");
+        let adaptive = AdaptiveParser::new(parser);
+        adaptive.user_agent.hits[1].fetch_add(5, Ordering::Relaxed);
+        adaptive.resort();
+        assert_eq!(adaptive.user_agent.current().indices, [1, 0]);
+
+        for mode in [ReorderMode::Verified, ReorderMode::Trusted] {
+            adaptive.set_mode(mode);
+            assert_eq!(adaptive.parse_user_agent("Hot/1").family, "Other");
+            assert_eq!(adaptive.parse_user_agent("Cold/1").family, "Cold");
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use rkyv::{rancor, string::ArchivedString, util::AlignedVec, Archive};
+
+use super::*;
+
+/// The rule data of a parser as an `rkyv` archive: every pattern cleaned and
+/// with its flags folded in, ready to compile, along with its replacements,
+/// priority and quality. Written with `to_bytes` and read back without
+/// copying with `access`, such as from a memory-mapped file. Rule `extras`
+/// aren't archived.
+///
+/// ```rust
+/// # use uaparser::*;
+/// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+/// let bytes = parser.to_rules().to_bytes().unwrap();
+/// let restored = UserAgentParser::from_archived(Rules::access(&bytes).unwrap()).unwrap();
+///
+/// assert_eq!(restored.parse("curl/8.4.0"), parser.parse("curl/8.4.0"));
+/// ```
+#[derive(Archive, rkyv::Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Rules {
+    device: Vec<Rule>,
+    os: Vec<Rule>,
+    user_agent: Vec<Rule>,
+}
+
+/// One rule, with its replacements in the order of the fields they fill
+#[derive(Archive, rkyv::Serialize, Clone, Debug, PartialEq)]
+struct Rule {
+    pattern: String,
+    replacements: Vec<Option<String>>,
+    priority: i32,
+    /// `MatchQuality` as its position, plus one, 0 when unset
+    quality: u8,
+}
+
+impl Rules {
+    /// Archives the rules into a buffer aligned for `access`
+    pub fn to_bytes(&self) -> Result<AlignedVec, Error> {
+        Ok(rkyv::to_bytes::<rancor::Error>(self)?)
+    }
+
+    /// Checks that `bytes` hold a valid archive and returns it without
+    /// copying. The bytes must be aligned to 16, as those of `to_bytes` and
+    /// memory-mapped files are. Untrusted, truncated or corrupted archives
+    /// fail with `Error::Archive`.
+    pub fn access(bytes: &[u8]) -> Result<&ArchivedRules, Error> {
+        Ok(rkyv::access::<ArchivedRules, rancor::Error>(bytes)?)
+    }
+}
+
+impl UserAgentParser {
+    /// The rules this parser runs, in the order they are tried, for archiving
+    /// with `Rules::to_bytes`. Custom sub-parsers and post-processors aren't
+    /// part of the rule data.
+    #[must_use]
+    pub fn to_rules(&self) -> Rules {
+        Rules {
+            device: self
+                .device_matchers
+                .iter()
+                .map(|matcher| {
+                    Rule::new(
+                        &matcher.regex,
+                        &[
+                            &matcher.device_replacement,
+                            &matcher.brand_replacement,
+                            &matcher.model_replacement,
+                        ],
+                        matcher.priority,
+                        matcher.quality,
+                    )
+                })
+                .collect(),
+            os: self
+                .os_matchers
+                .iter()
+                .map(|matcher| {
+                    Rule::new(
+                        &matcher.regex,
+                        &[
+                            &matcher.os_replacement,
+                            &matcher.os_v1_replacement,
+                            &matcher.os_v2_replacement,
+                            &matcher.os_v3_replacement,
+                        ],
+                        matcher.priority,
+                        matcher.quality,
+                    )
+                })
+                .collect(),
+            user_agent: self
+                .user_agent_matchers
+                .iter()
+                .map(|matcher| {
+                    Rule::new(
+                        &matcher.regex,
+                        &[
+                            &matcher.family_replacement,
+                            &matcher.v1_replacement,
+                            &matcher.v2_replacement,
+                            &matcher.v3_replacement,
+                            &matcher.v4_replacement,
+                        ],
+                        matcher.priority,
+                        matcher.quality,
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Builds a parser with the default settings from archived rules,
+    /// compiling each pattern straight from the archive
+    pub fn from_archived(rules: &ArchivedRules) -> Result<UserAgentParser, Error> {
+        let mut device_matchers = Vec::with_capacity(rules.device.len());
+        for rule in rules.device.iter() {
+            let [device_replacement, brand_replacement, model_replacement] =
+                rule.replacements();
+            device_matchers.push(device::Matcher {
+                regex: compile_cleaned(&rule.pattern).map_err(DeviceError::from)?,
+                has_group: GroupFlags::from_replacements(&[
+                    device_replacement,
+                    brand_replacement,
+                    model_replacement,
+                ]),
+                device_replacement: device_replacement.map(Box::from),
+                brand_replacement: brand_replacement.map(Box::from),
+                model_replacement: model_replacement.map(Box::from),
+                priority: rule.priority.to_native(),
+                quality: rule.quality(),
+                extras: None,
+            });
+        }
+
+        let mut os_matchers = Vec::with_capacity(rules.os.len());
+        for rule in rules.os.iter() {
+            let [os_replacement, os_v1_replacement, os_v2_replacement, os_v3_replacement] =
+                rule.replacements();
+            os_matchers.push(os::Matcher {
+                regex: compile_cleaned(&rule.pattern).map_err(OSError::from)?,
+                has_group: GroupFlags::from_replacements(&[
+                    os_replacement,
+                    os_v1_replacement,
+                    os_v2_replacement,
+                    os_v3_replacement,
+                ]),
+                os_replacement: os_replacement.map(Box::from),
+                os_v1_replacement: os_v1_replacement.map(Box::from),
+                os_v2_replacement: os_v2_replacement.map(Box::from),
+                os_v3_replacement: os_v3_replacement.map(Box::from),
+                priority: rule.priority.to_native(),
+                quality: rule.quality(),
+                extras: None,
+            });
+        }
+
+        let mut user_agent_matchers = Vec::with_capacity(rules.user_agent.len());
+        for rule in rules.user_agent.iter() {
+            let [family_replacement, v1_replacement, v2_replacement, v3_replacement, v4_replacement] =
+                rule.replacements();
+            user_agent_matchers.push(user_agent::Matcher {
+                regex: compile_cleaned(&rule.pattern).map_err(UserAgentError::from)?,
+                has_group: GroupFlags::from_replacements(&[family_replacement]),
+                family_replacement: family_replacement.map(Box::from),
+                v1_replacement: v1_replacement.map(Box::from),
+                v2_replacement: v2_replacement.map(Box::from),
+                v3_replacement: v3_replacement.map(Box::from),
+                v4_replacement: v4_replacement.map(Box::from),
+                priority: rule.priority.to_native(),
+                quality: rule.quality(),
+                extras: None,
+            });
+        }
+
+        // Stable sorts, so rules of equal priority keep their archived order
+        device_matchers.sort_by_key(|matcher| Reverse(matcher.priority));
+        os_matchers.sort_by_key(|matcher| Reverse(matcher.priority));
+        user_agent_matchers.sort_by_key(|matcher| Reverse(matcher.priority));
+
+        Ok(UserAgentParser::from_matchers(
+            device_matchers,
+            os_matchers,
+            user_agent_matchers,
+        ))
+    }
+}
+
+impl Rule {
+    fn new(
+        regex: &Regex,
+        replacements: &[&Option<Box<str>>],
+        priority: i32,
+        quality: Option<MatchQuality>,
+    ) -> Rule {
+        Rule {
+            pattern: regex.as_str().to_owned(),
+            replacements: replacements
+                .iter()
+                .map(|replacement| replacement.as_deref().map(str::to_owned))
+                .collect(),
+            priority,
+            quality: match quality {
+                None => 0,
+                Some(MatchQuality::None) => 1,
+                Some(MatchQuality::Generic) => 2,
+                Some(MatchQuality::Exact) => 3,
+            },
+        }
+    }
+}
+
+impl ArchivedRule {
+    /// The first `N` replacements, with any missing ones unset
+    fn replacements<const N: usize>(&self) -> [Option<&str>; N] {
+        let mut replacements = [None; N];
+        for (slot, replacement) in replacements.iter_mut().zip(self.replacements.iter()) {
+            *slot = replacement.as_ref().map(ArchivedString::as_str);
+        }
+        replacements
+    }
+
+    fn quality(&self) -> Option<MatchQuality> {
+        match self.quality {
+            1 => Some(MatchQuality::None),
+            2 => Some(MatchQuality::Generic),
+            3 => Some(MatchQuality::Exact),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER_AGENTS: &[&str] = &[
+        "curl/8.4.0",
+        "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 \
+         (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36",
+        "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 \
+         (KHTML, like Gecko) Version/17.1 Safari/605.1.15",
+        "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)",
+    ];
+
+    #[test]
+    fn archives_round_trip() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let rules = parser.to_rules();
+        let bytes = rules.to_bytes().expect("Archiving failed");
+        let restored =
+            UserAgentParser::from_archived(Rules::access(&bytes).expect("Access failed"))
+                .expect("Loading failed");
+
+        assert_eq!(restored.to_rules(), rules);
+        for user_agent in USER_AGENTS {
+            assert_eq!(restored.parse(user_agent), parser.parse(user_agent));
+            assert_eq!(
+                restored.parse_scored(user_agent),
+                parser.parse_scored(user_agent)
+            );
+        }
+    }
+
+    #[test]
+    fn corrupted_archives_are_rejected() {
+        let parser = UserAgentParser::from_bytes(
+            br"
+user_agent_parsers:
+  - regex: '(Acme)Browser/(\d+)'
+os_parsers: []
+device_parsers: []
+",
+        )
+        .expect("Parser creation failed");
+        let bytes = parser.to_rules().to_bytes().expect("Archiving failed");
+        assert!(Rules::access(&bytes).is_ok());
+
+        let mut invalid_utf8 = AlignedVec::<16>::new();
+        invalid_utf8.extend_from_slice(&bytes);
+        let start = bytes
+            .windows(4)
+            .position(|window| window == b"Acme")
+            .expect("Pattern not archived");
+        invalid_utf8[start] = 0xFF;
+        assert!(matches!(
+            Rules::access(&invalid_utf8),
+            Err(Error::Archive(_))
+        ));
+
+        let mut dangling = AlignedVec::<16>::new();
+        dangling.extend_from_slice(&bytes);
+        let len = dangling.len();
+        dangling[len - 8..].fill(0x7F);
+        assert!(Rules::access(&dangling).is_err());
+
+        assert!(Rules::access(&bytes[..bytes.len() / 2]).is_err());
+    }
+}
@@ -0,0 +1,558 @@
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Condvar, Mutex, MutexGuard, OnceLock, PoisonError,
+    },
+    thread,
+};
+
+use super::*;
+
+/// What a `BackgroundParser` does when none of the rules compiled so far
+/// match and more of the category are still being compiled
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum WhileCompiling {
+    /// Waits for more rules, until one matches or the category is done
+    #[default]
+    Wait,
+    /// Returns the default result for the category straight away
+    ReturnDefault,
+}
+
+/// A parser whose rules are compiled on a worker thread, returned by
+/// `UserAgentParser::build_in_background`. It can parse as soon as it is
+/// created: rules are compiled one category at a time in the order they are
+/// tried, and parses scan the rules compiled so far, so a match early in a
+/// category is found before the rest of it compiles. A miss with more rules
+/// to come is handled according to `WhileCompiling`. Once every rule is
+/// compiled, parsing is delegated to a regular `UserAgentParser` after a
+/// single atomic load.
+///
+/// Clones share the same compilation.
+#[derive(Clone, Debug)]
+pub struct BackgroundParser {
+    shared: Arc<Shared>,
+    while_compiling: WhileCompiling,
+}
+
+/// The number of rules compiled between wake-ups of waiting parses
+const CHUNK: usize = 16;
+
+#[derive(Debug)]
+struct Shared {
+    total: RuleCounts,
+    /// The length of each category's compiled prefix of `Interim` slots
+    device_compiled: AtomicUsize,
+    os_compiled: AtomicUsize,
+    user_agent_compiled: AtomicUsize,
+    /// `None` once the matchers are being moved into the compiled parser
+    interim: Mutex<Option<Interim>>,
+    changed: Condvar,
+    outcome: OnceLock<Result<UserAgentParser, Error>>,
+}
+
+/// One slot per rule of a category, in the order they are tried, filled by
+/// the worker from the front
+type Slots<M> = Arc<[OnceLock<M>]>;
+
+/// The slots of every category, shared by the worker and interim parses
+#[derive(Clone, Debug)]
+struct Interim {
+    device: Slots<device::Matcher>,
+    os: Slots<os::Matcher>,
+    user_agent: Slots<user_agent::Matcher>,
+}
+
+enum Matchers<'p, M> {
+    Ready(&'p UserAgentParser),
+    Interim(Slots<M>),
+    Unavailable,
+}
+
+impl UserAgentParser {
+    /// Starts compiling `regex_file` on a new thread and returns straight
+    /// away, for services with a readiness deadline shorter than a full
+    /// compilation. Parses before `BackgroundParser::wait_ready` returns may
+    /// be slower, and with `WhileCompiling::ReturnDefault` less complete.
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// let regex_file = RegexFile::from_slice(include_bytes!("../../src/core/regexes.yaml")).unwrap();
+    /// let parser = UserAgentParser::build_in_background(regex_file);
+    ///
+    /// assert_eq!(parser.parse_user_agent("curl/8.4.0").family, "curl");
+    /// parser.wait_ready().expect("Parser creation failed");
+    /// ```
+    #[must_use]
+    pub fn build_in_background(regex_file: RegexFile) -> BackgroundParser {
+        let shared = Arc::new(Shared::new(RuleCounts {
+            device: regex_file.device_parsers.len(),
+            os: regex_file.os_parsers.len(),
+            user_agent: regex_file.user_agent_parsers.len(),
+        }));
+
+        let worker = Arc::clone(&shared);
+        let slots = Interim::new(shared.total);
+        *shared.lock() = Some(slots.clone());
+        thread::spawn(move || worker.finish(|| worker.compile(regex_file, slots)));
+
+        BackgroundParser {
+            shared,
+            while_compiling: WhileCompiling::Wait,
+        }
+    }
+}
+
+impl BackgroundParser {
+    /// Sets what parsing does with categories that aren't compiled yet
+    #[must_use]
+    pub fn while_compiling(mut self, while_compiling: WhileCompiling) -> Self {
+        self.while_compiling = while_compiling;
+        self
+    }
+
+    /// Whether every rule has been compiled
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        self.ready().is_some()
+    }
+
+    /// Blocks until compilation finishes, returning the compiled parser or
+    /// the error that stopped it, which is `Error::CompilePanicked` if the
+    /// worker thread panicked. After an error, every parse returns the
+    /// default result.
+    pub fn wait_ready(&self) -> Result<&UserAgentParser, &Error> {
+        let mut interim = self.shared.lock();
+        loop {
+            if let Some(outcome) = self.shared.outcome.get() {
+                return outcome.as_ref();
+            }
+            interim = self.shared.wait(interim);
+        }
+    }
+
+    /// The number of rules of each category compiled so far
+    #[must_use]
+    pub fn progress(&self) -> RuleCounts {
+        RuleCounts {
+            device: self.shared.device_compiled.load(Ordering::Relaxed),
+            os: self.shared.os_compiled.load(Ordering::Relaxed),
+            user_agent: self.shared.user_agent_compiled.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The number of rules of each category in the rule file
+    #[must_use]
+    pub fn total(&self) -> RuleCounts {
+        self.shared.total
+    }
+
+    fn ready(&self) -> Option<&UserAgentParser> {
+        self.shared.outcome.get()?.as_ref().ok()
+    }
+
+    /// Finds the matchers for one category. While the compiled parser is
+    /// being put together, which only moves the matchers, this waits for it
+    /// whatever the `WhileCompiling`.
+    fn matchers<M>(&self, pick: fn(&Interim) -> &Slots<M>) -> Matchers<'_, M> {
+        let mut interim = self.shared.lock();
+        loop {
+            match self.shared.outcome.get() {
+                Some(Ok(parser)) => return Matchers::Ready(parser),
+                Some(Err(_)) => return Matchers::Unavailable,
+                None => {}
+            }
+            if let Some(slots) = &*interim {
+                return Matchers::Interim(Arc::clone(pick(slots)));
+            }
+            interim = self.shared.wait(interim);
+        }
+    }
+
+    /// Matches as a default `UserAgentParser` does, over the compiled prefix
+    /// of `slots`. When none of it matches, this waits for more rules or
+    /// gives up as `WhileCompiling` says.
+    fn first_match<'a, M: SubParser<'a>>(
+        &self,
+        slots: &[OnceLock<M>],
+        compiled: &AtomicUsize,
+        user_agent: &'a str,
+    ) -> M::Item
+    where
+        M::Item: Default,
+    {
+        if user_agent.trim().is_empty() {
+            return M::Item::default();
+        }
+        let mut scanned = 0;
+        loop {
+            let end = compiled.load(Ordering::Acquire);
+            let found = slots[scanned..end]
+                .iter()
+                .filter_map(OnceLock::get)
+                .find_map(|matcher| matcher.try_parse(user_agent));
+            if let Some(item) = found {
+                return item;
+            }
+            if end == slots.len() || self.while_compiling == WhileCompiling::ReturnDefault
+            {
+                return M::Item::default();
+            }
+            scanned = end;
+
+            let mut interim = self.shared.lock();
+            while compiled.load(Ordering::Acquire) == end
+                && self.shared.outcome.get().is_none()
+            {
+                interim = self.shared.wait(interim);
+            }
+            drop(interim);
+            if compiled.load(Ordering::Acquire) == end {
+                // Compilation failed before reaching the next rule
+                return M::Item::default();
+            }
+        }
+    }
+}
+
+impl Parser for BackgroundParser {
+    fn parse<'a>(&self, user_agent: &'a str) -> Client<'a> {
+        if let Some(parser) = self.ready() {
+            return parser.parse(user_agent);
+        }
+        Client {
+            device: self.parse_device(user_agent),
+            os: self.parse_os(user_agent),
+            user_agent: self.parse_user_agent(user_agent),
+        }
+    }
+
+    fn parse_device<'a>(&self, user_agent: &'a str) -> Device<'a> {
+        if let Some(parser) = self.ready() {
+            return parser.parse_device(user_agent);
+        }
+        match self.matchers(|interim| &interim.device) {
+            Matchers::Ready(parser) => parser.parse_device(user_agent),
+            Matchers::Interim(slots) => {
+                self.first_match(&slots, &self.shared.device_compiled, user_agent)
+            }
+            Matchers::Unavailable => Device::default(),
+        }
+    }
+
+    fn parse_os<'a>(&self, user_agent: &'a str) -> OS<'a> {
+        if let Some(parser) = self.ready() {
+            return parser.parse_os(user_agent);
+        }
+        match self.matchers(|interim| &interim.os) {
+            Matchers::Ready(parser) => parser.parse_os(user_agent),
+            Matchers::Interim(slots) => {
+                self.first_match(&slots, &self.shared.os_compiled, user_agent)
+            }
+            Matchers::Unavailable => OS::default(),
+        }
+    }
+
+    fn parse_user_agent<'a>(&self, user_agent: &'a str) -> UserAgent<'a> {
+        if let Some(parser) = self.ready() {
+            return parser.parse_user_agent(user_agent);
+        }
+        match self.matchers(|interim| &interim.user_agent) {
+            Matchers::Ready(parser) => parser.parse_user_agent(user_agent),
+            Matchers::Interim(slots) => {
+                self.first_match(&slots, &self.shared.user_agent_compiled, user_agent)
+            }
+            Matchers::Unavailable => UserAgent::default(),
+        }
+    }
+}
+
+impl Shared {
+    fn new(total: RuleCounts) -> Shared {
+        Shared {
+            total,
+            device_compiled: AtomicUsize::new(0),
+            os_compiled: AtomicUsize::new(0),
+            user_agent_compiled: AtomicUsize::new(0),
+            interim: Mutex::new(None),
+            changed: Condvar::new(),
+            outcome: OnceLock::new(),
+        }
+    }
+
+    /// Records the outcome of `compile`, or a failure when it panics, so
+    /// that waiting parses always wake up
+    fn finish(&self, compile: impl FnOnce() -> Result<UserAgentParser, Error>) {
+        let outcome =
+            panic::catch_unwind(AssertUnwindSafe(compile)).unwrap_or_else(|payload| {
+                Err(Error::CompilePanicked(panic_message(&*payload)))
+            });
+        let mut interim = self.lock();
+        *interim = None;
+        let _ = self.outcome.set(outcome);
+        drop(interim);
+        self.changed.notify_all();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<Interim>> {
+        self.interim.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn wait<'g>(
+        &self,
+        interim: MutexGuard<'g, Option<Interim>>,
+    ) -> MutexGuard<'g, Option<Interim>> {
+        self.changed
+            .wait(interim)
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Wakes parses waiting for more rules or the outcome
+    fn notify(&self) {
+        drop(self.lock());
+        self.changed.notify_all();
+    }
+
+    /// Compiles each category into its slots, then moves the matchers into
+    /// the compiled parser once the parses scanning them are done
+    fn compile(
+        &self,
+        regex_file: RegexFile,
+        slots: Interim,
+    ) -> Result<UserAgentParser, Error> {
+        self.compile_category(
+            regex_file.user_agent_parsers,
+            &slots.user_agent,
+            &self.user_agent_compiled,
+            user_agent::Matcher::try_from,
+            |entry| entry.priority,
+        )?;
+        self.compile_category(
+            regex_file.os_parsers,
+            &slots.os,
+            &self.os_compiled,
+            os::Matcher::try_from,
+            |entry| entry.priority,
+        )?;
+        self.compile_category(
+            regex_file.device_parsers,
+            &slots.device,
+            &self.device_compiled,
+            device::Matcher::try_from,
+            |entry| entry.priority,
+        )?;
+
+        *self.lock() = None;
+        Ok(UserAgentParser::from_matchers(
+            take_matchers(slots.device),
+            take_matchers(slots.os),
+            take_matchers(slots.user_agent),
+        ))
+    }
+
+    /// Compiles one category's rules into `slots`, stably sorted by priority
+    /// first as `UserAgentParser::try_from` sorts its matchers, so that every
+    /// compiled prefix is tried in the final order. Each rule is published
+    /// through `compiled` as soon as it is compiled.
+    fn compile_category<E, M, C: Into<Error>>(
+        &self,
+        mut entries: Vec<E>,
+        slots: &[OnceLock<M>],
+        compiled: &AtomicUsize,
+        compile: impl Fn(E) -> Result<M, C>,
+        priority: impl Fn(&E) -> Option<i32>,
+    ) -> Result<(), Error> {
+        entries.sort_by_key(|entry| Reverse(priority(entry).unwrap_or_default()));
+        for (index, (entry, slot)) in entries.into_iter().zip(slots).enumerate() {
+            let _ = slot.set(compile(entry).map_err(Into::into)?);
+            compiled.store(index + 1, Ordering::Release);
+            if (index + 1) % CHUNK == 0 {
+                self.notify();
+            }
+        }
+        self.notify();
+        Ok(())
+    }
+}
+
+impl Interim {
+    fn new(total: RuleCounts) -> Interim {
+        Interim {
+            device: empty_slots(total.device),
+            os: empty_slots(total.os),
+            user_agent: empty_slots(total.user_agent),
+        }
+    }
+}
+
+fn empty_slots<M>(len: usize) -> Slots<M> {
+    (0..len).map(|_| OnceLock::new()).collect()
+}
+
+/// Moves the matchers out of `slots`, once parses that were scanning them
+/// have let go, which takes no longer than the rest of their scans
+fn take_matchers<M>(mut slots: Slots<M>) -> Vec<M> {
+    loop {
+        if let Some(slots) = Arc::get_mut(&mut slots) {
+            return slots.iter_mut().filter_map(OnceLock::take).collect();
+        }
+        thread::yield_now();
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match (
+        payload.downcast_ref::<&str>(),
+        payload.downcast_ref::<String>(),
+    ) {
+        (Some(message), _) => (*message).to_owned(),
+        (_, Some(message)) => message.clone(),
+        _ => "unknown panic".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::testing::Category;
+
+    fn regex_file() -> RegexFile {
+        RegexFile::from_slice(include_bytes!("../../src/core/regexes.yaml"))
+            .expect("Rule file read failed")
+    }
+
+    #[test]
+    fn returns_before_compiling() {
+        let start = Instant::now();
+        let parser = UserAgentParser::build_in_background(regex_file());
+        assert!(start.elapsed() < Duration::from_millis(250));
+        assert_eq!(parser.total().device, regex_file().device_parsers.len());
+
+        // Scans the user agent rules compiled so far, which compile first
+        assert_eq!(parser.parse_user_agent("curl/8.4.0").family, "curl");
+
+        let compiled = parser.wait_ready().expect("Parser creation failed");
+        assert!(parser.is_ready());
+        assert_eq!(parser.progress(), parser.total());
+        assert_eq!(compiled.source_info().rule_counts, parser.total());
+    }
+
+    #[test]
+    fn matches_a_regular_parser_once_ready() {
+        let regular =
+            UserAgentParser::try_from(regex_file()).expect("Parser creation failed");
+        let background = UserAgentParser::build_in_background(regex_file());
+        let defaulting = background
+            .clone()
+            .while_compiling(WhileCompiling::ReturnDefault);
+
+        for category in Category::ALL {
+            for user_agent in category.user_agents() {
+                assert_eq!(background.parse(user_agent), regular.parse(user_agent));
+                let client = defaulting.parse(user_agent);
+                assert!(
+                    client == regular.parse(user_agent)
+                        || client.device.family == "Other"
+                );
+            }
+        }
+        background.wait_ready().expect("Parser creation failed");
+        for category in Category::ALL {
+            for user_agent in category.user_agents() {
+                assert_eq!(defaulting.parse(user_agent), regular.parse(user_agent));
+            }
+        }
+    }
+
+    #[test]
+    fn parses_from_the_compiled_prefix() {
+        let regex_file = RegexFile::from_slice(
+            br"
+user_agent_parsers:
+  - regex: '(Foo)/(\d+)'
+  - regex: '(Bar)/(\d+)'
+  - regex: '(Baz)/(\d+)'
+    priority: 1
+os_parsers: []
+device_parsers: []
+",
+        )
+        .expect("Rule file read failed");
+        let shared = Arc::new(Shared::new(RuleCounts {
+            user_agent: 3,
+            ..RuleCounts::default()
+        }));
+        let slots = Interim::new(shared.total);
+        *shared.lock() = Some(slots.clone());
+        let parser = BackgroundParser {
+            shared: Arc::clone(&shared),
+            while_compiling: WhileCompiling::Wait,
+        };
+
+        // The prioritized rule and the first in file order are tried first,
+        // so they fill the compiled prefix while `Bar` is still to come
+        let mut entries = regex_file.user_agent_parsers;
+        entries.remove(1);
+        shared
+            .compile_category(
+                entries,
+                &slots.user_agent,
+                &shared.user_agent_compiled,
+                user_agent::Matcher::try_from,
+                |entry| entry.priority,
+            )
+            .expect("Rule compilation failed");
+        assert_eq!(parser.progress().user_agent, 2);
+
+        assert_eq!(parser.parse_user_agent("Foo/1").family, "Foo");
+        assert_eq!(parser.parse_user_agent("Baz/1 Foo/1").family, "Baz");
+        let defaulting = parser
+            .clone()
+            .while_compiling(WhileCompiling::ReturnDefault);
+        assert_eq!(defaulting.parse_user_agent("Bar/1").family, "Other");
+    }
+
+    #[test]
+    fn reports_compile_errors() {
+        let regex_file = RegexFile::from_slice(
+            br"
+user_agent_parsers:
+  - regex: '(Foo)/(\d+)'
+os_parsers:
+  - regex: '(Bar'
+device_parsers:
+  - regex: '(FooPhone)'
+",
+        )
+        .expect("Rule file read failed");
+        let parser = UserAgentParser::build_in_background(regex_file);
+
+        assert!(matches!(parser.wait_ready(), Err(Error::OS(_))));
+        assert!(!parser.is_ready());
+        assert_eq!(parser.progress().os, 0);
+        assert_eq!(parser.parse("Foo/1 Bar FooPhone"), Client::default());
+    }
+
+    #[test]
+    fn worker_panics_fail_waiting_parses() {
+        let shared = Arc::new(Shared::new(RuleCounts::default()));
+        let parser = BackgroundParser {
+            shared: Arc::clone(&shared),
+            while_compiling: WhileCompiling::Wait,
+        };
+
+        let worker = thread::spawn(move || shared.finish(|| panic!("compiler bug")));
+        assert_eq!(parser.parse("curl/8.4.0"), Client::default());
+        worker.join().expect("Worker panicked");
+
+        assert!(matches!(
+            parser.wait_ready(),
+            Err(Error::CompilePanicked(message)) if message == "compiler bug"
+        ));
+        assert!(!parser.is_ready());
+    }
+}
@@ -0,0 +1,379 @@
+use regex::CaptureLocations;
+
+use super::*;
+use crate::client::FLAT_KEYS;
+
+pub(super) const UA_FAMILY: usize = 0;
+pub(super) const UA_MAJOR: usize = 1;
+pub(super) const UA_MINOR: usize = 2;
+pub(super) const UA_PATCH: usize = 3;
+pub(super) const OS_FAMILY: usize = 4;
+pub(super) const OS_MAJOR: usize = 5;
+pub(super) const OS_MINOR: usize = 6;
+pub(super) const OS_PATCH: usize = 7;
+pub(super) const OS_PATCH_MINOR: usize = 8;
+pub(super) const DEVICE_FAMILY: usize = 9;
+pub(super) const DEVICE_BRAND: usize = 10;
+pub(super) const DEVICE_MODEL: usize = 11;
+/// `UserAgent::patch_minor`, last as it was added after the others
+pub(super) const UA_PATCH_MINOR: usize = 12;
+
+/// Reusable storage for the fields of a parse result, filled by
+/// `UserAgentParser::parse_into`. Each parse clears and refills the same
+/// strings, which only ever grow, along with the capture locations of the
+/// rules that matched, so once a batch has warmed the buffer up, parsing
+/// user agents like the ones already seen doesn't allocate.
+///
+/// ```rust
+/// # use uaparser::*;
+/// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+/// let mut buf = ClientBuf::new();
+///
+/// for user_agent in ["curl/8.4.0", "Wget/1.21.4"] {
+///     parser.parse_into(user_agent, &mut buf);
+///     assert_eq!(buf.client(), parser.parse(user_agent));
+/// }
+/// assert_eq!(buf.ua_family(), "Wget");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ClientBuf {
+    pub(super) fields: FlatFields,
+    /// The capture locations of each rule, by category and rule index
+    locations: [Vec<Option<CaptureLocations>>; 3],
+}
+
+/// The parsed fields in the order of `FLAT_KEYS`, each present or not
+#[derive(Clone, Debug, Default)]
+pub(super) struct FlatFields {
+    values: [String; 13],
+    present: [bool; 13],
+}
+
+impl FlatFields {
+    /// The defaults of an unmatched user agent: every family `Other`
+    fn reset(&mut self) {
+        for index in 0..self.values.len() {
+            let family = matches!(index, UA_FAMILY | OS_FAMILY | DEVICE_FAMILY);
+            self.set(index, family.then_some("Other"));
+        }
+    }
+
+    pub(super) fn set(&mut self, index: usize, value: Option<&str>) {
+        self.values[index].clear();
+        self.values[index].push_str(value.unwrap_or_default());
+        self.present[index] = value.is_some();
+    }
+
+    /// Writes a replacement template, expanding its capture groups when it
+    /// has any, as `replace_cow` does
+    pub(super) fn replace(
+        &mut self,
+        index: usize,
+        replacement: &str,
+        has_group: bool,
+        groups: &Groups<'_>,
+    ) {
+        if !has_group {
+            return self.set(index, Some(replacement));
+        }
+        let value = &mut self.values[index];
+        value.clear();
+        groups.expand(replacement, value);
+        let end = value.trim_end().len();
+        value.truncate(end);
+        let start = value.len() - value.trim_start().len();
+        value.drain(..start);
+        self.present[index] = true;
+    }
+
+    /// Leaves the field out when it is empty, as `none_if_empty` does
+    pub(super) fn none_if_empty(&mut self, index: usize) {
+        if self.values[index].is_empty() {
+            self.present[index] = false;
+        }
+    }
+
+    fn get(&self, index: usize) -> Option<&str> {
+        self.present[index].then(|| self.values[index].as_str())
+    }
+}
+
+/// A matcher that can write its result into a `ClientBuf`
+pub(super) trait Fill {
+    fn regex(&self) -> &Regex;
+
+    /// Writes the fields of the matcher's category from a match, unless the
+    /// match gives no family, as `try_parse` would return `None`
+    fn fill(&self, groups: &Groups<'_>, fields: &mut FlatFields) -> bool;
+}
+
+/// The capture groups of a match, read into reused `CaptureLocations`
+pub(super) struct Groups<'t> {
+    text: &'t str,
+    locations: &'t CaptureLocations,
+}
+
+impl<'t> Groups<'t> {
+    /// The text of a non-empty group
+    pub(super) fn get(&self, index: usize) -> Option<&'t str> {
+        self.locations
+            .get(index)
+            .map(|(start, end)| &self.text[start..end])
+            .and_then(none_if_empty)
+    }
+
+    /// Appends `replacement` to `out` with its `$1` and `${1}` references
+    /// replaced by their groups, following `regex::Captures::expand`
+    fn expand(&self, mut replacement: &str, out: &mut String) {
+        while let Some(at) = replacement.find('$') {
+            out.push_str(&replacement[..at]);
+            replacement = &replacement[at + 1..];
+            if let Some(rest) = replacement.strip_prefix('$') {
+                out.push('$');
+                replacement = rest;
+                continue;
+            }
+            let (name, rest) = if let Some(braced) = replacement.strip_prefix('{') {
+                let Some(end) = braced.find('}') else {
+                    out.push('$');
+                    continue;
+                };
+                (&braced[..end], &braced[end + 1..])
+            } else {
+                let end = replacement
+                    .find(|c: char| c != '_' && !c.is_ascii_alphanumeric())
+                    .unwrap_or(replacement.len());
+                if end == 0 {
+                    out.push('$');
+                    continue;
+                }
+                replacement.split_at(end)
+            };
+            if let Some((start, end)) = name
+                .parse()
+                .ok()
+                .and_then(|index| self.locations.get(index))
+            {
+                out.push_str(&self.text[start..end]);
+            }
+            replacement = rest;
+        }
+        out.push_str(replacement);
+    }
+}
+
+impl ClientBuf {
+    #[must_use]
+    pub fn new() -> ClientBuf {
+        ClientBuf::default()
+    }
+
+    /// The fields as a `Client` borrowing from the buffer
+    #[must_use]
+    pub fn client(&self) -> Client<'_> {
+        Client {
+            device: self.device(),
+            os: self.os(),
+            user_agent: self.user_agent(),
+        }
+    }
+
+    #[must_use]
+    pub fn device(&self) -> Device<'_> {
+        Device {
+            family: Cow::Borrowed(self.device_family()),
+            brand: self.device_brand().map(Cow::Borrowed),
+            model: self.device_model().map(Cow::Borrowed),
+        }
+    }
+
+    #[must_use]
+    pub fn os(&self) -> OS<'_> {
+        OS {
+            family: Cow::Borrowed(self.os_family()),
+            major: self.os_major().map(Cow::Borrowed),
+            minor: self.os_minor().map(Cow::Borrowed),
+            patch: self.os_patch().map(Cow::Borrowed),
+            patch_minor: self.os_patch_minor().map(Cow::Borrowed),
+        }
+    }
+
+    #[must_use]
+    pub fn user_agent(&self) -> UserAgent<'_> {
+        UserAgent {
+            family: Cow::Borrowed(self.ua_family()),
+            major: self.ua_major().map(Cow::Borrowed),
+            minor: self.ua_minor().map(Cow::Borrowed),
+            patch: self.ua_patch().map(Cow::Borrowed),
+            patch_minor: self.ua_patch_minor().map(Cow::Borrowed),
+        }
+    }
+
+    /// The fields as flat `(key, value)` pairs, as with `Client::fields`
+    pub fn fields(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        FLAT_KEYS
+            .iter()
+            .copied()
+            .zip(self.flatten())
+            .filter_map(|(key, value)| Some((key, value?)))
+    }
+
+    /// The fields in the order of `FLAT_KEYS`
+    pub(crate) fn flatten(&self) -> [Option<&str>; 13] {
+        let mut fields = [None; 13];
+        for (index, field) in fields.iter_mut().enumerate() {
+            *field = self.fields.get(index);
+        }
+        fields
+    }
+
+    /// Copies a parse result into the buffer
+    pub(super) fn fill_from(&mut self, client: &Client<'_>) {
+        for (index, value) in client.flatten().iter().enumerate() {
+            self.fields.set(index, *value);
+        }
+    }
+
+    /// Runs the rules of one category, indexed by `category`, in order until
+    /// one fills in the fields
+    pub(super) fn fill_first<M: Fill>(
+        &mut self,
+        category: usize,
+        matchers: &[M],
+        text: &str,
+    ) {
+        let locations = &mut self.locations[category];
+        if locations.len() < matchers.len() {
+            locations.resize(matchers.len(), None);
+        }
+        for (matcher, slot) in matchers.iter().zip(locations.iter_mut()) {
+            let regex = matcher.regex();
+            if !regex.is_match(text) {
+                continue;
+            }
+            let slot = match slot {
+                Some(slot) if slot.len() == regex.captures_len() => slot,
+                _ => slot.insert(regex.capture_locations()),
+            };
+            regex.captures_read(slot, text);
+            let groups = Groups {
+                text,
+                locations: slot,
+            };
+            if matcher.fill(&groups, &mut self.fields) {
+                return;
+            }
+        }
+    }
+
+    pub(super) fn reset(&mut self) {
+        self.fields.reset();
+    }
+}
+
+macro_rules! accessors {
+    ($($family:ident: $family_index:ident),*; $($name:ident: $index:ident),*) => {
+        impl ClientBuf {
+            $(
+                #[must_use]
+                pub fn $family(&self) -> &str {
+                    self.fields.get($family_index).unwrap_or_default()
+                }
+            )*
+            $(
+                #[must_use]
+                pub fn $name(&self) -> Option<&str> {
+                    self.fields.get($index)
+                }
+            )*
+        }
+    };
+}
+
+accessors! {
+    ua_family: UA_FAMILY, os_family: OS_FAMILY, device_family: DEVICE_FAMILY;
+    ua_major: UA_MAJOR, ua_minor: UA_MINOR, ua_patch: UA_PATCH,
+    ua_patch_minor: UA_PATCH_MINOR, os_major: OS_MAJOR, os_minor: OS_MINOR,
+    os_patch: OS_PATCH, os_patch_minor: OS_PATCH_MINOR, device_brand: DEVICE_BRAND,
+    device_model: DEVICE_MODEL
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CapitalizeBrand, Parser};
+
+    fn parser() -> UserAgentParser {
+        UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed")
+    }
+
+    fn test_user_agents(path: &str) -> Vec<String> {
+        let file = std::fs::File::open(path).expect("Test cases failed to load");
+        let cases: serde_yaml::Value =
+            serde_yaml::from_reader(file).expect("Failed to deserialize test cases");
+        cases["test_cases"]
+            .as_sequence()
+            .expect("No test cases")
+            .iter()
+            .filter_map(|case| case["user_agent_string"].as_str())
+            .map(str::to_owned)
+            .collect()
+    }
+
+    #[test]
+    fn matches_parse() {
+        let parser = parser();
+        let mut buf = ClientBuf::new();
+        for path in [
+            "./src/core/tests/test_ua.yaml",
+            "./src/core/tests/test_os.yaml",
+            "./src/core/tests/test_device.yaml",
+        ] {
+            // Every few cases still cover each kind of rule, in a fraction
+            // of the time
+            for user_agent in test_user_agents(path).iter().step_by(4) {
+                parser.parse_into(user_agent, &mut buf);
+                assert_eq!(buf.client(), parser.parse(user_agent), "{user_agent}");
+            }
+        }
+
+        parser.parse_into("   ", &mut buf);
+        assert_eq!(buf.client(), Client::default());
+    }
+
+    #[test]
+    fn expands_like_regex() {
+        let regex = Regex::new(r"(\w+)-(\d+)").expect("Invalid regex");
+        let text = "Acme-100";
+        let mut locations = regex.capture_locations();
+        regex.captures_read(&mut locations, text);
+        let groups = Groups {
+            text,
+            locations: &locations,
+        };
+        let captures = regex.captures(text).expect("No match");
+
+        for replacement in [
+            "$1 $2", "${1}x", "$$1", "$", "${1", "$a $3 $_", "${}", "${x}y",
+        ] {
+            let mut expanded = String::new();
+            groups.expand(replacement, &mut expanded);
+            let mut expected = String::new();
+            captures.expand(replacement, &mut expected);
+            assert_eq!(expanded, expected, "{replacement}");
+        }
+    }
+
+    #[test]
+    fn copies_results_of_post_processed_parsers() {
+        let mut parser = parser();
+        parser.add_post_processor(CapitalizeBrand);
+        let user_agent = "Mozilla/5.0 (Linux; Android 13; SM-S901B) AppleWebKit/537.36 \
+                          (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36";
+        let mut buf = ClientBuf::new();
+        parser.parse_into(user_agent, &mut buf);
+
+        assert_eq!(buf.client(), parser.parse(user_agent));
+    }
+}
@@ -0,0 +1,394 @@
+#[cfg(not(target_family = "wasm"))]
+use std::io::Read;
+use std::{
+    ops::{BitOr, BitOrAssign},
+    time::Duration,
+};
+
+use super::*;
+
+/// How a parser picks a result when several rules in a category match
+#[derive(
+    Clone, Copy, Debug, Default, Eq, Hash, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub enum MatchStrategy {
+    /// The first matching rule wins, as in every other UA Parser implementation
+    #[default]
+    First,
+    /// Every rule is evaluated and the one producing the most populated
+    /// result wins, with ties going to the rule that comes first. This costs
+    /// a full pass over the rules, so it is opt-in.
+    MostSpecific,
+}
+
+/// The `MatchStrategy` used for each category of rules
+#[derive(
+    Clone, Copy, Debug, Default, Eq, Hash, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub struct MatchStrategies {
+    pub device: MatchStrategy,
+    pub os: MatchStrategy,
+    pub user_agent: MatchStrategy,
+}
+
+/// A set of rule categories, combined with `|`
+///
+/// ```rust
+/// # use uaparser::*;
+/// let categories = Categories::OS | Categories::USER_AGENT;
+///
+/// assert!(categories.contains(Categories::OS));
+/// assert!(!categories.contains(Categories::DEVICE));
+/// ```
+#[derive(
+    Clone, Copy, Debug, Eq, Hash, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+#[serde(transparent)]
+pub struct Categories(u8);
+
+impl Categories {
+    pub const NONE: Categories = Categories(0);
+    pub const DEVICE: Categories = Categories(1);
+    pub const OS: Categories = Categories(1 << 1);
+    pub const USER_AGENT: Categories = Categories(1 << 2);
+    pub const ALL: Categories = Categories(0b111);
+
+    /// Whether every category in `other` is in this set
+    #[must_use]
+    pub fn contains(self, other: Categories) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    #[must_use]
+    pub fn is_empty(self) -> bool {
+        self == Categories::NONE
+    }
+}
+
+impl Default for Categories {
+    fn default() -> Self {
+        Categories::ALL
+    }
+}
+
+impl BitOr for Categories {
+    type Output = Categories;
+
+    fn bitor(self, other: Categories) -> Categories {
+        Categories(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for Categories {
+    fn bitor_assign(&mut self, other: Categories) {
+        self.0 |= other.0;
+    }
+}
+
+/// Configures a `UserAgentParser` before the rules are loaded
+///
+/// ```rust
+/// # use uaparser::*;
+/// let parser = UserAgentParser::builder()
+///     .device_strategy(MatchStrategy::MostSpecific)
+///     .build_from_yaml("./src/core/regexes.yaml")
+///     .expect("Parser creation failed");
+/// ```
+#[derive(Clone, Debug, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct UserAgentParserBuilder {
+    strategies: MatchStrategies,
+    source_label: Option<String>,
+    strict: bool,
+    verify_inline_tests: bool,
+    match_blank: bool,
+    normalize_input: InputNormalization,
+    percent_decode: bool,
+    categories: Categories,
+    max_matchers_per_category: Option<usize>,
+    darwin_fallback: bool,
+    device_ua_override: DeviceUaOverride,
+    ua_header_policy: UaHeaderPolicy,
+    header_value_policy: HeaderValuePolicy,
+    slow_parse: Option<SlowParseHook>,
+    preprocessor: Option<PatternPreprocessor>,
+}
+
+impl UserAgentParserBuilder {
+    /// Sets how device rules are chosen between
+    #[must_use]
+    pub fn device_strategy(mut self, strategy: MatchStrategy) -> Self {
+        self.strategies.device = strategy;
+        self
+    }
+
+    /// Sets how OS rules are chosen between
+    #[must_use]
+    pub fn os_strategy(mut self, strategy: MatchStrategy) -> Self {
+        self.strategies.os = strategy;
+        self
+    }
+
+    /// Sets how user agent rules are chosen between
+    #[must_use]
+    pub fn user_agent_strategy(mut self, strategy: MatchStrategy) -> Self {
+        self.strategies.user_agent = strategy;
+        self
+    }
+
+    /// Names the rule set, such as `uap-core 0.18.0 + overrides v7`, for
+    /// `UserAgentParser::source_info`
+    #[must_use]
+    pub fn with_source_label(mut self, label: impl Into<String>) -> Self {
+        self.source_label = Some(label.into());
+        self
+    }
+
+    /// Rejects unknown keys and entries without a `regex` instead of
+    /// ignoring them, as `UserAgentParser::from_bytes_strict` does
+    #[must_use]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Runs the example `tests` rule entries carry, as `validate` does, and
+    /// fails with `Error::InlineTests` when any of them doesn't pass. Tests
+    /// of the categories left out by `categories` aren't run.
+    #[must_use]
+    pub fn verify_inline_tests(mut self, verify_inline_tests: bool) -> Self {
+        self.verify_inline_tests = verify_inline_tests;
+        self
+    }
+
+    /// Runs the rules against empty and whitespace-only user agents as well,
+    /// as earlier versions did, rather than returning the default result
+    /// straight away
+    #[must_use]
+    pub fn match_blank(mut self, match_blank: bool) -> Self {
+        self.match_blank = match_blank;
+        self
+    }
+
+    /// Cleans every user agent up before matching it, such as by stripping
+    /// control characters. Normalized user agents that differ from the
+    /// input are parsed into owned results.
+    #[must_use]
+    pub fn normalize_input(mut self, normalization: InputNormalization) -> Self {
+        self.normalize_input = normalization;
+        self
+    }
+
+    /// Decodes user agents that were percent-encoded somewhere upstream, such
+    /// as `Mozilla%2F5.0%20(Windows%20NT%2010.0...`, before matching them. See
+    /// `normalize::maybe_percent_decode` for how they are detected.
+    #[must_use]
+    pub fn percent_decode(mut self, percent_decode: bool) -> Self {
+        self.percent_decode = percent_decode;
+        self
+    }
+
+    /// Reads the OS of Apple native app user agents, like
+    /// `MyApp/7.2 CFNetwork/1474 Darwin/23.0.0`, off their Darwin version
+    /// with `extras::darwin_os` whenever the rules give no OS version
+    #[must_use]
+    pub fn darwin_fallback(mut self, darwin_fallback: bool) -> Self {
+        self.darwin_fallback = darwin_fallback;
+        self
+    }
+
+    /// Makes `UserAgentParser::parse_header_pairs` read the device, or the
+    /// whole client, from the user agent of the handset that proxy browsers
+    /// like Opera Mini send in a header of their own
+    #[must_use]
+    pub fn device_ua_override(mut self, device_ua_override: DeviceUaOverride) -> Self {
+        self.device_ua_override = device_ua_override;
+        self
+    }
+
+    /// Sets the headers `UserAgentParser::parse_header_pairs` reads the user
+    /// agent from, such as to prefer the `X-Original-User-Agent` a proxy
+    /// keeps the client's user agent in over the proxy's own
+    #[must_use]
+    pub fn ua_header_policy(mut self, policy: UaHeaderPolicy) -> Self {
+        self.ua_header_policy = policy;
+        self
+    }
+
+    /// Sets which user agent `UserAgentParser::parse_header_pairs` reads when
+    /// proxies folded several into one header value or sent the header more
+    /// than once, which is the first by default
+    #[must_use]
+    pub fn header_value_policy(mut self, policy: HeaderValuePolicy) -> Self {
+        self.header_value_policy = policy;
+        self
+    }
+
+    /// Compiles only the rules of `categories`, which saves the time and
+    /// memory of the others when their results aren't needed. Parsing a
+    /// skipped category returns its default result, as if no rule matched.
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// let parser = UserAgentParser::builder()
+    ///     .categories(Categories::OS | Categories::USER_AGENT)
+    ///     .build_from_yaml("./src/core/regexes.yaml")
+    ///     .expect("Parser creation failed");
+    ///
+    /// assert!(parser.device_matchers().is_empty());
+    /// ```
+    #[must_use]
+    pub fn categories(mut self, categories: Categories) -> Self {
+        self.categories = categories;
+        self
+    }
+
+    /// Stops each category's scan after evaluating `max` matchers, returning
+    /// the default for a category whose result wasn't found by then. This
+    /// bounds the work per parse; `parse_scored` reports the categories that
+    /// ran out in `ScoredClient::exhausted`. Unlimited by default.
+    #[must_use]
+    pub fn max_matchers_per_category(mut self, max: usize) -> Self {
+        self.max_matchers_per_category = Some(max);
+        self
+    }
+
+    /// Calls `callback` after every `parse` that took `threshold` or longer,
+    /// with the user agent truncated to 256 bytes. Parses aren't timed at
+    /// all without a callback.
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// # use std::time::Duration;
+    /// let parser = UserAgentParser::builder()
+    ///     .on_slow_parse(Duration::from_millis(5), |event| {
+    ///         eprintln!("slow parse ({:?}): {}", event.elapsed, event.user_agent);
+    ///     })
+    ///     .build_from_yaml("./src/core/regexes.yaml")
+    ///     .expect("Parser creation failed");
+    /// ```
+    #[must_use]
+    pub fn on_slow_parse(
+        mut self,
+        threshold: Duration,
+        callback: impl Fn(SlowParseEvent<'_>) + Send + Sync + 'static,
+    ) -> Self {
+        self.slow_parse = Some(SlowParseHook::new(threshold, callback));
+        self
+    }
+
+    /// Sets how many bytes of the user agent `on_slow_parse` callbacks get
+    #[must_use]
+    pub fn slow_parse_truncate(mut self, max_len: usize) -> Self {
+        if let Some(hook) = &mut self.slow_parse {
+            hook.set_max_len(max_len);
+        }
+        self
+    }
+
+    /// Rewrites every rule's raw pattern with `rewrite` before compiling it,
+    /// such as to translate syntax a vendor's rule file uses that `regex`
+    /// doesn't support. It gets the category of the rule as one of
+    /// `Categories::DEVICE`, `OS` and `USER_AGENT`, and runs before the
+    /// built-in escape cleanup and before device flags are prefixed. A
+    /// rewrite that doesn't compile fails the build with the category's
+    /// `Regex` error, which quotes the rewritten pattern. Serialized parsers
+    /// store the rewritten patterns, so loading them doesn't need the hook.
+    ///
+    /// ```rust
+    /// # use std::borrow::Cow;
+    /// # use uaparser::*;
+    /// let parser = UserAgentParser::builder()
+    ///     .pattern_preprocessor(|pattern, _| match pattern.strip_suffix(r"\Z") {
+    ///         Some(pattern) => Cow::Owned(format!("{pattern}$")),
+    ///         None => Cow::Borrowed(pattern),
+    ///     })
+    ///     .build_from_bytes(br"user_agent_parsers: [regex: '(VendorBot)\Z']")
+    ///     .expect("Parser creation failed");
+    ///
+    /// assert_eq!(parser.parse("VendorBot").user_agent.family, "VendorBot");
+    /// ```
+    #[must_use]
+    pub fn pattern_preprocessor(
+        mut self,
+        rewrite: impl Fn(&str, Categories) -> Cow<'_, str> + Send + Sync + 'static,
+    ) -> Self {
+        self.preprocessor = Some(PatternPreprocessor::new(rewrite));
+        self
+    }
+
+    /// Sets whether the `pattern_preprocessor` runs before the built-in
+    /// escape cleanup or replaces it
+    #[must_use]
+    pub fn pattern_preprocessor_mode(mut self, mode: PreprocessMode) -> Self {
+        if let Some(preprocessor) = &mut self.preprocessor {
+            preprocessor.set_mode(mode);
+        }
+        self
+    }
+
+    /// Builds a parser from the path to a `regexes.yaml` file
+    #[cfg(not(target_family = "wasm"))]
+    pub fn build_from_yaml(self, path: &str) -> Result<UserAgentParser, Error> {
+        let file = std::fs::File::open(path)?;
+        self.build_from_file(file)
+    }
+
+    /// Builds a parser from the raw bytes of a `regexes.yaml` file
+    pub fn build_from_bytes(self, bytes: &[u8]) -> Result<UserAgentParser, Error> {
+        if self.strict {
+            let value = read_value(bytes).map_err(YamlError::from)?;
+            validate_strict(&value)?;
+        }
+        let mut regex_file = RegexFile::from_slice(bytes)?;
+        if !self.categories.contains(Categories::DEVICE) {
+            regex_file.device_parsers = Vec::new();
+        }
+        if !self.categories.contains(Categories::OS) {
+            regex_file.os_parsers = Vec::new();
+        }
+        if !self.categories.contains(Categories::USER_AGENT) {
+            regex_file.user_agent_parsers = Vec::new();
+        }
+        let clean = match &self.preprocessor {
+            Some(preprocessor) => {
+                preprocessor.apply(&mut regex_file);
+                preprocessor.cleans()
+            }
+            None => true,
+        };
+        if self.verify_inline_tests {
+            let report = inline_tests::run(&regex_file, clean)?;
+            if !report.is_ok() {
+                return Err(Error::InlineTests(report));
+            }
+        }
+        let mut parser = UserAgentParser::compile(regex_file, clean)?;
+        parser.match_strategies = self.strategies;
+        parser.match_blank = self.match_blank;
+        parser.normalize_input = self.normalize_input;
+        parser.percent_decode = self.percent_decode;
+        parser.categories = self.categories;
+        parser.max_matchers_per_category = self.max_matchers_per_category;
+        parser.darwin_fallback = self.darwin_fallback;
+        parser.device_ua_override = self.device_ua_override;
+        parser.ua_header_policy = self.ua_header_policy;
+        parser.header_value_policy = self.header_value_policy;
+        parser.slow_parse = self.slow_parse;
+        parser.source_info.label = self.source_label;
+        parser.source_info.content_hash = Some(source::content_hash(bytes));
+        Ok(parser)
+    }
+
+    /// Builds a parser from an open `regexes.yaml` file, which is read in
+    /// full so its content hash can be recorded
+    #[cfg(not(target_family = "wasm"))]
+    pub fn build_from_file(
+        self,
+        mut file: std::fs::File,
+    ) -> Result<UserAgentParser, Error> {
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        self.build_from_bytes(&bytes)
+    }
+}
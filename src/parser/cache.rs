@@ -0,0 +1,385 @@
+use std::convert::TryFrom;
+
+use serde_yaml::Value;
+use sha2::{Digest, Sha256};
+
+use super::*;
+
+/// Opens every cache written by `UserAgentParser::to_cache_bytes`
+const MAGIC: &[u8; 8] = b"UAPCACHE";
+
+/// Bumped whenever the layout of the cache or of the serialized parser
+/// changes, so caches from other versions are rejected rather than misread
+pub const CACHE_FORMAT_VERSION: u32 = 2;
+
+/// The oldest format `UserAgentParser::from_cache_bytes` still reads, by
+/// migrating it on load
+const OLDEST_READABLE_FORMAT: u32 = 1;
+
+/// Why `UserAgentParser::from_cache_bytes` refused a cache
+#[derive(Clone, Debug, Display, Eq, PartialEq)]
+pub enum CacheMismatch {
+    #[display(fmt = "not a parser cache")]
+    NotACache,
+    #[display(
+        fmt = "cache format {found} written by uaparser {written_by}, expected format {CACHE_FORMAT_VERSION}"
+    )]
+    FormatVersion { found: u32, written_by: String },
+    #[display(fmt = "cache checksum mismatch")]
+    Checksum,
+    #[display(fmt = "cache rule counts don't match its contents")]
+    RuleCounts,
+}
+
+impl UserAgentParser {
+    /// Serializes the parser for `from_cache_bytes`, skipping rule file
+    /// parsing on later loads. The cache starts with a header holding a
+    /// magic string, `CACHE_FORMAT_VERSION`, the version of this crate that
+    /// wrote it, the number of rules of each category and a SHA-256 of the
+    /// rest. Rules are stored as rule file entries and recompiled on load.
+    /// Post-processors, custom sub-parsers and the slow parse hook aren't
+    /// kept.
+    pub fn to_cache_bytes(&self) -> Result<Vec<u8>, Error> {
+        let payload = serde_yaml::to_string(self).map_err(YamlError::from)?;
+        let version = env!("CARGO_PKG_VERSION").as_bytes();
+
+        let mut bytes = Vec::with_capacity(MAGIC.len() + 64 + payload.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&CACHE_FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(version);
+        bytes.push(0);
+        for count in self.matcher_counts() {
+            let count = u32::try_from(count).unwrap_or(u32::MAX);
+            bytes.extend_from_slice(&count.to_le_bytes());
+        }
+        bytes.extend_from_slice(&Sha256::digest(payload.as_bytes()));
+        bytes.extend_from_slice(payload.as_bytes());
+        Ok(bytes)
+    }
+
+    /// Loads a parser written by `to_cache_bytes`, including by earlier
+    /// versions of this crate back to format 1. Caches of a newer format
+    /// version, or that were truncated or altered, fail with
+    /// `Error::IncompatibleCache`.
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+    /// let cache = parser.to_cache_bytes().unwrap();
+    /// let restored = UserAgentParser::from_cache_bytes(&cache).unwrap();
+    ///
+    /// assert_eq!(restored.parse("curl/8.4.0"), parser.parse("curl/8.4.0"));
+    /// ```
+    pub fn from_cache_bytes(bytes: &[u8]) -> Result<UserAgentParser, Error> {
+        let mut header = Header(bytes);
+        if header.take(MAGIC.len()) != Some(MAGIC) {
+            return Err(CacheMismatch::NotACache.into());
+        }
+        let format = header.u32().ok_or(CacheMismatch::NotACache)?;
+        let written_by = header.until_nul().ok_or(CacheMismatch::NotACache)?;
+        if !(OLDEST_READABLE_FORMAT..=CACHE_FORMAT_VERSION).contains(&format) {
+            return Err(CacheMismatch::FormatVersion {
+                found: format,
+                written_by: String::from_utf8_lossy(written_by).into_owned(),
+            }
+            .into());
+        }
+        let counts = [header.u32(), header.u32(), header.u32()];
+        let checksum = header.take(32).ok_or(CacheMismatch::NotACache)?;
+        let payload = header.0;
+        if Sha256::digest(payload).as_slice() != checksum {
+            return Err(CacheMismatch::Checksum.into());
+        }
+
+        let parser: UserAgentParser = if format == 1 {
+            read_format_1(payload)?
+        } else {
+            serde_yaml::from_slice(payload).map_err(YamlError::from)?
+        };
+        if counts
+            != parser
+                .matcher_counts()
+                .map(|count| u32::try_from(count).ok())
+        {
+            return Err(CacheMismatch::RuleCounts.into());
+        }
+        Ok(parser)
+    }
+
+    /// Loads a cache file written from `to_cache_bytes` as with
+    /// `from_cache_bytes`, reading it through a memory map rather than into
+    /// a buffer first, so the file's pages can be dropped as soon as the
+    /// rules are compiled. Not available on WebAssembly targets.
+    ///
+    /// The file must not be modified or truncated while it loads, as is the
+    /// case for caches baked into an image. The checksum catches changes made
+    /// before it is verified, but not ones racing with deserialization.
+    #[cfg(all(feature = "mmap", not(target_family = "wasm")))]
+    pub fn from_cache_path_mmap(
+        path: &std::path::Path,
+    ) -> Result<UserAgentParser, Error> {
+        let map = map_file(&std::fs::File::open(path)?)?;
+        UserAgentParser::from_cache_bytes(&map)
+    }
+
+    fn matcher_counts(&self) -> [usize; 3] {
+        [
+            self.device_matchers.len(),
+            self.os_matchers.len(),
+            self.user_agent_matchers.len(),
+        ]
+    }
+}
+
+/// Maps a cache file for `from_cache_path_mmap`, which only reads the map
+/// while loading and documents that the file mustn't change meanwhile
+#[cfg(all(feature = "mmap", not(target_family = "wasm")))]
+fn map_file(file: &std::fs::File) -> std::io::Result<memmap2::Mmap> {
+    // Safety: see above; the map is never handed out
+    unsafe { memmap2::Mmap::map(file) }
+}
+
+/// The unread part of a cache header
+struct Header<'a>(&'a [u8]);
+
+impl<'a> Header<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(taken)
+    }
+
+    fn until_nul(&mut self) -> Option<&'a [u8]> {
+        let len = self.0.iter().position(|&byte| byte == 0)?;
+        let taken = self.take(len);
+        self.take(1);
+        taken
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let bytes = self.take(4)?;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+/// Serializes matchers as the rule file entries they compile from, with
+/// patterns already cleaned up and device flags back in `regex_flag`, and
+/// recompiles them on load. Group flags are recomputed rather than stored.
+macro_rules! stored_as_entry {
+    ($matcher:ty, $entry:ty) => {
+        impl serde::Serialize for $matcher {
+            fn serialize<S: serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                self.to_entry().serialize(serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $matcher {
+            fn deserialize<D: serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<$matcher, D::Error> {
+                let entry = <$entry>::deserialize(deserializer)?;
+                <$matcher>::compile(entry, false).map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+stored_as_entry!(device::Matcher, DeviceParserEntry);
+stored_as_entry!(os::Matcher, OSParserEntry);
+stored_as_entry!(user_agent::Matcher, UserAgentParserEntry);
+
+/// Reads the payload of a format 1 cache, whose matchers stored the pattern
+/// with device flags folded in, their group flags, and any extras under an
+/// `extras` key
+fn read_format_1(payload: &[u8]) -> Result<UserAgentParser, YamlError> {
+    let mut parser: Value = serde_yaml::from_slice(payload)?;
+    for section in ["device_matchers", "os_matchers", "user_agent_matchers"] {
+        let matchers = parser.get_mut(section).and_then(Value::as_sequence_mut);
+        for matcher in matchers
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_mapping_mut)
+        {
+            matcher.remove(&Value::from("has_group"));
+            if let Some(Value::Mapping(extras)) = matcher.remove(&Value::from("extras")) {
+                matcher.extend(extras);
+            }
+        }
+    }
+    Ok(serde_yaml::from_value(parser)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parser() -> UserAgentParser {
+        UserAgentParser::builder()
+            .with_source_label("uap-core")
+            .build_from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed")
+    }
+
+    #[test]
+    fn caches_round_trip() {
+        let parser = parser();
+        let cache = parser.to_cache_bytes().expect("Serialization failed");
+        assert!(cache.starts_with(MAGIC));
+
+        let restored = UserAgentParser::from_cache_bytes(&cache).expect("Loading failed");
+        assert_eq!(restored.source_info(), parser.source_info());
+        for user_agent in [
+            "curl/8.4.0",
+            "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 \
+             (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36",
+            "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)",
+        ] {
+            assert_eq!(restored.parse(user_agent), parser.parse(user_agent));
+        }
+    }
+
+    #[test]
+    fn mismatched_caches_are_rejected() {
+        let cache = parser().to_cache_bytes().expect("Serialization failed");
+        let rejection = |cache: &[u8]| match UserAgentParser::from_cache_bytes(cache) {
+            Err(Error::IncompatibleCache(mismatch)) => mismatch,
+            other => panic!("expected an incompatible cache, got {:?}", other),
+        };
+
+        let mut future = cache.clone();
+        let next = CACHE_FORMAT_VERSION + 1;
+        future[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&next.to_le_bytes());
+        assert_eq!(
+            rejection(&future),
+            CacheMismatch::FormatVersion {
+                found: next,
+                written_by: env!("CARGO_PKG_VERSION").to_owned(),
+            }
+        );
+
+        let mut altered = cache.clone();
+        let last = altered.len() - 2;
+        altered[last] ^= 1;
+        assert_eq!(rejection(&altered), CacheMismatch::Checksum);
+
+        assert_eq!(rejection(&cache[..20]), CacheMismatch::NotACache);
+        assert_eq!(rejection(b"device_parsers: []"), CacheMismatch::NotACache);
+    }
+
+    const ACME_RULES: &[u8] = br"
+user_agent_parsers:
+  - regex: '(Acme)Browser/(\d+)\.(\d+)'
+    family_replacement: '$1 Navigator'
+    owner: 'team-x'
+os_parsers:
+  - regex: 'AcmeOS (\d+)'
+    os_replacement: 'Acme OS'
+device_parsers:
+  - regex: 'acme(phone|pad)'
+    regex_flag: 'i'
+    device_replacement: 'Acme $1'
+    brand_replacement: 'Acme'
+";
+
+    const ACME_USER_AGENT: &str = "AcmeBrowser/3.1 (AcmeOS 12; ACMEPAD)";
+
+    /// Wraps a payload in a format 1 header, as written by 0.7.0
+    fn format_1_cache(payload: &[u8]) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(b"0.7.0\0");
+        for _ in 0..3 {
+            bytes.extend_from_slice(&1u32.to_le_bytes());
+        }
+        bytes.extend_from_slice(&Sha256::digest(payload));
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    #[test]
+    fn matchers_are_stored_as_entries() {
+        let parser =
+            UserAgentParser::from_bytes(ACME_RULES).expect("Parser creation failed");
+        let yaml = serde_yaml::to_string(&parser).expect("Serialization failed");
+        assert!(yaml.contains("regex_flag: i\n    regex: acme(phone|pad)"));
+        assert!(yaml.contains("owner: team-x"));
+        assert!(!yaml.contains("has_group"));
+
+        let restored: UserAgentParser =
+            serde_yaml::from_str(&yaml).expect("Deserialization failed");
+        assert_eq!(
+            restored.parse(ACME_USER_AGENT),
+            parser.parse(ACME_USER_AGENT)
+        );
+        assert_eq!(
+            restored.device_matchers()[0].pattern(),
+            "(?i)acme(phone|pad)"
+        );
+        assert!(restored.device_matchers()[0].device_replacement_has_group());
+        assert_eq!(serde_yaml::to_string(&restored).ok(), Some(yaml));
+    }
+
+    #[test]
+    fn format_1_caches_are_migrated() {
+        let payload = std::fs::read("./tests/fixtures/parser_cache_v1.yaml")
+            .expect("Fixture missing");
+        let restored = UserAgentParser::from_cache_bytes(&format_1_cache(&payload))
+            .expect("Loading failed");
+        let parser =
+            UserAgentParser::from_bytes(ACME_RULES).expect("Parser creation failed");
+
+        let client = restored.parse(ACME_USER_AGENT);
+        assert_eq!(client, parser.parse(ACME_USER_AGENT));
+        assert_eq!(client.device.family, "Acme PAD");
+        assert_eq!(client.user_agent.family, "Acme Navigator");
+        assert_eq!(
+            restored.user_agent_matchers()[0].extras()["owner"].as_str(),
+            Some("team-x")
+        );
+
+        let upgraded = restored.to_cache_bytes().expect("Serialization failed");
+        let reloaded =
+            UserAgentParser::from_cache_bytes(&upgraded).expect("Loading failed");
+        assert_eq!(reloaded.parse(ACME_USER_AGENT), client);
+    }
+
+    #[cfg(all(feature = "mmap", not(target_family = "wasm")))]
+    #[test]
+    fn mapped_caches_load_like_read_ones() {
+        let cache = parser().to_cache_bytes().expect("Serialization failed");
+        let path = std::env::temp_dir()
+            .join(format!("uaparser-mmap-{}.cache", std::process::id()));
+
+        std::fs::write(&path, &cache).expect("Writing the cache failed");
+        let mapped = UserAgentParser::from_cache_path_mmap(&path);
+        let read = UserAgentParser::from_cache_bytes(
+            &std::fs::read(&path).expect("Reading the cache failed"),
+        )
+        .expect("Loading failed");
+        assert_eq!(
+            mapped.expect("Mapping failed").to_cache_bytes().ok(),
+            read.to_cache_bytes().ok()
+        );
+
+        std::fs::write(&path, &cache[..cache.len() / 2])
+            .expect("Writing the cache failed");
+        let truncated = UserAgentParser::from_cache_path_mmap(&path);
+        std::fs::write(&path, "").expect("Writing the cache failed");
+        let empty = UserAgentParser::from_cache_path_mmap(&path);
+        std::fs::remove_file(&path).expect("Removing the cache failed");
+        assert!(matches!(
+            truncated,
+            Err(Error::IncompatibleCache(CacheMismatch::Checksum))
+        ));
+        assert!(matches!(
+            empty,
+            Err(Error::IncompatibleCache(CacheMismatch::NotACache))
+        ));
+    }
+}
@@ -0,0 +1,249 @@
+use std::{iter, slice};
+
+use super::*;
+
+/// A hand-written device matcher, registered with
+/// `UserAgentParser::register_device_subparser`
+pub type DeviceSubParser =
+    Box<dyn for<'a> SubParser<'a, Item = Device<'a>> + Send + Sync>;
+/// A hand-written OS matcher, registered with
+/// `UserAgentParser::register_os_subparser`
+pub type OSSubParser = Box<dyn for<'a> SubParser<'a, Item = OS<'a>> + Send + Sync>;
+/// A hand-written user agent matcher, registered with
+/// `UserAgentParser::register_user_agent_subparser`
+pub type UserAgentSubParser =
+    Box<dyn for<'a> SubParser<'a, Item = UserAgent<'a>> + Send + Sync>;
+
+/// Where a custom sub-parser runs relative to the regex matchers of its
+/// category
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Position {
+    /// Ahead of every regex matcher, after custom sub-parsers registered
+    /// before it at the front
+    Before,
+    /// After every regex matcher, and after custom sub-parsers registered
+    /// before it at the back
+    After,
+}
+
+/// The custom sub-parsers of one category
+pub(super) struct Custom<S> {
+    before: Vec<S>,
+    after: Vec<S>,
+}
+
+/// The candidates of a category in evaluation order
+type Around<'s, S, I, F> = iter::Chain<
+    iter::Chain<iter::FilterMap<slice::Iter<'s, S>, F>, I>,
+    iter::FilterMap<slice::Iter<'s, S>, F>,
+>;
+
+impl<S> Custom<S> {
+    fn push(&mut self, parser: S, position: Position) {
+        match position {
+            Position::Before => self.before.push(parser),
+            Position::After => self.after.push(parser),
+        }
+    }
+
+    /// Runs the sub-parsers at the front, then yields `matched`, then runs
+    /// those at the back
+    pub(super) fn around<'s, T, I, F>(
+        &'s self,
+        matched: I,
+        parse: F,
+    ) -> Around<'s, S, I, F>
+    where
+        I: Iterator<Item = T>,
+        F: FnMut(&'s S) -> Option<T> + Copy,
+    {
+        self.before
+            .iter()
+            .filter_map(parse)
+            .chain(matched)
+            .chain(self.after.iter().filter_map(parse))
+    }
+
+    /// The first result in evaluation order, with `matched` standing in for
+    /// the regex matchers
+    pub(super) fn first<'s, T, F>(
+        &'s self,
+        matched: impl FnOnce() -> Option<T>,
+        parse: F,
+    ) -> Option<T>
+    where
+        F: FnMut(&'s S) -> Option<T> + Copy,
+    {
+        self.before
+            .iter()
+            .find_map(parse)
+            .or_else(matched)
+            .or_else(|| self.after.iter().find_map(parse))
+    }
+
+    fn len(&self) -> usize {
+        self.before.len() + self.after.len()
+    }
+}
+
+impl<S> Default for Custom<S> {
+    fn default() -> Self {
+        Custom {
+            before: Vec::new(),
+            after: Vec::new(),
+        }
+    }
+}
+
+/// The custom sub-parsers registered on a parser. They can't be serialized,
+/// so a deserialized parser has none.
+#[derive(Default)]
+pub struct SubParsers {
+    pub(super) device: Custom<DeviceSubParser>,
+    pub(super) os: Custom<OSSubParser>,
+    pub(super) user_agent: Custom<UserAgentSubParser>,
+}
+
+impl SubParsers {
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.device.len() + self.os.len() + self.user_agent.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl fmt::Debug for SubParsers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubParsers")
+            .field("device", &self.device.len())
+            .field("os", &self.os.len())
+            .field("user_agent", &self.user_agent.len())
+            .finish()
+    }
+}
+
+impl UserAgentParser {
+    /// Adds a hand-written device matcher, such as one for a user agent
+    /// scheme regexes can't describe. Custom sub-parsers take part in
+    /// matching like the regex matchers, in the order given by `position`,
+    /// so with `MatchStrategy::First` one at the front wins over every regex
+    /// matcher. They don't count against `max_matchers_per_category`, report
+    /// no spans and score `MatchQuality::Exact`.
+    ///
+    /// ```rust
+    /// # use std::borrow::Cow;
+    /// # use uaparser::*;
+    /// struct Acme;
+    ///
+    /// impl<'a> SubParser<'a> for Acme {
+    ///     type Item = Device<'a>;
+    ///
+    ///     fn try_parse(&self, text: &'a str) -> Option<Device<'a>> {
+    ///         let model = text.strip_prefix("ACME:")?;
+    ///         Some(Device {
+    ///             family: Cow::Borrowed("Acme"),
+    ///             brand: Some(Cow::Borrowed("Acme")),
+    ///             model: Some(Cow::Borrowed(model)),
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let mut parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+    /// parser.register_device_subparser(Box::new(Acme), Position::Before);
+    ///
+    /// assert_eq!(parser.parse_device("ACME:X100").model.as_deref(), Some("X100"));
+    /// ```
+    pub fn register_device_subparser(
+        &mut self,
+        parser: DeviceSubParser,
+        position: Position,
+    ) {
+        self.sub_parsers.device.push(parser, position);
+    }
+
+    /// Adds a hand-written OS matcher, as `register_device_subparser`
+    pub fn register_os_subparser(&mut self, parser: OSSubParser, position: Position) {
+        self.sub_parsers.os.push(parser, position);
+    }
+
+    /// Adds a hand-written user agent matcher, as `register_device_subparser`
+    pub fn register_user_agent_subparser(
+        &mut self,
+        parser: UserAgentSubParser,
+        position: Position,
+    ) {
+        self.sub_parsers.user_agent.push(parser, position);
+    }
+
+    /// The custom sub-parsers registered on this parser
+    #[must_use]
+    pub fn sub_parsers(&self) -> &SubParsers {
+        &self.sub_parsers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Backs a sub-parser with a closure
+    struct Closure<F>(F);
+
+    impl<'a, F> SubParser<'a> for Closure<F>
+    where
+        F: Fn(&str) -> Option<OS<'static>>,
+    {
+        type Item = OS<'a>;
+
+        fn try_parse(&self, text: &'a str) -> Option<OS<'a>> {
+            (self.0)(text)
+        }
+    }
+
+    fn acme_os(text: &str) -> Option<OS<'static>> {
+        let version = text.split("AcmeOS/").nth(1)?;
+        Some(OS {
+            family: Cow::Borrowed("AcmeOS"),
+            major: version
+                .split('.')
+                .next()
+                .map(|major| Cow::Owned(major.to_owned())),
+            ..OS::default()
+        })
+    }
+
+    const USER_AGENT: &str = "Mozilla/5.0 (Linux; Android 14; AcmeOS/3.1) \
+                              AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 \
+                              Mobile Safari/537.36";
+
+    #[test]
+    fn front_sub_parsers_win() {
+        let mut parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        assert_eq!(parser.parse_os(USER_AGENT).family, "Android");
+
+        parser.register_os_subparser(Box::new(Closure(acme_os)), Position::Before);
+        let os = parser.parse_os(USER_AGENT);
+        assert_eq!(os.family, "AcmeOS");
+        assert_eq!(os.major.as_deref(), Some("3"));
+        assert_eq!(parser.parse(USER_AGENT).os, os);
+        assert_eq!(parser.parse_borrowed(USER_AGENT).os, os);
+        assert_eq!(parser.parse_with_spans(USER_AGENT).client.os, os);
+        assert_eq!(parser.parse_scored(USER_AGENT).os, MatchQuality::Exact);
+        assert_eq!(parser.sub_parsers().len(), 1);
+    }
+
+    #[test]
+    fn back_sub_parsers_only_fill_gaps() {
+        let mut parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        parser.register_os_subparser(Box::new(Closure(acme_os)), Position::After);
+
+        assert_eq!(parser.parse_os(USER_AGENT).family, "Android");
+        assert_eq!(parser.parse_os("AcmeOS/3.1").family, "AcmeOS");
+    }
+}
@@ -0,0 +1,347 @@
+use std::mem;
+
+use super::*;
+use crate::diff::{CategoryDelta, RuleSetDelta, Step};
+
+/// Why `UserAgentParser::apply_delta` refused a delta, which happens when
+/// the parser wasn't built from the delta's old rules
+#[derive(Clone, Debug, Display, Eq, PartialEq)]
+pub enum DeltaMismatch {
+    #[display(fmt = "parser has {found} {category} rules, the delta expects {expected}")]
+    RuleCount {
+        category: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    #[display(fmt = "{category} rule {position} isn't the one the delta expects")]
+    Pattern {
+        category: &'static str,
+        position: usize,
+    },
+}
+
+/// The work done by `UserAgentParser::apply_delta`, by category
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ApplyReport {
+    /// The added and modified rules, compiled from their entries
+    pub compiled: RuleCounts,
+    /// The unchanged rules, whose matchers were kept
+    pub reused: RuleCounts,
+    /// The rules dropped
+    pub removed: RuleCounts,
+}
+
+/// The parts of a compiled matcher that `apply_delta` checks and orders by
+trait Rule {
+    fn pattern(&self) -> &str;
+    fn priority(&self) -> i32;
+}
+
+macro_rules! impl_rule {
+    ($($matcher:ty),*) => {$(
+        impl Rule for $matcher {
+            fn pattern(&self) -> &str {
+                self.regex.as_str()
+            }
+
+            fn priority(&self) -> i32 {
+                self.priority
+            }
+        }
+    )*};
+}
+
+impl_rule!(device::Matcher, os::Matcher, user_agent::Matcher);
+
+impl UserAgentParser {
+    /// Updates a parser built from the old rules of `delta` to the new ones,
+    /// compiling only the added and modified rules. Rules end up in the
+    /// order building from the new file gives, so the parser classifies
+    /// exactly as a fresh one would. New rules are compiled as `try_from`
+    /// compiles them; the parser's other settings are kept.
+    ///
+    /// Fails without changing the parser when its rules aren't the delta's
+    /// old ones, such as when it was built with a `PatternPreprocessor` or
+    /// only some `Categories`, or when a new rule doesn't compile.
+    pub fn apply_delta(&mut self, delta: &RuleSetDelta) -> Result<ApplyReport, Error> {
+        let device_order = check("device", &self.device_matchers, &delta.device)?;
+        let os_order = check("OS", &self.os_matchers, &delta.os)?;
+        let user_agent_order =
+            check("user agent", &self.user_agent_matchers, &delta.user_agent)?;
+
+        let device = compile(&delta.device, |entry| {
+            Ok(device::Matcher::compile(entry, true)?)
+        })?;
+        let os = compile(&delta.os, |entry| Ok(os::Matcher::compile(entry, true)?))?;
+        let user_agent = compile(&delta.user_agent, |entry| {
+            Ok(user_agent::Matcher::compile(entry, true)?)
+        })?;
+
+        let report = ApplyReport {
+            compiled: RuleCounts {
+                device: device.len(),
+                os: os.len(),
+                user_agent: user_agent.len(),
+            },
+            reused: RuleCounts {
+                device: delta.device.steps.len() - device.len(),
+                os: delta.os.steps.len() - os.len(),
+                user_agent: delta.user_agent.steps.len() - user_agent.len(),
+            },
+            removed: RuleCounts {
+                device: delta.device.removed.len(),
+                os: delta.os.removed.len(),
+                user_agent: delta.user_agent.removed.len(),
+            },
+        };
+        splice(
+            &mut self.device_matchers,
+            &delta.device,
+            &device_order,
+            device,
+        );
+        splice(&mut self.os_matchers, &delta.os, &os_order, os);
+        splice(
+            &mut self.user_agent_matchers,
+            &delta.user_agent,
+            &user_agent_order,
+            user_agent,
+        );
+
+        self.source_info.content_hash = None;
+        self.source_info.rule_counts = RuleCounts {
+            device: self.device_matchers.len(),
+            os: self.os_matchers.len(),
+            user_agent: self.user_agent_matchers.len(),
+        };
+        Ok(report)
+    }
+}
+
+/// Checks that `matchers` were compiled from the old entries of `delta`,
+/// returning the old entry index of each matcher
+fn check<M: Rule, E>(
+    category: &'static str,
+    matchers: &[M],
+    delta: &CategoryDelta<E>,
+) -> Result<Vec<usize>, DeltaMismatch> {
+    if matchers.len() != delta.old.len() {
+        return Err(DeltaMismatch::RuleCount {
+            category,
+            expected: delta.old.len(),
+            found: matchers.len(),
+        });
+    }
+    // The order `compile` gives the old entries
+    let mut order: Vec<usize> = (0..delta.old.len()).collect();
+    order.sort_by_key(|&index| Reverse(delta.old[index].1));
+
+    for (position, (matcher, &index)) in matchers.iter().zip(&order).enumerate() {
+        let (regex, priority) = &delta.old[index];
+        if matcher.priority() != *priority || matcher.pattern() != clean_escapes(regex) {
+            return Err(DeltaMismatch::Pattern { category, position });
+        }
+    }
+    Ok(order)
+}
+
+/// Compiles the added and modified entries of `delta`, in order
+fn compile<E: Clone, M>(
+    delta: &CategoryDelta<E>,
+    compile: impl Fn(E) -> Result<M, Error>,
+) -> Result<Vec<M>, Error> {
+    delta
+        .steps
+        .iter()
+        .filter_map(|step| match step {
+            Step::Reuse(_) => None,
+            Step::Compile(entry) => Some(compile(entry.clone())),
+        })
+        .collect()
+}
+
+/// Replaces `matchers` with those of the new entries, taking unchanged ones
+/// from `matchers`, whose old entry indices `check` returned as `order`
+fn splice<M: Rule, E>(
+    matchers: &mut Vec<M>,
+    delta: &CategoryDelta<E>,
+    order: &[usize],
+    compiled: Vec<M>,
+) {
+    let mut old: Vec<Option<M>> = (0..order.len()).map(|_| None).collect();
+    for (matcher, &index) in mem::take(matchers).into_iter().zip(order) {
+        old[index] = Some(matcher);
+    }
+    let mut compiled = compiled.into_iter();
+    // Each old entry is reused at most once, so every `take` finds a matcher
+    *matchers = delta
+        .steps
+        .iter()
+        .filter_map(|step| match step {
+            Step::Reuse(index) => old[*index].take(),
+            Step::Compile(_) => compiled.next(),
+        })
+        .collect();
+    // Stable, as in `compile`, so rules of equal priority keep file order
+    matchers.sort_by_key(|matcher| Reverse(matcher.priority()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SELF_TEST_CASES;
+
+    const RULES: &str = include_str!("../../data/regexes_mini.yaml");
+    const CORPUS: &str = include_str!("../../tests/fixtures/traffic.txt");
+
+    /// A small xorshift generator, so the edits are random but repeatable
+    struct Rng(u64);
+
+    impl Rng {
+        #[allow(clippy::cast_possible_truncation)]
+        fn below(&mut self, bound: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % bound as u64) as usize
+        }
+    }
+
+    /// Removes, copies, moves, reprioritizes or rewrites a random entry
+    fn edit<E: Clone>(
+        rng: &mut Rng,
+        entries: &mut Vec<E>,
+        rewrite: impl Fn(&mut E, bool),
+    ) {
+        let index = rng.below(entries.len());
+        match rng.below(5) {
+            0 if entries.len() > 1 => {
+                entries.remove(index);
+            }
+            1 => {
+                let copy = entries[index].clone();
+                entries.insert(rng.below(entries.len() + 1), copy);
+            }
+            2 => {
+                let entry = entries.remove(index);
+                entries.insert(rng.below(entries.len() + 1), entry);
+            }
+            kind => rewrite(&mut entries[index], kind == 3),
+        }
+    }
+
+    fn edited(rng: &mut Rng, file: &RegexFile) -> RegexFile {
+        let mut file = file.clone();
+        for _ in 0..=rng.below(3) {
+            match rng.below(3) {
+                0 => edit(rng, &mut file.user_agent_parsers, |entry, reprioritize| {
+                    if reprioritize {
+                        entry.priority = Some(1);
+                    } else {
+                        entry.family_replacement = Some("Edited".to_owned());
+                    }
+                }),
+                1 => edit(rng, &mut file.os_parsers, |entry, reprioritize| {
+                    if reprioritize {
+                        entry.priority = Some(-1);
+                    } else {
+                        entry.os_v1_replacement = Some("99".to_owned());
+                    }
+                }),
+                _ => edit(rng, &mut file.device_parsers, |entry, reprioritize| {
+                    if reprioritize {
+                        entry.priority = Some(2);
+                    } else {
+                        entry.brand_replacement = Some("Edited".to_owned());
+                    }
+                }),
+            }
+        }
+        file
+    }
+
+    fn patterns(parser: &UserAgentParser) -> Vec<&str> {
+        let mut patterns: Vec<&str> = Vec::new();
+        patterns.extend(parser.device_matchers.iter().map(Rule::pattern));
+        patterns.extend(parser.os_matchers.iter().map(Rule::pattern));
+        patterns.extend(parser.user_agent_matchers.iter().map(Rule::pattern));
+        patterns
+    }
+
+    #[test]
+    fn patched_parsers_match_fresh_ones() {
+        let base: RegexFile = serde_yaml::from_str(RULES).unwrap();
+        let corpus: Vec<&str> = CORPUS
+            .lines()
+            .chain(SELF_TEST_CASES.iter().map(|case| case.user_agent))
+            .collect();
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+
+        for _ in 0..25 {
+            let new = edited(&mut rng, &base);
+            let delta = RuleSetDelta::compute(&base, &new);
+            let mut patched = UserAgentParser::try_from(base.clone()).unwrap();
+            let report = patched.apply_delta(&delta).unwrap();
+            let fresh = UserAgentParser::try_from(new.clone()).unwrap();
+
+            assert_eq!(patterns(&patched), patterns(&fresh));
+            for user_agent in &corpus {
+                assert_eq!(patched.parse(user_agent), fresh.parse(user_agent));
+            }
+            assert_eq!(
+                patched.source_info.rule_counts,
+                fresh.source_info.rule_counts
+            );
+            assert_eq!(
+                report.compiled.user_agent + report.reused.user_agent,
+                new.user_agent_parsers.len()
+            );
+            assert!(report.compiled.device <= delta.device.steps.len());
+        }
+    }
+
+    #[test]
+    fn reports_compiled_and_reused_rules() {
+        let base: RegexFile = serde_yaml::from_str(RULES).unwrap();
+        let mut new = base.clone();
+        new.os_parsers.remove(0);
+        new.user_agent_parsers[0].family_replacement = Some("Edited".to_owned());
+        let delta = RuleSetDelta::compute(&base, &new);
+        let mut parser = UserAgentParser::try_from(base.clone()).unwrap();
+        let report = parser.apply_delta(&delta).unwrap();
+
+        assert_eq!(
+            report.compiled,
+            RuleCounts {
+                device: 0,
+                os: 0,
+                user_agent: 1,
+            }
+        );
+        assert_eq!(report.reused.os, base.os_parsers.len() - 1);
+        assert_eq!(report.removed.os, 1);
+    }
+
+    #[test]
+    fn refuses_parsers_of_other_rules() {
+        let base: RegexFile = serde_yaml::from_str(RULES).unwrap();
+        let mut other = base.clone();
+        other.os_parsers.swap(0, 1);
+        let delta = RuleSetDelta::compute(&base, &base);
+        let mut parser = UserAgentParser::try_from(other).unwrap();
+        let before = parser.os_matchers.len();
+
+        assert!(matches!(
+            parser.apply_delta(&delta),
+            Err(Error::Delta(DeltaMismatch::Pattern { category: "OS", .. }))
+        ));
+        assert_eq!(parser.os_matchers.len(), before);
+
+        let mut empty =
+            UserAgentParser::from_matchers(Vec::new(), Vec::new(), Vec::new());
+        assert!(matches!(
+            empty.apply_delta(&delta),
+            Err(Error::Delta(DeltaMismatch::RuleCount { .. }))
+        ));
+    }
+}
@@ -5,103 +5,255 @@ pub enum Error {
     Regex(regex::Error),
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+const DEVICE_REPLACEMENT: usize = 0;
+const BRAND_REPLACEMENT: usize = 1;
+const MODEL_REPLACEMENT: usize = 2;
+
+#[derive(Clone, Debug)]
 pub struct Matcher {
-    #[serde(with = "serde_regex")]
-    pub regex: regex::Regex,
-    pub device_replacement: Option<String>,
-    pub brand_replacement: Option<String>,
-    pub model_replacement: Option<String>,
-    pub device_replacement_has_group: bool,
-    pub brand_replacement_has_group: bool,
-    pub model_replacement_has_group: bool,
+    pub(super) regex: regex::Regex,
+    pub(super) device_replacement: Option<Box<str>>,
+    pub(super) brand_replacement: Option<Box<str>>,
+    pub(super) model_replacement: Option<Box<str>>,
+    pub(super) has_group: GroupFlags,
+    pub(super) priority: i32,
+    pub(super) quality: Option<MatchQuality>,
+    pub(super) extras: Option<Box<Extras>>,
 }
 
 impl<'a> SubParser<'a> for Matcher {
     type Item = Device<'a>;
 
     fn try_parse(&self, text: &'a str) -> Option<Self::Item> {
+        let captures = self.captures(text)?;
+        self.parse_captures(&captures, intern)
+    }
+}
+
+impl Fill for Matcher {
+    fn regex(&self) -> &Regex {
+        &self.regex
+    }
+
+    fn fill(&self, groups: &Groups<'_>, fields: &mut FlatFields) -> bool {
+        if let Some(device_replacement) = &self.device_replacement {
+            fields.replace(
+                DEVICE_FAMILY,
+                device_replacement,
+                self.has_group.contains(DEVICE_REPLACEMENT),
+                groups,
+            );
+        } else if let Some(family) = groups.get(1) {
+            fields.set(DEVICE_FAMILY, Some(family));
+        } else {
+            return false;
+        }
+        if let Some(brand_replacement) = &self.brand_replacement {
+            fields.replace(
+                DEVICE_BRAND,
+                brand_replacement,
+                self.has_group.contains(BRAND_REPLACEMENT),
+                groups,
+            );
+            fields.none_if_empty(DEVICE_BRAND);
+        } else {
+            fields.set(DEVICE_BRAND, None);
+        }
+        if let Some(model_replacement) = &self.model_replacement {
+            fields.replace(
+                DEVICE_MODEL,
+                model_replacement,
+                self.has_group.contains(MODEL_REPLACEMENT),
+                groups,
+            );
+            fields.none_if_empty(DEVICE_MODEL);
+        } else {
+            fields.set(DEVICE_MODEL, groups.get(1));
+        }
+        true
+    }
+}
+
+impl Matcher {
+    /// Like `try_parse`, but replacements without capture groups are borrowed
+    /// from the matcher rather than copied into the result
+    pub fn try_parse_borrowed<'a>(&'a self, text: &'a str) -> Option<Device<'a>> {
+        let captures = self.captures(text)?;
+        self.parse_captures(&captures, Cow::Borrowed)
+    }
+
+    /// Like `try_parse`, but also reports where in `text` each captured field
+    /// was found
+    pub fn try_parse_with_spans<'a>(
+        &self,
+        text: &'a str,
+    ) -> Option<(Device<'a>, DeviceSpans)> {
+        let captures = self.captures(text)?;
+        let item = self.parse_captures(&captures, intern)?;
+        Some((item, self.spans(&captures)))
+    }
+
+    fn captures<'a>(&self, text: &'a str) -> Option<regex::Captures<'a>> {
         if !self.regex.is_match(text) {
             return None;
         }
 
-        if let Some(captures) = self.regex.captures(text) {
-            let family: Cow<'a, str> =
-                if let Some(device_replacement) = &self.device_replacement {
-                    replace_cow(
-                        device_replacement,
-                        self.device_replacement_has_group,
-                        &captures,
-                    )
-                } else {
-                    captures
-                        .get(1)
-                        .map(|x| x.as_str())
-                        .and_then(none_if_empty)
-                        .map(Cow::Borrowed)?
-                };
-
-            let brand: Option<Cow<'a, str>> = self
-                .brand_replacement
-                .as_ref()
-                .map(|br| replace_cow(br, self.brand_replacement_has_group, &captures))
-                .and_then(none_if_empty);
-
-            let model: Option<Cow<'a, str>> =
-                if let Some(model_replacement) = &self.model_replacement {
-                    none_if_empty(replace_cow(
-                        model_replacement,
-                        self.model_replacement_has_group,
-                        &captures,
-                    ))
-                } else {
-                    captures
-                        .get(1)
-                        .map(|x| x.as_str())
-                        .and_then(none_if_empty)
-                        .map(Cow::Borrowed)
-                };
-
-            Some(Device {
-                family,
-                brand,
-                model,
+        self.regex.captures(text)
+    }
+
+    fn parse_captures<'a, 'm>(
+        &'m self,
+        captures: &regex::Captures<'a>,
+        literal: fn(&'m str) -> Cow<'a, str>,
+    ) -> Option<Device<'a>> {
+        let family: Cow<'a, str> =
+            if let Some(device_replacement) = &self.device_replacement {
+                replace_cow(
+                    device_replacement,
+                    self.has_group.contains(DEVICE_REPLACEMENT),
+                    captures,
+                    literal,
+                )
+            } else {
+                captures
+                    .get(1)
+                    .map(|x| x.as_str())
+                    .and_then(none_if_empty)
+                    .map(Cow::Borrowed)?
+            };
+
+        let brand: Option<Cow<'a, str>> = self
+            .brand_replacement
+            .as_ref()
+            .map(|br| {
+                replace_cow(
+                    br,
+                    self.has_group.contains(BRAND_REPLACEMENT),
+                    captures,
+                    literal,
+                )
             })
-        } else {
-            None
+            .and_then(none_if_empty);
+
+        let model: Option<Cow<'a, str>> =
+            if let Some(model_replacement) = &self.model_replacement {
+                none_if_empty(replace_cow(
+                    model_replacement,
+                    self.has_group.contains(MODEL_REPLACEMENT),
+                    captures,
+                    literal,
+                ))
+            } else {
+                captures
+                    .get(1)
+                    .map(|x| x.as_str())
+                    .and_then(none_if_empty)
+                    .map(Cow::Borrowed)
+            };
+
+        Some(Device {
+            family,
+            brand,
+            model,
+        })
+    }
+
+    fn spans(&self, captures: &regex::Captures) -> DeviceSpans {
+        DeviceSpans {
+            family: capture_span(captures, 1)
+                .filter(|_| self.device_replacement.is_none()),
+            brand: None,
+            model: capture_span(captures, 1).filter(|_| self.model_replacement.is_none()),
         }
     }
 }
 
 impl Matcher {
+    /// Compiles a rule file entry, which is the only way to build a matcher
     pub fn try_from(entry: DeviceParserEntry) -> Result<Matcher, Error> {
-        let regex_with_flags = if entry.regex_flag.as_ref().map_or(true, String::is_empty)
-        {
-            entry.regex
-        } else {
-            format!("(?{}){}", entry.regex_flag.unwrap_or_default(), entry.regex)
-        };
-        let regex = regex::RegexBuilder::new(&clean_escapes(&regex_with_flags))
-            .size_limit(20 * (1 << 20))
-            .build();
+        Matcher::compile(entry, true)
+    }
+
+    pub(super) fn compile(
+        entry: DeviceParserEntry,
+        clean: bool,
+    ) -> Result<Matcher, Error> {
+        let regex = compile_pattern(&entry.flagged_regex(), clean);
 
         Ok(Matcher {
             regex: regex?,
-            device_replacement_has_group: entry
-                .device_replacement
-                .as_ref()
-                .map_or(false, |x| has_group(x.as_str())),
-            device_replacement: entry.device_replacement,
-            brand_replacement_has_group: entry
-                .brand_replacement
-                .as_ref()
-                .map_or(false, |x| has_group(x.as_str())),
-            brand_replacement: entry.brand_replacement,
-            model_replacement_has_group: entry
-                .model_replacement
-                .as_ref()
-                .map_or(false, |x| has_group(x.as_str())),
-            model_replacement: entry.model_replacement,
+            priority: entry.priority.unwrap_or_default(),
+            quality: entry.quality,
+            extras: into_extras(entry.extras),
+            has_group: GroupFlags::from_replacements(&[
+                entry.device_replacement.as_deref(),
+                entry.brand_replacement.as_deref(),
+                entry.model_replacement.as_deref(),
+            ]),
+            device_replacement: into_boxed(entry.device_replacement),
+            brand_replacement: into_boxed(entry.brand_replacement),
+            model_replacement: into_boxed(entry.model_replacement),
         })
     }
+
+    /// The regular expression this matcher runs, as compiled
+    #[must_use]
+    pub fn pattern(&self) -> &str {
+        self.regex.as_str()
+    }
+
+    /// The template for `Device::family`
+    #[must_use]
+    pub fn device_replacement(&self) -> Option<&str> {
+        self.device_replacement.as_deref()
+    }
+
+    /// The template for `Device::brand`
+    #[must_use]
+    pub fn brand_replacement(&self) -> Option<&str> {
+        self.brand_replacement.as_deref()
+    }
+
+    /// The template for `Device::model`
+    #[must_use]
+    pub fn model_replacement(&self) -> Option<&str> {
+        self.model_replacement.as_deref()
+    }
+
+    /// The rule's `priority`, with higher priorities tried first
+    #[must_use]
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// The rule's `quality` override, as used by `parse_scored`
+    #[must_use]
+    pub fn quality(&self) -> Option<MatchQuality> {
+        self.quality
+    }
+
+    /// The keys of the rule's entry this crate doesn't know, such as
+    /// annotations for the caller's own use
+    #[must_use]
+    pub fn extras(&self) -> &BTreeMap<String, serde_yaml::Value> {
+        self.extras.as_deref().unwrap_or(&NO_EXTRAS)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn device_replacement_has_group(&self) -> bool {
+        self.has_group.contains(DEVICE_REPLACEMENT)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn brand_replacement_has_group(&self) -> bool {
+        self.has_group.contains(BRAND_REPLACEMENT)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn model_replacement_has_group(&self) -> bool {
+        self.has_group.contains(MODEL_REPLACEMENT)
+    }
 }
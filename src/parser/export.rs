@@ -0,0 +1,230 @@
+use super::*;
+
+/// Something about a parser that `UserAgentParser::to_regex_file` couldn't
+/// write to the rule file
+#[derive(Clone, Copy, Debug, Display, Eq, PartialEq)]
+pub enum ExportWarning {
+    /// Custom sub-parsers have no rule file form and were left out
+    #[display(fmt = "{_0} custom sub-parsers were left out")]
+    SubParsers(usize),
+    /// Post-processors have no rule file form and were left out
+    #[display(fmt = "{_0} post-processors were left out")]
+    PostProcessors(usize),
+}
+
+impl UserAgentParser {
+    /// Rebuilds a rule file from the parser's current rules, in the order
+    /// they are tried, so edits such as `prune_unhit` can be saved for other
+    /// UA Parser implementations or later runs. A parser built from the file
+    /// classifies user agents as this one does, apart from anything reported
+    /// in the warnings. Builder settings such as match strategies aren't part
+    /// of rule files either.
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+    /// let (file, warnings) = parser.to_regex_file();
+    /// let yaml = file.to_yaml_string().unwrap();
+    /// let rebuilt = UserAgentParser::from_bytes(yaml.as_bytes()).unwrap();
+    ///
+    /// assert!(warnings.is_empty());
+    /// assert_eq!(rebuilt.parse("curl/8.4.0"), parser.parse("curl/8.4.0"));
+    /// ```
+    #[must_use]
+    pub fn to_regex_file(&self) -> (RegexFile, Vec<ExportWarning>) {
+        let file = RegexFile {
+            user_agent_parsers: self
+                .user_agent_matchers
+                .iter()
+                .map(user_agent::Matcher::to_entry)
+                .collect(),
+            os_parsers: self.os_matchers.iter().map(os::Matcher::to_entry).collect(),
+            device_parsers: self
+                .device_matchers
+                .iter()
+                .map(device::Matcher::to_entry)
+                .collect(),
+        };
+
+        let mut warnings = Vec::new();
+        if !self.sub_parsers.is_empty() {
+            warnings.push(ExportWarning::SubParsers(self.sub_parsers.len()));
+        }
+        if !self.post_processors.is_empty() {
+            warnings.push(ExportWarning::PostProcessors(self.post_processors.len()));
+        }
+        (file, warnings)
+    }
+}
+
+impl device::Matcher {
+    pub(super) fn to_entry(&self) -> DeviceParserEntry {
+        let (regex_flag, regex) = split_flags(self.regex.as_str());
+        DeviceParserEntry {
+            regex_flag: regex_flag.map(str::to_owned),
+            regex: regex.to_owned(),
+            device_replacement: self.device_replacement.as_deref().map(str::to_owned),
+            brand_replacement: self.brand_replacement.as_deref().map(str::to_owned),
+            model_replacement: self.model_replacement.as_deref().map(str::to_owned),
+            priority: to_priority(self.priority),
+            quality: self.quality,
+            extras: self.extras().clone(),
+        }
+    }
+}
+
+impl os::Matcher {
+    pub(super) fn to_entry(&self) -> OSParserEntry {
+        OSParserEntry {
+            regex: self.regex.as_str().to_owned(),
+            os_replacement: self.os_replacement.as_deref().map(str::to_owned),
+            os_v1_replacement: self.os_v1_replacement.as_deref().map(str::to_owned),
+            os_v2_replacement: self.os_v2_replacement.as_deref().map(str::to_owned),
+            os_v3_replacement: self.os_v3_replacement.as_deref().map(str::to_owned),
+            priority: to_priority(self.priority),
+            quality: self.quality,
+            extras: self.extras().clone(),
+        }
+    }
+}
+
+impl user_agent::Matcher {
+    pub(super) fn to_entry(&self) -> UserAgentParserEntry {
+        UserAgentParserEntry {
+            regex: self.regex.as_str().to_owned(),
+            family_replacement: self.family_replacement.as_deref().map(str::to_owned),
+            v1_replacement: self.v1_replacement.as_deref().map(str::to_owned),
+            v2_replacement: self.v2_replacement.as_deref().map(str::to_owned),
+            v3_replacement: self.v3_replacement.as_deref().map(str::to_owned),
+            v4_replacement: self.v4_replacement.as_deref().map(str::to_owned),
+            priority: to_priority(self.priority),
+            quality: self.quality,
+            extras: self.extras().clone(),
+        }
+    }
+}
+
+/// Splits a leading `(?i)` style flag group back out of a device pattern, as
+/// `regex_flag` is prepended to the pattern when compiling it
+fn split_flags(pattern: &str) -> (Option<&str>, &str) {
+    pattern
+        .strip_prefix("(?")
+        .and_then(|rest| {
+            let end = rest.find(')')?;
+            let flags = &rest[..end];
+            (!flags.is_empty() && flags.bytes().all(|byte| byte.is_ascii_alphabetic()))
+                .then(|| (Some(flags), &rest[end + 1..]))
+        })
+        .unwrap_or((None, pattern))
+}
+
+/// Leaves out the default priority, which rule files don't spell out
+fn to_priority(priority: i32) -> Option<i32> {
+    (priority != 0).then_some(priority)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CapitalizeBrand, ParserStats};
+
+    const RULES: &[u8] = br"
+user_agent_parsers:
+  - regex: '(Acme)Browser/(\d+)\.(\d+)'
+    v4_replacement: '7'
+  - regex: '(Stale)/(\d+)'
+  - regex: '(Urgent)/(\d+)'
+    priority: 5
+    quality: generic
+os_parsers:
+  - regex: 'AcmeOS (\d+)'
+    os_replacement: 'AcmeOS'
+device_parsers:
+  - regex: 'acme-(\w+)'
+    regex_flag: 'i'
+    device_replacement: 'Acme $1'
+    brand_replacement: 'Acme'
+    model_replacement: '$1'
+";
+
+    struct Never;
+
+    impl<'a> SubParser<'a> for Never {
+        type Item = Device<'a>;
+
+        fn try_parse(&self, _: &'a str) -> Option<Device<'a>> {
+            None
+        }
+    }
+
+    const USER_AGENTS: &[&str] = &[
+        "AcmeBrowser/3.1 (AcmeOS 12; ACME-X100)",
+        "Urgent/2 Stale/4",
+        "Stale/4",
+        "nothing to see",
+    ];
+
+    #[test]
+    fn modified_parsers_round_trip() {
+        let mut parser =
+            UserAgentParser::from_bytes(RULES).expect("Parser creation failed");
+        let mut stats = ParserStats::default();
+        for pattern in [r"(Acme)Browser/(\d+)\.(\d+)", r"(Urgent)/(\d+)"] {
+            stats.user_agent.insert(pattern.to_owned(), 1);
+        }
+        stats.os.insert(r"AcmeOS (\d+)".to_owned(), 1);
+        stats.device.insert(r"(?i)acme-(\w+)".to_owned(), 1);
+        let report = parser.prune_unhit(&stats, 1);
+        assert_eq!(report.user_agent_patterns, [r"(Stale)/(\d+)"]);
+
+        parser.register_device_subparser(Box::new(Never), Position::Before);
+
+        let (file, warnings) = parser.to_regex_file();
+        assert_eq!(warnings, [ExportWarning::SubParsers(1)]);
+        let yaml = file.to_yaml_string().expect("Serialization failed");
+        assert!(yaml.contains("regex_flag: i"));
+        assert!(!yaml.contains("Stale"));
+
+        let rebuilt = UserAgentParser::builder()
+            .strict(true)
+            .build_from_bytes(yaml.as_bytes())
+            .expect("Parser creation failed");
+        for user_agent in USER_AGENTS {
+            assert_eq!(rebuilt.parse(user_agent), parser.parse(user_agent));
+            assert_eq!(
+                rebuilt.parse_scored(user_agent),
+                parser.parse_scored(user_agent)
+            );
+        }
+        assert_eq!(rebuilt.parse("Urgent/2").user_agent.family, "Urgent");
+    }
+
+    #[test]
+    fn uap_core_round_trips() {
+        let mut parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        parser.add_post_processor(CapitalizeBrand);
+
+        let (file, warnings) = parser.to_regex_file();
+        assert_eq!(warnings, [ExportWarning::PostProcessors(1)]);
+        let mut yaml = Vec::new();
+        file.to_yaml_writer(&mut yaml)
+            .expect("Serialization failed");
+        let rebuilt = UserAgentParser::from_bytes(&yaml).expect("Parser creation failed");
+
+        let patterns = |parser: &UserAgentParser| {
+            let device = parser.device_matchers().iter().map(DeviceMatcher::pattern);
+            let os = parser.os_matchers().iter().map(OSMatcher::pattern);
+            let user_agent = parser
+                .user_agent_matchers()
+                .iter()
+                .map(UserAgentMatcher::pattern);
+            device
+                .chain(os)
+                .chain(user_agent)
+                .map(str::to_owned)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(patterns(&rebuilt), patterns(&parser));
+    }
+}
@@ -0,0 +1,451 @@
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_yaml::Value;
+
+use super::*;
+use crate::file::EntryLocation;
+
+/// What the inline `tests` of a rule file found, one result per entry that
+/// has any, from `validate` or `UserAgentParserBuilder::verify_inline_tests`
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct InlineTestReport {
+    pub results: Vec<InlineTestResult>,
+}
+
+/// The inline tests of one rule entry
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InlineTestResult {
+    /// The section of the entry, such as `device_parsers`
+    pub section: &'static str,
+    /// The position of the entry in its section
+    pub index: usize,
+    /// The number of tests the entry has
+    pub checked: usize,
+    pub misses: Vec<InlineTestMiss>,
+}
+
+/// A field an inline test expected differently than the entry's rule gave.
+/// When the rule doesn't match the user agent at all, `field` is `regex`,
+/// `expected` the pattern and `found` `None`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct InlineTestMiss {
+    pub user_agent: String,
+    /// The test key, such as `brand`
+    pub field: &'static str,
+    pub expected: String,
+    pub found: Option<String>,
+}
+
+impl InlineTestReport {
+    /// Whether every inline test passed
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.results.iter().all(InlineTestResult::passed)
+    }
+
+    /// The results of the entries with a failing test
+    pub fn failures(&self) -> impl Iterator<Item = &InlineTestResult> {
+        self.results.iter().filter(|result| !result.passed())
+    }
+}
+
+impl InlineTestResult {
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.misses.is_empty()
+    }
+}
+
+impl fmt::Display for InlineTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} entries with inline tests failed",
+            self.failures().count(),
+            self.results.len()
+        )?;
+        for result in self.failures() {
+            for miss in &result.misses {
+                write!(
+                    f,
+                    "\n{}[{}]: {}: expected {:?}, found {:?} for {}",
+                    result.section,
+                    result.index,
+                    miss.field,
+                    miss.expected,
+                    miss.found,
+                    miss.user_agent
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An item of a `user_agent_parsers` entry's `tests`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UserAgentCase {
+    ua: String,
+    family: Option<String>,
+    major: Option<String>,
+    minor: Option<String>,
+    patch: Option<String>,
+    patch_minor: Option<String>,
+}
+
+/// An item of an `os_parsers` entry's `tests`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OSCase {
+    ua: String,
+    family: Option<String>,
+    major: Option<String>,
+    minor: Option<String>,
+    patch: Option<String>,
+    patch_minor: Option<String>,
+}
+
+/// An item of a `device_parsers` entry's `tests`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DeviceCase {
+    ua: String,
+    family: Option<String>,
+    brand: Option<String>,
+    model: Option<String>,
+}
+
+/// A test key, its expected value and what the rule gave
+type Field<'a> = (&'static str, Option<&'a str>, Option<String>);
+
+/// A test item of one category, and how to run it against its entry
+trait Case: DeserializeOwned {
+    type Entry: Clone;
+    type Matcher;
+    const SECTION: &'static str;
+
+    fn regex(entry: &Self::Entry) -> &str;
+    fn extras(entry: &Self::Entry) -> &BTreeMap<String, Value>;
+    fn compile(entry: Self::Entry, clean: bool) -> Result<Self::Matcher, Error>;
+    fn user_agent(&self) -> &str;
+    /// The fields of the test against what `matcher` gave, or `None` when
+    /// it didn't match
+    fn fields(&self, matcher: &Self::Matcher) -> Option<Vec<Field<'_>>>;
+}
+
+impl Case for UserAgentCase {
+    type Entry = UserAgentParserEntry;
+    type Matcher = user_agent::Matcher;
+    const SECTION: &'static str = "user_agent_parsers";
+
+    fn regex(entry: &Self::Entry) -> &str {
+        &entry.regex
+    }
+
+    fn extras(entry: &Self::Entry) -> &BTreeMap<String, Value> {
+        &entry.extras
+    }
+
+    fn compile(entry: Self::Entry, clean: bool) -> Result<Self::Matcher, Error> {
+        Ok(user_agent::Matcher::compile(entry, clean)?)
+    }
+
+    fn user_agent(&self) -> &str {
+        &self.ua
+    }
+
+    fn fields(&self, matcher: &Self::Matcher) -> Option<Vec<Field<'_>>> {
+        let found = matcher.try_parse_borrowed(&self.ua)?;
+        Some(vec![
+            (
+                "family",
+                self.family.as_deref(),
+                Some(found.family.into_owned()),
+            ),
+            (
+                "major",
+                self.major.as_deref(),
+                found.major.map(Cow::into_owned),
+            ),
+            (
+                "minor",
+                self.minor.as_deref(),
+                found.minor.map(Cow::into_owned),
+            ),
+            (
+                "patch",
+                self.patch.as_deref(),
+                found.patch.map(Cow::into_owned),
+            ),
+            (
+                "patch_minor",
+                self.patch_minor.as_deref(),
+                found.patch_minor.map(Cow::into_owned),
+            ),
+        ])
+    }
+}
+
+impl Case for OSCase {
+    type Entry = OSParserEntry;
+    type Matcher = os::Matcher;
+    const SECTION: &'static str = "os_parsers";
+
+    fn regex(entry: &Self::Entry) -> &str {
+        &entry.regex
+    }
+
+    fn extras(entry: &Self::Entry) -> &BTreeMap<String, Value> {
+        &entry.extras
+    }
+
+    fn compile(entry: Self::Entry, clean: bool) -> Result<Self::Matcher, Error> {
+        Ok(os::Matcher::compile(entry, clean)?)
+    }
+
+    fn user_agent(&self) -> &str {
+        &self.ua
+    }
+
+    fn fields(&self, matcher: &Self::Matcher) -> Option<Vec<Field<'_>>> {
+        let found = matcher.try_parse_borrowed(&self.ua)?;
+        Some(vec![
+            (
+                "family",
+                self.family.as_deref(),
+                Some(found.family.into_owned()),
+            ),
+            (
+                "major",
+                self.major.as_deref(),
+                found.major.map(Cow::into_owned),
+            ),
+            (
+                "minor",
+                self.minor.as_deref(),
+                found.minor.map(Cow::into_owned),
+            ),
+            (
+                "patch",
+                self.patch.as_deref(),
+                found.patch.map(Cow::into_owned),
+            ),
+            (
+                "patch_minor",
+                self.patch_minor.as_deref(),
+                found.patch_minor.map(Cow::into_owned),
+            ),
+        ])
+    }
+}
+
+impl Case for DeviceCase {
+    type Entry = DeviceParserEntry;
+    type Matcher = device::Matcher;
+    const SECTION: &'static str = "device_parsers";
+
+    fn regex(entry: &Self::Entry) -> &str {
+        &entry.regex
+    }
+
+    fn extras(entry: &Self::Entry) -> &BTreeMap<String, Value> {
+        &entry.extras
+    }
+
+    fn compile(entry: Self::Entry, clean: bool) -> Result<Self::Matcher, Error> {
+        Ok(device::Matcher::compile(entry, clean)?)
+    }
+
+    fn user_agent(&self) -> &str {
+        &self.ua
+    }
+
+    fn fields(&self, matcher: &Self::Matcher) -> Option<Vec<Field<'_>>> {
+        let found = matcher.try_parse_borrowed(&self.ua)?;
+        Some(vec![
+            (
+                "family",
+                self.family.as_deref(),
+                Some(found.family.into_owned()),
+            ),
+            (
+                "brand",
+                self.brand.as_deref(),
+                found.brand.map(Cow::into_owned),
+            ),
+            (
+                "model",
+                self.model.as_deref(),
+                found.model.map(Cow::into_owned),
+            ),
+        ])
+    }
+}
+
+/// Runs the inline `tests` of every entry of a rule file. Each entry's tests
+/// run against its own rule, compiled on its own, so they check what the
+/// rule gives a user agent, not which rule of the file wins it.
+///
+/// Fails when the file doesn't deserialize, a `tests` list is malformed or a
+/// rule with tests doesn't compile; failing tests are in the report.
+///
+/// ```rust
+/// # use uaparser::*;
+/// let report = validate(br"
+/// user_agent_parsers:
+///   - regex: '(Acme)/(\d+)'
+///     family_replacement: 'Acme Browser'
+///     tests:
+///       - ua: 'Acme/2'
+///         family: 'Acme Browser'
+///         major: '2'
+/// ").unwrap();
+///
+/// assert!(report.is_ok());
+/// assert_eq!(report.results[0].checked, 1);
+/// ```
+pub fn validate(bytes: &[u8]) -> Result<InlineTestReport, Error> {
+    run(&RegexFile::from_slice(bytes)?, true)
+}
+
+pub(super) fn run(file: &RegexFile, clean: bool) -> Result<InlineTestReport, Error> {
+    let mut report = InlineTestReport::default();
+    run_section::<UserAgentCase>(&mut report, &file.user_agent_parsers, clean)?;
+    run_section::<OSCase>(&mut report, &file.os_parsers, clean)?;
+    run_section::<DeviceCase>(&mut report, &file.device_parsers, clean)?;
+    Ok(report)
+}
+
+fn run_section<C: Case>(
+    report: &mut InlineTestReport,
+    entries: &[C::Entry],
+    clean: bool,
+) -> Result<(), Error> {
+    for (index, entry) in entries.iter().enumerate() {
+        let Some(tests) = C::extras(entry).get("tests") else {
+            continue;
+        };
+        let cases: Vec<C> = serde_yaml::from_value(tests.clone()).map_err(|error| {
+            YamlError::at_entry(
+                error,
+                EntryLocation {
+                    section: C::SECTION,
+                    index,
+                    regex: Some(C::regex(entry).to_owned()),
+                },
+            )
+        })?;
+        let matcher = C::compile(entry.clone(), clean)?;
+
+        let mut result = InlineTestResult {
+            section: C::SECTION,
+            index,
+            checked: cases.len(),
+            misses: Vec::new(),
+        };
+        for case in &cases {
+            let Some(fields) = case.fields(&matcher) else {
+                result.misses.push(InlineTestMiss {
+                    user_agent: case.user_agent().to_owned(),
+                    field: "regex",
+                    expected: C::regex(entry).to_owned(),
+                    found: None,
+                });
+                continue;
+            };
+            for (field, expected, found) in fields {
+                let Some(expected) = expected else {
+                    continue;
+                };
+                if found.as_deref() != Some(expected) {
+                    result.misses.push(InlineTestMiss {
+                        user_agent: case.user_agent().to_owned(),
+                        field,
+                        expected: expected.to_owned(),
+                        found,
+                    });
+                }
+            }
+        }
+        report.results.push(result);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OVERLAY: &[u8] = include_bytes!("../../tests/fixtures/inline_tests.yaml");
+
+    #[test]
+    fn reports_only_failing_tests() {
+        let report = validate(OVERLAY).unwrap();
+
+        assert_eq!(report.results.len(), 2);
+        assert!(!report.is_ok());
+        let failures: Vec<_> = report.failures().collect();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(
+            (failures[0].section, failures[0].index),
+            ("device_parsers", 1)
+        );
+        assert_eq!(
+            failures[0].misses,
+            [InlineTestMiss {
+                user_agent: "Mozilla/5.0 (Linux; Android 14; Pixel 8)".to_owned(),
+                field: "brand",
+                expected: "Alphabet".to_owned(),
+                found: Some("Google".to_owned()),
+            }]
+        );
+        assert_eq!(
+            report.to_string(),
+            "1 of 2 entries with inline tests failed\n\
+             device_parsers[1]: brand: expected \"Alphabet\", found Some(\"Google\") \
+             for Mozilla/5.0 (Linux; Android 14; Pixel 8)"
+        );
+    }
+
+    #[test]
+    fn builder_verifies_inline_tests() {
+        assert!(UserAgentParser::from_bytes(OVERLAY).is_ok());
+        assert!(UserAgentParser::from_bytes_strict(OVERLAY).is_ok());
+        assert!(matches!(
+            UserAgentParser::builder()
+                .verify_inline_tests(true)
+                .build_from_bytes(OVERLAY),
+            Err(Error::InlineTests(report)) if report.failures().count() == 1
+        ));
+    }
+
+    #[test]
+    fn unmatched_and_malformed_tests() {
+        let report = validate(
+            br"
+os_parsers:
+  - regex: 'Acme OS (\d+)'
+    os_replacement: 'Acme OS'
+    tests:
+      - ua: 'Other OS 1'
+",
+        )
+        .unwrap();
+        assert_eq!(report.failures().next().unwrap().misses[0].field, "regex");
+
+        let error = validate(
+            br"
+os_parsers:
+  - regex: 'Acme OS (\d+)'
+    tests:
+      - ua: 'Acme OS 1'
+        brand: 'Acme'
+",
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Yaml(error) if error.entry().map(|entry| entry.section) == Some("os_parsers")
+        ));
+    }
+}
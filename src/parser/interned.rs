@@ -0,0 +1,426 @@
+// @generated by scripts/generate_interned.py from src/core/regexes.yaml.
+// Do not edit by hand.
+
+pub(super) static INTERNED: [&str; 421] = [
+    "3Q",
+    "ATV OS X",
+    "Acer",
+    "Adobe CreativeCloud",
+    "Advent",
+    "Ainol",
+    "Airis",
+    "Airpad",
+    "Alcatel",
+    "Alcatel One Touch 918D",
+    "Alcatel One Touch 998",
+    "Allfine",
+    "Allview",
+    "Allwinner",
+    "Amarok",
+    "Amaway",
+    "Amazon",
+    "Amazon Silk",
+    "Amoi",
+    "Android",
+    "AntennaPod",
+    "Aoc",
+    "Aoson",
+    "Apanda",
+    "Apollo Lite",
+    "Apple",
+    "Apple Mail",
+    "AppleTV",
+    "ArcGIS Earth",
+    "ArcMap",
+    "Archos",
+    "Arival",
+    "Arnova",
+    "Assistant",
+    "Asus",
+    "Attab",
+    "AudioBoom",
+    "Audiosonic",
+    "Axioo",
+    "Azend",
+    "Baidu Browser",
+    "Baidu Explorer",
+    "Bak",
+    "Barca",
+    "Basilisk",
+    "Bedove",
+    "Benss",
+    "Bird",
+    "BlackBerry",
+    "BlackBerry OS",
+    "BlackBerry Playbook",
+    "BlackBerry Tablet OS",
+    "BlackBerry WebKit",
+    "Blackberry",
+    "Blaupunkt",
+    "Blu",
+    "Blusens",
+    "Bmobile",
+    "Bon Echo",
+    "Braun",
+    "Brave",
+    "Brew MP",
+    "CFNetwork",
+    "Captiva",
+    "Casio",
+    "Cat",
+    "Celkon",
+    "Cellular",
+    "ChangJia",
+    "Chrome Frame",
+    "Chrome Mobile",
+    "Chrome Mobile WebView",
+    "Chrome Mobile iOS",
+    "Chrome OS",
+    "Chromecast",
+    "CloudFoundry",
+    "Cloudfone",
+    "Cmx",
+    "CobyKyros",
+    "Coc Coc",
+    "Collector for ArcGIS",
+    "Comodo Dragon",
+    "Conkeror",
+    "Coolpad",
+    "Cube",
+    "Cubot",
+    "DNS",
+    "DOOV",
+    "Danew",
+    "Debian",
+    "Dell",
+    "Denver",
+    "Dex",
+    "DoCoMo",
+    "DuckDuckGo Mobile",
+    "ESPN",
+    "Edge",
+    "Edge Mobile",
+    "Enot",
+    "Evercoss",
+    "Explay",
+    "Explorer for ArcGIS",
+    "Facebook",
+    "Facebook Messenger",
+    "FacebookBot",
+    "FancyMusic",
+    "FireHbbTV",
+    "Firefox Alpha",
+    "Firefox Beta",
+    "Firefox Mobile",
+    "Firefox OS",
+    "Firefox iOS",
+    "Fly",
+    "FreeBSD",
+    "Freescale",
+    "Fujitsu",
+    "Galapad",
+    "Garmin-Asus",
+    "Generic",
+    "Generic Feature Phone",
+    "Generic Smartphone",
+    "Generic Tablet",
+    "Generic_Android",
+    "Generic_Android_Tablet",
+    "Generic_Inettv",
+    "Gentoo",
+    "Gfive",
+    "Gigabyte",
+    "Gionee",
+    "GmailImageProxy",
+    "GoClever",
+    "Google",
+    "GooglePlusBot",
+    "HCLme",
+    "HP",
+    "HP TouchPad",
+    "HP Veer",
+    "HTC",
+    "HTTPClient",
+    "Haier",
+    "Haipad",
+    "Hannspree",
+    "Hena",
+    "Hero",
+    "HipChat Desktop Client",
+    "Hisense",
+    "Huawei",
+    "Hyundai",
+    "IE",
+    "IE Large Screen",
+    "IE Mobile",
+    "IMO",
+    "IconBIT",
+    "Impression",
+    "Inettv",
+    "Infinix",
+    "Informer",
+    "Intenso",
+    "Intex",
+    "Iron",
+    "Iru",
+    "Itel",
+    "Ivio",
+    "JXD",
+    "Jaytech",
+    "Jiayu",
+    "KDDI",
+    "KTtech",
+    "KaiOS",
+    "Karbonn",
+    "Kindle",
+    "Kindle Fire",
+    "Kindle Fire 2",
+    "Kindle Fire HD",
+    "Kindle Fire HD 7\" WiFi",
+    "Kindle Fire HD 8.9\" 4G",
+    "Kindle Fire HD 8.9\" WiFi",
+    "Kindle Fire HDX 7\" 4G",
+    "Kindle Fire HDX 7\" WiFi",
+    "Kindle Fire HDX 8.9\" 4G",
+    "Kindle Fire HDX 8.9\" WiFi",
+    "Kingcom",
+    "Kobo",
+    "Konqueror",
+    "Ktouch",
+    "Kurio App",
+    "Kyocera",
+    "LG",
+    "LINE",
+    "LYF",
+    "Lava",
+    "Lemon",
+    "Lenco",
+    "Lenovo",
+    "Lenovo Ideapad K1",
+    "Lexibook",
+    "Linux",
+    "Lotus Notes",
+    "LuaKit",
+    "MSIECrawler",
+    "Mac",
+    "Mac OS",
+    "Mac OS X",
+    "Mail.ru Chromium Browser",
+    "Malata",
+    "Manta",
+    "Match",
+    "Maxthon",
+    "Maxx",
+    "Mediacom",
+    "Medias",
+    "Medion",
+    "Meizu",
+    "MicroB",
+    "Micromax",
+    "Microsoft",
+    "Microsoft Surface RT",
+    "Mint Browser",
+    "Mito",
+    "MiuiBrowser",
+    "Mobile Safari",
+    "Mobile Safari UI/WKWebView",
+    "Mobistel",
+    "Modecom",
+    "Monitis",
+    "Motorola",
+    "Mpman",
+    "Msi",
+    "Multilaser",
+    "My Internet Browser",
+    "MyPhone",
+    "Mytab",
+    "NCSA Mosaic",
+    "Nabi",
+    "Nec",
+    "NetFront",
+    "NetFront NX",
+    "Netscape",
+    "NewRelicPingerBot",
+    "Nextbook",
+    "Nintendo",
+    "Nintendo Wii U",
+    "Nokia",
+    "Nokia Browser",
+    "Nokia OSS Browser",
+    "Nokia Series 30 Plus",
+    "Nokia Series 40",
+    "Nokia Services (WAP) Browser",
+    "Nook",
+    "ONE Browser",
+    "Obigo",
+    "Odys",
+    "Olivetti",
+    "Omega",
+    "OnePlus",
+    "OpenVAS Scanner",
+    "Openpeak",
+    "Opera",
+    "Opera Coast",
+    "Opera Mini",
+    "Opera Mobile",
+    "Opera Neon",
+    "Operations Dashboard for ArcGIS",
+    "Oppo",
+    "Oppo F1s",
+    "Orion",
+    "Other",
+    "Outlook",
+    "Ovi Browser",
+    "Owncloud",
+    "POV",
+    "PackardBell",
+    "Pale Moon",
+    "Palm",
+    "Palm Blazer",
+    "Palm Pre",
+    "Panasonic",
+    "Pantech",
+    "Papyre",
+    "Pearl",
+    "Phantom Browser",
+    "Phicomm",
+    "Philips",
+    "PingdomBot",
+    "Pinterestbot",
+    "Pipo",
+    "PlayStation 3",
+    "Ployer",
+    "PodcastAddict",
+    "Podkicker",
+    "Polaris",
+    "Polaroid",
+    "Pomp",
+    "Positivo",
+    "Postbox",
+    "Prestigio",
+    "Proscan",
+    "Python Requests",
+    "Python aiohttp",
+    "QQ Browser",
+    "QQ Browser Mini",
+    "QQ Browser Mobile",
+    "Qmobile",
+    "Qmobilevn",
+    "Quanta",
+    "RCA",
+    "RSSRadio",
+    "RackspaceBot",
+    "Red Hat",
+    "Regza",
+    "Rekonq",
+    "Rockchip",
+    "SKtelesys",
+    "Safari",
+    "Sailfish Browser",
+    "Samsung",
+    "Samsung Internet",
+    "Sega",
+    "Seznam prohlížeč",
+    "Sharp",
+    "Siemens",
+    "Simvalley",
+    "Skype",
+    "Skytex",
+    "Slack Desktop Client",
+    "Smartbitt",
+    "Softbank",
+    "Sogou Explorer",
+    "Solaris",
+    "Sony",
+    "SonyEricsson",
+    "Spice",
+    "Spider",
+    "Sprint",
+    "StatusCakeBot",
+    "Superhuman",
+    "Swiftfox",
+    "Symbian OS",
+    "Symbian^3",
+    "Symbian^3 Anna",
+    "Symbian^3 Belle",
+    "Tableau",
+    "Tagi",
+    "Tecmobile",
+    "Tecno",
+    "Teleca Browser",
+    "Telstra",
+    "Tenta Browser",
+    "Terra",
+    "Tesla",
+    "Tesla Model S",
+    "Texet",
+    "Thalia",
+    "Thl",
+    "Thomson",
+    "Thor",
+    "Thunderbird",
+    "Tizen Browser",
+    "Tmobile",
+    "Tomtec",
+    "Tooky",
+    "TopBuzz",
+    "Toshiba",
+    "Touchmate",
+    "Trekstor",
+    "Treq",
+    "Twitter",
+    "Twitterbot",
+    "UC Browser",
+    "Umeox",
+    "VLC",
+    "Vernee",
+    "Versus",
+    "Vertu",
+    "ViaFree",
+    "Videocon",
+    "Viewsonic",
+    "WMPlayer",
+    "Walton",
+    "WatchOS",
+    "WeTab",
+    "WebKit Nightly",
+    "WebPageTest.org bot",
+    "Wellcom",
+    "Whale",
+    "Wiko",
+    "Windows",
+    "Windows Live Mail",
+    "Windows Mobile",
+    "Windows Phone",
+    "Wolfgang",
+    "Workforce for ArcGIS",
+    "Woxter",
+    "Xianghe",
+    "XiaoMi",
+    "Xolo",
+    "Xoro",
+    "YahooMailProxy",
+    "Yandex Browser",
+    "Yarvik",
+    "Yifang",
+    "ZTE",
+    "ZiiLabs",
+    "Zopo",
+    "Zync",
+    "bq",
+    "hitech",
+    "i-mate",
+    "iBall",
+    "iBrowser Mini",
+    "iOCEAN",
+    "iOS",
+    "iOS-Device",
+    "iPad",
+    "iPhone",
+    "imobile",
+    "ionik",
+    "tvOS",
+    "vivo",
+    "webOS",
+    "webOS Browser",
+];
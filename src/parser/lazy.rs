@@ -0,0 +1,203 @@
+use std::cell::OnceCell;
+
+use super::*;
+
+/// A user agent whose `Device`, `OS` and `UserAgent` are each parsed on
+/// first access and kept, from `UserAgentParser::parse_lazy`. Reading only
+/// the browser family skips the device rules, the most expensive category.
+///
+/// Post-processors and slow parse hooks work on whole clients, so with
+/// either set, the first access parses everything as `parse` would.
+#[derive(Debug)]
+pub struct LazyClient<'a> {
+    parser: &'a UserAgentParser,
+    user_agent: &'a str,
+    device: OnceCell<Device<'a>>,
+    os: OnceCell<OS<'a>>,
+    parsed_user_agent: OnceCell<UserAgent<'a>>,
+}
+
+impl<'a> LazyClient<'a> {
+    /// The user agent string being parsed
+    #[must_use]
+    pub fn as_str(&self) -> &'a str {
+        self.user_agent
+    }
+
+    #[must_use]
+    pub fn device(&self) -> &Device<'a> {
+        self.device.get_or_init(|| match self.whole() {
+            Some(client) => {
+                let _ = self.os.set(client.os);
+                let _ = self.parsed_user_agent.set(client.user_agent);
+                client.device
+            }
+            None => self.parser.parse_device_borrowed(self.user_agent),
+        })
+    }
+
+    #[must_use]
+    pub fn os(&self) -> &OS<'a> {
+        self.os.get_or_init(|| match self.whole() {
+            Some(client) => {
+                let _ = self.device.set(client.device);
+                let _ = self.parsed_user_agent.set(client.user_agent);
+                client.os
+            }
+            None => self.parser.parse_os_borrowed(self.user_agent),
+        })
+    }
+
+    #[must_use]
+    pub fn user_agent(&self) -> &UserAgent<'a> {
+        self.parsed_user_agent.get_or_init(|| match self.whole() {
+            Some(client) => {
+                let _ = self.device.set(client.device);
+                let _ = self.os.set(client.os);
+                client.user_agent
+            }
+            None => self.parser.parse_user_agent_borrowed(self.user_agent),
+        })
+    }
+
+    /// Parses whatever hasn't been accessed yet, giving the `Client` that
+    /// `parse` would have
+    #[must_use]
+    pub fn into_client(self) -> Client<'a> {
+        let untouched = self.device.get().is_none()
+            && self.os.get().is_none()
+            && self.parsed_user_agent.get().is_none();
+        if let Some(client) = untouched.then(|| self.whole()).flatten() {
+            return client;
+        }
+        let (parser, user_agent) = (self.parser, self.user_agent);
+        Client {
+            device: self
+                .device
+                .into_inner()
+                .unwrap_or_else(|| parser.parse_device_borrowed(user_agent)),
+            os: self
+                .os
+                .into_inner()
+                .unwrap_or_else(|| parser.parse_os_borrowed(user_agent)),
+            user_agent: self
+                .parsed_user_agent
+                .into_inner()
+                .unwrap_or_else(|| parser.parse_user_agent_borrowed(user_agent)),
+        }
+    }
+
+    /// The whole client, for parsers whose results only come whole, with
+    /// which the first access fills every category. `None` when categories
+    /// parse on their own.
+    fn whole(&self) -> Option<Client<'a>> {
+        if self.parser.post_processors.is_empty() && self.parser.slow_parse.is_none() {
+            None
+        } else {
+            Some(self.parser.parse(self.user_agent))
+        }
+    }
+}
+
+impl UserAgentParser {
+    /// Parses each of the `Device`, `OS` and `UserAgent` only when first
+    /// asked for, so callers that look at one category don't pay for the
+    /// others. Borrows from the parser like `parse_borrowed`.
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+    /// let lazy = parser.parse_lazy("curl/8.4.0");
+    ///
+    /// assert_eq!(lazy.user_agent().family, "curl");
+    /// assert_eq!(lazy.into_client(), parser.parse("curl/8.4.0"));
+    /// ```
+    #[must_use]
+    pub fn parse_lazy<'a>(&'a self, user_agent: &'a str) -> LazyClient<'a> {
+        LazyClient {
+            parser: self,
+            user_agent,
+            device: OnceCell::new(),
+            os: OnceCell::new(),
+            parsed_user_agent: OnceCell::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::*;
+    use crate::{FamilyAliases, Position, SELF_TEST_CASES};
+
+    /// A device sub-parser that matches nothing and counts its calls
+    struct Counting(Arc<AtomicUsize>);
+
+    impl<'a> SubParser<'a> for Counting {
+        type Item = Device<'a>;
+
+        fn try_parse(&self, _: &'a str) -> Option<Device<'a>> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            None
+        }
+    }
+
+    fn parser() -> UserAgentParser {
+        UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed")
+    }
+
+    #[test]
+    fn lazy_clients_are_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<LazyClient<'_>>();
+    }
+
+    #[test]
+    fn parses_only_what_is_accessed() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut parser = parser();
+        parser.register_device_subparser(
+            Box::new(Counting(Arc::clone(&calls))),
+            Position::Before,
+        );
+        let lazy = parser.parse_lazy(SELF_TEST_CASES[3].user_agent);
+
+        assert_eq!(lazy.user_agent().family, "Chrome Mobile");
+        assert_eq!(lazy.os().family, "Android");
+        assert_eq!(calls.load(Ordering::Relaxed), 0);
+
+        assert_eq!(lazy.device().brand.as_deref(), Some("Samsung"));
+        let _ = lazy.device();
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn into_client_matches_parse() {
+        let parser = parser();
+        for user_agent in SELF_TEST_CASES
+            .iter()
+            .map(|case| case.user_agent)
+            .chain(["", "totally-unknown-agent"])
+        {
+            let lazy = parser.parse_lazy(user_agent);
+            let _ = lazy.os();
+            assert_eq!(lazy.into_client(), parser.parse(user_agent));
+        }
+    }
+
+    #[test]
+    fn post_processors_see_whole_clients() {
+        let mut parser = parser();
+        parser.add_post_processor(FamilyAliases::new().alias("Chrome Mobile", "Chrome"));
+        let user_agent = SELF_TEST_CASES[3].user_agent;
+        let lazy = parser.parse_lazy(user_agent);
+
+        assert_eq!(lazy.user_agent().family, "Chrome");
+        assert_eq!(lazy.into_client(), parser.parse(user_agent));
+    }
+}
@@ -0,0 +1,206 @@
+use super::*;
+
+/// Where a rule found by `UserAgentParser::rules_for_family` gets its family
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FamilyOrigin {
+    /// The replacement is the family, with no group references
+    Static,
+    /// The family is built from what the rule captures, through group
+    /// references in the replacement or as capture group 1 without one, so
+    /// the rule could produce it
+    Dynamic,
+}
+
+/// A rule that can produce a family, from `UserAgentParser::rules_for_family`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RuleSummary<'p> {
+    pub category: Categories,
+    /// The rule's position in the order its category's rules are tried
+    pub index: usize,
+    pub pattern: &'p str,
+    /// The family replacement, `None` when the family is capture group 1
+    pub replacement: Option<&'p str>,
+    pub origin: FamilyOrigin,
+}
+
+impl UserAgentParser {
+    /// The rules of `category`, one of `Categories::DEVICE`, `OS` and
+    /// `USER_AGENT`, that can produce `family`, such as to track down the
+    /// rule behind an unexpected family. Rules whose replacement is `family`
+    /// come first, followed by every rule with a dynamic family, as any of
+    /// those could produce it too; both in the order the rules are tried.
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+    /// let rules = parser.rules_for_family(Categories::USER_AGENT, "firefox mobile", true);
+    ///
+    /// assert_eq!(rules[0].replacement, Some("Firefox Mobile"));
+    /// assert_eq!(rules[0].origin, FamilyOrigin::Static);
+    /// ```
+    #[must_use]
+    pub fn rules_for_family(
+        &self,
+        category: Categories,
+        family: &str,
+        ignore_case: bool,
+    ) -> Vec<RuleSummary<'_>> {
+        let replacements: Vec<(&str, Option<&str>, bool)> =
+            if category == Categories::DEVICE {
+                self.device_matchers
+                    .iter()
+                    .map(|matcher| {
+                        (
+                            matcher.pattern(),
+                            matcher.device_replacement(),
+                            matcher.device_replacement_has_group(),
+                        )
+                    })
+                    .collect()
+            } else if category == Categories::OS {
+                self.os_matchers
+                    .iter()
+                    .map(|matcher| {
+                        (
+                            matcher.pattern(),
+                            matcher.os_replacement(),
+                            matcher.os_replacement_has_group(),
+                        )
+                    })
+                    .collect()
+            } else if category == Categories::USER_AGENT {
+                self.user_agent_matchers
+                    .iter()
+                    .map(|matcher| {
+                        (
+                            matcher.pattern(),
+                            matcher.family_replacement(),
+                            matcher.family_replacement_has_group(),
+                        )
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+        let is_family = |replacement: &str| {
+            if ignore_case {
+                replacement.to_lowercase() == family.to_lowercase()
+            } else {
+                replacement == family
+            }
+        };
+        let summaries = replacements.into_iter().enumerate().filter_map(
+            |(index, (pattern, replacement, has_group))| {
+                let origin = match replacement {
+                    Some(_) if has_group => FamilyOrigin::Dynamic,
+                    Some(replacement) if is_family(replacement) => FamilyOrigin::Static,
+                    Some(_) => return None,
+                    None => FamilyOrigin::Dynamic,
+                };
+                Some(RuleSummary {
+                    category,
+                    index,
+                    pattern,
+                    replacement,
+                    origin,
+                })
+            },
+        );
+        let (mut rules, dynamic): (Vec<_>, Vec<_>) =
+            summaries.partition(|rule| rule.origin == FamilyOrigin::Static);
+        rules.extend(dynamic);
+        rules
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &[u8] = br"
+user_agent_parsers:
+  - regex: '(Firefox)/(\d+)'
+  - regex: 'Fennec/(\d+)'
+    family_replacement: 'Firefox'
+  - regex: 'Iceweasel/(\d+)'
+    family_replacement: 'Firefox ($1)'
+  - regex: 'FxiOS/(\d+)'
+    family_replacement: 'Firefox iOS'
+  - regex: 'Mobile.*Firefox/(\d+)'
+    family_replacement: 'firefox'
+os_parsers:
+  - regex: 'Windows NT'
+    os_replacement: 'Windows'
+device_parsers: []
+";
+
+    #[test]
+    fn static_rules_come_before_dynamic_ones() {
+        let parser = UserAgentParser::from_bytes(RULES).expect("Parser creation failed");
+
+        let rules = parser.rules_for_family(Categories::USER_AGENT, "Firefox", false);
+        let found: Vec<_> = rules.iter().map(|rule| (rule.index, rule.origin)).collect();
+        assert_eq!(
+            found,
+            [
+                (1, FamilyOrigin::Static),
+                (0, FamilyOrigin::Dynamic),
+                (2, FamilyOrigin::Dynamic),
+            ]
+        );
+        assert_eq!(rules[0].pattern, r"Fennec/(\d+)");
+        assert_eq!(rules[1].replacement, None);
+
+        let ignoring_case =
+            parser.rules_for_family(Categories::USER_AGENT, "FIREFOX", true);
+        assert_eq!(
+            ignoring_case
+                .iter()
+                .filter(|rule| rule.origin == FamilyOrigin::Static)
+                .map(|rule| rule.index)
+                .collect::<Vec<_>>(),
+            [1, 4]
+        );
+
+        let made_up = parser.rules_for_family(Categories::USER_AGENT, "Iron Fox", false);
+        assert!(made_up
+            .iter()
+            .all(|rule| rule.origin == FamilyOrigin::Dynamic));
+        assert_eq!(made_up.len(), 2);
+
+        assert!(parser
+            .rules_for_family(Categories::OS, "Linux", false)
+            .is_empty());
+        assert_eq!(
+            parser.rules_for_family(Categories::OS, "Windows", false)[0].category,
+            Categories::OS
+        );
+        assert!(parser
+            .rules_for_family(Categories::ALL, "Firefox", false)
+            .is_empty());
+    }
+
+    #[test]
+    fn stock_rules_name_their_families() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+
+        let rules =
+            parser.rules_for_family(Categories::USER_AGENT, "Firefox Mobile", false);
+        let fixed: Vec<_> = rules
+            .iter()
+            .take_while(|rule| rule.origin == FamilyOrigin::Static)
+            .collect();
+        assert!(!fixed.is_empty());
+        assert!(fixed
+            .iter()
+            .all(|rule| rule.replacement == Some("Firefox Mobile")));
+        assert!(rules.iter().any(|rule| rule.replacement.is_none()));
+
+        let device = parser.rules_for_family(Categories::DEVICE, "Spider", false);
+        assert!(device
+            .iter()
+            .any(|rule| rule.origin == FamilyOrigin::Static));
+    }
+}
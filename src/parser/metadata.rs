@@ -0,0 +1,185 @@
+use super::*;
+
+/// A parsed `Client` along with the rules that produced each part, from
+/// `UserAgentParser::parse_with_metadata`. A part is `None` when no rule
+/// matched or a sub-parser gave the result.
+#[derive(Clone, Debug)]
+pub struct ClientMetadata<'a, 'p> {
+    pub client: Client<'a>,
+    pub device: Option<&'p DeviceMatcher>,
+    pub os: Option<&'p OSMatcher>,
+    pub user_agent: Option<&'p UserAgentMatcher>,
+}
+
+impl<'p> ClientMetadata<'_, 'p> {
+    /// Copies any borrowed fields of the client, as `Client::into_owned`
+    #[must_use]
+    pub fn into_owned(self) -> ClientMetadata<'static, 'p> {
+        ClientMetadata {
+            client: self.client.into_owned(),
+            device: self.device,
+            os: self.os,
+            user_agent: self.user_agent,
+        }
+    }
+}
+
+impl UserAgentParser {
+    /// Like `parse`, but also returns the rule behind each part, such as to
+    /// read the `extras` a rule file annotated it with. Post-processors don't
+    /// run.
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// let parser = UserAgentParser::from_bytes(br"
+    /// device_parsers:
+    ///   - regex: '(SM-T\d+)'
+    ///     brand_replacement: 'Samsung'
+    ///     device_type: 'tablet'
+    /// ").unwrap();
+    /// let metadata = parser.parse_with_metadata("Mozilla/5.0 (Linux; Android 13; SM-T510)");
+    ///
+    /// let device_type = metadata.device.and_then(|rule| rule.extras().get("device_type"));
+    /// assert_eq!(device_type.and_then(|value| value.as_str()), Some("tablet"));
+    /// ```
+    #[must_use]
+    pub fn parse_with_metadata<'a>(&self, user_agent: &'a str) -> ClientMetadata<'a, '_> {
+        normalized!(self, user_agent, self.match_with_metadata(user_agent))
+    }
+
+    fn match_with_metadata<'a>(&self, user_agent: &'a str) -> ClientMetadata<'a, '_> {
+        if self.skips(user_agent) {
+            return ClientMetadata {
+                client: Client::default(),
+                device: None,
+                os: None,
+                user_agent: None,
+            };
+        }
+        let (device, device_rule) = select(
+            self.match_strategies.device,
+            self.sub_parsers.device.around(
+                self.budgeted(&self.device_matchers)
+                    .iter()
+                    .filter_map(|matcher| {
+                        Some((matcher.try_parse(user_agent)?, Some(matcher)))
+                    }),
+                |parser| Some((parser.try_parse(user_agent)?, None)),
+            ),
+            |(item, _)| device_specificity(item),
+        )
+        .unwrap_or_default();
+        let (os, os_rule) = select(
+            self.match_strategies.os,
+            self.sub_parsers.os.around(
+                self.budgeted(&self.os_matchers)
+                    .iter()
+                    .filter_map(|matcher| {
+                        Some((matcher.try_parse(user_agent)?, Some(matcher)))
+                    }),
+                |parser| Some((parser.try_parse(user_agent)?, None)),
+            ),
+            |(item, _)| os_specificity(item),
+        )
+        .unwrap_or_default();
+        let os = self.darwin_os(user_agent, os);
+        let (user_agent, user_agent_rule) =
+            select(
+                self.match_strategies.user_agent,
+                self.sub_parsers.user_agent.around(
+                    self.budgeted(&self.user_agent_matchers).iter().filter_map(
+                        |matcher| Some((matcher.try_parse(user_agent)?, Some(matcher))),
+                    ),
+                    |parser| Some((parser.try_parse(user_agent)?, None)),
+                ),
+                |(item, _)| user_agent_specificity(item),
+            )
+            .unwrap_or_default();
+
+        ClientMetadata {
+            client: Client {
+                device,
+                os,
+                user_agent,
+            },
+            device: device_rule,
+            os: os_rule,
+            user_agent: user_agent_rule,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANNOTATED_RULES: &[u8] = br"
+user_agent_parsers:
+  - regex: '(Chrome)/(\d+)'
+    owner: 'team-x'
+os_parsers:
+  - regex: '(Android) (\d+)'
+device_parsers:
+  - regex: '(SM-T\d+)'
+    brand_replacement: 'Samsung'
+    device_type: 'tablet'
+    tags: ['galaxy', 'tab']
+";
+
+    #[test]
+    fn metadata_exposes_rule_extras() {
+        let parser =
+            UserAgentParser::from_bytes(ANNOTATED_RULES).expect("Parser creation failed");
+        let user_agent = "Mozilla/5.0 (Linux; Android 13; SM-T510) Chrome/120.0.0.0";
+        let metadata = parser.parse_with_metadata(user_agent);
+
+        assert_eq!(metadata.client, parser.parse(user_agent));
+        let device = metadata.device.expect("No device rule");
+        assert_eq!(device.extras()["device_type"].as_str(), Some("tablet"));
+        assert_eq!(
+            device.extras()["tags"],
+            serde_yaml::from_str::<serde_yaml::Value>("[galaxy, tab]").unwrap()
+        );
+        assert!(!device.extras().contains_key("brand_replacement"));
+        assert!(metadata.os.expect("No OS rule").extras().is_empty());
+        let user_agent = metadata.user_agent.expect("No user agent rule");
+        assert_eq!(user_agent.extras()["owner"].as_str(), Some("team-x"));
+
+        let cached = serde_yaml::to_string(&parser).expect("Serialization failed");
+        let restored: UserAgentParser =
+            serde_yaml::from_str(&cached).expect("Deserialization failed");
+        assert_eq!(restored.device_matchers()[0].extras(), device.extras());
+        assert_eq!(
+            restored.to_regex_file().0.device_parsers[0].extras,
+            *device.extras()
+        );
+
+        let unmatched = parser.parse_with_metadata("curl/8.4.0");
+        assert!(unmatched.device.is_none() && unmatched.user_agent.is_none());
+        assert_eq!(unmatched.client, Client::default());
+    }
+
+    #[test]
+    fn files_without_extras_are_unchanged() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        assert!(parser
+            .device_matchers()
+            .iter()
+            .all(|matcher| matcher.extras().is_empty()));
+        assert!(!serde_yaml::to_string(&parser)
+            .expect("Serialization failed")
+            .contains("extras"));
+
+        let metadata = parser.parse_with_metadata("curl/8.4.0");
+        assert_eq!(metadata.client, parser.parse("curl/8.4.0"));
+        assert_eq!(
+            metadata.user_agent.map(UserAgentMatcher::pattern),
+            parser
+                .user_agent_matchers()
+                .iter()
+                .find(|matcher| matcher.try_parse("curl/8.4.0").is_some())
+                .map(UserAgentMatcher::pattern)
+        );
+    }
+}
@@ -1,80 +1,614 @@
-use std::borrow::Cow;
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+use std::{
+    borrow::Cow, cmp::Reverse, collections::BTreeMap, fmt, ops::Range, time::Instant,
+};
 
 use derive_more::{Display, From};
 use regex::Regex;
 
+use buffer::*;
+
 use super::{
     client::Client,
     device::Device,
-    file::{DeviceParserEntry, OSParserEntry, RegexFile, UserAgentParserEntry},
+    extras::darwin_os,
+    file::{
+        read_value, validate_strict, DeviceParserEntry, OSParserEntry, RegexFile,
+        UserAgentParserEntry, ValidationError, YamlError,
+    },
+    headers::{
+        app_context, effective_device_ua, DeviceUaOverride, HeaderClient,
+        HeaderValuePolicy, UaHeaderPolicy,
+    },
+    normalize::{extract_ua, maybe_percent_decode, InputNormalization},
     os::OS,
     parser::{
         device::Error as DeviceError, os::Error as OSError,
         user_agent::Error as UserAgentError,
     },
+    post_process::{PostProcessor, PostProcessors},
+    spans::{ClientSpans, DeviceSpans, OSSpans, UserAgentSpans},
     user_agent::UserAgent,
     Parser, SubParser,
 };
 
+/// Runs `$parse` with `$user_agent` rebound to the normalized user agent,
+/// copying the result when normalization had to allocate
+macro_rules! normalized {
+    ($parser:expr, $user_agent:ident, $parse:expr) => {
+        match $parser.normalized_input($user_agent) {
+            Cow::Borrowed($user_agent) => $parse,
+            Cow::Owned(normalized) => {
+                let $user_agent = normalized.as_str();
+                $parse.into_owned()
+            }
+        }
+    };
+}
+
+mod adaptive;
+#[cfg(feature = "rkyv")]
+mod archive;
+mod background;
+mod buffer;
+mod builder;
+mod cache;
+mod custom;
+mod delta;
 mod device;
+mod export;
+mod inline_tests;
+mod interned;
+mod lazy;
+mod lookup;
+mod metadata;
 mod os;
+mod preprocess;
+mod prune;
+mod quality;
+mod self_test;
+mod slow;
+mod source;
+mod tenant;
+mod thread_cache;
 mod user_agent;
 
+pub use adaptive::{AdaptiveParser, ReorderMode};
+#[cfg(feature = "rkyv")]
+pub use archive::{ArchivedRules, Rules};
+pub use background::{BackgroundParser, WhileCompiling};
+pub use buffer::ClientBuf;
+pub use builder::{Categories, MatchStrategies, MatchStrategy, UserAgentParserBuilder};
+pub use cache::{CacheMismatch, CACHE_FORMAT_VERSION};
+pub use custom::{
+    DeviceSubParser, OSSubParser, Position, SubParsers, UserAgentSubParser,
+};
+pub use delta::{ApplyReport, DeltaMismatch};
+pub use device::Matcher as DeviceMatcher;
+pub use export::ExportWarning;
+pub use inline_tests::{validate, InlineTestMiss, InlineTestReport, InlineTestResult};
+pub use lazy::LazyClient;
+pub use lookup::{FamilyOrigin, RuleSummary};
+pub use metadata::ClientMetadata;
+pub use os::Matcher as OSMatcher;
+pub use preprocess::{PatternPreprocessor, PreprocessMode};
+pub use prune::{ParserStats, PruneReport};
+pub use quality::{MatchQuality, ScoredClient};
+pub use self_test::{SelfTestCase, SelfTestFailure, SelfTestMiss, SELF_TEST_CASES};
+pub use slow::{SlowParseEvent, SlowParseHook};
+pub use source::{RuleCounts, SourceInfo};
+pub use tenant::{SharedBaseParser, TenantParser};
+pub use thread_cache::{ThreadCacheStats, ThreadLocalCachingParser};
+pub use user_agent::Matcher as UserAgentMatcher;
+
+/// The rules embedded by the `mini-regexes` feature
+#[cfg(feature = "mini-regexes")]
+const MINI_REGEXES: &[u8] = include_bytes!("../../data/regexes_mini.yaml");
+
 #[derive(Debug, Display, From)]
 pub enum Error {
     IO(std::io::Error),
-    Yaml(serde_yaml::Error),
+    Yaml(YamlError),
     Device(DeviceError),
     OS(OSError),
     UserAgent(UserAgentError),
+    Validation(ValidationError),
+    IncompatibleCache(CacheMismatch),
+    Delta(DeltaMismatch),
+    InlineTests(InlineTestReport),
+    /// The thread compiling a `BackgroundParser` panicked, with the panic's
+    /// message
+    #[display(fmt = "rule compilation panicked: {_0}")]
+    #[from(ignore)]
+    CompilePanicked(String),
+    #[cfg(feature = "rkyv")]
+    Archive(rkyv::rancor::Error),
 }
 
 /// Handles the actual parsing of a user agent string by delegating to
 /// the respective `SubParser`
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+///
+/// Empty and whitespace-only user agents skip the rules and parse as the
+/// default `Client`, with every family `Other`, unless the parser was built
+/// with `UserAgentParserBuilder::match_blank`. Post-processors still apply.
+///
+/// Parsing doesn't panic on any input, however long or malformed, and
+/// neither does loading rules or caches, which report bad input as an
+/// `Error`. Custom sub-parsers, post-processors and slow parse callbacks are
+/// outside that guarantee.
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct UserAgentParser {
-    pub device_matchers: Vec<device::Matcher>,
-    pub os_matchers: Vec<os::Matcher>,
-    pub user_agent_matchers: Vec<user_agent::Matcher>,
+    device_matchers: Vec<device::Matcher>,
+    os_matchers: Vec<os::Matcher>,
+    user_agent_matchers: Vec<user_agent::Matcher>,
+    #[serde(default)]
+    match_strategies: MatchStrategies,
+    #[serde(skip)]
+    post_processors: PostProcessors,
+    /// Registered with `register_device_subparser` and its siblings. Custom
+    /// sub-parsers aren't serialized, so they must be registered again on a
+    /// deserialized parser.
+    #[serde(skip)]
+    sub_parsers: SubParsers,
+    #[serde(default)]
+    source_info: SourceInfo,
+    /// Runs the rules against empty and whitespace-only user agents, which
+    /// otherwise get the default result without any matching
+    #[serde(default)]
+    match_blank: bool,
+    /// Applied to every user agent before matching
+    #[serde(default)]
+    normalize_input: InputNormalization,
+    /// Decodes user agents that look percent-encoded before matching, ahead
+    /// of `normalize_input`
+    #[serde(default)]
+    percent_decode: bool,
+    /// The categories whose rules were compiled, as set with
+    /// `UserAgentParserBuilder::categories`
+    #[serde(default)]
+    categories: Categories,
+    /// How many matchers of each category a parse evaluates at most before
+    /// giving up on that category, as set with
+    /// `UserAgentParserBuilder::max_matchers_per_category`. Unlimited when
+    /// `None`.
+    #[serde(default)]
+    max_matchers_per_category: Option<usize>,
+    /// Reads the OS of `CFNetwork` user agents off their Darwin version when
+    /// the rules give no OS version, as set with
+    /// `UserAgentParserBuilder::darwin_fallback`
+    #[serde(default)]
+    darwin_fallback: bool,
+    /// How `parse_header_pairs` uses the user agent of the handset behind a
+    /// proxy browser, as set with `UserAgentParserBuilder::device_ua_override`
+    #[serde(default)]
+    device_ua_override: DeviceUaOverride,
+    /// The headers `parse_header_pairs` reads the user agent from, as set
+    /// with `UserAgentParserBuilder::ua_header_policy`
+    #[serde(default)]
+    ua_header_policy: UaHeaderPolicy,
+    /// Which of several user agents in the headers `parse_header_pairs`
+    /// reads, as set with `UserAgentParserBuilder::header_value_policy`
+    #[serde(default)]
+    header_value_policy: HeaderValuePolicy,
+    /// Set with `UserAgentParserBuilder::on_slow_parse`
+    #[serde(skip)]
+    slow_parse: Option<SlowParseHook>,
+}
+
+/// Formats as a one line summary of the rules, such as
+/// `UserAgentParser(uap-core sha256:3c4f… (620 device, 179 OS, 344 user agent rules))`,
+/// or with `{:#?}` as every field including each matcher
+impl fmt::Debug for UserAgentParser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !f.alternate() {
+            return write!(f, "UserAgentParser({})", self.source_info);
+        }
+
+        f.debug_struct("UserAgentParser")
+            .field("device_matchers", &self.device_matchers)
+            .field("os_matchers", &self.os_matchers)
+            .field("user_agent_matchers", &self.user_agent_matchers)
+            .field("match_strategies", &self.match_strategies)
+            .field("post_processors", &self.post_processors)
+            .field("sub_parsers", &self.sub_parsers)
+            .field("source_info", &self.source_info)
+            .field("match_blank", &self.match_blank)
+            .field("normalize_input", &self.normalize_input)
+            .field("percent_decode", &self.percent_decode)
+            .field("categories", &self.categories)
+            .field("max_matchers_per_category", &self.max_matchers_per_category)
+            .field("darwin_fallback", &self.darwin_fallback)
+            .field("device_ua_override", &self.device_ua_override)
+            .field("ua_header_policy", &self.ua_header_policy)
+            .field("header_value_policy", &self.header_value_policy)
+            .field("slow_parse", &self.slow_parse)
+            .finish()
+    }
 }
 
 impl Parser for UserAgentParser {
     /// Returns the full `Client` info when given a user agent string
     fn parse<'a>(&self, user_agent: &'a str) -> Client<'a> {
-        Client {
-            device: self.parse_device(user_agent),
-            os: self.parse_os(user_agent),
-            user_agent: self.parse_user_agent(user_agent),
-        }
+        self.timed(user_agent, || self.parse_untimed(user_agent))
     }
 
     /// Returns just the `Device` info when given a user agent string
     fn parse_device<'a>(&self, user_agent: &'a str) -> Device<'a> {
-        self.device_matchers
-            .iter()
-            .find_map(|matcher| matcher.try_parse(user_agent))
-            .unwrap_or_default()
+        normalized!(self, user_agent, self.match_device(user_agent))
     }
 
     /// Returns just the `OS` info when given a user agent string
     fn parse_os<'a>(&self, user_agent: &'a str) -> OS<'a> {
-        self.os_matchers
-            .iter()
-            .find_map(|matcher| matcher.try_parse(user_agent))
-            .unwrap_or_default()
+        normalized!(self, user_agent, self.match_os(user_agent))
     }
 
     /// Returns just the `UserAgent` info when given a user agent string
     fn parse_user_agent<'a>(&self, user_agent: &'a str) -> UserAgent<'a> {
-        self.user_agent_matchers
-            .iter()
-            .find_map(|matcher| matcher.try_parse(user_agent))
-            .unwrap_or_default()
+        normalized!(self, user_agent, self.match_user_agent(user_agent))
     }
 }
 
 impl UserAgentParser {
-    /// Attempts to construct a `UserAgentParser` from the path to a file
+    /// Returns the full `Client` info when given a user agent string, borrowing
+    /// any replacement text that has no capture groups from the parser itself
+    /// rather than allocating a copy of it. The result can't outlive either the
+    /// parser or the user agent string.
+    ///
+    /// ```rust
+    /// # use std::borrow::Cow;
+    /// # use uaparser::*;
+    /// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+    /// let client = parser.parse_borrowed(
+    ///     "Mozilla/5.0 (Linux; Android 13; SM-S901B) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36",
+    /// );
+    ///
+    /// assert!(matches!(client.device.brand, Some(Cow::Borrowed("Samsung"))));
+    /// ```
+    #[must_use]
+    pub fn parse_borrowed<'a>(&'a self, user_agent: &'a str) -> Client<'a> {
+        let mut client = normalized!(
+            self,
+            user_agent,
+            Client {
+                device: self.match_device_borrowed(user_agent),
+                os: self.match_os_borrowed(user_agent),
+                user_agent: self.match_user_agent_borrowed(user_agent),
+            }
+        );
+        self.post_processors.apply(&mut client);
+        client
+    }
+
+    /// Parses into reusable buffers, so a loop over many user agents doesn't
+    /// allocate a result for each. The buffer reads as `parse` would have
+    /// returned until the next call. Parsers with post-processors, custom
+    /// sub-parsers, a `MostSpecific` strategy, `darwin_fallback` or a slow
+    /// parse hook, and user agents that normalization rewrites, take the
+    /// usual path and copy its result into the buffer.
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+    /// let mut buf = ClientBuf::new();
+    /// parser.parse_into("curl/8.4.0", &mut buf);
+    ///
+    /// assert_eq!(buf.ua_family(), "curl");
+    /// assert_eq!(buf.ua_major(), Some("8"));
+    /// ```
+    pub fn parse_into(&self, user_agent: &str, out: &mut ClientBuf) {
+        let plain = self.post_processors.is_empty()
+            && self.sub_parsers.is_empty()
+            && self.match_strategies == MatchStrategies::default()
+            && !self.darwin_fallback
+            && self.slow_parse.is_none();
+        let text = match self.normalized_input(user_agent) {
+            Cow::Borrowed(text) if plain => text,
+            _ => return out.fill_from(&self.parse(user_agent)),
+        };
+
+        out.reset();
+        if self.skips(text) {
+            return;
+        }
+        out.fill_first(0, self.budgeted(&self.device_matchers), text);
+        out.fill_first(1, self.budgeted(&self.os_matchers), text);
+        out.fill_first(2, self.budgeted(&self.user_agent_matchers), text);
+    }
+
+    /// Parses text that may hold more than a user agent, such as a header
+    /// line pasted from a HAR file, after cleaning it up with
+    /// `normalize::extract_ua`. Clean user agents parse as with `parse`.
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+    ///
+    /// assert_eq!(
+    ///     parser.parse_lenient("User-Agent: \"curl/8.4.0\""),
+    ///     parser.parse("curl/8.4.0")
+    /// );
+    /// ```
+    #[must_use]
+    pub fn parse_lenient<'a>(&self, input: &'a str) -> Client<'a> {
+        self.parse(extract_ua(input))
+    }
+
+    /// Parses the `User-Agent` value from `(name, value)` header pairs, as
+    /// exposed by Lambda events or Kafka message headers, and ignores every
+    /// other header. The name is matched in any case and, by default, the
+    /// first user agent wins, as in `headers::find_user_agent`. A
+    /// `header_value_policy` can pick the last or longest of the user agents
+    /// in folded values and repeated headers instead. Without one, the result
+    /// is the default `Client`.
+    ///
+    /// A `ua_header_policy` can prefer other headers, such as the
+    /// `X-Original-User-Agent` a proxy keeps the client's user agent in.
+    /// Their values are read and normalized as the `User-Agent` is.
+    ///
+    /// With a `device_ua_override` set, the user agent of the handset behind
+    /// a proxy browser such as Opera Mini, from `headers::effective_device_ua`,
+    /// is parsed for the device or in full.
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+    /// let headers = [("Accept", "*/*"), ("user-agent", "curl/8.4.0")];
+    /// let client = parser.parse_header_pairs(headers.iter().copied());
+    ///
+    /// assert_eq!(client.user_agent.family, "curl");
+    /// ```
+    #[must_use]
+    pub fn parse_header_pairs<'a>(
+        &self,
+        headers: impl Iterator<Item = (&'a str, &'a str)>,
+    ) -> Client<'a> {
+        self.parse_headers(headers).0
+    }
+
+    /// Like `parse_header_pairs`, and also reads the Android app that sent
+    /// the request from its `X-Requested-With` header, as
+    /// `headers::app_context`, and records the header the user agent was
+    /// read from
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+    /// let headers = [
+    ///     ("User-Agent", "Mozilla/5.0 (Linux; Android 14; Pixel 8; wv) AppleWebKit/537.36"),
+    ///     ("X-Requested-With", "com.instagram.android"),
+    /// ];
+    /// let parsed = parser.parse_header_pairs_with_app(headers.iter().copied());
+    ///
+    /// assert_eq!(parsed.app.and_then(|app| app.name), Some("Instagram"));
+    /// ```
+    #[must_use]
+    pub fn parse_header_pairs_with_app<'a>(
+        &self,
+        headers: impl Iterator<Item = (&'a str, &'a str)>,
+    ) -> HeaderClient<'a> {
+        let headers: Vec<_> = headers.collect();
+        let (client, source) = self.parse_headers(headers.iter().copied());
+        HeaderClient {
+            client,
+            app: app_context(headers.into_iter()),
+            source,
+        }
+    }
+
+    /// The client of `parse_header_pairs`, and the name of the header its
+    /// user agent came from
+    fn parse_headers<'a>(
+        &self,
+        headers: impl Iterator<Item = (&'a str, &'a str)>,
+    ) -> (Client<'a>, Option<&'a str>) {
+        let mut device_ua = None;
+        let found = if self.device_ua_override == DeviceUaOverride::Ignore {
+            self.ua_header_policy
+                .find_with(headers, self.header_value_policy)
+        } else {
+            let headers: Vec<_> = headers.collect();
+            device_ua = effective_device_ua(headers.iter().copied());
+            self.ua_header_policy
+                .find_with(headers.into_iter(), self.header_value_policy)
+        };
+        let user_agent = found.map_or("", |(_, value)| value);
+        let client = match device_ua {
+            None => self.parse(user_agent),
+            Some(device_ua) if self.device_ua_override == DeviceUaOverride::Full => {
+                self.parse(device_ua)
+            }
+            Some(device_ua) => self.timed(user_agent, || {
+                let mut client = normalized!(
+                    self,
+                    user_agent,
+                    Client {
+                        device: Device::default(),
+                        os: self.match_os(user_agent),
+                        user_agent: self.match_user_agent(user_agent),
+                    }
+                );
+                client.device = self.parse_device(device_ua);
+                self.post_processors.apply(&mut client);
+                client
+            }),
+        };
+        (client, found.map(|(name, _)| name))
+    }
+
+    /// Registers a hook that rewrites every `Client` returned by `parse` and
+    /// `parse_borrowed`. Hooks run in registration order after the regex
+    /// rules; the `parse_device`, `parse_os` and `parse_user_agent` methods
+    /// and `parse_with_spans` report the unmodified rule output.
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// let mut parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+    /// parser.add_post_processor(FamilyAliases::new().alias("Chrome Mobile WebView", "Chrome"));
+    /// parser.add_post_processor(CapitalizeBrand);
+    /// ```
+    pub fn add_post_processor(&mut self, processor: impl PostProcessor + 'static) {
+        self.post_processors.push(Box::new(processor));
+    }
+
+    /// Returns just the `Device` info, borrowing from the parser where possible
+    #[must_use]
+    pub fn parse_device_borrowed<'a>(&'a self, user_agent: &'a str) -> Device<'a> {
+        normalized!(self, user_agent, self.match_device_borrowed(user_agent))
+    }
+
+    /// Returns just the `OS` info, borrowing from the parser where possible
+    #[must_use]
+    pub fn parse_os_borrowed<'a>(&'a self, user_agent: &'a str) -> OS<'a> {
+        normalized!(self, user_agent, self.match_os_borrowed(user_agent))
+    }
+
+    /// Returns just the `UserAgent` info, borrowing from the parser where
+    /// possible
+    #[must_use]
+    pub fn parse_user_agent_borrowed<'a>(&'a self, user_agent: &'a str) -> UserAgent<'a> {
+        normalized!(self, user_agent, self.match_user_agent_borrowed(user_agent))
+    }
+
+    /// Returns the full `Client` info along with the byte range of the user
+    /// agent string that each captured field was taken from. With input
+    /// normalization on, the ranges are into `normalized_input`.
+    #[must_use]
+    pub fn parse_with_spans<'a>(&self, user_agent: &'a str) -> ClientSpans<'a> {
+        normalized!(self, user_agent, self.match_with_spans(user_agent))
+    }
+
+    /// The device rules, in the order they are tried
+    #[must_use]
+    pub fn device_matchers(&self) -> &[DeviceMatcher] {
+        &self.device_matchers
+    }
+
+    /// The OS rules, in the order they are tried
+    #[must_use]
+    pub fn os_matchers(&self) -> &[OSMatcher] {
+        &self.os_matchers
+    }
+
+    /// The user agent rules, in the order they are tried
+    #[must_use]
+    pub fn user_agent_matchers(&self) -> &[UserAgentMatcher] {
+        &self.user_agent_matchers
+    }
+
+    /// Returns a builder for configuring how the parser matches rules
+    #[must_use]
+    pub fn builder() -> UserAgentParserBuilder {
+        UserAgentParserBuilder::default()
+    }
+
+    /// Describes the rule set this parser was built from. Include it when
+    /// logging a misclassification to pin down the exact rules involved.
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// let parser = UserAgentParser::builder()
+    ///     .with_source_label("uap-core")
+    ///     .build_from_yaml("./src/core/regexes.yaml")
+    ///     .expect("Parser creation failed");
+    ///
+    /// assert_eq!(parser.source_info().label.as_deref(), Some("uap-core"));
+    /// assert!(parser.source_info().content_hash.is_some());
+    /// ```
+    #[must_use]
+    pub fn source_info(&self) -> &SourceInfo {
+        &self.source_info
+    }
+
+    /// The match strategy of each category, as set with
+    /// `UserAgentParserBuilder::match_strategies`
+    #[must_use]
+    pub fn match_strategies(&self) -> MatchStrategies {
+        self.match_strategies
+    }
+
+    /// Whether blank user agents are matched against the rules, as set with
+    /// `UserAgentParserBuilder::match_blank`
+    #[must_use]
+    pub fn match_blank(&self) -> bool {
+        self.match_blank
+    }
+
+    /// As set with `UserAgentParserBuilder::normalize_input`
+    #[must_use]
+    pub fn normalize_input(&self) -> InputNormalization {
+        self.normalize_input
+    }
+
+    /// As set with `UserAgentParserBuilder::percent_decode`
+    #[must_use]
+    pub fn percent_decode(&self) -> bool {
+        self.percent_decode
+    }
+
+    /// The categories whose rules were compiled, as set with
+    /// `UserAgentParserBuilder::categories`
+    #[must_use]
+    pub fn categories(&self) -> Categories {
+        self.categories
+    }
+
+    /// As set with `UserAgentParserBuilder::max_matchers_per_category`
+    #[must_use]
+    pub fn max_matchers_per_category(&self) -> Option<usize> {
+        self.max_matchers_per_category
+    }
+
+    /// As set with `UserAgentParserBuilder::darwin_fallback`
+    #[must_use]
+    pub fn darwin_fallback(&self) -> bool {
+        self.darwin_fallback
+    }
+
+    /// As set with `UserAgentParserBuilder::device_ua_override`
+    #[must_use]
+    pub fn device_ua_override(&self) -> DeviceUaOverride {
+        self.device_ua_override
+    }
+
+    /// As set with `UserAgentParserBuilder::ua_header_policy`
+    #[must_use]
+    pub fn ua_header_policy(&self) -> &UaHeaderPolicy {
+        &self.ua_header_policy
+    }
+
+    /// As set with `UserAgentParserBuilder::header_value_policy`
+    #[must_use]
+    pub fn header_value_policy(&self) -> HeaderValuePolicy {
+        self.header_value_policy
+    }
+
+    /// The string the rules are matched against, which is `user_agent`
+    /// itself unless the parser was built with
+    /// `UserAgentParserBuilder::normalize_input` or
+    /// `UserAgentParserBuilder::percent_decode`. Spans from
+    /// `parse_with_spans` index into this string.
+    #[must_use]
+    pub fn normalized_input<'a>(&self, user_agent: &'a str) -> Cow<'a, str> {
+        let decoded = if self.percent_decode {
+            maybe_percent_decode(user_agent)
+        } else {
+            Cow::Borrowed(user_agent)
+        };
+        match decoded {
+            Cow::Borrowed(decoded) => self.normalize_input.apply(decoded),
+            Cow::Owned(decoded) => match self.normalize_input.apply(&decoded) {
+                Cow::Borrowed(normalized) if normalized.len() == decoded.len() => {
+                    Cow::Owned(decoded)
+                }
+                normalized => Cow::Owned(normalized.into_owned()),
+            },
+        }
+    }
+
+    /// Attempts to construct a `UserAgentParser` from the path to a file. Not
+    /// available on WebAssembly targets, where `from_bytes` takes its place.
+    #[cfg(not(target_family = "wasm"))]
     pub fn from_yaml(path: &str) -> Result<UserAgentParser, Error> {
         let file = std::fs::File::open(path)?;
         UserAgentParser::from_file(file)
@@ -91,42 +625,443 @@ impl UserAgentParser {
     /// let parser = UserAgentParser::from_bytes(regexes);
     /// ```
     pub fn from_bytes(bytes: &[u8]) -> Result<UserAgentParser, Error> {
-        let regex_file: RegexFile = serde_yaml::from_slice(bytes)?;
-        UserAgentParser::try_from(regex_file)
+        UserAgentParser::builder().build_from_bytes(bytes)
+    }
+
+    /// Builds a parser from the subset of the rules embedded by the
+    /// `mini-regexes` feature, at about an eighth of the size of the full
+    /// set. It covers Chrome, Safari, Firefox, Edge and Samsung Internet,
+    /// Windows, macOS, iOS, Android, Linux and Chrome OS, and crawlers, all
+    /// parsed exactly as the full rules would. Anything else, including most
+    /// device models beyond Apple hardware and the generic phone and tablet
+    /// classes, parses as `Other` or as the nearest covered family, such as
+    /// Opera as Chrome.
+    ///
+    /// The subset is generated from `src/core/regexes.yaml` by
+    /// `scripts/generate_mini_regexes.py`, and should be regenerated whenever
+    /// the full rules are updated.
+    ///
+    /// # Panics
+    ///
+    /// Never for the embedded rules, which are checked by the tests
+    #[cfg(feature = "mini-regexes")]
+    #[allow(clippy::expect_used)]
+    #[must_use]
+    pub fn new_mini() -> UserAgentParser {
+        UserAgentParser::builder()
+            .with_source_label("uap-core mini subset")
+            .build_from_bytes(MINI_REGEXES)
+            .expect("Embedded mini rules failed to load")
+    }
+
+    /// Like `from_bytes`, but rejects rule files with keys that aren't part
+    /// of the format, such as a misspelled `device_replacement`, or entries
+    /// without a `regex`. Lenient parsing ignores unknown keys so newer
+    /// uap-core releases keep loading.
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// let overlay = b"
+    /// user_agent_parsers: []
+    /// os_parsers: []
+    /// device_parsers:
+    ///   - regex: 'FooPhone'
+    ///     device_replacemnt: 'Foo Phone'
+    /// ";
+    ///
+    /// assert!(UserAgentParser::from_bytes(overlay).is_ok());
+    /// assert!(matches!(
+    ///     UserAgentParser::from_bytes_strict(overlay),
+    ///     Err(Error::Validation(_))
+    /// ));
+    /// ```
+    pub fn from_bytes_strict(bytes: &[u8]) -> Result<UserAgentParser, Error> {
+        UserAgentParser::builder()
+            .strict(true)
+            .build_from_bytes(bytes)
     }
 
     /// Attempts to construct a `UserAgentParser` from a reference to an open
     /// `File`. This `File` should be a the `regexes.yaml` depended on by
     /// all the various implementations of the UA Parser library.
+    #[cfg(not(target_family = "wasm"))]
     pub fn from_file(file: std::fs::File) -> Result<UserAgentParser, Error> {
-        let regex_file: RegexFile = serde_yaml::from_reader(file)?;
-        UserAgentParser::try_from(regex_file)
+        UserAgentParser::builder().build_from_file(file)
     }
 
     pub fn try_from(regex_file: RegexFile) -> Result<UserAgentParser, Error> {
+        UserAgentParser::compile(regex_file, true)
+    }
+
+    /// Compiles every entry, cleaning up escapes `regex` rejects when `clean`
+    fn compile(regex_file: RegexFile, clean: bool) -> Result<UserAgentParser, Error> {
         let mut device_matchers = Vec::with_capacity(regex_file.device_parsers.len());
         let mut os_matchers = Vec::with_capacity(regex_file.os_parsers.len());
         let mut user_agent_matchers =
             Vec::with_capacity(regex_file.user_agent_parsers.len());
 
         for parser in regex_file.device_parsers {
-            device_matchers.push(device::Matcher::try_from(parser)?);
+            device_matchers.push(device::Matcher::compile(parser, clean)?);
         }
 
         for parser in regex_file.os_parsers {
-            os_matchers.push(os::Matcher::try_from(parser)?);
+            os_matchers.push(os::Matcher::compile(parser, clean)?);
         }
 
         for parser in regex_file.user_agent_parsers {
-            user_agent_matchers.push(user_agent::Matcher::try_from(parser)?);
+            user_agent_matchers.push(user_agent::Matcher::compile(parser, clean)?);
         }
 
-        Ok(UserAgentParser {
+        // Stable sorts, so rules of equal priority keep their order in the file
+        device_matchers.sort_by_key(|matcher| Reverse(matcher.priority));
+        os_matchers.sort_by_key(|matcher| Reverse(matcher.priority));
+        user_agent_matchers.sort_by_key(|matcher| Reverse(matcher.priority));
+
+        Ok(UserAgentParser::from_matchers(
+            device_matchers,
+            os_matchers,
+            user_agent_matchers,
+        ))
+    }
+
+    /// Assembles a parser with the default settings from compiled matchers
+    /// that are already in priority order, such as matchers built with
+    /// `DeviceMatcher::try_from` from rule file entries
+    #[must_use]
+    pub fn from_matchers(
+        device_matchers: Vec<device::Matcher>,
+        os_matchers: Vec<os::Matcher>,
+        user_agent_matchers: Vec<user_agent::Matcher>,
+    ) -> UserAgentParser {
+        let source_info = SourceInfo::new(RuleCounts {
+            device: device_matchers.len(),
+            os: os_matchers.len(),
+            user_agent: user_agent_matchers.len(),
+        });
+
+        UserAgentParser {
             device_matchers,
             os_matchers,
             user_agent_matchers,
-        })
+            match_strategies: MatchStrategies::default(),
+            post_processors: PostProcessors::default(),
+            sub_parsers: SubParsers::default(),
+            source_info,
+            match_blank: false,
+            normalize_input: InputNormalization::Off,
+            percent_decode: false,
+            categories: Categories::ALL,
+            max_matchers_per_category: None,
+            darwin_fallback: false,
+            device_ua_override: DeviceUaOverride::Ignore,
+            ua_header_policy: UaHeaderPolicy::default(),
+            header_value_policy: HeaderValuePolicy::UseFirst,
+            slow_parse: None,
+        }
+    }
+}
+
+impl UserAgentParser {
+    /// Runs `parse` under the slow parse hook, if any, which reports the
+    /// client as parsed from `user_agent`
+    fn timed<'a>(
+        &self,
+        user_agent: &str,
+        parse: impl FnOnce() -> Client<'a>,
+    ) -> Client<'a> {
+        let Some(hook) = &self.slow_parse else {
+            return parse();
+        };
+        let start = Instant::now();
+        let client = parse();
+        hook.observe(start.elapsed(), user_agent, &client);
+        client
+    }
+
+    fn parse_untimed<'a>(&self, user_agent: &'a str) -> Client<'a> {
+        let mut client = normalized!(
+            self,
+            user_agent,
+            Client {
+                device: self.match_device(user_agent),
+                os: self.match_os(user_agent),
+                user_agent: self.match_user_agent(user_agent),
+            }
+        );
+        self.post_processors.apply(&mut client);
+        client
+    }
+
+    /// Whether `user_agent` is empty or whitespace-only, and so gets the
+    /// default result without running any rules
+    fn skips(&self, user_agent: &str) -> bool {
+        !self.match_blank && user_agent.trim().is_empty()
+    }
+
+    /// The matchers of a category that a parse may evaluate, as limited by
+    /// `max_matchers_per_category`
+    fn budgeted<'m, M>(&self, matchers: &'m [M]) -> &'m [M] {
+        match self.max_matchers_per_category {
+            Some(max) if max < matchers.len() => &matchers[..max],
+            _ => matchers,
+        }
     }
+
+    /// `os`, or the OS `extras::darwin_os` reads off the user agent when
+    /// `darwin_fallback` is set and the rules gave no version
+    fn darwin_os<'a>(&self, user_agent: &str, os: OS<'a>) -> OS<'a> {
+        if !self.darwin_fallback || os.major.is_some() {
+            return os;
+        }
+        darwin_os(user_agent).unwrap_or(os)
+    }
+
+    fn match_device<'a>(&self, user_agent: &'a str) -> Device<'a> {
+        if self.skips(user_agent) {
+            return Device::default();
+        }
+        select(
+            self.match_strategies.device,
+            self.sub_parsers.device.around(
+                self.budgeted(&self.device_matchers)
+                    .iter()
+                    .filter_map(|matcher| matcher.try_parse(user_agent)),
+                |parser| parser.try_parse(user_agent),
+            ),
+            device_specificity,
+        )
+        .unwrap_or_default()
+    }
+
+    fn match_os<'a>(&self, user_agent: &'a str) -> OS<'a> {
+        if self.skips(user_agent) {
+            return OS::default();
+        }
+        let os = select(
+            self.match_strategies.os,
+            self.sub_parsers.os.around(
+                self.budgeted(&self.os_matchers)
+                    .iter()
+                    .filter_map(|matcher| matcher.try_parse(user_agent)),
+                |parser| parser.try_parse(user_agent),
+            ),
+            os_specificity,
+        )
+        .unwrap_or_default();
+        self.darwin_os(user_agent, os)
+    }
+
+    fn match_user_agent<'a>(&self, user_agent: &'a str) -> UserAgent<'a> {
+        if self.skips(user_agent) {
+            return UserAgent::default();
+        }
+        select(
+            self.match_strategies.user_agent,
+            self.sub_parsers.user_agent.around(
+                self.budgeted(&self.user_agent_matchers)
+                    .iter()
+                    .filter_map(|matcher| matcher.try_parse(user_agent)),
+                |parser| parser.try_parse(user_agent),
+            ),
+            user_agent_specificity,
+        )
+        .unwrap_or_default()
+    }
+
+    fn match_device_borrowed<'a>(&'a self, user_agent: &'a str) -> Device<'a> {
+        if self.skips(user_agent) {
+            return Device::default();
+        }
+        select(
+            self.match_strategies.device,
+            self.sub_parsers.device.around(
+                self.budgeted(&self.device_matchers)
+                    .iter()
+                    .filter_map(|matcher| matcher.try_parse_borrowed(user_agent)),
+                |parser| parser.try_parse(user_agent),
+            ),
+            device_specificity,
+        )
+        .unwrap_or_default()
+    }
+
+    fn match_os_borrowed<'a>(&'a self, user_agent: &'a str) -> OS<'a> {
+        if self.skips(user_agent) {
+            return OS::default();
+        }
+        let os = select(
+            self.match_strategies.os,
+            self.sub_parsers.os.around(
+                self.budgeted(&self.os_matchers)
+                    .iter()
+                    .filter_map(|matcher| matcher.try_parse_borrowed(user_agent)),
+                |parser| parser.try_parse(user_agent),
+            ),
+            os_specificity,
+        )
+        .unwrap_or_default();
+        self.darwin_os(user_agent, os)
+    }
+
+    fn match_user_agent_borrowed<'a>(&'a self, user_agent: &'a str) -> UserAgent<'a> {
+        if self.skips(user_agent) {
+            return UserAgent::default();
+        }
+        select(
+            self.match_strategies.user_agent,
+            self.sub_parsers.user_agent.around(
+                self.budgeted(&self.user_agent_matchers)
+                    .iter()
+                    .filter_map(|matcher| matcher.try_parse_borrowed(user_agent)),
+                |parser| parser.try_parse(user_agent),
+            ),
+            user_agent_specificity,
+        )
+        .unwrap_or_default()
+    }
+
+    fn match_with_spans<'a>(&self, user_agent: &'a str) -> ClientSpans<'a> {
+        if self.skips(user_agent) {
+            return ClientSpans::default();
+        }
+        let (device, device_spans) = select(
+            self.match_strategies.device,
+            self.sub_parsers.device.around(
+                self.budgeted(&self.device_matchers)
+                    .iter()
+                    .filter_map(|matcher| matcher.try_parse_with_spans(user_agent)),
+                |parser| {
+                    parser
+                        .try_parse(user_agent)
+                        .map(|item| (item, DeviceSpans::default()))
+                },
+            ),
+            |(item, _)| device_specificity(item),
+        )
+        .unwrap_or_default();
+        let (os, os_spans) = select(
+            self.match_strategies.os,
+            self.sub_parsers.os.around(
+                self.budgeted(&self.os_matchers)
+                    .iter()
+                    .filter_map(|matcher| matcher.try_parse_with_spans(user_agent)),
+                |parser| {
+                    parser
+                        .try_parse(user_agent)
+                        .map(|item| (item, OSSpans::default()))
+                },
+            ),
+            |(item, _)| os_specificity(item),
+        )
+        .unwrap_or_default();
+        let os = self.darwin_os(user_agent, os);
+        let (user_agent, user_agent_spans) = select(
+            self.match_strategies.user_agent,
+            self.sub_parsers.user_agent.around(
+                self.budgeted(&self.user_agent_matchers)
+                    .iter()
+                    .filter_map(|matcher| matcher.try_parse_with_spans(user_agent)),
+                |parser| {
+                    parser
+                        .try_parse(user_agent)
+                        .map(|item| (item, UserAgentSpans::default()))
+                },
+            ),
+            |(item, _)| user_agent_specificity(item),
+        )
+        .unwrap_or_default();
+
+        ClientSpans {
+            client: Client {
+                device,
+                os,
+                user_agent,
+            },
+            device: device_spans,
+            os: os_spans,
+            user_agent: user_agent_spans,
+        }
+    }
+}
+
+/// Picks a result from the matching rules, in rule order, according to
+/// `strategy`. Only `MatchStrategy::MostSpecific` looks past the first match.
+fn select<T>(
+    strategy: MatchStrategy,
+    mut candidates: impl Iterator<Item = T>,
+    specificity: impl Fn(&T) -> usize,
+) -> Option<T> {
+    match strategy {
+        MatchStrategy::First => candidates.next(),
+        MatchStrategy::MostSpecific => {
+            let mut best: Option<(usize, T)> = None;
+            for candidate in candidates {
+                let score = specificity(&candidate);
+                match &best {
+                    Some((best_score, _)) if *best_score >= score => {}
+                    _ => best = Some((score, candidate)),
+                }
+            }
+            best.map(|(_, candidate)| candidate)
+        }
+    }
+}
+
+fn device_specificity(device: &Device<'_>) -> usize {
+    1 + usize::from(device.brand.is_some()) + usize::from(device.model.is_some())
+}
+
+fn os_specificity(os: &OS<'_>) -> usize {
+    1 + [&os.major, &os.minor, &os.patch, &os.patch_minor]
+        .iter()
+        .filter(|part| part.is_some())
+        .count()
+}
+
+fn user_agent_specificity(user_agent: &UserAgent<'_>) -> usize {
+    1 + [&user_agent.major, &user_agent.minor, &user_agent.patch]
+        .iter()
+        .filter(|part| part.is_some())
+        .count()
+}
+
+/// Records which of a matcher's replacement templates reference capture
+/// groups, packed one bit per replacement in the order the matcher lists them
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct GroupFlags(u8);
+
+impl GroupFlags {
+    fn from_replacements(replacements: &[Option<&str>]) -> GroupFlags {
+        let mut flags = 0;
+        for (bit, replacement) in replacements.iter().enumerate() {
+            if replacement.is_some_and(has_group) {
+                flags |= 1 << bit;
+            }
+        }
+        GroupFlags(flags)
+    }
+
+    /// Whether the replacement at `index` contains a `$` group reference
+    #[inline]
+    #[must_use]
+    pub fn contains(self, index: usize) -> bool {
+        self.0 & (1 << index) != 0
+    }
+}
+
+/// The unknown keys of a rule file entry
+type Extras = BTreeMap<String, serde_yaml::Value>;
+
+/// What `extras` returns for rules without any
+static NO_EXTRAS: Extras = BTreeMap::new();
+
+fn into_extras(extras: Extras) -> Option<Box<Extras>> {
+    (!extras.is_empty()).then(|| Box::new(extras))
+}
+
+#[inline]
+fn into_boxed(replacement: Option<String>) -> Option<Box<str>> {
+    replacement.map(String::into_boxed_str)
 }
 
 #[inline]
@@ -144,24 +1079,1184 @@ pub(self) fn has_group(replacement: &str) -> bool {
 }
 
 #[inline]
-pub(self) fn replace_cow<'a>(
-    replacement: &str,
+pub(self) fn replace_cow<'a, 'm>(
+    replacement: &'m str,
     replacement_has_group: bool,
     captures: &regex::Captures,
+    literal: fn(&'m str) -> Cow<'a, str>,
 ) -> Cow<'a, str> {
     if replacement_has_group && captures.len() > 0 {
         let mut target = String::with_capacity(31);
         captures.expand(replacement, &mut target);
-        Cow::Owned(target.trim().to_owned())
+        intern(target.trim())
     } else {
-        Cow::Owned(replacement.to_owned())
+        literal(replacement)
     }
 }
 
-lazy_static::lazy_static! {
-    static ref INVALID_ESCAPES: Regex = Regex::new("\\\\([! /])").unwrap();
+#[inline]
+fn capture_span(captures: &regex::Captures, index: usize) -> Option<Range<usize>> {
+    captures
+        .get(index)
+        .filter(|x| !x.as_str().is_empty())
+        .map(|x| x.range())
+}
+
+/// Borrows the `'static` copy of `value` when it is one of the common
+/// replacement strings from the stock rule file, and allocates otherwise
+#[inline]
+fn intern<'a>(value: &str) -> Cow<'a, str> {
+    match interned::INTERNED.binary_search(&value) {
+        Ok(index) => Cow::Borrowed(interned::INTERNED[index]),
+        Err(_) => Cow::Owned(value.to_owned()),
+    }
 }
 
+/// Drops the backslash from `\!`, `\ ` and `\/`, escapes other regex engines
+/// accept but `regex` rejects. Hand-rolled rather than a regex replace, so
+/// there's no pattern to compile and nothing that can fail.
 fn clean_escapes(pattern: &str) -> Cow<'_, str> {
-    INVALID_ESCAPES.replace_all(pattern, "$1")
+    let is_invalid = |pair: &[u8]| matches!(pair, [b'\\', b'!' | b' ' | b'/']);
+    if !pattern.as_bytes().windows(2).any(is_invalid) {
+        return Cow::Borrowed(pattern);
+    }
+
+    let mut cleaned = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        if !(c == '\\' && matches!(chars.peek(), Some('!' | ' ' | '/'))) {
+            cleaned.push(c);
+        }
+    }
+    Cow::Owned(cleaned)
+}
+
+/// Compiles a rule's pattern, dropping the escapes other regex engines
+/// accept but `regex` rejects, with room for the largest uap-core rules
+pub(crate) fn compile_rule(pattern: &str) -> Result<Regex, regex::Error> {
+    compile_cleaned(&clean_escapes(pattern))
+}
+
+/// Compiles a rule's pattern, cleaned up with `compile_rule` when `clean`
+fn compile_pattern(pattern: &str, clean: bool) -> Result<Regex, regex::Error> {
+    if clean {
+        compile_rule(pattern)
+    } else {
+        compile_cleaned(pattern)
+    }
+}
+
+/// Compiles a pattern that `compile_rule` already cleaned up, as stored in
+/// serialized parsers
+fn compile_cleaned(pattern: &str) -> Result<Regex, regex::Error> {
+    regex::RegexBuilder::new(pattern)
+        .size_limit(20 * (1 << 20))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        iter,
+        mem::size_of,
+        panic::{self, AssertUnwindSafe},
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use serde::Deserialize;
+
+    use crate::file::EntryLocation;
+
+    #[test]
+    fn matchers_are_compact() {
+        // With `Option<String>` replacements and one `bool` per flag these
+        // measured 136 (OS), 112 (device) and 136 (user agent) bytes on
+        // x86_64; boxed replacements and packed flags bring them to 104, 88
+        // and 104 while leaving no spare capacity behind the replacements.
+        // User agents have since gained a fourth version replacement, and
+        // every matcher a pointer to the rule's extras.
+        let replacement = size_of::<Option<Box<str>>>();
+        let regex = size_of::<Regex>();
+        let extras = size_of::<Option<Box<Extras>>>();
+
+        assert!(size_of::<os::Matcher>() <= regex + 4 * replacement + extras + 8);
+        assert!(size_of::<device::Matcher>() <= regex + 3 * replacement + extras + 8);
+        assert!(size_of::<user_agent::Matcher>() <= regex + 5 * replacement + extras + 8);
+        assert!(replacement < size_of::<Option<String>>());
+    }
+
+    #[test]
+    fn interned_table_is_sorted() {
+        assert!(interned::INTERNED.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn common_replacements_are_borrowed() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let client = parser.parse(
+            "Mozilla/5.0 (Linux; Android 13; SM-S901B) AppleWebKit/537.36 \
+             (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36",
+        );
+
+        assert_eq!(client.user_agent.family, "Chrome Mobile");
+        assert!(matches!(client.user_agent.family, Cow::Borrowed(_)));
+        assert_eq!(client.device.brand.as_deref(), Some("Samsung"));
+        assert!(matches!(client.device.brand, Some(Cow::Borrowed(_))));
+    }
+
+    #[test]
+    fn uncommon_replacements_are_owned() {
+        let regexes = br"
+user_agent_parsers:
+  - regex: '(Acme)Browser/(\d+)'
+    family_replacement: '$1 Navigator'
+os_parsers: []
+device_parsers: []
+";
+        let parser =
+            UserAgentParser::from_bytes(regexes).expect("Parser creation failed");
+        let user_agent = parser.parse_user_agent("AcmeBrowser/3");
+
+        assert_eq!(user_agent.family, "Acme Navigator");
+        assert!(matches!(user_agent.family, Cow::Owned(_)));
+    }
+
+    #[test]
+    fn borrowed_parse_borrows_static_replacements() {
+        let regexes = br"
+user_agent_parsers: []
+os_parsers: []
+device_parsers:
+  - regex: 'Acme-(\w+)'
+    device_replacement: 'Acme $1'
+    brand_replacement: 'Acme Corp'
+    model_replacement: '$1'
+";
+        let parser =
+            UserAgentParser::from_bytes(regexes).expect("Parser creation failed");
+        let user_agent = "Mozilla/5.0 (Acme-X100)";
+
+        let device = parser.parse_device_borrowed(user_agent);
+        assert!(matches!(device.brand, Some(Cow::Borrowed("Acme Corp"))));
+        assert_eq!(device.family, "Acme X100");
+        assert_eq!(device.model.as_deref(), Some("X100"));
+
+        let borrowed_brand = device.brand.as_deref().map(str::as_ptr);
+        let replacement = parser.device_matchers()[0].brand_replacement();
+        assert_eq!(borrowed_brand, replacement.map(str::as_ptr));
+
+        assert_eq!(device, parser.parse_device(user_agent));
+        assert!(matches!(
+            parser.parse_device(user_agent).brand,
+            Some(Cow::Owned(_))
+        ));
+    }
+
+    #[test]
+    fn borrowed_parse_matches_parse() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let user_agent = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1 like Mac OS X) \
+                          AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1 \
+                          Mobile/15E148 Safari/604.1";
+
+        assert_eq!(parser.parse_borrowed(user_agent), parser.parse(user_agent));
+    }
+
+    #[test]
+    fn spans_cover_captured_fields() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let user_agent = "Mozilla/5.0 (X11; Ubuntu; Linux x86_64; rv:115.0) \
+                          Gecko/20100101 Firefox/115.0";
+        let parsed = parser.parse_with_spans(user_agent);
+
+        assert_eq!(parsed.client, parser.parse(user_agent));
+
+        let family = parsed.user_agent.family.expect("family span");
+        assert_eq!(&user_agent[family], "Firefox");
+        let major = parsed.user_agent.major.expect("major span");
+        assert_eq!(&user_agent[major], "115");
+        let minor = parsed.user_agent.minor.expect("minor span");
+        assert_eq!(&user_agent[minor], "0");
+        assert_eq!(parsed.user_agent.patch, None);
+
+        let os_family = parsed.os.family.expect("os family span");
+        assert_eq!(&user_agent[os_family], "Ubuntu");
+    }
+
+    #[test]
+    fn fourth_version_component_is_patch_minor() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let chrome = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                      (KHTML, like Gecko) Chrome/120.0.6099.129 Safari/537.36";
+        let firefox = "Mozilla/5.0 (X11; Ubuntu; Linux x86_64; rv:115.0) \
+                       Gecko/20100101 Firefox/115.0.2";
+
+        let user_agent = parser.parse_user_agent(chrome);
+        assert_eq!(user_agent.family, "Chrome");
+        assert_eq!(
+            [
+                user_agent.major.as_deref(),
+                user_agent.minor.as_deref(),
+                user_agent.patch.as_deref(),
+                user_agent.patch_minor.as_deref(),
+            ],
+            [Some("120"), Some("0"), Some("6099"), Some("129")]
+        );
+        let span = parser.parse_with_spans(chrome).user_agent.patch_minor;
+        assert_eq!(span.map(|x| &chrome[x]), Some("129"));
+
+        let user_agent = parser.parse_user_agent(firefox);
+        assert_eq!(user_agent.patch.as_deref(), Some("2"));
+        assert_eq!(user_agent.patch_minor, None);
+    }
+
+    #[test]
+    fn patch_minor_is_serialized_only_when_present() {
+        let mut user_agent = UserAgent {
+            family: Cow::Borrowed("Chrome"),
+            major: Some(Cow::Borrowed("120")),
+            ..UserAgent::default()
+        };
+        let yaml = serde_yaml::to_string(&user_agent).expect("Serialization failed");
+        assert!(!yaml.contains("patch_minor"));
+
+        user_agent.patch_minor = Some(Cow::Borrowed("129"));
+        let yaml = serde_yaml::to_string(&user_agent).expect("Serialization failed");
+        assert!(yaml.contains("patch_minor:"));
+        let parsed = UserAgent::deserialize(serde_yaml::Deserializer::from_str(&yaml))
+            .expect("Deserialization failed");
+        assert_eq!(parsed, user_agent);
+    }
+
+    #[test]
+    fn v4_replacement_sets_patch_minor() {
+        let regexes = br"
+user_agent_parsers:
+  - regex: '(Acme)Browser/(\d+)\.(\d+)\.(\d+)\.(\d+)'
+    v4_replacement: '0'
+os_parsers: []
+device_parsers: []
+";
+        let parser =
+            UserAgentParser::from_bytes_strict(regexes).expect("Parser creation failed");
+        let parsed = parser.parse_with_spans("AcmeBrowser/3.1.4.77");
+
+        assert_eq!(parsed.client.user_agent.patch.as_deref(), Some("4"));
+        assert_eq!(parsed.client.user_agent.patch_minor.as_deref(), Some("0"));
+        assert_eq!(parsed.user_agent.patch_minor, None);
+    }
+
+    #[test]
+    fn replaced_fields_have_no_span() {
+        let regexes = br"
+user_agent_parsers: []
+os_parsers: []
+device_parsers:
+  - regex: 'Acme-(\w+)'
+    device_replacement: 'Acme $1'
+    brand_replacement: 'Acme Corp'
+";
+        let parser =
+            UserAgentParser::from_bytes(regexes).expect("Parser creation failed");
+        let user_agent = "Mozilla/5.0 (Acme-X100)";
+        let parsed = parser.parse_with_spans(user_agent);
+
+        assert_eq!(parsed.client.device.family, "Acme X100");
+        assert_eq!(parsed.device.family, None);
+        assert_eq!(parsed.device.brand, None);
+        assert_eq!(parsed.device.model.map(|x| &user_agent[x]), Some("X100"));
+        assert_eq!(parsed.os, OSSpans::default());
+    }
+
+    #[test]
+    fn group_flags_follow_replacements() {
+        let flags = GroupFlags::from_replacements(&[Some("$1"), None, Some("Samsung")]);
+
+        assert!(flags.contains(0));
+        assert!(!flags.contains(1));
+        assert!(!flags.contains(2));
+    }
+
+    #[test]
+    fn priority_overrides_file_order() {
+        let parser = UserAgentParser::from_bytes(
+            br"
+user_agent_parsers:
+  - regex: '(Widget)/(\d+)'
+  - regex: '(Widget)/(\d+)'
+    family_replacement: 'Internal Widget'
+    priority: 10
+  - regex: '(Widget)'
+    family_replacement: 'Low Widget'
+    priority: -1
+os_parsers:
+  - regex: '(Gadget OS) (\d+)'
+  - regex: 'Gadget OS (\d+)'
+    os_replacement: 'Gadget'
+    os_v1_replacement: '$1'
+    priority: 5
+device_parsers:
+  - regex: '(Gizmo)'
+  - regex: 'Gizmo'
+    device_replacement: 'First'
+    priority: 2
+  - regex: 'Gizmo'
+    device_replacement: 'Second'
+    priority: 2
+",
+        )
+        .expect("Parser creation failed");
+        let client = parser.parse("Widget/3 (Gadget OS 7; Gizmo)");
+
+        assert_eq!(client.user_agent.family, "Internal Widget");
+        assert_eq!(client.user_agent.major.as_deref(), Some("3"));
+        assert_eq!(client.os.family, "Gadget");
+        assert_eq!(client.os.major.as_deref(), Some("7"));
+        assert_eq!(client.device.family, "First");
+        assert_eq!(
+            parser
+                .user_agent_matchers()
+                .iter()
+                .map(UserAgentMatcher::priority)
+                .collect::<Vec<_>>(),
+            [10, 0, -1]
+        );
+    }
+
+    const GALAXY: &str = "Mozilla/5.0 (Linux; Android 13; SM-S901B) AppleWebKit/537.36 \
+                          (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36";
+
+    const GENERIC_ANDROID: &str = r"
+  - regex: 'Android [\d.]+; ([^;)]+)'
+    device_replacement: 'Generic $1'";
+
+    const SAMSUNG: &str = r"
+  - regex: '; (SM-[A-Z0-9]+)'
+    device_replacement: 'Samsung $1'
+    brand_replacement: 'Samsung'
+    model_replacement: '$1'";
+
+    fn device_rules(rules: &[&str]) -> String {
+        format!(
+            "user_agent_parsers: []\nos_parsers: []\ndevice_parsers:{}\n",
+            rules.concat()
+        )
+    }
+
+    #[test]
+    fn most_specific_device_wins() {
+        for rules in [[GENERIC_ANDROID, SAMSUNG], [SAMSUNG, GENERIC_ANDROID]] {
+            let parser = UserAgentParser::builder()
+                .device_strategy(MatchStrategy::MostSpecific)
+                .build_from_bytes(device_rules(&rules).as_bytes())
+                .expect("Parser creation failed");
+            let device = parser.parse_device(GALAXY);
+
+            assert_eq!(device.brand.as_deref(), Some("Samsung"));
+            assert_eq!(device.model.as_deref(), Some("SM-S901B"));
+            assert_eq!(parser.parse_device_borrowed(GALAXY), device);
+            assert_eq!(parser.parse_with_spans(GALAXY).client.device, device);
+        }
+    }
+
+    #[test]
+    fn first_match_follows_rule_order() {
+        let parser = UserAgentParser::builder()
+            .build_from_bytes(device_rules(&[GENERIC_ANDROID, SAMSUNG]).as_bytes())
+            .expect("Parser creation failed");
+
+        assert_eq!(parser.match_strategies.device, MatchStrategy::First);
+        assert_eq!(parser.parse_device(GALAXY).family, "Generic SM-S901B");
+        assert_eq!(parser.parse_device(GALAXY).brand, None);
+    }
+
+    #[test]
+    fn most_specific_ties_keep_rule_order() {
+        let parser = UserAgentParser::builder()
+            .os_strategy(MatchStrategy::MostSpecific)
+            .build_from_bytes(
+                br"
+user_agent_parsers: []
+device_parsers: []
+os_parsers:
+  - regex: '(Android) (\d+)'
+  - regex: 'Android (\d+)'
+    os_replacement: 'Android Go'
+    os_v1_replacement: '$1'
+  - regex: '(Android) (\d+)\.(\d+)'
+",
+            )
+            .expect("Parser creation failed");
+
+        assert_eq!(parser.parse_os("Android 13").family, "Android");
+        assert_eq!(parser.parse_os("Android 13.1").minor.as_deref(), Some("1"));
+    }
+
+    const SOURCE_RULES: &[u8] = br"
+user_agent_parsers:
+  - regex: '(Foo)/(\d+)'
+os_parsers:
+  - regex: '(Bar) (\d+)'
+device_parsers: []
+";
+
+    #[test]
+    fn content_hash_tracks_bytes() {
+        let parser =
+            UserAgentParser::from_bytes(SOURCE_RULES).expect("Parser creation failed");
+        let mut changed = SOURCE_RULES.to_vec();
+        let index = changed.iter().position(|&byte| byte == b'F').unwrap();
+        changed[index] = b'G';
+        let changed =
+            UserAgentParser::from_bytes(&changed).expect("Parser creation failed");
+
+        let info = parser.source_info();
+        assert_eq!(info.label, None);
+        assert_eq!(
+            info.rule_counts,
+            RuleCounts {
+                device: 0,
+                os: 1,
+                user_agent: 1,
+            }
+        );
+        assert!(info.content_hash.is_some());
+        assert_ne!(info.content_hash, changed.source_info().content_hash);
+        assert_eq!(
+            UserAgentParser::from_bytes(SOURCE_RULES)
+                .unwrap()
+                .source_info()
+                .content_hash,
+            info.content_hash
+        );
+    }
+
+    #[test]
+    fn source_info_survives_serialization() {
+        let parser = UserAgentParser::builder()
+            .with_source_label("uap-core 0.18.0 + overrides v7")
+            .build_from_bytes(SOURCE_RULES)
+            .expect("Parser creation failed");
+
+        let cached = serde_yaml::to_string(&parser).expect("Serialization failed");
+        let restored: UserAgentParser =
+            serde_yaml::from_str(&cached).expect("Deserialization failed");
+
+        assert_eq!(restored.source_info(), parser.source_info());
+        assert_eq!(
+            restored.source_info().label.as_deref(),
+            Some("uap-core 0.18.0 + overrides v7")
+        );
+        assert!(format!("{parser:?}").contains("uap-core 0.18.0 + overrides v7"));
+
+        let display = parser.source_info().to_string();
+        assert!(display.starts_with("uap-core 0.18.0 + overrides v7 sha256:"));
+        assert!(display.ends_with(" (0 device, 1 OS, 1 user agent rules)"));
+    }
+
+    #[test]
+    fn strict_mode_rejects_unknown_fields() {
+        let overlay = br"
+user_agent_parsers: []
+os_parsers: []
+device_parsers:
+  - regex: 'BarPhone'
+  - regex: '(FooPhone)'
+    device_replacemnt: 'Foo Phone'
+";
+
+        let lenient =
+            UserAgentParser::from_bytes(overlay).expect("Parser creation failed");
+        assert_eq!(lenient.parse_device("FooPhone").family, "FooPhone");
+
+        let error = UserAgentParser::from_bytes_strict(overlay).unwrap_err();
+        assert!(matches!(
+            &error,
+            Error::Validation(ValidationError::UnknownField {
+                section: "device_parsers",
+                index: 1,
+                key,
+            }) if key == "device_replacemnt"
+        ));
+        assert_eq!(
+            error.to_string(),
+            "device_parsers[1]: unknown field `device_replacemnt`"
+        );
+    }
+
+    #[test]
+    fn strict_mode_checks_sections_and_regexes() {
+        let error = UserAgentParser::from_bytes_strict(
+            br"
+user_agent_parsers:
+  - family_replacement: 'Foo'
+os_parsers: []
+device_parsers: []
+",
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "user_agent_parsers[0]: missing field `regex`"
+        );
+
+        let error = UserAgentParser::from_bytes_strict(
+            br"
+user_agent_parsers: []
+os_parsers: []
+device_parsers: []
+engine_parsers: []
+",
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "unknown section `engine_parsers`");
+    }
+
+    #[test]
+    fn stock_rules_pass_strict_mode() {
+        let regexes = std::fs::read("./src/core/regexes.yaml").unwrap();
+        let parser =
+            UserAgentParser::from_bytes_strict(&regexes).expect("Parser creation failed");
+
+        assert_eq!(
+            parser.source_info().rule_counts,
+            UserAgentParser::from_bytes(&regexes)
+                .unwrap()
+                .source_info()
+                .rule_counts
+        );
+    }
+
+    #[test]
+    fn missing_sections_are_empty() {
+        let parser = UserAgentParser::from_bytes(
+            br"
+device_parsers:
+  - regex: '(FooPhone) (\d+)'
+    device_replacement: '$1 $2'
+    brand_replacement: 'Foo'
+",
+        )
+        .expect("Parser creation failed");
+        let client = parser.parse("Mozilla/5.0 (FooPhone 7) Chrome/120.0.0.0");
+
+        assert_eq!(client.device.family, "FooPhone 7");
+        assert_eq!(client.device.brand.as_deref(), Some("Foo"));
+        assert_eq!(client.os, OS::default());
+        assert_eq!(client.user_agent, UserAgent::default());
+
+        let parser = UserAgentParser::from_bytes_strict(
+            br"
+user_agent_parsers:
+os_parsers: ~
+device_parsers: []
+",
+        )
+        .expect("Parser creation failed");
+        assert_eq!(parser.source_info().rule_counts, RuleCounts::default());
+        assert_eq!(parser.parse("FooPhone 7").device, Device::default());
+    }
+
+    #[test]
+    fn merge_keys_share_fields() {
+        let regexes = include_bytes!("../../tests/fixtures/merged_regexes.yaml");
+        let parser =
+            UserAgentParser::from_bytes(regexes).expect("Parser creation failed");
+
+        let device = parser.parse_device("Mozilla/5.0 (Linux; GT-I9300)");
+        assert_eq!(device.family, "GT-I9300");
+        assert_eq!(device.brand.as_deref(), Some("Samsung"));
+        assert_eq!(device.model.as_deref(), Some("GT-I9300"));
+
+        let device = parser.parse_device("Mozilla/5.0 (Linux; SM-T510)");
+        assert_eq!(device.family, "Galaxy Tab");
+        assert_eq!(device.brand.as_deref(), Some("Samsung Tablets"));
+        assert_eq!(device.model.as_deref(), Some("SM-T510"));
+
+        let device = parser.parse_device("Mozilla/5.0 (Linux; SM-G991B)");
+        assert_eq!(device.family, "Samsung SM-G991B");
+        assert_eq!(device.brand.as_deref(), Some("Samsung"));
+
+        let strict =
+            UserAgentParser::from_bytes_strict(regexes).expect("Parser creation failed");
+        assert_eq!(
+            strict.parse_device("GT-I9300"),
+            parser.parse_device("GT-I9300")
+        );
+
+        let error = UserAgentParser::from_bytes_strict(
+            br"
+device_parsers:
+  - regex: 'Foo'
+    <<: 'Bar'
+",
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "device_parsers[0]: unknown field `<<`");
+    }
+
+    #[test]
+    fn yaml_errors_locate_the_entry() {
+        let error = UserAgentParser::from_bytes(include_bytes!(
+            "../../tests/fixtures/bad_regexes.yaml"
+        ))
+        .unwrap_err();
+        let Error::Yaml(error) = error else {
+            panic!("expected a YAML error, got {}", error);
+        };
+
+        assert_eq!(error.line(), Some(12));
+        assert_eq!(
+            error.entry(),
+            Some(&EntryLocation {
+                section: "device_parsers",
+                index: 1,
+                regex: Some("(BarPhone)".to_owned()),
+            })
+        );
+        let message = error.to_string();
+        assert!(message.contains("line 12"), "{}", message);
+        assert!(
+            message.ends_with("(entry regex `(BarPhone)`)"),
+            "{}",
+            message
+        );
+
+        let error =
+            UserAgentParser::from_bytes(b"device_parsers:\n  - regex: 'a\n").unwrap_err();
+        let Error::Yaml(error) = error else {
+            panic!("expected a YAML error, got {}", error);
+        };
+        assert_eq!(error.entry(), None);
+        assert!(error.line().is_some());
+    }
+
+    const BLANK_RULES: &[u8] = br"
+user_agent_parsers:
+  - regex: '^\s*$'
+    family_replacement: 'Blank'
+os_parsers:
+  - regex: '^\s*$'
+    os_replacement: 'Blank'
+device_parsers:
+  - regex: '^\s*$'
+    device_replacement: 'Blank'
+";
+
+    #[test]
+    fn blank_user_agents_skip_the_rules() {
+        let parser =
+            UserAgentParser::from_bytes(BLANK_RULES).expect("Parser creation failed");
+        let matching = UserAgentParser::builder()
+            .match_blank(true)
+            .build_from_bytes(BLANK_RULES)
+            .expect("Parser creation failed");
+
+        for user_agent in ["", "   ", "\t"] {
+            assert_eq!(parser.parse(user_agent), Client::default());
+            assert_eq!(parser.parse_borrowed(user_agent), Client::default());
+            assert_eq!(parser.parse_with_spans(user_agent), ClientSpans::default());
+
+            let client = matching.parse(user_agent);
+            assert_eq!(client.user_agent.family, "Blank");
+            assert_eq!(client.os.family, "Blank");
+            assert_eq!(client.device.family, "Blank");
+        }
+        assert_eq!(parser.parse(" \t").device.family, "Other");
+    }
+
+    #[test]
+    fn control_characters_parse_as_default() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+
+        for user_agent in ["\0", "\u{1}\u{1f}\u{7f}", "\r\n\0", "\u{85}\u{9f}"] {
+            assert_eq!(parser.parse(user_agent), Client::default());
+            assert_eq!(
+                parser.parse_with_spans(user_agent).client,
+                Client::default()
+            );
+        }
+    }
+
+    #[test]
+    fn strips_control_characters_before_matching() {
+        let parser = UserAgentParser::builder()
+            .normalize_input(InputNormalization::StripControls)
+            .build_from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let clean = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                     (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+        let injected =
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64)\r\n AppleWebKit/537.36 \
+                        (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36\0";
+
+        assert_eq!(parser.parse(injected), parser.parse(clean));
+        assert_eq!(
+            parser.parse_borrowed(injected),
+            parser.parse_borrowed(clean)
+        );
+        assert_eq!(parser.parse_os(injected).family, "Windows");
+        assert_eq!(
+            parser.parse_with_spans(injected),
+            parser.parse_with_spans(clean)
+        );
+        assert_eq!(parser.normalized_input(injected), clean);
+        assert_eq!(parser.parse("\r\n").user_agent.family, "Other");
+    }
+
+    #[test]
+    fn clean_input_stays_borrowed() {
+        let parser = UserAgentParser::builder()
+            .normalize_input(InputNormalization::StripControlsAndTrim)
+            .build_from_bytes(
+                br"
+user_agent_parsers:
+  - regex: '(Foo)/(\d+)'
+os_parsers: []
+device_parsers: []
+",
+            )
+            .expect("Parser creation failed");
+        let user_agent = "Foo/1";
+
+        let Cow::Borrowed(normalized) = parser.normalized_input(user_agent) else {
+            panic!("clean input was copied");
+        };
+        assert_eq!(normalized.as_ptr(), user_agent.as_ptr());
+
+        let client = parser.parse_borrowed(user_agent);
+        let Cow::Borrowed(family) = client.user_agent.family else {
+            panic!("family was copied");
+        };
+        assert_eq!(family.as_ptr(), user_agent.as_ptr());
+
+        let client = parser.parse_borrowed(" Foo/1\r\n");
+        assert!(matches!(client.user_agent.family, Cow::Owned(_)));
+        assert_eq!(client.user_agent.major.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn decodes_percent_encoded_user_agents() {
+        let encoded = "Mozilla%2F5.0%20(Windows%20NT%2010.0%3B%20Win64%3B%20x64)%20\
+                       AppleWebKit%2F537.36%20(KHTML%2C%20like%20Gecko)%20\
+                       Chrome%2F120.0.0.0%20Safari%2F537.36";
+        let decoding = UserAgentParser::builder()
+            .percent_decode(true)
+            .build_from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+
+        let client = decoding.parse(encoded);
+        assert_eq!(client.user_agent.family, "Chrome");
+        assert_eq!(client.user_agent.major.as_deref(), Some("120"));
+        assert_eq!(client.os.family, "Windows");
+
+        assert_eq!(parser.parse(encoded).user_agent.family, "Other");
+    }
+
+    #[test]
+    fn darwin_fallback_fills_in_os_versions() {
+        let app = "MyApp/7.2 CFNetwork/1474 Darwin/23.0.0";
+        let falling_back = UserAgentParser::builder()
+            .darwin_fallback(true)
+            .build_from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+
+        assert_eq!(parser.parse_os(app).major, None);
+        let os = falling_back.parse_os(app);
+        assert_eq!(os.family, "iOS");
+        assert_eq!(os.major.as_deref(), Some("17"));
+        assert_eq!(falling_back.parse(app).os, os);
+        assert_eq!(falling_back.parse_os_borrowed(app), os);
+
+        let versioned = "Pinterest/3356 CFNetwork/808.3 Darwin/16.3.0";
+        assert_eq!(falling_back.parse_os(versioned), parser.parse_os(versioned));
+        assert_eq!(
+            falling_back.parse_os("curl/8.4.0"),
+            parser.parse_os("curl/8.4.0")
+        );
+    }
+
+    #[test]
+    fn darwin_fallback_applies_to_every_os_path() {
+        let app = "MyApp/7.2 CFNetwork/1474 Darwin/23.0.0";
+        let parser = || {
+            UserAgentParser::builder()
+                .darwin_fallback(true)
+                .build_from_yaml("./src/core/regexes.yaml")
+                .expect("Parser creation failed")
+        };
+        let falling_back = parser();
+        let expected = falling_back.parse(app);
+        assert_eq!(expected.os.major.as_deref(), Some("17"));
+
+        assert_eq!(falling_back.parse_scored(app).client, expected);
+        assert_eq!(falling_back.parse_with_spans(app).client, expected);
+        assert_eq!(falling_back.parse_with_metadata(app).client, expected);
+        let adaptive = AdaptiveParser::new(parser());
+        assert_eq!(adaptive.parse(app), expected);
+        adaptive.set_mode(ReorderMode::Trusted);
+        assert_eq!(adaptive.parse_os(app), expected.os);
+    }
+
+    #[test]
+    fn lenient_parsing_strips_header_names_and_quotes() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let user_agent = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_2 like Mac OS X) \
+                          AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.2 \
+                          Mobile/15E148 Safari/604.1";
+        let expected = parser.parse(user_agent);
+        assert_eq!(expected.os.family, "iOS");
+
+        let prefixed = format!("User-Agent: {user_agent}");
+        let quoted = format!("\"{user_agent}\"");
+        let both = format!("user-agent: '{user_agent}'\r\n");
+        for input in [user_agent, &prefixed, &quoted, &both] {
+            assert_eq!(parser.parse_lenient(input), expected);
+        }
+    }
+
+    #[test]
+    fn device_from_opera_mini_phone_headers() {
+        let headers = [
+            (
+                "User-Agent",
+                "Opera/9.80 (Android; Opera Mini/36.2.2254/119.132; U; id) \
+                 Presto/2.12.423 Version/12.16",
+            ),
+            (
+                "X-OperaMini-Phone-UA",
+                "Mozilla/5.0 (Linux; Android 13; SM-S901B) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/120.0.6099.144 Mobile Safari/537.36",
+            ),
+        ];
+        let parse = |device_ua_override| {
+            UserAgentParser::builder()
+                .device_ua_override(device_ua_override)
+                .build_from_yaml("./src/core/regexes.yaml")
+                .expect("Parser creation failed")
+                .parse_header_pairs(headers.iter().copied())
+        };
+
+        let client = parse(DeviceUaOverride::Device);
+        assert_eq!(client.user_agent.family, "Opera Mini");
+        assert_eq!(client.device.family, "Samsung SM-S901B");
+        assert_eq!(client.device.brand.as_deref(), Some("Samsung"));
+
+        let ignored = parse(DeviceUaOverride::Ignore);
+        assert_eq!(ignored.user_agent.family, "Opera Mini");
+        assert_ne!(ignored.device, client.device);
+
+        let full = parse(DeviceUaOverride::Full);
+        assert_eq!(full.user_agent.family, "Chrome Mobile");
+        assert_eq!(full.device, client.device);
+    }
+
+    #[test]
+    fn device_overrides_post_process_once() {
+        let headers = [
+            (
+                "User-Agent",
+                "Opera/9.80 (J2ME/MIDP; Opera Mini/9.80) Presto/2.12",
+            ),
+            (
+                "Device-Stock-UA",
+                "Mozilla/5.0 (Linux; Android 13; SM-S901B) AppleWebKit/537.36",
+            ),
+        ];
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let slow = Arc::new(Mutex::new(0));
+        let mut parser = UserAgentParser::builder()
+            .device_ua_override(DeviceUaOverride::Device)
+            .on_slow_parse(Duration::ZERO, {
+                let slow = Arc::clone(&slow);
+                move |_| *slow.lock().unwrap() += 1
+            })
+            .build_from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        parser.add_post_processor({
+            let seen = Arc::clone(&seen);
+            move |client: &mut Client<'_>| {
+                seen.lock().unwrap().push(client.device.family.to_string());
+            }
+        });
+
+        let client = parser.parse_header_pairs(headers.iter().copied());
+        assert_eq!(client.device.family, "Samsung SM-S901B");
+        assert_eq!(*seen.lock().unwrap(), ["Samsung SM-S901B"]);
+        assert_eq!(*slow.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn ua_header_policy_prefers_original_user_agent() {
+        let headers = [
+            ("User-Agent", "Wget/1.21"),
+            ("X-Original-User-Agent", "curl/8.4.0"),
+        ];
+        let policy = UaHeaderPolicy::new(["X-Original-User-Agent", "User-Agent"]);
+        let parser = UserAgentParser::builder()
+            .ua_header_policy(policy)
+            .build_from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+
+        let parsed = parser.parse_header_pairs_with_app(headers.iter().copied());
+        assert_eq!(parsed.client.user_agent.family, "curl");
+        assert_eq!(parsed.source, Some("X-Original-User-Agent"));
+        let fallback = parser.parse_header_pairs_with_app(headers[..1].iter().copied());
+        assert_eq!(fallback.client.user_agent.family, "Wget");
+        assert_eq!(fallback.source, Some("User-Agent"));
+
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let parsed = parser.parse_header_pairs_with_app(headers.iter().copied());
+        assert_eq!(parsed.client.user_agent.family, "Wget");
+        assert_eq!(parsed.source, Some("User-Agent"));
+        assert_eq!(
+            parser
+                .parse_header_pairs_with_app(std::iter::once(("Accept", "*/*")))
+                .source,
+            None
+        );
+    }
+
+    #[test]
+    fn header_value_policy_picks_among_folded_user_agents() {
+        let headers = [("User-Agent", "curl/8.4.0, Wget/1.21")];
+        let parse = |policy| {
+            UserAgentParser::builder()
+                .header_value_policy(policy)
+                .build_from_yaml("./src/core/regexes.yaml")
+                .expect("Parser creation failed")
+                .parse_header_pairs(headers.iter().copied())
+                .user_agent
+                .family
+                .into_owned()
+        };
+
+        assert_eq!(parse(HeaderValuePolicy::UseFirst), "curl");
+        assert_eq!(parse(HeaderValuePolicy::UseLast), "Wget");
+    }
+
+    #[test]
+    fn parses_header_pairs() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let headers = [
+            ("Host".to_owned(), "example.com".to_owned()),
+            ("User-agent".to_owned(), "curl/8.4.0".to_owned()),
+            ("USER-AGENT".to_owned(), "Wget/1.21".to_owned()),
+        ];
+        let pairs = || headers.iter().map(|(name, value)| (&**name, &**value));
+
+        let client = parser.parse_header_pairs(pairs());
+        assert_eq!(client.user_agent.family, "curl");
+        assert_eq!(client.user_agent.major.as_deref(), Some("8"));
+
+        assert_eq!(
+            parser.parse_header_pairs(pairs().take(1)),
+            Client::default()
+        );
+        assert_eq!(
+            parser.parse_header_pairs(std::iter::empty()),
+            Client::default()
+        );
+
+        let parsed = parser.parse_header_pairs_with_app(pairs());
+        assert_eq!(parsed.client, client);
+        assert_eq!(parsed.app, None);
+        let with_app = pairs().chain(Some(("X-Requested-With", "com.twitter.android")));
+        let app = parser.parse_header_pairs_with_app(with_app).app;
+        assert_eq!(app.map(|app| app.package), Some("com.twitter.android"));
+    }
+
+    #[test]
+    fn skipped_categories_are_not_compiled() {
+        let full = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let parser = UserAgentParser::builder()
+            .categories(Categories::OS | Categories::USER_AGENT)
+            .build_from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+
+        assert!(parser.device_matchers().is_empty());
+        assert_eq!(parser.os_matchers().len(), full.os_matchers().len());
+        assert_eq!(parser.categories, Categories::OS | Categories::USER_AGENT);
+
+        let user_agent = "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 \
+                          (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36";
+        assert_eq!(parser.parse_os(user_agent), full.parse_os(user_agent));
+        assert_eq!(
+            parser.parse_user_agent(user_agent),
+            full.parse_user_agent(user_agent)
+        );
+        assert_eq!(full.parse_device(user_agent).family, "Pixel 8");
+        assert_eq!(parser.parse_device(user_agent), Device::default());
+    }
+
+    #[cfg(feature = "mini-regexes")]
+    #[test]
+    fn mini_rules_match_the_full_rules() {
+        use crate::testing::corpus;
+
+        let full = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let mini = UserAgentParser::new_mini();
+        let top = [
+            &corpus::CHROME_DESKTOP[..3],
+            &corpus::CHROME_ANDROID[..3],
+            &corpus::SAFARI_IOS[..3],
+            &corpus::SAFARI_DESKTOP[..3],
+            &corpus::FIREFOX[..3],
+            &corpus::EDGE[..2],
+            &corpus::SAMSUNG_INTERNET[..2],
+            &corpus::BOTS[..1],
+        ]
+        .concat();
+        assert_eq!(top.len(), 20);
+
+        for user_agent in top {
+            assert_eq!(
+                mini.parse_user_agent(user_agent),
+                full.parse_user_agent(user_agent),
+                "{user_agent}"
+            );
+            assert_eq!(
+                mini.parse_os(user_agent),
+                full.parse_os(user_agent),
+                "{user_agent}"
+            );
+        }
+
+        let counts = |parser: &UserAgentParser| {
+            let counts = parser.source_info().rule_counts;
+            counts.device + counts.os + counts.user_agent
+        };
+        assert!(counts(&mini) * 5 < counts(&full));
+        assert!(MINI_REGEXES.len() * 5 < include_bytes!("../core/regexes.yaml").len());
+    }
+
+    #[test]
+    fn debug_is_a_short_summary() {
+        let parser = UserAgentParser::builder()
+            .with_source_label("uap-core")
+            .build_from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+
+        let summary = format!("{parser:?}");
+        assert!(summary.len() < 200, "{}", summary);
+        assert!(summary.starts_with("UserAgentParser(uap-core sha256:"));
+        assert!(summary.ends_with(&format!(
+            "({} device, {} OS, {} user agent rules))",
+            parser.device_matchers().len(),
+            parser.os_matchers().len(),
+            parser.user_agent_matchers().len()
+        )));
+
+        let dump = format!("{parser:#?}");
+        assert!(dump.len() > 10_000);
+        assert!(dump.contains("user_agent_matchers"));
+        assert!(dump.contains("GeoEvent Server"));
+    }
+
+    #[test]
+    fn invalid_escapes_are_dropped() {
+        assert_eq!(clean_escapes(r"Foo\/(\d+)\!\ bar"), r"Foo/(\d+)! bar");
+        assert_eq!(clean_escapes(r"a\\/b"), r"a\/b");
+        assert!(matches!(clean_escapes(r"\d+\.\d+"), Cow::Borrowed(_)));
+        assert!(matches!(clean_escapes("trailing\\"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn adversarial_input_never_panics() {
+        let huge = "Mozilla/5.0 (".repeat(2_000);
+        let digits = format!("Android {}", "9".repeat(20_000));
+        let escapes = "%FF%2F%00".repeat(2_000);
+        let inputs = [
+            "",
+            " \t\r\n",
+            "\0\0\0",
+            "(((((((((((((((",
+            "$1 $2 $9 ${10}",
+            "\u{202e}Mozilla/5.0\u{feff} (iPhone; CPU iPhone OS 17_0 \u{0301}like Mac OS X)",
+            "Mozilla%2F5.0%20(Linux%3B%20Android%2014%3B%20Pixel%208)",
+            "User-Agent: curl/8.4.0, Mozilla/5.0 (X11; Linux x86_64)",
+            &huge,
+            &digits,
+            &escapes,
+        ];
+        let rules = b"
+user_agent_parsers:
+  - regex: '(Mozilla)/(\\d+)'
+    family_replacement: '$1 $9 ${99}'
+    v1_replacement: '$2$2$2'
+os_parsers:
+  - regex: '(Android) (\\d+)?'
+    os_v1_replacement: '$7'
+device_parsers:
+  - regex: '(\\d+)'
+    device_replacement: '$0 $1 $2'
+    brand_replacement: '$'
+";
+        let parsers = [
+            UserAgentParser::builder()
+                .percent_decode(true)
+                .normalize_input(InputNormalization::StripControlsAndTrim)
+                .device_strategy(MatchStrategy::MostSpecific)
+                .os_strategy(MatchStrategy::MostSpecific)
+                .user_agent_strategy(MatchStrategy::MostSpecific)
+                .build_from_yaml("./src/core/regexes.yaml")
+                .expect("Parser creation failed"),
+            UserAgentParser::builder()
+                .match_blank(true)
+                .build_from_bytes(rules)
+                .expect("Parser creation failed"),
+        ];
+
+        for parser in &parsers {
+            for input in &inputs {
+                let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+                    (
+                        parser.parse(input),
+                        parser.parse_borrowed(input),
+                        parser.parse_lenient(input),
+                        parser.parse_with_spans(input),
+                        parser.parse_scored(input),
+                        parser.parse_header_pairs(iter::once(("User-Agent", *input))),
+                    )
+                }));
+                assert!(
+                    outcome.is_ok(),
+                    "panicked on {:?}",
+                    input.get(..64).unwrap_or(input)
+                );
+            }
+        }
+
+        let garbage: [&[u8]; 5] = [
+            b"",
+            b"\xff\xfe\x00",
+            b"user_agent_parsers: 7",
+            b"device_parsers:\n  - regex: '(unclosed'\n",
+            b"UAPCACHE\x01\x00\x00\x00",
+        ];
+        for bytes in &garbage {
+            let outcome = panic::catch_unwind(|| {
+                let _ = UserAgentParser::from_bytes(bytes);
+                let _ = UserAgentParser::from_bytes_strict(bytes);
+                let _ = UserAgentParser::from_cache_bytes(bytes);
+            });
+            assert!(outcome.is_ok(), "panicked loading {:?}", bytes);
+        }
+    }
 }
@@ -5,132 +5,284 @@ pub enum Error {
     Regex(regex::Error),
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-#[allow(clippy::struct_excessive_bools)]
+const OS_REPLACEMENT: usize = 0;
+const OS_V1_REPLACEMENT: usize = 1;
+const OS_V2_REPLACEMENT: usize = 2;
+const OS_V3_REPLACEMENT: usize = 3;
+
+#[derive(Clone, Debug)]
 pub struct Matcher {
-    #[serde(with = "serde_regex")]
-    pub regex: regex::Regex,
-    pub os_replacement: Option<String>,
-    pub os_v1_replacement: Option<String>,
-    pub os_v2_replacement: Option<String>,
-    pub os_v3_replacement: Option<String>,
-    pub os_replacement_has_group: bool,
-    pub os_v1_replacement_has_group: bool,
-    pub os_v2_replacement_has_group: bool,
-    pub os_v3_replacement_has_group: bool,
+    pub(super) regex: regex::Regex,
+    pub(super) os_replacement: Option<Box<str>>,
+    pub(super) os_v1_replacement: Option<Box<str>>,
+    pub(super) os_v2_replacement: Option<Box<str>>,
+    pub(super) os_v3_replacement: Option<Box<str>>,
+    pub(super) has_group: GroupFlags,
+    pub(super) priority: i32,
+    pub(super) quality: Option<MatchQuality>,
+    pub(super) extras: Option<Box<Extras>>,
 }
 
-
 impl<'a> SubParser<'a> for Matcher {
     type Item = OS<'a>;
 
     fn try_parse(&self, text: &'a str) -> Option<Self::Item> {
+        let captures = self.captures(text)?;
+        self.parse_captures(&captures, intern)
+    }
+}
+
+impl Fill for Matcher {
+    fn regex(&self) -> &Regex {
+        &self.regex
+    }
+
+    fn fill(&self, groups: &Groups<'_>, fields: &mut FlatFields) -> bool {
+        if let Some(os_replacement) = &self.os_replacement {
+            fields.replace(
+                OS_FAMILY,
+                os_replacement,
+                self.has_group.contains(OS_REPLACEMENT),
+                groups,
+            );
+        } else if let Some(family) = groups.get(1) {
+            fields.set(OS_FAMILY, Some(family));
+        } else {
+            return false;
+        }
+        let versions = [
+            (OS_MAJOR, &self.os_v1_replacement, OS_V1_REPLACEMENT),
+            (OS_MINOR, &self.os_v2_replacement, OS_V2_REPLACEMENT),
+            (OS_PATCH, &self.os_v3_replacement, OS_V3_REPLACEMENT),
+        ];
+        for (group, (field, replacement, flag)) in (2..).zip(versions) {
+            if let Some(replacement) = replacement {
+                fields.replace(field, replacement, self.has_group.contains(flag), groups);
+                fields.none_if_empty(field);
+            } else {
+                fields.set(field, groups.get(group));
+            }
+        }
+        fields.set(OS_PATCH_MINOR, groups.get(5));
+        true
+    }
+}
+
+impl Matcher {
+    /// Like `try_parse`, but replacements without capture groups are borrowed
+    /// from the matcher rather than copied into the result
+    pub fn try_parse_borrowed<'a>(&'a self, text: &'a str) -> Option<OS<'a>> {
+        let captures = self.captures(text)?;
+        self.parse_captures(&captures, Cow::Borrowed)
+    }
+
+    /// Like `try_parse`, but also reports where in `text` each captured field
+    /// was found
+    pub fn try_parse_with_spans<'a>(&self, text: &'a str) -> Option<(OS<'a>, OSSpans)> {
+        let captures = self.captures(text)?;
+        let item = self.parse_captures(&captures, intern)?;
+        Some((item, self.spans(&captures)))
+    }
+
+    fn captures<'a>(&self, text: &'a str) -> Option<regex::Captures<'a>> {
         if !self.regex.is_match(text) {
             return None;
         }
 
-        if let Some(captures) = self.regex.captures(text) {
-            let family: Cow<'a, str> = if let Some(os_replacement) = &self.os_replacement
-            {
-                replace_cow(os_replacement, self.os_replacement_has_group, &captures)
+        self.regex.captures(text)
+    }
+
+    fn parse_captures<'a, 'm>(
+        &'m self,
+        captures: &regex::Captures<'a>,
+        literal: fn(&'m str) -> Cow<'a, str>,
+    ) -> Option<OS<'a>> {
+        let family: Cow<'a, str> = if let Some(os_replacement) = &self.os_replacement {
+            replace_cow(
+                os_replacement,
+                self.has_group.contains(OS_REPLACEMENT),
+                captures,
+                literal,
+            )
+        } else {
+            captures
+                .get(1)
+                .map(|x| x.as_str())
+                .and_then(none_if_empty)
+                .map(Cow::Borrowed)?
+        };
+
+        let major: Option<Cow<'a, str>> =
+            if let Some(os_v1_replacement) = &self.os_v1_replacement {
+                none_if_empty(replace_cow(
+                    os_v1_replacement,
+                    self.has_group.contains(OS_V1_REPLACEMENT),
+                    captures,
+                    literal,
+                ))
             } else {
                 captures
-                    .get(1)
+                    .get(2)
                     .map(|x| x.as_str())
                     .and_then(none_if_empty)
-                    .map(Cow::Borrowed)?
+                    .map(Cow::Borrowed)
             };
 
-            let major: Option<Cow<'a, str>> =
-                if let Some(os_v1_replacement) = &self.os_v1_replacement {
-                    none_if_empty(replace_cow(
-                        os_v1_replacement,
-                        self.os_v1_replacement_has_group,
-                        &captures,
-                    ))
-                } else {
-                    captures
-                        .get(2)
-                        .map(|x| x.as_str())
-                        .and_then(none_if_empty)
-                        .map(Cow::Borrowed)
-                };
-
-            let minor: Option<Cow<'a, str>> =
-                if let Some(os_v2_replacement) = &self.os_v2_replacement {
-                    none_if_empty(replace_cow(
-                        os_v2_replacement,
-                        self.os_v2_replacement_has_group,
-                        &captures,
-                    ))
-                } else {
-                    captures
-                        .get(3)
-                        .map(|x| x.as_str())
-                        .and_then(none_if_empty)
-                        .map(Cow::Borrowed)
-                };
-
-            let patch: Option<Cow<'a, str>> =
-                if let Some(os_v3_replacement) = &self.os_v3_replacement {
-                    none_if_empty(replace_cow(
-                        os_v3_replacement,
-                        self.os_v3_replacement_has_group,
-                        &captures,
-                    ))
-                } else {
-                    captures
-                        .get(4)
-                        .map(|x| x.as_str())
-                        .and_then(none_if_empty)
-                        .map(Cow::Borrowed)
-                };
-
-            let patch_minor: Option<Cow<'a, str>> = captures
-                .get(5)
-                .map(|x| x.as_str())
-                .and_then(none_if_empty)
-                .map(Cow::Borrowed);
-
-            Some(OS {
-                family,
-                major,
-                minor,
-                patch,
-                patch_minor,
-            })
-        } else {
-            None
+        let minor: Option<Cow<'a, str>> =
+            if let Some(os_v2_replacement) = &self.os_v2_replacement {
+                none_if_empty(replace_cow(
+                    os_v2_replacement,
+                    self.has_group.contains(OS_V2_REPLACEMENT),
+                    captures,
+                    literal,
+                ))
+            } else {
+                captures
+                    .get(3)
+                    .map(|x| x.as_str())
+                    .and_then(none_if_empty)
+                    .map(Cow::Borrowed)
+            };
+
+        let patch: Option<Cow<'a, str>> =
+            if let Some(os_v3_replacement) = &self.os_v3_replacement {
+                none_if_empty(replace_cow(
+                    os_v3_replacement,
+                    self.has_group.contains(OS_V3_REPLACEMENT),
+                    captures,
+                    literal,
+                ))
+            } else {
+                captures
+                    .get(4)
+                    .map(|x| x.as_str())
+                    .and_then(none_if_empty)
+                    .map(Cow::Borrowed)
+            };
+
+        let patch_minor: Option<Cow<'a, str>> = captures
+            .get(5)
+            .map(|x| x.as_str())
+            .and_then(none_if_empty)
+            .map(Cow::Borrowed);
+
+        Some(OS {
+            family,
+            major,
+            minor,
+            patch,
+            patch_minor,
+        })
+    }
+
+    fn spans(&self, captures: &regex::Captures) -> OSSpans {
+        OSSpans {
+            family: capture_span(captures, 1).filter(|_| self.os_replacement.is_none()),
+            major: capture_span(captures, 2).filter(|_| self.os_v1_replacement.is_none()),
+            minor: capture_span(captures, 3).filter(|_| self.os_v2_replacement.is_none()),
+            patch: capture_span(captures, 4).filter(|_| self.os_v3_replacement.is_none()),
+            patch_minor: capture_span(captures, 5),
         }
     }
 }
 
 impl Matcher {
+    /// Compiles a rule file entry, which is the only way to build a matcher
     pub fn try_from(entry: OSParserEntry) -> Result<Matcher, Error> {
-        let regex = regex::Regex::new(&clean_escapes(&entry.regex));
+        Matcher::compile(entry, true)
+    }
+
+    pub(super) fn compile(entry: OSParserEntry, clean: bool) -> Result<Matcher, Error> {
+        let regex = compile_pattern(&entry.regex, clean);
 
         Ok(Matcher {
             regex: regex?,
-            os_replacement_has_group: entry
-                .os_replacement
-                .as_ref()
-                .map_or(false, |x| has_group(x.as_str())),
-            os_replacement: entry.os_replacement,
-            os_v1_replacement_has_group: entry
-                .os_v1_replacement
-                .as_ref()
-                .map_or(false, |x| has_group(x.as_str())),
-            os_v1_replacement: entry.os_v1_replacement,
-            os_v2_replacement_has_group: entry
-                .os_v2_replacement
-                .as_ref()
-                .map_or(false, |x| has_group(x.as_str())),
-            os_v2_replacement: entry.os_v2_replacement,
-            os_v3_replacement_has_group: entry
-                .os_v3_replacement
-                .as_ref()
-                .map_or(false, |x| has_group(x.as_str())),
-            os_v3_replacement: entry.os_v3_replacement,
+            priority: entry.priority.unwrap_or_default(),
+            quality: entry.quality,
+            extras: into_extras(entry.extras),
+            has_group: GroupFlags::from_replacements(&[
+                entry.os_replacement.as_deref(),
+                entry.os_v1_replacement.as_deref(),
+                entry.os_v2_replacement.as_deref(),
+                entry.os_v3_replacement.as_deref(),
+            ]),
+            os_replacement: into_boxed(entry.os_replacement),
+            os_v1_replacement: into_boxed(entry.os_v1_replacement),
+            os_v2_replacement: into_boxed(entry.os_v2_replacement),
+            os_v3_replacement: into_boxed(entry.os_v3_replacement),
         })
     }
+
+    /// The regular expression this matcher runs, as compiled
+    #[must_use]
+    pub fn pattern(&self) -> &str {
+        self.regex.as_str()
+    }
+
+    /// The template for `OS::family`
+    #[must_use]
+    pub fn os_replacement(&self) -> Option<&str> {
+        self.os_replacement.as_deref()
+    }
+
+    /// The template for `OS::major`
+    #[must_use]
+    pub fn os_v1_replacement(&self) -> Option<&str> {
+        self.os_v1_replacement.as_deref()
+    }
+
+    /// The template for `OS::minor`
+    #[must_use]
+    pub fn os_v2_replacement(&self) -> Option<&str> {
+        self.os_v2_replacement.as_deref()
+    }
+
+    /// The template for `OS::patch`
+    #[must_use]
+    pub fn os_v3_replacement(&self) -> Option<&str> {
+        self.os_v3_replacement.as_deref()
+    }
+
+    /// The rule's `priority`, with higher priorities tried first
+    #[must_use]
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// The rule's `quality` override, as used by `parse_scored`
+    #[must_use]
+    pub fn quality(&self) -> Option<MatchQuality> {
+        self.quality
+    }
+
+    /// The keys of the rule's entry this crate doesn't know, such as
+    /// annotations for the caller's own use
+    #[must_use]
+    pub fn extras(&self) -> &BTreeMap<String, serde_yaml::Value> {
+        self.extras.as_deref().unwrap_or(&NO_EXTRAS)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn os_replacement_has_group(&self) -> bool {
+        self.has_group.contains(OS_REPLACEMENT)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn os_v1_replacement_has_group(&self) -> bool {
+        self.has_group.contains(OS_V1_REPLACEMENT)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn os_v2_replacement_has_group(&self) -> bool {
+        self.has_group.contains(OS_V2_REPLACEMENT)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn os_v3_replacement_has_group(&self) -> bool {
+        self.has_group.contains(OS_V3_REPLACEMENT)
+    }
 }
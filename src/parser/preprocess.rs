@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use super::*;
+
+/// Where a `PatternPreprocessor` runs relative to the built-in cleanup of
+/// the escapes other regex engines accept but `regex` rejects
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum PreprocessMode {
+    /// The rewritten pattern is still cleaned up
+    #[default]
+    BeforeCleaning,
+    /// The rewritten pattern is compiled as it is
+    InsteadOfCleaning,
+}
+
+type Rewrite = dyn Fn(&str, Categories) -> Cow<'_, str> + Send + Sync;
+
+/// The rewrite registered with `UserAgentParserBuilder::pattern_preprocessor`
+#[derive(Clone)]
+pub struct PatternPreprocessor {
+    mode: PreprocessMode,
+    rewrite: Arc<Rewrite>,
+}
+
+impl PatternPreprocessor {
+    pub(crate) fn new(
+        rewrite: impl Fn(&str, Categories) -> Cow<'_, str> + Send + Sync + 'static,
+    ) -> PatternPreprocessor {
+        PatternPreprocessor {
+            mode: PreprocessMode::default(),
+            rewrite: Arc::new(rewrite),
+        }
+    }
+
+    pub(crate) fn set_mode(&mut self, mode: PreprocessMode) {
+        self.mode = mode;
+    }
+
+    /// Whether the built-in cleanup still runs after the rewrite
+    pub(crate) fn cleans(&self) -> bool {
+        self.mode == PreprocessMode::BeforeCleaning
+    }
+
+    /// Rewrites the raw pattern of every entry, before device flags are
+    /// prefixed
+    pub(crate) fn apply(&self, regex_file: &mut RegexFile) {
+        for entry in &mut regex_file.device_parsers {
+            self.rewrite(&mut entry.regex, Categories::DEVICE);
+        }
+        for entry in &mut regex_file.os_parsers {
+            self.rewrite(&mut entry.regex, Categories::OS);
+        }
+        for entry in &mut regex_file.user_agent_parsers {
+            self.rewrite(&mut entry.regex, Categories::USER_AGENT);
+        }
+    }
+
+    fn rewrite(&self, pattern: &mut String, category: Categories) {
+        let rewritten = (self.rewrite)(pattern, category);
+        if rewritten != pattern.as_str() {
+            let rewritten = rewritten.into_owned();
+            *pattern = rewritten;
+        }
+    }
+}
+
+impl fmt::Debug for PatternPreprocessor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PatternPreprocessor")
+            .field("mode", &self.mode)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VENDOR_RULES: &[u8] = br"
+user_agent_parsers:
+  - regex: '^(VendorBot)/(\d+)\Z'
+os_parsers:
+  - regex: '(VendorOS) (\d+)\Z'
+device_parsers:
+  - regex: 'vendor(phone)\Z'
+    regex_flag: 'i'
+    device_replacement: 'Vendor $1'
+";
+
+    fn end_anchors(pattern: &str, _: Categories) -> Cow<'_, str> {
+        match pattern.strip_suffix(r"\Z") {
+            Some(pattern) => Cow::Owned(format!("{pattern}$")),
+            None => Cow::Borrowed(pattern),
+        }
+    }
+
+    #[test]
+    fn preprocessor_fixes_unsupported_syntax() {
+        assert!(matches!(
+            UserAgentParser::from_bytes(VENDOR_RULES),
+            Err(Error::Device(DeviceError::Regex(_)))
+        ));
+
+        let parser = UserAgentParser::builder()
+            .pattern_preprocessor(end_anchors)
+            .build_from_bytes(VENDOR_RULES)
+            .expect("Parser creation failed");
+
+        let client = parser.parse("VendorBot/3");
+        assert_eq!(client.user_agent.family, "VendorBot");
+        assert_eq!(client.user_agent.major.as_deref(), Some("3"));
+        assert_eq!(parser.parse("VendorBot/3 extra").user_agent.family, "Other");
+        assert_eq!(parser.parse("VendorOS 12").os.major.as_deref(), Some("12"));
+        assert_eq!(parser.parse("VENDORPHONE").device.family, "Vendor PHONE");
+        assert_eq!(parser.device_matchers()[0].pattern(), "(?i)vendor(phone)$");
+
+        let cached = serde_yaml::to_string(&parser).expect("Serialization failed");
+        let restored: UserAgentParser =
+            serde_yaml::from_str(&cached).expect("Deserialization failed");
+        assert_eq!(restored.parse("VendorBot/3"), client);
+    }
+
+    #[test]
+    fn preprocessor_sees_categories_and_can_replace_cleaning() {
+        let rules = br"
+user_agent_parsers:
+  - regex: '(Foo)\/(\d+)'
+os_parsers:
+  - regex: '(Bar)\/(\d+)'
+device_parsers: []
+";
+        let build = |mode| {
+            UserAgentParser::builder()
+                .pattern_preprocessor(|pattern, category| {
+                    if category == Categories::OS {
+                        Cow::Owned(pattern.replace("Bar", "Baz"))
+                    } else {
+                        Cow::Borrowed(pattern)
+                    }
+                })
+                .pattern_preprocessor_mode(mode)
+                .build_from_bytes(rules)
+                .expect("Parser creation failed")
+        };
+
+        let cleaned = build(PreprocessMode::BeforeCleaning);
+        assert_eq!(cleaned.user_agent_matchers()[0].pattern(), r"(Foo)/(\d+)");
+        assert_eq!(cleaned.os_matchers()[0].pattern(), r"(Baz)/(\d+)");
+
+        let raw = build(PreprocessMode::InsteadOfCleaning);
+        assert_eq!(raw.user_agent_matchers()[0].pattern(), r"(Foo)\/(\d+)");
+        assert_eq!(raw.os_matchers()[0].pattern(), r"(Baz)\/(\d+)");
+        assert_eq!(raw.parse("Baz/2").os.family, "Baz");
+    }
+}
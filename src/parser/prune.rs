@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+
+use super::*;
+
+/// Hit counts of each rule, keyed by the rule's compiled pattern so that
+/// counts gathered by one parser can be applied to another built from the
+/// same rules. Serializes as a plain map per category.
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ParserStats {
+    #[serde(default)]
+    pub device: BTreeMap<String, u64>,
+    #[serde(default)]
+    pub os: BTreeMap<String, u64>,
+    #[serde(default)]
+    pub user_agent: BTreeMap<String, u64>,
+}
+
+/// The rules removed by `UserAgentParser::prune_unhit`, which can be put
+/// back with `UserAgentParser::undo_prune`
+#[derive(Debug)]
+pub struct PruneReport {
+    pub removed: RuleCounts,
+    pub device_patterns: Vec<String>,
+    pub os_patterns: Vec<String>,
+    pub user_agent_patterns: Vec<String>,
+    device: Vec<(usize, device::Matcher)>,
+    os: Vec<(usize, os::Matcher)>,
+    user_agent: Vec<(usize, user_agent::Matcher)>,
+}
+
+impl UserAgentParser {
+    /// Removes the rules with fewer than `min_hits` hits in `stats`, where a
+    /// rule missing from `stats` counts as never hit. The source info rule
+    /// counts follow the removal.
+    pub fn prune_unhit(&mut self, stats: &ParserStats, min_hits: u64) -> PruneReport {
+        let device = prune(&mut self.device_matchers, &stats.device, min_hits, |m| {
+            m.regex.as_str()
+        });
+        let os = prune(&mut self.os_matchers, &stats.os, min_hits, |m| {
+            m.regex.as_str()
+        });
+        let user_agent = prune(
+            &mut self.user_agent_matchers,
+            &stats.user_agent,
+            min_hits,
+            |m| m.regex.as_str(),
+        );
+        self.source_info.rule_counts = self.rule_counts();
+
+        PruneReport {
+            removed: RuleCounts {
+                device: device.len(),
+                os: os.len(),
+                user_agent: user_agent.len(),
+            },
+            device_patterns: patterns(&device, |m| m.regex.as_str()),
+            os_patterns: patterns(&os, |m| m.regex.as_str()),
+            user_agent_patterns: patterns(&user_agent, |m| m.regex.as_str()),
+            device,
+            os,
+            user_agent,
+        }
+    }
+
+    /// Puts back the rules removed by the `prune_unhit` call that returned
+    /// `report`, at their original positions. Only meaningful when no rules
+    /// were added or removed in between.
+    pub fn undo_prune(&mut self, report: PruneReport) {
+        restore(&mut self.device_matchers, report.device);
+        restore(&mut self.os_matchers, report.os);
+        restore(&mut self.user_agent_matchers, report.user_agent);
+        self.source_info.rule_counts = self.rule_counts();
+    }
+
+    fn rule_counts(&self) -> RuleCounts {
+        RuleCounts {
+            device: self.device_matchers.len(),
+            os: self.os_matchers.len(),
+            user_agent: self.user_agent_matchers.len(),
+        }
+    }
+}
+
+/// Removes the unhit matchers, returning them with their original indices
+fn prune<M>(
+    matchers: &mut Vec<M>,
+    hits: &BTreeMap<String, u64>,
+    min_hits: u64,
+    pattern: impl Fn(&M) -> &str,
+) -> Vec<(usize, M)> {
+    let mut removed = Vec::new();
+    let mut kept = Vec::with_capacity(matchers.len());
+    for (index, matcher) in matchers.drain(..).enumerate() {
+        if hits.get(pattern(&matcher)).copied().unwrap_or(0) < min_hits {
+            removed.push((index, matcher));
+        } else {
+            kept.push(matcher);
+        }
+    }
+    *matchers = kept;
+    removed
+}
+
+fn patterns<M>(removed: &[(usize, M)], pattern: impl Fn(&M) -> &str) -> Vec<String> {
+    removed
+        .iter()
+        .map(|(_, matcher)| pattern(matcher).to_owned())
+        .collect()
+}
+
+/// Inserting in ascending index order puts every matcher back where it was
+fn restore<M>(matchers: &mut Vec<M>, removed: Vec<(usize, M)>) {
+    for (index, matcher) in removed {
+        matchers.insert(index.min(matchers.len()), matcher);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &[u8] = br"
+user_agent_parsers:
+  - regex: '(Hot)/(\d+)'
+  - regex: '(Cold)/(\d+)'
+  - regex: '(Warm)/(\d+)'
+os_parsers:
+  - regex: '(Linux)'
+";
+
+    #[test]
+    fn prunes_unhit_rules_and_undoes() {
+        let mut parser =
+            UserAgentParser::from_bytes(RULES).expect("Parser creation failed");
+        let stats: ParserStats = serde_yaml::from_str(
+            r"
+user_agent:
+  '(Hot)/(\d+)': 120
+  '(Cold)/(\d+)': 2
+  '(Warm)/(\d+)': 15
+os:
+  '(Linux)': 50
+",
+        )
+        .expect("Stats import failed");
+
+        let report = parser.prune_unhit(&stats, 10);
+        assert_eq!(
+            report.removed,
+            RuleCounts {
+                device: 0,
+                os: 0,
+                user_agent: 1
+            }
+        );
+        assert_eq!(report.user_agent_patterns, [r"(Cold)/(\d+)"]);
+        assert_eq!(parser.source_info().rule_counts.user_agent, 2);
+        assert_eq!(parser.parse_user_agent("Hot/1").family, "Hot");
+        assert_eq!(parser.parse_user_agent("Warm/3").family, "Warm");
+        assert_eq!(parser.parse_os("Linux").family, "Linux");
+        assert_eq!(parser.parse_user_agent("Cold/2").family, "Other");
+
+        parser.undo_prune(report);
+        let families: Vec<_> = ["Hot/1", "Cold/2", "Warm/3"]
+            .iter()
+            .map(|ua| parser.parse_user_agent(ua).family.into_owned())
+            .collect();
+        assert_eq!(families, ["Hot", "Cold", "Warm"]);
+        assert_eq!(parser.user_agent_matchers()[1].pattern(), r"(Cold)/(\d+)");
+        assert_eq!(parser.source_info().rule_counts.user_agent, 3);
+    }
+}
@@ -0,0 +1,389 @@
+use super::*;
+
+/// Device brands given by the catch-all rules of uap-core, such as the rules
+/// for Android devices that don't name their manufacturer
+const GENERIC_BRANDS: &[&str] = &[
+    "Generic",
+    "Generic_Android",
+    "Generic_Android_Tablet",
+    "Generic_Inettv",
+    "Spider",
+];
+
+/// Patterns with fewer literal characters than this outside of alternations
+/// match too broadly to be trusted
+const MIN_LITERALS: usize = 4;
+
+/// How far a parsed field can be trusted, going by the rule that produced it
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchQuality {
+    /// No rule matched, so the field is the default
+    #[default]
+    None,
+    /// A catch-all rule matched
+    Generic,
+    /// A rule for a specific product matched
+    Exact,
+}
+
+/// A parsed `Client` along with how well each of its parts was matched
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ScoredClient<'a> {
+    pub client: Client<'a>,
+    pub device: MatchQuality,
+    pub os: MatchQuality,
+    pub user_agent: MatchQuality,
+    /// The categories whose scan was cut short by
+    /// `UserAgentParserBuilder::max_matchers_per_category` before finding
+    /// the result, rather than after running out of rules
+    pub exhausted: Categories,
+}
+
+impl Default for ScoredClient<'_> {
+    fn default() -> Self {
+        ScoredClient {
+            client: Client::default(),
+            device: MatchQuality::default(),
+            os: MatchQuality::default(),
+            user_agent: MatchQuality::default(),
+            exhausted: Categories::NONE,
+        }
+    }
+}
+
+impl ScoredClient<'_> {
+    /// Copies any borrowed fields of the client, as `Client::into_owned`
+    #[must_use]
+    pub fn into_owned(self) -> ScoredClient<'static> {
+        ScoredClient {
+            client: self.client.into_owned(),
+            device: self.device,
+            os: self.os,
+            user_agent: self.user_agent,
+            exhausted: self.exhausted,
+        }
+    }
+}
+
+impl UserAgentParser {
+    /// Like `parse_with_spans`, but reports the `MatchQuality` of each part
+    /// rather than its spans. A rule that matched scores `Exact` unless
+    ///
+    /// - it is a device rule giving a catch-all brand, such as
+    ///   `Generic_Android` or `Spider`, or no brand at all, or
+    /// - its pattern has fewer than four literal characters outside of
+    ///   alternations, as bot rules like `(?:bot|crawler|spider)` do,
+    ///
+    /// in which case it scores `Generic`. A rule's `quality` key in the rule
+    /// file overrides this. Post-processors don't run.
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+    /// let scored = parser.parse_scored(
+    ///     "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 \
+    ///      (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36",
+    /// );
+    ///
+    /// assert_eq!(scored.device, MatchQuality::Exact);
+    /// ```
+    #[must_use]
+    pub fn parse_scored<'a>(&self, user_agent: &'a str) -> ScoredClient<'a> {
+        normalized!(self, user_agent, self.match_scored(user_agent))
+    }
+
+    fn match_scored<'a>(&self, user_agent: &'a str) -> ScoredClient<'a> {
+        if self.skips(user_agent) {
+            return ScoredClient::default();
+        }
+        let (device, device_quality) = select(
+            self.match_strategies.device,
+            self.sub_parsers.device.around(
+                self.budgeted(&self.device_matchers)
+                    .iter()
+                    .filter_map(|matcher| {
+                        let device = matcher.try_parse(user_agent)?;
+                        let quality = matcher.scored_quality(&device);
+                        Some((device, quality))
+                    }),
+                |parser| Some((parser.try_parse(user_agent)?, MatchQuality::Exact)),
+            ),
+            |(item, _)| device_specificity(item),
+        )
+        .unwrap_or_default();
+        let mut exhausted = Categories::NONE;
+        if self.exhausted(
+            self.match_strategies.device,
+            self.device_matchers.len(),
+            device_quality,
+        ) {
+            exhausted |= Categories::DEVICE;
+        }
+        let (os, os_quality) = select(
+            self.match_strategies.os,
+            self.sub_parsers.os.around(
+                self.budgeted(&self.os_matchers)
+                    .iter()
+                    .filter_map(|matcher| {
+                        let os = matcher.try_parse(user_agent)?;
+                        Some((os, rule_quality(matcher.quality, &matcher.regex)))
+                    }),
+                |parser| Some((parser.try_parse(user_agent)?, MatchQuality::Exact)),
+            ),
+            |(item, _)| os_specificity(item),
+        )
+        .unwrap_or_default();
+        let os = self.darwin_os(user_agent, os);
+        if self.exhausted(self.match_strategies.os, self.os_matchers.len(), os_quality) {
+            exhausted |= Categories::OS;
+        }
+        let (user_agent, user_agent_quality) = select(
+            self.match_strategies.user_agent,
+            self.sub_parsers.user_agent.around(
+                self.budgeted(&self.user_agent_matchers)
+                    .iter()
+                    .filter_map(|matcher| {
+                        let user_agent = matcher.try_parse(user_agent)?;
+                        Some((user_agent, rule_quality(matcher.quality, &matcher.regex)))
+                    }),
+                |parser| Some((parser.try_parse(user_agent)?, MatchQuality::Exact)),
+            ),
+            |(item, _)| user_agent_specificity(item),
+        )
+        .unwrap_or_default();
+        if self.exhausted(
+            self.match_strategies.user_agent,
+            self.user_agent_matchers.len(),
+            user_agent_quality,
+        ) {
+            exhausted |= Categories::USER_AGENT;
+        }
+
+        ScoredClient {
+            client: Client {
+                device,
+                os,
+                user_agent,
+            },
+            device: device_quality,
+            os: os_quality,
+            user_agent: user_agent_quality,
+            exhausted,
+        }
+    }
+
+    /// Whether the matcher budget stopped a category's scan early. Taking the
+    /// first match, that only matters when none of the evaluated rules
+    /// matched; taking the most specific, any rule left out could have won.
+    fn exhausted(
+        &self,
+        strategy: MatchStrategy,
+        rules: usize,
+        found: MatchQuality,
+    ) -> bool {
+        self.max_matchers_per_category.is_some_and(|max| {
+            max < rules
+                && (strategy == MatchStrategy::MostSpecific
+                    || found == MatchQuality::None)
+        })
+    }
+}
+
+impl device::Matcher {
+    fn scored_quality(&self, device: &Device<'_>) -> MatchQuality {
+        let generic_brand = device
+            .brand
+            .as_deref()
+            .is_none_or(|brand| GENERIC_BRANDS.contains(&brand));
+        match self.quality {
+            None if generic_brand => MatchQuality::Generic,
+            quality => rule_quality(quality, &self.regex),
+        }
+    }
+}
+
+fn rule_quality(quality: Option<MatchQuality>, regex: &Regex) -> MatchQuality {
+    quality.unwrap_or_else(|| {
+        if literal_chars(regex.as_str()) < MIN_LITERALS {
+            MatchQuality::Generic
+        } else {
+            MatchQuality::Exact
+        }
+    })
+}
+
+/// Counts the letters and digits a pattern matches literally, leaving out
+/// character classes, quantifiers and any group with alternatives
+fn literal_chars(pattern: &str) -> usize {
+    // The literal count of the innermost open group and whether it has
+    // alternatives, with those of the groups around it on the stack
+    let (mut literals, mut alternation) = (0, false);
+    let mut outer = Vec::new();
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' => {
+                let mut len = 0;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        ']' if len > 0 => break,
+                        _ => {}
+                    }
+                    len += 1;
+                }
+            }
+            '{' => {
+                chars.by_ref().find(|&c| c == '}');
+            }
+            '(' => {
+                if chars.peek() == Some(&'?') {
+                    // Flags such as `(?i)` stand alone; `(?:`, `(?i:` and
+                    // `(?P<name>` open a group
+                    let end = chars.by_ref().find(|c| matches!(c, ':' | ')' | '>'));
+                    if end == Some(')') {
+                        continue;
+                    }
+                }
+                outer.push((literals, alternation));
+                (literals, alternation) = (0, false);
+            }
+            ')' => {
+                if let Some((outer_literals, outer_alternation)) = outer.pop() {
+                    let group = if alternation { 0 } else { literals };
+                    (literals, alternation) = (outer_literals + group, outer_alternation);
+                }
+            }
+            '|' => alternation = true,
+            c if c.is_alphanumeric() => literals += 1,
+            _ => {}
+        }
+    }
+
+    if alternation {
+        0
+    } else {
+        literals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literal_chars_skip_classes_and_alternations() {
+        assert_eq!(literal_chars(r"(Chrome)/(\d+)\.(\d+)"), 6);
+        assert_eq!(literal_chars(r"(?i)(?:bot|crawler|spider)"), 0);
+        assert_eq!(literal_chars(r"; {0,2}(Pixel.{0,200}?)(?: Build|\))"), 5);
+        assert_eq!(literal_chars(r"(?P<name>[A-Za-z0-9]+)/(\d+)"), 0);
+        assert_eq!(literal_chars(r"Foo|Bar"), 0);
+    }
+
+    #[test]
+    fn scores_follow_rule_specificity() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+
+        let pixel = parser.parse_scored(
+            "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 \
+             (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36",
+        );
+        assert_eq!(pixel.client.device.brand.as_deref(), Some("Google"));
+        assert_eq!(
+            [pixel.device, pixel.os, pixel.user_agent],
+            [MatchQuality::Exact; 3]
+        );
+
+        let generic = parser.parse_scored(
+            "Mozilla/5.0 (Linux; Android 9; QX-500 Build/PPR1.180610.011) \
+             AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36",
+        );
+        assert_eq!(
+            generic.client.device.brand.as_deref(),
+            Some("Generic_Android")
+        );
+        assert_eq!(generic.device, MatchQuality::Generic);
+        assert_eq!(generic.os, MatchQuality::Exact);
+
+        let garbage = parser.parse_scored("zxqv 123 !!");
+        assert_eq!(garbage, ScoredClient::default());
+    }
+
+    #[test]
+    fn rule_files_override_quality() {
+        let parser = UserAgentParser::from_bytes_strict(
+            br"
+user_agent_parsers:
+  - regex: '(Acme)Browser/(\d+)'
+    quality: generic
+os_parsers:
+  - regex: '(AcmeOS)'
+device_parsers:
+  - regex: 'Acme-(\w+)'
+    device_replacement: 'Acme $1'
+    quality: exact
+",
+        )
+        .expect("Parser creation failed");
+        let scored = parser.parse_scored("AcmeBrowser/3 (AcmeOS; Acme-X100)");
+
+        assert_eq!(scored.user_agent, MatchQuality::Generic);
+        assert_eq!(scored.os, MatchQuality::Exact);
+        assert_eq!(scored.device, MatchQuality::Exact);
+        assert_eq!(scored.client.device.brand, None);
+    }
+
+    #[test]
+    fn budgets_cut_scans_short() {
+        let full = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let firefox =
+            "Mozilla/5.0 (X11; Linux x86_64; rv:109.0) Gecko/20100101 Firefox/115.0";
+
+        let budgeted = UserAgentParser::builder()
+            .max_matchers_per_category(1)
+            .build_from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let scored = budgeted.parse_scored(firefox);
+        assert_eq!(scored.client.user_agent, UserAgent::default());
+        assert_eq!(scored.client.os, OS::default());
+        assert!(scored
+            .exhausted
+            .contains(Categories::OS | Categories::USER_AGENT));
+        assert_eq!(budgeted.parse(firefox).user_agent, UserAgent::default());
+
+        let rules = full
+            .device_matchers()
+            .len()
+            .max(full.os_matchers().len())
+            .max(full.user_agent_matchers().len());
+        let adequate = UserAgentParser::builder()
+            .max_matchers_per_category(rules)
+            .build_from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let scored = adequate.parse_scored(firefox);
+        assert_eq!(scored, full.parse_scored(firefox));
+        assert!(scored.exhausted.is_empty());
+        assert_eq!(adequate.parse(firefox), full.parse(firefox));
+    }
+}
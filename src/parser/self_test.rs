@@ -0,0 +1,212 @@
+use super::*;
+
+/// A user agent and the families a working parser gives it, for
+/// `UserAgentParser::self_test_with`. Families left `None` aren't checked.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SelfTestCase<'a> {
+    pub user_agent: &'a str,
+    pub ua_family: Option<&'a str>,
+    pub os_family: Option<&'a str>,
+    pub device_family: Option<&'a str>,
+}
+
+/// The checks run by `UserAgentParser::self_test`: recent desktop Chrome,
+/// Safari and Firefox, Chrome on Android, Safari on iOS, and Googlebot
+pub const SELF_TEST_CASES: &[SelfTestCase<'static>] = &[
+    SelfTestCase {
+        user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                     (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+        ua_family: Some("Chrome"),
+        os_family: Some("Windows"),
+        device_family: None,
+    },
+    SelfTestCase {
+        user_agent: "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) \
+                     AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1 \
+                     Safari/605.1.15",
+        ua_family: Some("Safari"),
+        os_family: Some("Mac OS X"),
+        device_family: None,
+    },
+    SelfTestCase {
+        user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:121.0) Gecko/20100101 \
+                     Firefox/121.0",
+        ua_family: Some("Firefox"),
+        os_family: Some("Windows"),
+        device_family: None,
+    },
+    SelfTestCase {
+        user_agent: "Mozilla/5.0 (Linux; Android 13; SM-S901B) AppleWebKit/537.36 \
+                     (KHTML, like Gecko) Chrome/120.0.6099.144 Mobile Safari/537.36",
+        ua_family: Some("Chrome Mobile"),
+        os_family: Some("Android"),
+        device_family: None,
+    },
+    SelfTestCase {
+        user_agent: "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1_2 like Mac OS X) \
+                     AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1 Mobile/15E148 \
+                     Safari/604.1",
+        ua_family: Some("Mobile Safari"),
+        os_family: Some("iOS"),
+        device_family: Some("iPhone"),
+    },
+    SelfTestCase {
+        user_agent: "Mozilla/5.0 (compatible; Googlebot/2.1; \
+                     +http://www.google.com/bot.html)",
+        ua_family: Some("Googlebot"),
+        os_family: None,
+        device_family: Some("Spider"),
+    },
+];
+
+/// The checks a parser failed in `UserAgentParser::self_test`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SelfTestFailure {
+    /// The number of checks run
+    pub checked: usize,
+    pub misses: Vec<SelfTestMiss>,
+}
+
+/// A family that came out differently than a `SelfTestCase` expected
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SelfTestMiss {
+    pub user_agent: String,
+    /// The flattened field name, such as `ua_family`
+    pub field: &'static str,
+    pub expected: String,
+    pub found: String,
+}
+
+impl fmt::Display for SelfTestFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} self-test checks failed",
+            self.misses.len(),
+            self.checked
+        )?;
+        for miss in &self.misses {
+            write!(
+                f,
+                "\n{}: expected {:?}, found {:?} for {}",
+                miss.field, miss.expected, miss.found, miss.user_agent
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl UserAgentParser {
+    /// Checks that the parser classifies a few common user agents, listed in
+    /// `SELF_TEST_CASES`, as the stock rules do. A truncated or mismatched
+    /// rule file can build fine and still parse everything as `Other`, which
+    /// this catches, such as in a health check.
+    ///
+    /// ```rust
+    /// # use uaparser::*;
+    /// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+    /// assert!(parser.self_test().is_ok());
+    /// ```
+    pub fn self_test(&self) -> Result<(), SelfTestFailure> {
+        self.self_test_with(SELF_TEST_CASES)
+    }
+
+    /// Like `self_test`, with checks of your own, such as for rules that
+    /// name families differently from the stock ones
+    pub fn self_test_with(
+        &self,
+        cases: &[SelfTestCase<'_>],
+    ) -> Result<(), SelfTestFailure> {
+        let mut failure = SelfTestFailure {
+            checked: 0,
+            misses: Vec::new(),
+        };
+        for case in cases {
+            let client = self.parse(case.user_agent);
+            for (field, expected, found) in [
+                ("ua_family", case.ua_family, &client.user_agent.family),
+                ("os_family", case.os_family, &client.os.family),
+                ("device_family", case.device_family, &client.device.family),
+            ] {
+                let Some(expected) = expected else {
+                    continue;
+                };
+                failure.checked += 1;
+                if expected != found {
+                    failure.misses.push(SelfTestMiss {
+                        user_agent: case.user_agent.to_owned(),
+                        field,
+                        expected: expected.to_owned(),
+                        found: found.clone().into_owned(),
+                    });
+                }
+            }
+        }
+        if failure.misses.is_empty() {
+            Ok(())
+        } else {
+            Err(failure)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stock_rules_pass() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+
+        assert_eq!(parser.self_test(), Ok(()));
+    }
+
+    #[test]
+    fn empty_rules_fail_every_check() {
+        let parser = UserAgentParser::try_from(RegexFile {
+            user_agent_parsers: Vec::new(),
+            os_parsers: Vec::new(),
+            device_parsers: Vec::new(),
+        })
+        .expect("Parser creation failed");
+        let failure = parser.self_test().expect_err("Self-test passed");
+
+        assert_eq!(failure.checked, 13);
+        assert_eq!(failure.misses.len(), 13);
+        assert!(failure.misses.iter().all(|miss| miss.found == "Other"));
+        for case in SELF_TEST_CASES {
+            assert!(failure
+                .misses
+                .iter()
+                .any(|miss| miss.user_agent == case.user_agent));
+        }
+        assert!(failure.to_string().starts_with(
+            "13 of 13 self-test checks failed\n\
+             ua_family: expected \"Chrome\", found \"Other\" for Mozilla/5.0"
+        ));
+    }
+
+    #[test]
+    fn custom_cases() {
+        let parser = UserAgentParser::from_bytes(
+            br"
+user_agent_parsers:
+  - regex: '(Acme)/(\d+)'
+    family_replacement: 'Acme Browser'
+os_parsers: []
+device_parsers: []
+",
+        )
+        .expect("Parser creation failed");
+        let cases = [SelfTestCase {
+            user_agent: "Acme/2",
+            ua_family: Some("Acme Browser"),
+            os_family: Some("Other"),
+            device_family: None,
+        }];
+
+        assert_eq!(parser.self_test_with(&cases), Ok(()));
+        assert!(parser.self_test().is_err());
+    }
+}
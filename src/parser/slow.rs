@@ -0,0 +1,157 @@
+use std::{sync::Arc, time::Duration};
+
+use super::*;
+
+/// User agents longer than this are truncated in `SlowParseEvent`s, unless
+/// set otherwise with `UserAgentParserBuilder::slow_parse_truncate`
+const DEFAULT_MAX_LEN: usize = 256;
+
+/// A `parse` call that took longer than the threshold given to
+/// `UserAgentParserBuilder::on_slow_parse`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SlowParseEvent<'a> {
+    pub elapsed: Duration,
+    /// The user agent, cut at a character boundary when it was longer than
+    /// the configured limit
+    pub user_agent: &'a str,
+    pub truncated: bool,
+    /// The categories in which a rule matched, going by a family other than
+    /// `Other`
+    pub matched: Categories,
+}
+
+/// The callback registered with `UserAgentParserBuilder::on_slow_parse`
+#[derive(Clone)]
+pub struct SlowParseHook {
+    threshold: Duration,
+    max_len: usize,
+    callback: Arc<dyn Fn(SlowParseEvent<'_>) + Send + Sync>,
+}
+
+impl SlowParseHook {
+    pub(crate) fn new(
+        threshold: Duration,
+        callback: impl Fn(SlowParseEvent<'_>) + Send + Sync + 'static,
+    ) -> SlowParseHook {
+        SlowParseHook {
+            threshold,
+            max_len: DEFAULT_MAX_LEN,
+            callback: Arc::new(callback),
+        }
+    }
+
+    pub(crate) fn set_max_len(&mut self, max_len: usize) {
+        self.max_len = max_len;
+    }
+
+    pub(crate) fn observe(
+        &self,
+        elapsed: Duration,
+        user_agent: &str,
+        client: &Client<'_>,
+    ) {
+        if elapsed < self.threshold {
+            return;
+        }
+
+        let mut end = user_agent.len().min(self.max_len);
+        while !user_agent.is_char_boundary(end) {
+            end -= 1;
+        }
+        let mut matched = Categories::NONE;
+        if client.device.family != "Other" {
+            matched |= Categories::DEVICE;
+        }
+        if client.os.family != "Other" {
+            matched |= Categories::OS;
+        }
+        if client.user_agent.family != "Other" {
+            matched |= Categories::USER_AGENT;
+        }
+
+        (self.callback)(SlowParseEvent {
+            elapsed,
+            user_agent: &user_agent[..end],
+            truncated: end < user_agent.len(),
+            matched,
+        });
+    }
+}
+
+impl fmt::Debug for SlowParseHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SlowParseHook")
+            .field("threshold", &self.threshold)
+            .field("max_len", &self.max_len)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    type Events = Arc<Mutex<Vec<(String, bool, Categories)>>>;
+
+    fn parser(threshold: Duration, events: &Events) -> UserAgentParser {
+        let events = Arc::clone(events);
+        UserAgentParser::builder()
+            .on_slow_parse(threshold, move |event| {
+                events.lock().unwrap().push((
+                    event.user_agent.to_owned(),
+                    event.truncated,
+                    event.matched,
+                ));
+            })
+            .slow_parse_truncate(8)
+            .build_from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed")
+    }
+
+    #[test]
+    fn zero_threshold_fires_for_every_parse() {
+        let events = Events::default();
+        let parser = parser(Duration::ZERO, &events);
+
+        parser.parse("curl/8.4.0");
+        parser.parse("zxqv");
+        parser.parse("Mozilla/5.0 (Windows NT 10.0; Win64; x64)");
+
+        let events = events.lock().unwrap();
+        assert_eq!(
+            *events,
+            [
+                ("curl/8.4".to_owned(), true, Categories::USER_AGENT),
+                ("zxqv".to_owned(), false, Categories::NONE),
+                ("Mozilla/".to_owned(), true, Categories::OS),
+            ]
+        );
+    }
+
+    #[test]
+    fn wrapping_parsers_fire_the_hook() {
+        let events = Events::default();
+        let adaptive = AdaptiveParser::new(parser(Duration::ZERO, &events));
+
+        adaptive.parse("curl/8.4.0");
+        adaptive.parse_user_agent("zxqv");
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            [("curl/8.4".to_owned(), true, Categories::USER_AGENT)]
+        );
+    }
+
+    #[test]
+    fn enormous_threshold_never_fires() {
+        let events = Events::default();
+        let parser = parser(Duration::from_secs(60 * 60), &events);
+
+        parser.parse("curl/8.4.0");
+        parser.parse(&"x".repeat(10_000));
+
+        assert!(events.lock().unwrap().is_empty());
+    }
+}
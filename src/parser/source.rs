@@ -0,0 +1,96 @@
+use std::{
+    fmt::{self, Write},
+    time::SystemTime,
+};
+
+use sha2::{Digest, Sha256};
+
+/// Identifies the rule set a `UserAgentParser` was built from, so results can
+/// be traced back to an exact version of the rules
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SourceInfo {
+    /// Set with `UserAgentParserBuilder::with_source_label`
+    pub label: Option<String>,
+    /// The SHA-256 of the rule file bytes, missing for parsers built with
+    /// `UserAgentParser::try_from` from an already deserialized `RegexFile`
+    pub content_hash: Option<[u8; 32]>,
+    /// When the rules were compiled, kept as is by serialization. Always the
+    /// Unix epoch on `wasm32-unknown-unknown`.
+    pub built_at: SystemTime,
+    pub rule_counts: RuleCounts,
+}
+
+/// The current time, or the Unix epoch on `wasm32-unknown-unknown`, which
+/// has no clock to read
+fn now() -> SystemTime {
+    if cfg!(all(target_family = "wasm", target_os = "unknown")) {
+        SystemTime::UNIX_EPOCH
+    } else {
+        SystemTime::now()
+    }
+}
+
+/// The number of rules of each category in a parser
+#[derive(
+    Clone, Copy, Debug, Default, Eq, Hash, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub struct RuleCounts {
+    pub device: usize,
+    pub os: usize,
+    pub user_agent: usize,
+}
+
+impl SourceInfo {
+    pub(super) fn new(rule_counts: RuleCounts) -> SourceInfo {
+        SourceInfo {
+            label: None,
+            content_hash: None,
+            built_at: now(),
+            rule_counts,
+        }
+    }
+
+    /// The content hash as lowercase hex
+    #[must_use]
+    pub fn content_hash_hex(&self) -> Option<String> {
+        self.content_hash.map(|hash| {
+            hash.iter()
+                .fold(String::with_capacity(64), |mut hex, byte| {
+                    let _ = write!(hex, "{byte:02x}");
+                    hex
+                })
+        })
+    }
+}
+
+impl Default for SourceInfo {
+    fn default() -> Self {
+        Self {
+            label: None,
+            content_hash: None,
+            built_at: SystemTime::UNIX_EPOCH,
+            rule_counts: RuleCounts::default(),
+        }
+    }
+}
+
+/// Formats as the label and content hash followed by the rule counts, such
+/// as `uap-core 0.18.0 sha256:3c4f… (1 device, 2 OS, 3 user agent rules)`
+impl fmt::Display for SourceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label.as_deref().unwrap_or("unlabelled rules"))?;
+        if let Some(hash) = self.content_hash_hex() {
+            write!(f, " sha256:{hash}")?;
+        }
+        let counts = self.rule_counts;
+        write!(
+            f,
+            " ({} device, {} OS, {} user agent rules)",
+            counts.device, counts.os, counts.user_agent
+        )
+    }
+}
+
+pub(super) fn content_hash(bytes: &[u8]) -> [u8; 32] {
+    Sha256::digest(bytes).into()
+}
@@ -0,0 +1,281 @@
+use std::sync::Arc;
+
+use super::*;
+
+/// A parser of base rules shared between `TenantParser`s, built once
+#[derive(Debug)]
+pub struct SharedBaseParser {
+    parser: UserAgentParser,
+}
+
+impl SharedBaseParser {
+    #[must_use]
+    pub fn new(parser: UserAgentParser) -> SharedBaseParser {
+        SharedBaseParser { parser }
+    }
+
+    #[must_use]
+    pub fn parser(&self) -> &UserAgentParser {
+        &self.parser
+    }
+}
+
+impl From<UserAgentParser> for SharedBaseParser {
+    fn from(parser: UserAgentParser) -> Self {
+        SharedBaseParser::new(parser)
+    }
+}
+
+/// A tenant's own rules in front of a shared base parser, for serving many
+/// tenants with small custom overlays from one process without compiling
+/// the base rules for each. Only the overlay's rules are compiled, so a
+/// tenant costs memory in proportion to its overlay.
+///
+/// Each category tries the overlay's rules before the base's, so an overlay
+/// rule wins over any base rule matching the same user agent. Otherwise the
+/// base's settings apply throughout: its input normalization, match
+/// strategies, post-processors and slow parse hook.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// # use uaparser::*;
+/// let base = Arc::new(SharedBaseParser::new(
+///     UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap(),
+/// ));
+/// let overlay: RegexFile = serde_yaml::from_str("
+/// user_agent_parsers:
+///   - regex: '(curl)/(\\d+)'
+///     family_replacement: 'Acme Monitor'
+/// ").unwrap();
+/// let tenant = TenantParser::new(Arc::clone(&base), overlay).unwrap();
+///
+/// assert_eq!(tenant.parse_user_agent("curl/8.4.0").family, "Acme Monitor");
+/// assert_eq!(tenant.parse_user_agent("Wget/1.21").family, "Wget");
+/// ```
+#[derive(Debug)]
+pub struct TenantParser {
+    base: Arc<SharedBaseParser>,
+    device_matchers: Vec<device::Matcher>,
+    os_matchers: Vec<os::Matcher>,
+    user_agent_matchers: Vec<user_agent::Matcher>,
+}
+
+impl TenantParser {
+    /// Compiles the rules of `overlay`, in the order a parser built from
+    /// them would try them
+    pub fn new(
+        base: Arc<SharedBaseParser>,
+        overlay: RegexFile,
+    ) -> Result<TenantParser, Error> {
+        let overlay = UserAgentParser::try_from(overlay)?;
+        Ok(TenantParser {
+            base,
+            device_matchers: overlay.device_matchers,
+            os_matchers: overlay.os_matchers,
+            user_agent_matchers: overlay.user_agent_matchers,
+        })
+    }
+
+    #[must_use]
+    pub fn base(&self) -> &Arc<SharedBaseParser> {
+        &self.base
+    }
+
+    /// The number of rules of each category in the overlay
+    #[must_use]
+    pub fn overlay_rule_counts(&self) -> RuleCounts {
+        RuleCounts {
+            device: self.device_matchers.len(),
+            os: self.os_matchers.len(),
+            user_agent: self.user_agent_matchers.len(),
+        }
+    }
+
+    fn parse_untimed<'a>(&self, user_agent: &'a str) -> Client<'a> {
+        let base = &self.base.parser;
+        let mut client = normalized!(
+            base,
+            user_agent,
+            Client {
+                device: self.match_device(user_agent),
+                os: self.match_os(user_agent),
+                user_agent: self.match_user_agent(user_agent),
+            }
+        );
+        base.post_processors.apply(&mut client);
+        client
+    }
+
+    fn match_device<'a>(&self, user_agent: &'a str) -> Device<'a> {
+        let base = &self.base.parser;
+        if base.skips(user_agent) {
+            return Device::default();
+        }
+        select(
+            base.match_strategies.device,
+            self.device_matchers
+                .iter()
+                .filter_map(|matcher| matcher.try_parse(user_agent)),
+            device_specificity,
+        )
+        .unwrap_or_else(|| base.match_device(user_agent))
+    }
+
+    fn match_os<'a>(&self, user_agent: &'a str) -> OS<'a> {
+        let base = &self.base.parser;
+        if base.skips(user_agent) {
+            return OS::default();
+        }
+        match select(
+            base.match_strategies.os,
+            self.os_matchers
+                .iter()
+                .filter_map(|matcher| matcher.try_parse(user_agent)),
+            os_specificity,
+        ) {
+            Some(os) => base.darwin_os(user_agent, os),
+            None => base.match_os(user_agent),
+        }
+    }
+
+    fn match_user_agent<'a>(&self, user_agent: &'a str) -> UserAgent<'a> {
+        let base = &self.base.parser;
+        if base.skips(user_agent) {
+            return UserAgent::default();
+        }
+        select(
+            base.match_strategies.user_agent,
+            self.user_agent_matchers
+                .iter()
+                .filter_map(|matcher| matcher.try_parse(user_agent)),
+            user_agent_specificity,
+        )
+        .unwrap_or_else(|| base.match_user_agent(user_agent))
+    }
+}
+
+impl Parser for SharedBaseParser {
+    fn parse<'a>(&self, user_agent: &'a str) -> Client<'a> {
+        self.parser.parse(user_agent)
+    }
+
+    fn parse_device<'a>(&self, user_agent: &'a str) -> Device<'a> {
+        self.parser.parse_device(user_agent)
+    }
+
+    fn parse_os<'a>(&self, user_agent: &'a str) -> OS<'a> {
+        self.parser.parse_os(user_agent)
+    }
+
+    fn parse_user_agent<'a>(&self, user_agent: &'a str) -> UserAgent<'a> {
+        self.parser.parse_user_agent(user_agent)
+    }
+}
+
+impl Parser for TenantParser {
+    fn parse<'a>(&self, user_agent: &'a str) -> Client<'a> {
+        let Some(hook) = &self.base.parser.slow_parse else {
+            return self.parse_untimed(user_agent);
+        };
+        let start = Instant::now();
+        let client = self.parse_untimed(user_agent);
+        hook.observe(start.elapsed(), user_agent, &client);
+        client
+    }
+
+    fn parse_device<'a>(&self, user_agent: &'a str) -> Device<'a> {
+        normalized!(self.base.parser, user_agent, self.match_device(user_agent))
+    }
+
+    fn parse_os<'a>(&self, user_agent: &'a str) -> OS<'a> {
+        normalized!(self.base.parser, user_agent, self.match_os(user_agent))
+    }
+
+    fn parse_user_agent<'a>(&self, user_agent: &'a str) -> UserAgent<'a> {
+        normalized!(
+            self.base.parser,
+            user_agent,
+            self.match_user_agent(user_agent)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SELF_TEST_CASES;
+
+    const CORPUS: &str = include_str!("../../tests/fixtures/traffic.txt");
+
+    fn base() -> Arc<SharedBaseParser> {
+        Arc::new(SharedBaseParser::new(
+            UserAgentParser::from_yaml("./src/core/regexes.yaml")
+                .expect("Parser creation failed"),
+        ))
+    }
+
+    fn tenant(base: &Arc<SharedBaseParser>, brand: &str) -> TenantParser {
+        let overlay = serde_yaml::from_str(&format!(
+            r"
+device_parsers:
+  - regex: '; (SM-S\d+\w?)\)'
+    device_replacement: '{brand} $1'
+    brand_replacement: '{brand}'
+    model_replacement: '$1'
+"
+        ))
+        .unwrap();
+        TenantParser::new(Arc::clone(base), overlay).unwrap()
+    }
+
+    #[test]
+    fn tenants_get_their_own_overlays() {
+        let base = base();
+        let (first, second) = (tenant(&base, "Acme"), tenant(&base, "Globex"));
+        let user_agent = SELF_TEST_CASES[3].user_agent;
+
+        let client = first.parse(user_agent);
+        assert_eq!(client.device.brand.as_deref(), Some("Acme"));
+        assert_eq!(client.device.family, "Acme SM-S901B");
+        assert_eq!(client.user_agent.family, "Chrome Mobile");
+        assert_eq!(
+            second.parse_device(user_agent).brand.as_deref(),
+            Some("Globex")
+        );
+        assert_eq!(
+            base.parse_device(user_agent).brand.as_deref(),
+            Some("Samsung")
+        );
+
+        assert!(Arc::ptr_eq(first.base(), second.base()));
+        assert!(Arc::ptr_eq(first.base(), &base));
+        assert_eq!(
+            first.overlay_rule_counts(),
+            RuleCounts {
+                device: 1,
+                os: 0,
+                user_agent: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn other_user_agents_parse_as_the_base_does() {
+        let base = base();
+        let tenant = tenant(&base, "Acme");
+
+        for user_agent in CORPUS
+            .lines()
+            .chain(SELF_TEST_CASES.iter().map(|case| case.user_agent))
+            .chain(["", "   "])
+            .filter(|user_agent| !user_agent.contains("SM-S"))
+        {
+            assert_eq!(
+                tenant.parse(user_agent),
+                base.parse(user_agent),
+                "{user_agent}"
+            );
+            assert_eq!(tenant.parse_os(user_agent), base.parse_os(user_agent));
+        }
+    }
+}
@@ -0,0 +1,392 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex, PoisonError, Weak,
+    },
+    thread::{self, ThreadId},
+};
+
+use super::*;
+use crate::OwnedParser;
+
+/// Tells the caches of different `ThreadLocalCachingParser`s apart within a
+/// thread's storage
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    static CACHES: RefCell<HashMap<u64, ThreadCache>> = RefCell::new(HashMap::new());
+}
+
+/// Wraps a shared `UserAgentParser` with a small least recently used cache of
+/// full parse results on every thread that uses it, so pinned workers never
+/// contend on a cache shared between cores. Only `parse` and `parse_cached`
+/// go through the cache; the single category methods parse directly.
+///
+/// Results are cached behind an `Arc`, which `parse_cached` shares, while
+/// `parse` copies them. When the parser is dropped, the current thread drops
+/// its cache right away, and other threads on their next cache miss.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// # use uaparser::*;
+/// let parser = Arc::new(UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap());
+/// let cached = ThreadLocalCachingParser::new(parser, 1_024);
+///
+/// let client = cached.parse_cached("curl/8.4.0");
+/// assert_eq!(client.user_agent.family, "curl");
+/// assert_eq!(cached.parse_cached("curl/8.4.0"), client);
+/// assert_eq!(cached.per_thread_stats()[0].hits, 1);
+/// ```
+#[derive(Debug)]
+pub struct ThreadLocalCachingParser {
+    id: u64,
+    parser: Arc<UserAgentParser>,
+    capacity: usize,
+    shared: Arc<Shared>,
+}
+
+/// What the threads' caches report back to their parser
+#[derive(Debug, Default)]
+struct Shared {
+    threads: Mutex<Vec<Registered>>,
+}
+
+/// A thread with a cache for the parser
+#[derive(Debug)]
+struct Registered {
+    thread: ThreadId,
+    thread_name: Option<String>,
+    counters: Arc<Counters>,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    len: AtomicUsize,
+}
+
+/// The cache one thread keeps for one parser
+struct ThreadCache {
+    owner: Weak<Shared>,
+    counters: Arc<Counters>,
+    entries: HashMap<Box<str>, (Arc<Client<'static>>, u64)>,
+    /// Bumped on every lookup, stamping entries with when they were last used
+    clock: u64,
+}
+
+/// The cache activity of one thread, from
+/// `ThreadLocalCachingParser::per_thread_stats`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ThreadCacheStats {
+    pub thread: ThreadId,
+    pub thread_name: Option<String>,
+    pub hits: u64,
+    pub misses: u64,
+    /// The number of results currently cached
+    pub len: usize,
+}
+
+impl ThreadCacheStats {
+    /// The share of lookups answered from the cache, `0.0` before any
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+impl ThreadLocalCachingParser {
+    /// Caches up to `capacity` results on each thread. A capacity of 0
+    /// disables caching.
+    #[must_use]
+    pub fn new(
+        parser: Arc<UserAgentParser>,
+        capacity: usize,
+    ) -> ThreadLocalCachingParser {
+        ThreadLocalCachingParser {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            parser,
+            capacity,
+            shared: Arc::default(),
+        }
+    }
+
+    /// The wrapped parser
+    #[must_use]
+    pub fn parser(&self) -> &Arc<UserAgentParser> {
+        &self.parser
+    }
+
+    /// Parses `user_agent`, or shares the result cached on this thread by
+    /// an earlier parse of the same string
+    #[must_use]
+    pub fn parse_cached(&self, user_agent: &str) -> Arc<Client<'static>> {
+        if self.capacity == 0 {
+            return Arc::new(self.parser.parse(user_agent).into_owned());
+        }
+
+        CACHES.with(|caches| {
+            let mut caches = caches.borrow_mut();
+            let cache = caches.entry(self.id).or_insert_with(|| self.register());
+            if let Some(client) = cache.get(user_agent) {
+                return client;
+            }
+            let client = Arc::new(self.parser.parse(user_agent).into_owned());
+            cache.insert(user_agent, Arc::clone(&client), self.capacity);
+            // Drop the caches of parsers that are gone
+            caches.retain(|_, cache| cache.owner.strong_count() > 0);
+            client
+        })
+    }
+
+    /// The hits, misses and size of every thread's cache, in the order the
+    /// threads first parsed with this parser
+    #[must_use]
+    pub fn per_thread_stats(&self) -> Vec<ThreadCacheStats> {
+        self.shared
+            .threads
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|registered| ThreadCacheStats {
+                thread: registered.thread,
+                thread_name: registered.thread_name.clone(),
+                hits: registered.counters.hits.load(Ordering::Relaxed),
+                misses: registered.counters.misses.load(Ordering::Relaxed),
+                len: registered.counters.len.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
+    fn register(&self) -> ThreadCache {
+        let counters = Arc::<Counters>::default();
+        let current = thread::current();
+        self.shared
+            .threads
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Registered {
+                thread: current.id(),
+                thread_name: current.name().map(str::to_owned),
+                counters: Arc::clone(&counters),
+            });
+        ThreadCache {
+            owner: Arc::downgrade(&self.shared),
+            counters,
+            entries: HashMap::new(),
+            clock: 0,
+        }
+    }
+}
+
+impl Drop for ThreadLocalCachingParser {
+    fn drop(&mut self) {
+        // Fails only while the thread's storage is being torn down
+        let _ = CACHES.try_with(|caches| {
+            if let Ok(mut caches) = caches.try_borrow_mut() {
+                caches.remove(&self.id);
+            }
+        });
+    }
+}
+
+impl ThreadCache {
+    /// The cached result for `user_agent`, counting a hit or a miss
+    fn get(&mut self, user_agent: &str) -> Option<Arc<Client<'static>>> {
+        self.clock += 1;
+        if let Some((client, last_used)) = self.entries.get_mut(user_agent) {
+            *last_used = self.clock;
+            self.counters.hits.fetch_add(1, Ordering::Relaxed);
+            return Some(Arc::clone(client));
+        }
+        self.counters.misses.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    fn insert(
+        &mut self,
+        user_agent: &str,
+        client: Arc<Client<'static>>,
+        capacity: usize,
+    ) {
+        if self.entries.len() >= capacity {
+            // A linear scan, which is cheap at the small sizes this is meant for
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(user_agent.into(), (client, self.clock));
+        self.counters
+            .len
+            .store(self.entries.len(), Ordering::Relaxed);
+    }
+}
+
+impl Parser for ThreadLocalCachingParser {
+    fn parse<'a>(&self, user_agent: &'a str) -> Client<'a> {
+        Arc::unwrap_or_clone(self.parse_cached(user_agent))
+    }
+
+    fn parse_device<'a>(&self, user_agent: &'a str) -> Device<'a> {
+        self.parser.parse_device(user_agent)
+    }
+
+    fn parse_os<'a>(&self, user_agent: &'a str) -> OS<'a> {
+        self.parser.parse_os(user_agent)
+    }
+
+    fn parse_user_agent<'a>(&self, user_agent: &'a str) -> UserAgent<'a> {
+        self.parser.parse_user_agent(user_agent)
+    }
+}
+
+impl OwnedParser for ThreadLocalCachingParser {
+    fn parse_owned(&self, user_agent: &str) -> Client<'static> {
+        Arc::unwrap_or_clone(self.parse_cached(user_agent))
+    }
+
+    fn parse_device_owned(&self, user_agent: &str) -> Device<'static> {
+        self.parser.parse_device_owned(user_agent)
+    }
+
+    fn parse_os_owned(&self, user_agent: &str) -> OS<'static> {
+        self.parser.parse_os_owned(user_agent)
+    }
+
+    fn parse_user_agent_owned(&self, user_agent: &str) -> UserAgent<'static> {
+        self.parser.parse_user_agent_owned(user_agent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER_AGENTS: &[&str] = &[
+        "curl/8.4.0",
+        "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 \
+         (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36",
+        "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)",
+    ];
+
+    fn parser() -> Arc<UserAgentParser> {
+        Arc::new(
+            UserAgentParser::from_yaml("./src/core/regexes.yaml")
+                .expect("Parser creation failed"),
+        )
+    }
+
+    #[test]
+    fn threads_keep_their_own_caches() {
+        let parser = parser();
+        let cached = Arc::new(ThreadLocalCachingParser::new(Arc::clone(&parser), 16));
+
+        let workers: Vec<_> = ["first", "second"]
+            .iter()
+            .map(|name| {
+                let cached = Arc::clone(&cached);
+                thread::Builder::new()
+                    .name((*name).to_owned())
+                    .spawn(move || {
+                        for _ in 0..2 {
+                            for user_agent in USER_AGENTS {
+                                let _ = cached.parse_cached(user_agent);
+                            }
+                        }
+                    })
+                    .expect("Spawning failed")
+            })
+            .collect();
+        for worker in workers {
+            worker.join().expect("Worker panicked");
+        }
+
+        let mut stats = cached.per_thread_stats();
+        stats.sort_by(|a, b| a.thread_name.cmp(&b.thread_name));
+        assert_eq!(stats.len(), 2);
+        for (stats, name) in stats.iter().zip(["first", "second"]) {
+            assert_eq!(stats.thread_name.as_deref(), Some(name));
+            assert_eq!((stats.hits, stats.misses, stats.len), (3, 3, 3));
+            assert!((stats.hit_rate() - 0.5).abs() < f64::EPSILON);
+        }
+
+        for user_agent in USER_AGENTS {
+            assert_eq!(cached.parse(user_agent), parser.parse(user_agent));
+            assert_eq!(cached.parse_owned(user_agent), parser.parse(user_agent));
+        }
+    }
+
+    #[test]
+    fn least_recently_used_results_are_evicted() {
+        let cached = ThreadLocalCachingParser::new(parser(), 2);
+        for index in [0, 1, 0, 2, 0, 1] {
+            let _ = cached.parse_cached(USER_AGENTS[index]);
+        }
+
+        let stats = &cached.per_thread_stats()[0];
+        assert_eq!((stats.hits, stats.misses, stats.len), (2, 4, 2));
+
+        let uncached = ThreadLocalCachingParser::new(parser(), 0);
+        let _ = uncached.parse_cached(USER_AGENTS[0]);
+        assert!(uncached.per_thread_stats().is_empty());
+    }
+
+    fn cached_parsers() -> usize {
+        CACHES.with(|caches| caches.borrow().len())
+    }
+
+    #[test]
+    fn hits_share_results() {
+        let cached = ThreadLocalCachingParser::new(parser(), 4);
+
+        let client = cached.parse_cached(USER_AGENTS[0]);
+        assert!(Arc::ptr_eq(&cached.parse_cached(USER_AGENTS[0]), &client));
+        assert_eq!(cached.parse(USER_AGENTS[0]), *client);
+
+        assert_eq!(cached_parsers(), 1);
+        drop(cached);
+        assert_eq!(cached_parsers(), 0);
+        assert_eq!(Arc::strong_count(&client), 1);
+    }
+
+    #[test]
+    fn other_threads_drop_caches_on_their_next_miss() {
+        let parser = parser();
+        let dropped = Arc::new(ThreadLocalCachingParser::new(Arc::clone(&parser), 4));
+        let live = ThreadLocalCachingParser::new(parser, 4);
+        let (parsed, parsed_receiver) = std::sync::mpsc::channel();
+        let (gone, gone_receiver) = std::sync::mpsc::channel();
+
+        let worker = {
+            let dropped = Arc::clone(&dropped);
+            thread::spawn(move || {
+                let client = dropped.parse_cached(USER_AGENTS[0]);
+                drop(dropped);
+                parsed.send(()).expect("Receiver dropped");
+                gone_receiver.recv().expect("Sender dropped");
+                let held = Arc::strong_count(&client);
+                let _ = live.parse_cached(USER_AGENTS[1]);
+                (held, Arc::strong_count(&client), cached_parsers())
+            })
+        };
+        parsed_receiver.recv().expect("Sender dropped");
+        drop(dropped);
+        gone.send(()).expect("Receiver dropped");
+
+        let (held, after_miss, cached) = worker.join().expect("Worker panicked");
+        assert_eq!((held, after_miss, cached), (2, 1, 1));
+    }
+}
@@ -5,101 +5,257 @@ pub enum Error {
     Regex(regex::Error),
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+const FAMILY_REPLACEMENT: usize = 0;
+
+#[derive(Clone, Debug)]
 pub struct Matcher {
-    #[serde(with = "serde_regex")]
-    pub regex: regex::Regex,
-    pub family_replacement_has_group: bool,
-    pub family_replacement: Option<String>,
-    pub v1_replacement: Option<String>,
-    pub v2_replacement: Option<String>,
-    pub v3_replacement: Option<String>,
+    pub(super) regex: regex::Regex,
+    pub(super) has_group: GroupFlags,
+    pub(super) family_replacement: Option<Box<str>>,
+    pub(super) v1_replacement: Option<Box<str>>,
+    pub(super) v2_replacement: Option<Box<str>>,
+    pub(super) v3_replacement: Option<Box<str>>,
+    pub(super) v4_replacement: Option<Box<str>>,
+    pub(super) priority: i32,
+    pub(super) quality: Option<MatchQuality>,
+    pub(super) extras: Option<Box<Extras>>,
 }
 
 impl<'a> SubParser<'a> for Matcher {
     type Item = UserAgent<'a>;
 
     fn try_parse(&self, text: &'a str) -> Option<Self::Item> {
-        if let Some(captures) = self.regex.captures(text) {
-            let family: Cow<'a, str> =
-                if let Some(family_replacement) = &self.family_replacement {
-                    replace_cow(
-                        family_replacement,
-                        self.family_replacement_has_group,
-                        &captures,
-                    )
-                } else {
-                    captures
-                        .get(1)
-                        .map(|x| x.as_str())
-                        .and_then(none_if_empty)
-                        .map(Cow::Borrowed)?
-                };
-
-            let major: Option<Cow<'a, str>> = self
-                .v1_replacement
-                .as_ref()
-                .map(|x| Cow::Owned(x.clone()))
-                .or_else(|| {
-                    captures
-                        .get(2)
-                        .map(|x| x.as_str())
-                        .and_then(none_if_empty)
-                        .map(Cow::Borrowed)
-                });
-
-            let minor: Option<Cow<'a, str>> = self
-                .v2_replacement
-                .as_ref()
-                .map(|x| Cow::Owned(x.clone()))
-                .or_else(|| {
-                    captures
-                        .get(3)
-                        .map(|x| x.as_str())
-                        .and_then(none_if_empty)
-                        .map(Cow::Borrowed)
-                });
-
-            let patch: Option<Cow<'a, str>> = self
-                .v3_replacement
-                .as_ref()
-                .map(|x| Cow::Owned(x.clone()))
-                .or_else(|| {
-                    captures
-                        .get(4)
-                        .map(|x| x.as_str())
-                        .and_then(none_if_empty)
-                        .map(Cow::Borrowed)
-                });
-
-            Some(UserAgent {
-                family,
-                major,
-                minor,
-                patch,
-            })
+        let captures = self.captures(text)?;
+        self.parse_captures(&captures, intern)
+    }
+}
+
+impl Fill for Matcher {
+    fn regex(&self) -> &Regex {
+        &self.regex
+    }
+
+    fn fill(&self, groups: &Groups<'_>, fields: &mut FlatFields) -> bool {
+        if let Some(family_replacement) = &self.family_replacement {
+            fields.replace(
+                UA_FAMILY,
+                family_replacement,
+                self.has_group.contains(FAMILY_REPLACEMENT),
+                groups,
+            );
+        } else if let Some(family) = groups.get(1) {
+            fields.set(UA_FAMILY, Some(family));
         } else {
-            None
+            return false;
         }
+        fields.set(
+            UA_MAJOR,
+            self.v1_replacement.as_deref().or_else(|| groups.get(2)),
+        );
+        fields.set(
+            UA_MINOR,
+            self.v2_replacement.as_deref().or_else(|| groups.get(3)),
+        );
+        fields.set(
+            UA_PATCH,
+            self.v3_replacement.as_deref().or_else(|| groups.get(4)),
+        );
+        fields.set(
+            UA_PATCH_MINOR,
+            self.v4_replacement.as_deref().or_else(|| groups.get(5)),
+        );
+        true
     }
 }
 
 impl Matcher {
+    /// Like `try_parse`, but replacements without capture groups are borrowed
+    /// from the matcher rather than copied into the result
+    pub fn try_parse_borrowed<'a>(&'a self, text: &'a str) -> Option<UserAgent<'a>> {
+        let captures = self.captures(text)?;
+        self.parse_captures(&captures, Cow::Borrowed)
+    }
+
+    /// Like `try_parse`, but also reports where in `text` each captured field
+    /// was found
+    pub fn try_parse_with_spans<'a>(
+        &self,
+        text: &'a str,
+    ) -> Option<(UserAgent<'a>, UserAgentSpans)> {
+        let captures = self.captures(text)?;
+        let item = self.parse_captures(&captures, intern)?;
+        Some((item, self.spans(&captures)))
+    }
+
+    fn captures<'a>(&self, text: &'a str) -> Option<regex::Captures<'a>> {
+        self.regex.captures(text)
+    }
+
+    fn parse_captures<'a, 'm>(
+        &'m self,
+        captures: &regex::Captures<'a>,
+        literal: fn(&'m str) -> Cow<'a, str>,
+    ) -> Option<UserAgent<'a>> {
+        let family: Cow<'a, str> =
+            if let Some(family_replacement) = &self.family_replacement {
+                replace_cow(
+                    family_replacement,
+                    self.has_group.contains(FAMILY_REPLACEMENT),
+                    captures,
+                    literal,
+                )
+            } else {
+                captures
+                    .get(1)
+                    .map(|x| x.as_str())
+                    .and_then(none_if_empty)
+                    .map(Cow::Borrowed)?
+            };
+
+        let major: Option<Cow<'a, str>> =
+            self.v1_replacement.as_deref().map(literal).or_else(|| {
+                captures
+                    .get(2)
+                    .map(|x| x.as_str())
+                    .and_then(none_if_empty)
+                    .map(Cow::Borrowed)
+            });
+
+        let minor: Option<Cow<'a, str>> =
+            self.v2_replacement.as_deref().map(literal).or_else(|| {
+                captures
+                    .get(3)
+                    .map(|x| x.as_str())
+                    .and_then(none_if_empty)
+                    .map(Cow::Borrowed)
+            });
+
+        let patch: Option<Cow<'a, str>> =
+            self.v3_replacement.as_deref().map(literal).or_else(|| {
+                captures
+                    .get(4)
+                    .map(|x| x.as_str())
+                    .and_then(none_if_empty)
+                    .map(Cow::Borrowed)
+            });
+
+        let patch_minor: Option<Cow<'a, str>> =
+            self.v4_replacement.as_deref().map(literal).or_else(|| {
+                captures
+                    .get(5)
+                    .map(|x| x.as_str())
+                    .and_then(none_if_empty)
+                    .map(Cow::Borrowed)
+            });
+
+        Some(UserAgent {
+            family,
+            major,
+            minor,
+            patch,
+            patch_minor,
+        })
+    }
+
+    fn spans(&self, captures: &regex::Captures) -> UserAgentSpans {
+        UserAgentSpans {
+            family: capture_span(captures, 1)
+                .filter(|_| self.family_replacement.is_none()),
+            major: capture_span(captures, 2).filter(|_| self.v1_replacement.is_none()),
+            minor: capture_span(captures, 3).filter(|_| self.v2_replacement.is_none()),
+            patch: capture_span(captures, 4).filter(|_| self.v3_replacement.is_none()),
+            patch_minor: capture_span(captures, 5)
+                .filter(|_| self.v4_replacement.is_none()),
+        }
+    }
+}
+
+impl Matcher {
+    /// Compiles a rule file entry, which is the only way to build a matcher
     pub fn try_from(entry: UserAgentParserEntry) -> Result<Matcher, Error> {
-        let regex = regex::RegexBuilder::new(&clean_escapes(&entry.regex))
-            .size_limit(20 * (1 << 20))
-            .build();
+        Matcher::compile(entry, true)
+    }
+
+    pub(super) fn compile(
+        entry: UserAgentParserEntry,
+        clean: bool,
+    ) -> Result<Matcher, Error> {
+        let regex = compile_pattern(&entry.regex, clean);
 
         Ok(Matcher {
             regex: regex?,
-            family_replacement_has_group: entry
+            priority: entry.priority.unwrap_or_default(),
+            quality: entry.quality,
+            extras: into_extras(entry.extras),
+            has_group: GroupFlags::from_replacements(&[entry
                 .family_replacement
-                .as_ref()
-                .map_or(false, |x| has_group(x.as_str())),
-            family_replacement: entry.family_replacement,
-            v1_replacement: entry.v1_replacement,
-            v2_replacement: entry.v2_replacement,
-            v3_replacement: entry.v3_replacement,
+                .as_deref()]),
+            family_replacement: into_boxed(entry.family_replacement),
+            v1_replacement: into_boxed(entry.v1_replacement),
+            v2_replacement: into_boxed(entry.v2_replacement),
+            v3_replacement: into_boxed(entry.v3_replacement),
+            v4_replacement: into_boxed(entry.v4_replacement),
         })
     }
+
+    /// The regular expression this matcher runs, as compiled
+    #[must_use]
+    pub fn pattern(&self) -> &str {
+        self.regex.as_str()
+    }
+
+    /// The template for `UserAgent::family`
+    #[must_use]
+    pub fn family_replacement(&self) -> Option<&str> {
+        self.family_replacement.as_deref()
+    }
+
+    /// The replacement for `UserAgent::major`
+    #[must_use]
+    pub fn v1_replacement(&self) -> Option<&str> {
+        self.v1_replacement.as_deref()
+    }
+
+    /// The replacement for `UserAgent::minor`
+    #[must_use]
+    pub fn v2_replacement(&self) -> Option<&str> {
+        self.v2_replacement.as_deref()
+    }
+
+    /// The replacement for `UserAgent::patch`
+    #[must_use]
+    pub fn v3_replacement(&self) -> Option<&str> {
+        self.v3_replacement.as_deref()
+    }
+
+    /// The replacement for `UserAgent::patch_minor`
+    #[must_use]
+    pub fn v4_replacement(&self) -> Option<&str> {
+        self.v4_replacement.as_deref()
+    }
+
+    /// The rule's `priority`, with higher priorities tried first
+    #[must_use]
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// The rule's `quality` override, as used by `parse_scored`
+    #[must_use]
+    pub fn quality(&self) -> Option<MatchQuality> {
+        self.quality
+    }
+
+    /// The keys of the rule's entry this crate doesn't know, such as
+    /// annotations for the caller's own use
+    #[must_use]
+    pub fn extras(&self) -> &BTreeMap<String, serde_yaml::Value> {
+        self.extras.as_deref().unwrap_or(&NO_EXTRAS)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn family_replacement_has_group(&self) -> bool {
+        self.has_group.contains(FAMILY_REPLACEMENT)
+    }
 }
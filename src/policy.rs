@@ -0,0 +1,171 @@
+//! Checks parsed browsers against a minimum version per family, for warning
+//! users whose browser is older than what a product supports.
+//!
+//! ```rust
+//! # use uaparser::{policy::{BrowserPolicy, PolicyDecision}, Parser, UserAgentParser, Version};
+//! let policy: BrowserPolicy = serde_yaml::from_str("
+//! minimums:
+//!   Chrome: 100
+//!   Firefox: '115.0'
+//! ").unwrap();
+//!
+//! let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+//! let client = parser.parse("Mozilla/5.0 (X11; Linux x86_64; rv:109.0) Gecko/20100101 Firefox/109.0");
+//!
+//! assert_eq!(
+//!     policy.check(&client),
+//!     PolicyDecision::Outdated {
+//!         required: Version::new(115, 0, 0),
+//!         found: Version::new(109, 0, 0),
+//!     }
+//! );
+//! ```
+
+use std::collections::BTreeMap;
+
+use super::{Client, Deserialize, Serialize, Version};
+
+/// The outcome of checking a client against a `BrowserPolicy`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum PolicyDecision {
+    /// The browser is at or above its family's minimum version
+    Supported,
+    /// The browser is below its family's minimum version
+    Outdated { required: Version, found: Version },
+    /// The family has no minimum, or the browser's version isn't numeric
+    Unknown,
+}
+
+/// Minimum browser versions by user agent family, such as `Chrome` or
+/// `Mobile Safari`. Families match in any case. Deserializes from a
+/// `minimums` map of family to version, with an optional `allow_unknown`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BrowserPolicy {
+    #[serde(default)]
+    minimums: BTreeMap<String, Version>,
+    #[serde(default)]
+    allow_unknown: bool,
+}
+
+impl BrowserPolicy {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requires at least `minimum` of `family`
+    #[must_use]
+    pub fn require(mut self, family: impl Into<String>, minimum: Version) -> Self {
+        self.minimums.insert(family.into(), minimum);
+        self
+    }
+
+    /// Whether `allows` lets through browsers the policy can't decide on,
+    /// which it doesn't by default
+    #[must_use]
+    pub fn allow_unknown(mut self, allow: bool) -> Self {
+        self.allow_unknown = allow;
+        self
+    }
+
+    #[must_use]
+    pub fn check(&self, client: &Client<'_>) -> PolicyDecision {
+        let family = &client.user_agent.family;
+        let required = self.minimums.get(&**family).or_else(|| {
+            self.minimums
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(family))
+                .map(|(_, minimum)| minimum)
+        });
+        let (Some(&required), Some(found)) = (required, client.user_agent.version())
+        else {
+            return PolicyDecision::Unknown;
+        };
+
+        if found < required {
+            PolicyDecision::Outdated { required, found }
+        } else {
+            PolicyDecision::Supported
+        }
+    }
+
+    /// Whether `client` passes the policy, with `Unknown` decisions passing
+    /// only when the policy allows unknown browsers
+    #[must_use]
+    pub fn allows(&self, client: &Client<'_>) -> bool {
+        match self.check(client) {
+            PolicyDecision::Supported => true,
+            PolicyDecision::Outdated { .. } => false,
+            PolicyDecision::Unknown => self.allow_unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+    use crate::UserAgent;
+
+    fn client(family: &'static str, major: Option<&'static str>) -> Client<'static> {
+        Client {
+            user_agent: UserAgent {
+                family: Cow::Borrowed(family),
+                major: major.map(Cow::Borrowed),
+                ..UserAgent::default()
+            },
+            ..Client::default()
+        }
+    }
+
+    fn policy() -> BrowserPolicy {
+        BrowserPolicy::new().require("Chrome", Version::new(100, 0, 0))
+    }
+
+    #[test]
+    fn versions_compare_numerically() {
+        assert_eq!(
+            policy().check(&client("Chrome", Some("99"))),
+            PolicyDecision::Outdated {
+                required: Version::new(100, 0, 0),
+                found: Version::new(99, 0, 0),
+            }
+        );
+        assert_eq!(
+            policy().check(&client("Chrome", Some("120"))),
+            PolicyDecision::Supported
+        );
+        assert_eq!(
+            policy().check(&client("chrome", Some("100"))),
+            PolicyDecision::Supported
+        );
+        assert!(!policy().allows(&client("Chrome", Some("99"))));
+    }
+
+    #[test]
+    fn unknown_follows_the_configured_default() {
+        let obscure = client("Obscura", Some("3"));
+        let versionless = client("Chrome", None);
+
+        for client in [&obscure, &versionless] {
+            assert_eq!(policy().check(client), PolicyDecision::Unknown);
+            assert!(!policy().allows(client));
+            assert!(policy().allow_unknown(true).allows(client));
+        }
+    }
+
+    #[test]
+    fn deserializes_from_yaml() {
+        let policy: BrowserPolicy = serde_yaml::from_str(
+            "
+minimums:
+  Chrome: 100
+allow_unknown: true
+",
+        )
+        .expect("Deserialization failed");
+
+        assert_eq!(policy, self::policy().allow_unknown(true));
+    }
+}
@@ -0,0 +1,329 @@
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+use std::{borrow::Cow, collections::HashMap, fmt};
+
+use serde::Deserialize;
+
+#[cfg(not(target_family = "wasm"))]
+use super::Error;
+use super::{Client, YamlError};
+
+/// Rewrites a parsed `Client` after the regex rules have run. Closures taking
+/// `&mut Client` implement this trait, so most fixups can be registered
+/// inline with `UserAgentParser::add_post_processor`.
+pub trait PostProcessor: Send + Sync {
+    fn process(&self, client: &mut Client<'_>);
+}
+
+impl<F> PostProcessor for F
+where
+    F: Fn(&mut Client<'_>) + Send + Sync,
+{
+    fn process(&self, client: &mut Client<'_>) {
+        self(client);
+    }
+}
+
+/// The post processors registered on a parser, applied in registration order
+#[derive(Default)]
+pub struct PostProcessors(Vec<Box<dyn PostProcessor>>);
+
+impl PostProcessors {
+    pub(crate) fn push(&mut self, processor: Box<dyn PostProcessor>) {
+        self.0.push(processor);
+    }
+
+    pub(crate) fn apply(&self, client: &mut Client<'_>) {
+        for processor in &self.0 {
+            processor.process(client);
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Debug for PostProcessors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PostProcessors")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
+
+/// Renames families, e.g. reporting `Chrome Mobile WebView` as `Chrome`,
+/// with separate aliases for user agent, OS and device families. Aliases can
+/// be loaded from YAML mapping each alias to its canonical family:
+///
+/// ```yaml
+/// user_agent:
+///   Chrome Mobile: Chrome
+///   Chromium: Chrome
+/// os:
+///   Mac OS X: macOS
+/// ```
+///
+/// Families without an alias pass through unchanged.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FamilyAliases {
+    #[serde(default)]
+    user_agent: HashMap<String, String>,
+    #[serde(default)]
+    os: HashMap<String, String>,
+    #[serde(default)]
+    device: HashMap<String, String>,
+}
+
+impl FamilyAliases {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads aliases from YAML with `user_agent`, `os` and `device` sections,
+    /// any of which may be left out
+    pub fn from_bytes(bytes: &[u8]) -> Result<FamilyAliases, YamlError> {
+        Ok(serde_yaml::from_slice(bytes)?)
+    }
+
+    /// Reads aliases from the YAML file at `path`, as `from_bytes`
+    #[cfg(not(target_family = "wasm"))]
+    pub fn from_yaml(path: &str) -> Result<FamilyAliases, Error> {
+        let bytes = std::fs::read(path)?;
+        Ok(FamilyAliases::from_bytes(&bytes)?)
+    }
+
+    /// Reports the user agent family `from` as `to`
+    #[must_use]
+    pub fn alias(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.user_agent.insert(from.into(), to.into());
+        self
+    }
+
+    /// Reports the OS family `from` as `to`
+    #[must_use]
+    pub fn os_alias(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.os.insert(from.into(), to.into());
+        self
+    }
+
+    /// Reports the device family `from` as `to`
+    #[must_use]
+    pub fn device_alias(
+        mut self,
+        from: impl Into<String>,
+        to: impl Into<String>,
+    ) -> Self {
+        self.device.insert(from.into(), to.into());
+        self
+    }
+
+    /// Replaces the aliased families of `client` with their canonical ones
+    pub fn apply(&self, client: &mut Client<'_>) {
+        canonicalize(&self.user_agent, &mut client.user_agent.family);
+        canonicalize(&self.os, &mut client.os.family);
+        canonicalize(&self.device, &mut client.device.family);
+    }
+
+    /// The canonical user agent family of `client`, leaving it unchanged
+    #[must_use]
+    pub fn canonical_ua_family<'s>(&'s self, client: &'s Client<'_>) -> &'s str {
+        canonical(&self.user_agent, &client.user_agent.family)
+    }
+
+    /// The canonical OS family of `client`, leaving it unchanged
+    #[must_use]
+    pub fn canonical_os_family<'s>(&'s self, client: &'s Client<'_>) -> &'s str {
+        canonical(&self.os, &client.os.family)
+    }
+
+    /// The canonical device family of `client`, leaving it unchanged
+    #[must_use]
+    pub fn canonical_device_family<'s>(&'s self, client: &'s Client<'_>) -> &'s str {
+        canonical(&self.device, &client.device.family)
+    }
+}
+
+fn canonical<'s>(aliases: &'s HashMap<String, String>, family: &'s str) -> &'s str {
+    aliases.get(family).map_or(family, String::as_str)
+}
+
+fn canonicalize(aliases: &HashMap<String, String>, family: &mut Cow<'_, str>) {
+    if let Some(to) = aliases.get(&**family) {
+        *family = Cow::Owned(to.clone());
+    }
+}
+
+impl PostProcessor for FamilyAliases {
+    fn process(&self, client: &mut Client<'_>) {
+        self.apply(client);
+    }
+}
+
+/// Rewrites device brands written in all capitals, such as `SAMSUNG`, to
+/// title case. Brands with any lowercase letter are left alone.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CapitalizeBrand;
+
+impl PostProcessor for CapitalizeBrand {
+    fn process(&self, client: &mut Client<'_>) {
+        let brand = match &client.device.brand {
+            Some(brand) if brand.chars().any(char::is_uppercase) => brand,
+            _ => return,
+        };
+        if brand.chars().any(char::is_lowercase) {
+            return;
+        }
+
+        let mut capitalized = String::with_capacity(brand.len());
+        let mut word_start = true;
+        for c in brand.chars() {
+            if word_start {
+                capitalized.push(c);
+            } else {
+                capitalized.extend(c.to_lowercase());
+            }
+            word_start = !c.is_alphanumeric();
+        }
+        client.device.brand = Some(Cow::Owned(capitalized));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Device, Parser, UserAgentParser};
+
+    const WEBVIEW: &str =
+        "Mozilla/5.0 (Linux; Android 13; SM-S901B; wv) AppleWebKit/537.36 \
+         (KHTML, like Gecko) Version/4.0 Chrome/120.0.6099.144 Mobile Safari/537.36";
+
+    #[test]
+    fn family_aliases() {
+        let mut parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        assert_eq!(
+            parser.parse(WEBVIEW).user_agent.family,
+            "Chrome Mobile WebView"
+        );
+
+        parser.add_post_processor(
+            FamilyAliases::new().alias("Chrome Mobile WebView", "Chrome"),
+        );
+
+        assert_eq!(parser.parse(WEBVIEW).user_agent.family, "Chrome");
+        assert_eq!(parser.parse_borrowed(WEBVIEW).user_agent.family, "Chrome");
+        // Only the full parse runs post processors
+        assert_eq!(
+            parser.parse_user_agent(WEBVIEW).family,
+            "Chrome Mobile WebView"
+        );
+    }
+
+    #[test]
+    fn family_aliases_load_from_yaml() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let aliases = FamilyAliases::from_yaml("./tests/fixtures/family_aliases.yaml")
+            .expect("Loading aliases failed");
+
+        let mut webview = parser.parse(WEBVIEW);
+        assert_eq!(aliases.canonical_ua_family(&webview), "Chrome");
+        assert_eq!(aliases.canonical_os_family(&webview), "Android");
+        assert_eq!(webview.user_agent.family, "Chrome Mobile WebView");
+        aliases.apply(&mut webview);
+        assert_eq!(webview.user_agent.family, "Chrome");
+
+        let mut firefox = parser.parse(
+            "Mozilla/5.0 (Macintosh; Intel Mac OS X 10.15; rv:121.0) Gecko/20100101 \
+             Firefox/121.0",
+        );
+        let unchanged = firefox.clone();
+        assert_eq!(aliases.canonical_ua_family(&firefox), "Firefox");
+        assert_eq!(aliases.canonical_device_family(&firefox), "Apple Mac");
+        aliases.apply(&mut firefox);
+        assert_eq!(firefox.user_agent, unchanged.user_agent);
+        assert_eq!(firefox.os.family, "macOS");
+        assert_eq!(firefox.device.family, "Apple Mac");
+
+        let mobile = parser.parse(
+            "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 \
+             (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36",
+        );
+        assert_eq!(mobile.user_agent.family, "Chrome Mobile");
+        assert_eq!(aliases.canonical_ua_family(&mobile), "Chrome");
+
+        assert!(FamilyAliases::from_bytes(b"browser:\n  Foo: Bar\n").is_err());
+        assert_eq!(
+            FamilyAliases::from_bytes(b"os: {}")
+                .map(|aliases| aliases.user_agent.len())
+                .ok(),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn processors_run_in_order() {
+        let mut parser = UserAgentParser::from_bytes(
+            br"
+user_agent_parsers:
+  - regex: '(Foo)/(\d+)'
+os_parsers: []
+device_parsers:
+  - regex: '(SAMSUNG-LTE) (\S+)'
+    brand_replacement: 'SAMSUNG-LTE'
+    model_replacement: '$2'
+",
+        )
+        .expect("Parser creation failed");
+        parser.add_post_processor(CapitalizeBrand);
+        parser.add_post_processor(|client: &mut Client<'_>| {
+            if let Some(brand) = &client.device.brand {
+                client.device.family = Cow::Owned(format!("{brand} device"));
+            }
+        });
+
+        let client = parser.parse("Foo/1 SAMSUNG-LTE SM-T595");
+        assert_eq!(
+            client.device,
+            Device {
+                family: Cow::Borrowed("Samsung-Lte device"),
+                brand: Some(Cow::Borrowed("Samsung-Lte")),
+                model: Some(Cow::Borrowed("SM-T595")),
+            }
+        );
+        assert_eq!(
+            parser
+                .parse_device("Foo/1 SAMSUNG-LTE SM-T595")
+                .brand
+                .unwrap(),
+            "SAMSUNG-LTE"
+        );
+    }
+
+    #[test]
+    fn untouched_fields_stay_borrowed() {
+        let mut parser = UserAgentParser::from_bytes(
+            br"
+user_agent_parsers:
+  - regex: '(Foo)/(\d+)'
+os_parsers: []
+device_parsers: []
+",
+        )
+        .expect("Parser creation failed");
+        parser.add_post_processor(FamilyAliases::new().alias("Bar", "Baz"));
+        parser.add_post_processor(CapitalizeBrand);
+
+        let client = parser.parse("Foo/1");
+        assert!(matches!(client.user_agent.family, Cow::Borrowed("Foo")));
+    }
+}
@@ -0,0 +1,182 @@
+//! proptest strategies for the owned parse results, drawing plausible
+//! families and version numbers with most optional fields left `None`, and
+//! for synthetic user agent strings to test the parser itself.
+//!
+//! ```rust
+//! use proptest::{
+//!     strategy::{Strategy, ValueTree},
+//!     test_runner::TestRunner,
+//! };
+//!
+//! let mut runner = TestRunner::default();
+//! let client = uaparser::proptest::client()
+//!     .new_tree(&mut runner)
+//!     .unwrap()
+//!     .current();
+//!
+//! assert!(!client.user_agent.family.is_empty());
+//! ```
+
+use std::borrow::Cow;
+
+use ::proptest::{collection, option, prelude::*, sample};
+
+use crate::{
+    synthetic::{
+        compose, Token, BRANDS, COMMENTS, DEVICE_FAMILIES, MAX_TOKENS, MODELS, NONE_ODDS,
+        NONE_OUT_OF, OS_FAMILIES, PRODUCTS, USER_AGENT_FAMILIES, VERSION_LIMITS,
+    },
+    Client, Device, UserAgent, OS,
+};
+
+pub fn device() -> impl Strategy<Value = Device<'static>> {
+    (
+        pick(DEVICE_FAMILIES),
+        optional(pick(BRANDS)),
+        optional(pick(MODELS)),
+    )
+        .prop_map(|(family, brand, model)| Device {
+            family,
+            brand,
+            model,
+        })
+}
+
+pub fn os() -> impl Strategy<Value = OS<'static>> {
+    (pick(OS_FAMILIES), version_strings(&VERSION_LIMITS)).prop_map(
+        |(family, versions)| {
+            let mut versions = versions.into_iter();
+            OS {
+                family,
+                major: versions.next().flatten(),
+                minor: versions.next().flatten(),
+                patch: versions.next().flatten(),
+                patch_minor: versions.next().flatten(),
+            }
+        },
+    )
+}
+
+pub fn user_agent() -> impl Strategy<Value = UserAgent<'static>> {
+    (pick(USER_AGENT_FAMILIES), version_strings(&VERSION_LIMITS)).prop_map(
+        |(family, versions)| {
+            let mut versions = versions.into_iter();
+            UserAgent {
+                family,
+                major: versions.next().flatten(),
+                minor: versions.next().flatten(),
+                patch: versions.next().flatten(),
+                patch_minor: versions.next().flatten(),
+            }
+        },
+    )
+}
+
+pub fn client() -> impl Strategy<Value = Client<'static>> {
+    (device(), os(), user_agent()).prop_map(|(device, os, user_agent)| Client {
+        device,
+        os,
+        user_agent,
+    })
+}
+
+/// User agent strings made of product tokens, such as
+/// `Mozilla/5.0 (X11; Linux x86_64) Firefox/115.0`, with the odd run of
+/// arbitrary text appended
+pub fn synthetic_user_agent() -> impl Strategy<Value = String> {
+    let token = (
+        sample::select(PRODUCTS),
+        versions(&VERSION_LIMITS),
+        optional(sample::select(COMMENTS)),
+    );
+    (
+        collection::vec(token, 1..=MAX_TOKENS),
+        option::weighted(0.125, "\\PC{0,16}"),
+    )
+        .prop_map(|(tokens, noise)| {
+            let tokens: Vec<_> = tokens
+                .into_iter()
+                .map(|(product, version, comment)| Token {
+                    product,
+                    version: version.into_iter().flatten().collect(),
+                    comment,
+                })
+                .collect();
+            compose(&tokens, noise.as_deref())
+        })
+}
+
+fn pick(words: &'static [&'static str]) -> impl Strategy<Value = Cow<'static, str>> {
+    sample::select(words).prop_map(Cow::Borrowed)
+}
+
+fn optional<S: Strategy>(strategy: S) -> impl Strategy<Value = Option<S::Value>> {
+    option::weighted(
+        f64::from(NONE_OUT_OF - NONE_ODDS) / f64::from(NONE_OUT_OF),
+        strategy,
+    )
+}
+
+/// Version numbers below each of `limits`, where every one after the first
+/// `None` is `None` too, as a parser would give
+fn versions(limits: &[u32]) -> impl Strategy<Value = Vec<Option<u32>>> {
+    limits
+        .iter()
+        .map(|&limit| optional(0..limit))
+        .collect::<Vec<_>>()
+        .prop_map(|versions| {
+            let present = versions
+                .iter()
+                .take_while(|version| version.is_some())
+                .count();
+            versions
+                .into_iter()
+                .enumerate()
+                .map(|(index, version)| version.filter(|_| index < present))
+                .collect()
+        })
+}
+
+fn version_strings(
+    limits: &[u32],
+) -> impl Strategy<Value = Vec<Option<Cow<'static, str>>>> {
+    versions(limits).prop_map(|versions| {
+        versions
+            .into_iter()
+            .map(|version| version.map(|version| Cow::Owned(version.to_string())))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::LazyLock;
+
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{Parser, UserAgentParser};
+
+    static PARSER: LazyLock<UserAgentParser> = LazyLock::new(|| {
+        UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed")
+    });
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn parsing_synthetic_user_agents_never_panics(user_agent in synthetic_user_agent()) {
+            PARSER.parse(&user_agent);
+        }
+
+        #[test]
+        fn clients_round_trip(client in client()) {
+            let yaml = serde_yaml::to_string(&client).expect("Serialization failed");
+            let parsed = Client::deserialize(serde_yaml::Deserializer::from_str(&yaml))
+                .expect("Deserialization failed");
+            prop_assert_eq!(parsed, client.clone());
+            prop_assert!(client.os.major.is_some() || client.os.minor.is_none());
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! Summarizes the browsers and operating systems in a corpus of user agents,
+//! such as a week of access logs, as counts and percentages.
+//!
+//! ```rust
+//! # use uaparser::*;
+//! let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+//! let report = uaparser::report::summarize(&parser, ["curl/8.4.0", "curl/8.4.0"].iter());
+//!
+//! assert_eq!(report.total, 2);
+//! assert_eq!(report.browsers[0].name, "curl");
+//! assert_eq!(report.browsers[0].percent, 100.0);
+//! ```
+
+use std::{collections::HashMap, fmt};
+
+use super::{Deserialize, Serialize};
+use crate::{families, Parser, UserAgentParser};
+
+/// Controls the unmatched examples kept by `summarize_with_options`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReportOptions {
+    /// The number of distinct unmatched user agents kept, most frequent first
+    pub max_unmatched: usize,
+}
+
+impl Default for ReportOptions {
+    fn default() -> Self {
+        Self { max_unmatched: 10 }
+    }
+}
+
+/// The breakdown of a corpus produced by `summarize`. Serializes as is, such
+/// as to JSON, and displays as plain-text tables.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct TrafficReport {
+    /// The number of user agents summarized
+    pub total: u64,
+    /// The number of different user agents among them, each parsed once
+    pub distinct: u64,
+    /// By user agent family
+    pub browsers: Vec<Share>,
+    /// By user agent family and major version, such as `Chrome 120`
+    pub browser_versions: Vec<Share>,
+    /// By OS family
+    pub os: Vec<Share>,
+    /// The user agents no user agent rule matched
+    pub unmatched: Unmatched,
+}
+
+/// The user agents with one value, such as a browser family. Every list in
+/// a `TrafficReport` is ordered by descending count, then by name.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Share {
+    pub name: String,
+    pub count: u64,
+    /// The share of the total, from 0 to 100
+    pub percent: f64,
+}
+
+/// The user agents the rules gave the `Other` user agent family
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct Unmatched {
+    pub count: u64,
+    pub percent: f64,
+    /// The most frequent ones, up to `ReportOptions::max_unmatched`
+    pub top: Vec<UnmatchedAgent>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, Hash, PartialEq)]
+pub struct UnmatchedAgent {
+    pub user_agent: String,
+    pub count: u64,
+}
+
+/// Parses the user agents into a `TrafficReport`, under the default
+/// `ReportOptions`
+pub fn summarize<I>(parser: &UserAgentParser, user_agents: I) -> TrafficReport
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    summarize_with_options(parser, user_agents, &ReportOptions::default())
+}
+
+/// Like `summarize`, with control over the unmatched examples kept. User
+/// agents are counted before they are parsed, so each distinct one is only
+/// parsed once, however often it repeats.
+pub fn summarize_with_options<I>(
+    parser: &UserAgentParser,
+    user_agents: I,
+    options: &ReportOptions,
+) -> TrafficReport
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    let mut distinct: HashMap<String, u64> = HashMap::new();
+    let mut total = 0;
+    for user_agent in user_agents {
+        let user_agent = user_agent.as_ref();
+        total += 1;
+        match distinct.get_mut(user_agent) {
+            Some(count) => *count += 1,
+            None => {
+                distinct.insert(user_agent.to_owned(), 1);
+            }
+        }
+    }
+
+    let mut browsers = HashMap::new();
+    let mut browser_versions = HashMap::new();
+    let mut os = HashMap::new();
+    let mut unmatched = Vec::new();
+    for (user_agent, &count) in &distinct {
+        let client = parser.parse(user_agent);
+        let family = client.user_agent.family.as_ref();
+        let version = match &client.user_agent.major {
+            Some(major) => format!("{family} {major}"),
+            None => family.to_owned(),
+        };
+        *browsers.entry(family.to_owned()).or_insert(0) += count;
+        *browser_versions.entry(version).or_insert(0) += count;
+        *os.entry(client.os.family.into_owned()).or_insert(0) += count;
+        if family == families::OTHER {
+            unmatched.push(UnmatchedAgent {
+                user_agent: user_agent.clone(),
+                count,
+            });
+        }
+    }
+
+    unmatched.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.user_agent.cmp(&b.user_agent))
+    });
+    let unmatched_count = unmatched.iter().map(|agent| agent.count).sum();
+    unmatched.truncate(options.max_unmatched);
+
+    TrafficReport {
+        total,
+        distinct: distinct.len() as u64,
+        browsers: shares(browsers, total),
+        browser_versions: shares(browser_versions, total),
+        os: shares(os, total),
+        unmatched: Unmatched {
+            count: unmatched_count,
+            percent: percent(unmatched_count, total),
+            top: unmatched,
+        },
+    }
+}
+
+fn shares(counts: HashMap<String, u64>, total: u64) -> Vec<Share> {
+    let mut shares: Vec<_> = counts
+        .into_iter()
+        .map(|(name, count)| Share {
+            name,
+            count,
+            percent: percent(count, total),
+        })
+        .collect();
+    shares.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    shares
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn percent(count: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 * 100.0 / total as f64
+    }
+}
+
+impl fmt::Display for TrafficReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} user agents, {} distinct", self.total, self.distinct)?;
+        table(f, "Browser", &self.browsers)?;
+        table(f, "Browser version", &self.browser_versions)?;
+        table(f, "OS", &self.os)?;
+        write!(
+            f,
+            "\n\nUnmatched: {} ({:.1}%)",
+            self.unmatched.count, self.unmatched.percent
+        )?;
+        for agent in &self.unmatched.top {
+            write!(f, "\n{:>8}  {}", agent.count, agent.user_agent)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes a blank line, then one row per share under a header, with the
+/// name column as wide as the longest name
+fn table(f: &mut fmt::Formatter<'_>, header: &str, shares: &[Share]) -> fmt::Result {
+    let width = shares
+        .iter()
+        .map(|share| share.name.chars().count())
+        .chain(Some(header.len()))
+        .max()
+        .unwrap_or_default();
+    write!(f, "\n\n{header:<width$}  {:>8}  {:>6}", "Count", "%")?;
+    for share in shares {
+        write!(
+            f,
+            "\n{:<width$}  {:>8}  {:>6.1}",
+            share.name, share.count, share.percent
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CORPUS: &str = include_str!("../tests/fixtures/traffic.txt");
+
+    fn parser() -> UserAgentParser {
+        UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed")
+    }
+
+    #[test]
+    fn summarizes_fixture_corpus() {
+        let report = summarize(&parser(), CORPUS.lines());
+
+        assert_eq!(report.total, 20);
+        assert_eq!(report.distinct, 13);
+        assert_eq!(report.browsers[0].name, "Chrome");
+        assert_eq!(report.browsers[0].count, 6);
+        assert!((report.browsers[0].percent - 30.0).abs() < 1e-9);
+        assert_eq!(report.browser_versions[1].name, "Chrome 120");
+        assert_eq!(report.browser_versions[1].count, 4);
+        assert_eq!(report.os[1].name, "Windows");
+        assert_eq!(report.os[1].count, 6);
+        assert_eq!(report.unmatched.count, 5);
+        assert_eq!(
+            report.unmatched.top[0],
+            UnmatchedAgent {
+                user_agent: "totally-unknown-agent".to_owned(),
+                count: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn caps_unmatched_examples() {
+        let options = ReportOptions { max_unmatched: 1 };
+        let report = summarize_with_options(&parser(), CORPUS.lines(), &options);
+
+        assert_eq!(report.unmatched.count, 5);
+        assert_eq!(report.unmatched.top.len(), 1);
+    }
+
+    #[test]
+    fn displays_tables() {
+        let report = summarize(&parser(), ["curl/8.4.0", "-"].iter());
+
+        assert_eq!(
+            report.to_string(),
+            "2 user agents, 2 distinct\n\
+             \n\
+             Browser     Count       %\n\
+             Other           1    50.0\n\
+             curl            1    50.0\n\
+             \n\
+             Browser version     Count       %\n\
+             Other                   1    50.0\n\
+             curl 8                  1    50.0\n\
+             \n\
+             OS        Count       %\n\
+             Other         2   100.0\n\
+             \n\
+             Unmatched: 1 (50.0%)\n       \
+             1  -"
+        );
+
+        let round_trip: TrafficReport =
+            serde_yaml::from_str(&serde_yaml::to_string(&report).unwrap()).unwrap();
+        assert_eq!(round_trip, report);
+    }
+}
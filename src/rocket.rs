@@ -0,0 +1,63 @@
+//! A Rocket request guard that parses the `User-Agent` header with a parser
+//! kept in managed state.
+//!
+//! ```rust,no_run
+//! # use std::sync::Arc;
+//! # use uaparser::{rocket::UserAgentInfo, UserAgentParser};
+//! #[rocket::get("/")]
+//! fn index(user_agent: UserAgentInfo) -> String {
+//!     user_agent.0.user_agent.family.into_owned()
+//! }
+//!
+//! let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+//! let rocket = rocket::build()
+//!     .manage(Arc::new(parser))
+//!     .mount("/", rocket::routes![index]);
+//! ```
+
+use std::{ops::Deref, sync::Arc};
+
+use ::rocket::{
+    http::Status,
+    request::{FromRequest, Outcome, Request},
+};
+use derive_more::Display;
+
+use crate::{Client, UserAgentParser};
+
+/// The user agent of a request, parsed from its headers with
+/// `UserAgentParser::parse_header_pairs`, so folded `User-Agent` values are
+/// split and the parser's header settings apply. Requests without one get
+/// the default `Client`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserAgentInfo(pub Client<'static>);
+
+impl Deref for UserAgentInfo {
+    type Target = Client<'static>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Why `UserAgentInfo` failed, which is answered with a 500
+#[derive(Clone, Copy, Debug, Display, Eq, PartialEq)]
+#[display(fmt = "no Arc<UserAgentParser> is managed by Rocket")]
+pub struct MissingParser;
+
+#[::rocket::async_trait]
+impl<'r> FromRequest<'r> for UserAgentInfo {
+    type Error = MissingParser;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let Some(parser) = request.rocket().state::<Arc<UserAgentParser>>() else {
+            ::rocket::error!("UserAgentInfo: {}", MissingParser);
+            return Outcome::Error((Status::InternalServerError, MissingParser));
+        };
+        let headers: Vec<_> = request.headers().iter().collect();
+        let pairs = headers
+            .iter()
+            .map(|header| (header.name.as_str(), header.value()));
+        Outcome::Success(UserAgentInfo(parser.parse_header_pairs(pairs).into_owned()))
+    }
+}
@@ -0,0 +1,257 @@
+//! Scrubs personal data that misbehaving SDKs sometimes leak into user agent
+//! strings, such as email addresses, device identifiers and IP addresses.
+//!
+//! Detectors only match whole tokens and leave product versions, build
+//! numbers and model codes alone, so a redacted user agent parses to the same
+//! `Client` as the original.
+//!
+//! ```rust
+//! # use uaparser::sanitize::{redact, RedactionPolicy};
+//! let redacted = redact(
+//!     "MyApp/2.1 (user=jane.doe@example.com; Android 14)",
+//!     &RedactionPolicy::default(),
+//! );
+//!
+//! assert_eq!(redacted, "MyApp/2.1 (user=[email]; Android 14)");
+//! ```
+
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+use std::{borrow::Cow, ops::Range, sync::LazyLock};
+
+use regex::Regex;
+
+pub const EMAIL_PLACEHOLDER: &str = "[email]";
+pub const TOKEN_PLACEHOLDER: &str = "[token]";
+pub const IP_PLACEHOLDER: &str = "[ip]";
+
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    detector(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}")
+});
+static HEX_TOKEN: LazyLock<Regex> = LazyLock::new(|| {
+    detector(
+        r"\b(?:[0-9A-Fa-f]{8}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{12}|[0-9A-Fa-f]{24,})\b",
+    )
+});
+static IPV4: LazyLock<Regex> = LazyLock::new(|| {
+    detector(
+        r"\b(?:(?:25[0-5]|2[0-4][0-9]|1[0-9]{2}|[1-9]?[0-9])\.){3}(?:25[0-5]|2[0-4][0-9]|1[0-9]{2}|[1-9]?[0-9])\b",
+    )
+});
+
+/// Compiles a built-in detector. Skipping a detector would let the data it
+/// scrubs through unredacted, so a pattern that fails to compile panics
+/// instead, which can't happen for the constant patterns above as
+/// `detectors_compile` checks.
+#[allow(clippy::expect_used)]
+fn detector(pattern: &str) -> Regex {
+    Regex::new(pattern).expect("built-in redaction pattern is valid")
+}
+
+/// Selects which detectors `redact` runs. All built-in detectors are enabled
+/// by default.
+#[derive(Clone, Debug)]
+pub struct RedactionPolicy {
+    /// Replaces email addresses with `[email]`
+    pub emails: bool,
+    /// Replaces UUIDs and hex runs of 24 or more digits with `[token]`
+    pub hex_tokens: bool,
+    /// Replaces standalone IPv4 addresses with `[ip]`. Dotted versions that
+    /// follow a product name, like `Chrome/120.0.0.0`, are left as they are.
+    pub ip_addresses: bool,
+    /// Additional patterns, each replaced by its placeholder
+    pub custom: Vec<(Regex, String)>,
+}
+
+impl Default for RedactionPolicy {
+    fn default() -> Self {
+        Self {
+            emails: true,
+            hex_tokens: true,
+            ip_addresses: true,
+            custom: Vec::new(),
+        }
+    }
+}
+
+impl RedactionPolicy {
+    /// Adds a custom pattern whose matches are replaced with `placeholder`
+    pub fn add_pattern(
+        &mut self,
+        pattern: &str,
+        placeholder: impl Into<String>,
+    ) -> Result<(), regex::Error> {
+        self.custom.push((Regex::new(pattern)?, placeholder.into()));
+        Ok(())
+    }
+}
+
+/// Replaces the personal data selected by `policy` with fixed placeholders,
+/// borrowing the input when there is nothing to redact
+#[must_use]
+pub fn redact<'a>(user_agent: &'a str, policy: &RedactionPolicy) -> Cow<'a, str> {
+    let mut found: Vec<(Range<usize>, &str)> = Vec::new();
+
+    if policy.emails {
+        found.extend(
+            EMAIL
+                .find_iter(user_agent)
+                .map(|m| (m.range(), EMAIL_PLACEHOLDER)),
+        );
+    }
+    if policy.hex_tokens {
+        found.extend(
+            HEX_TOKEN
+                .find_iter(user_agent)
+                .map(|m| (m.range(), TOKEN_PLACEHOLDER)),
+        );
+    }
+    if policy.ip_addresses {
+        found.extend(
+            IPV4.find_iter(user_agent)
+                .filter(|m| is_standalone(user_agent, m.range()))
+                .map(|m| (m.range(), IP_PLACEHOLDER)),
+        );
+    }
+    for (regex, placeholder) in &policy.custom {
+        found.extend(
+            regex
+                .find_iter(user_agent)
+                .filter(|m| !m.range().is_empty())
+                .map(|m| (m.range(), placeholder.as_str())),
+        );
+    }
+
+    if found.is_empty() {
+        return Cow::Borrowed(user_agent);
+    }
+
+    // Earlier and then longer matches win; anything overlapping them is dropped
+    found.sort_by(|(a, _), (b, _)| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+
+    let mut redacted = String::with_capacity(user_agent.len());
+    let mut position = 0;
+    for (range, placeholder) in found {
+        if range.start < position {
+            continue;
+        }
+        redacted.push_str(&user_agent[position..range.start]);
+        redacted.push_str(placeholder);
+        position = range.end;
+    }
+    redacted.push_str(&user_agent[position..]);
+
+    Cow::Owned(redacted)
+}
+
+/// Whether a dotted quad stands on its own rather than being part of a
+/// version such as `Chrome/120.0.0.0` or `1.2.3.4.5`
+fn is_standalone(text: &str, range: Range<usize>) -> bool {
+    let before = text[..range.start].chars().next_back();
+    let after = text[range.end..].chars().next();
+
+    !matches!(before, Some('/' | '.' | '_' | '-'))
+        && !matches!(after, Some('.' | '_' | '-'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, UserAgentParser};
+
+    #[test]
+    fn detectors_compile() {
+        LazyLock::force(&EMAIL);
+        LazyLock::force(&HEX_TOKEN);
+        LazyLock::force(&IPV4);
+    }
+
+    #[test]
+    fn redacts_emails() {
+        let redacted = redact(
+            "Mozilla/5.0 (Linux; Android 13; SM-S908B; account=john.smith+ua@mail.example.co.uk) \
+             AppleWebKit/537.36 (KHTML, like Gecko) Chrome/119.0.0.0 Mobile Safari/537.36",
+            &RedactionPolicy::default(),
+        );
+
+        assert!(!redacted.contains('@'));
+        assert!(redacted.contains("account=[email])"));
+    }
+
+    #[test]
+    fn redacts_tokens_and_addresses() {
+        let policy = RedactionPolicy::default();
+
+        assert_eq!(
+            redact(
+                "SDK/4.1 (id 3f2b8c1e-9d4a-4e7b-a1c2-5f6e7d8c9b0a; ip 192.168.10.4)",
+                &policy
+            ),
+            "SDK/4.1 (id [token]; ip [ip])"
+        );
+        assert_eq!(
+            redact("Agent/1.0 token:0123456789abcdef0123456789abcdef", &policy),
+            "Agent/1.0 token:[token]"
+        );
+    }
+
+    #[test]
+    fn respects_policy() {
+        let mut policy = RedactionPolicy {
+            emails: false,
+            ..RedactionPolicy::default()
+        };
+        policy
+            .add_pattern(r"session=\w+", "session=[redacted]")
+            .unwrap();
+
+        assert_eq!(
+            redact("App/1.0 (me@example.com; session=abc123)", &policy),
+            "App/1.0 (me@example.com; session=[redacted])"
+        );
+    }
+
+    #[test]
+    fn borrows_clean_input() {
+        let user_agent = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                          (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+        assert!(matches!(
+            redact(user_agent, &RedactionPolicy::default()),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn redacted_user_agents_parse_the_same() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let policy = RedactionPolicy::default();
+
+        for user_agent in [
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) \
+             Chrome/120.0.0.0 Safari/537.36",
+            "Mozilla/5.0 (Linux; Android 14; SM-G991B Build/UP1A.231005.007) \
+             AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.6099.144 Mobile \
+             Safari/537.36",
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1_2 like Mac OS X) AppleWebKit/605.1.15 \
+             (KHTML, like Gecko) Version/17.1.2 Mobile/15E148 Safari/604.1",
+            "Mozilla/5.0 (X11; Ubuntu; Linux x86_64; rv:121.0) Gecko/20100101 Firefox/121.0",
+            "Mozilla/4.0 (compatible; MSIE 8.0; Windows NT 6.1; Trident/4.0; SLCC2; \
+             .NET CLR 2.0.50727; .NET CLR 3.5.30729)",
+        ] {
+            let redacted = redact(user_agent, &policy);
+
+            assert_eq!(redacted, user_agent);
+            assert_eq!(parser.parse(&redacted), parser.parse(user_agent));
+        }
+
+        let leaky = "Mozilla/5.0 (Linux; Android 14; SM-G991B; jane@example.com) \
+                     AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.6099.144 Mobile \
+                     Safari/537.36";
+        let redacted = redact(leaky, &policy);
+
+        assert!(!redacted.contains("jane@example.com"));
+        assert_eq!(parser.parse(&redacted), parser.parse(leaky));
+    }
+}
@@ -0,0 +1,143 @@
+//! JSON Schemas for the parse results. `Client`, `Device`, `OS` and
+//! `UserAgent` derive `schemars::JsonSchema` for their nested form; the flat
+//! form written by `enrich::OutputStyle::Prefixed` is described here.
+//!
+//! ```rust
+//! # use uaparser::{schema::flat_client_schema, Client};
+//! let nested = schemars::schema_for!(Client);
+//! let flat = flat_client_schema("");
+//!
+//! let required = &flat.schema.object.as_ref().unwrap().required;
+//! assert!(required.contains("ua_family"));
+//! assert!(!required.contains("ua_major"));
+//! ```
+
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{
+        InstanceType, Metadata, ObjectValidation, RootSchema, Schema, SchemaObject,
+    },
+    JsonSchema,
+};
+
+use crate::{client::FLAT_KEYS, Device, UserAgent, OS};
+
+/// The schema of the flattened fields, such as `ua_family` and `os_major`,
+/// each preceded by `prefix`. Field descriptions and nullability are taken
+/// from the nested types.
+///
+/// # Panics
+///
+/// Never, as every flattened field is a field of the nested types
+#[must_use]
+pub fn flat_client_schema(prefix: &str) -> RootSchema {
+    let mut generator = SchemaGenerator::default();
+    let sections = [
+        ("ua_", UserAgent::json_schema(&mut generator)),
+        ("os_", OS::json_schema(&mut generator)),
+        ("device_", Device::json_schema(&mut generator)),
+    ];
+
+    let mut flat = ObjectValidation::default();
+    for key in FLAT_KEYS {
+        let (section, field) = sections
+            .iter()
+            .find_map(|(section, schema)| Some((schema, key.strip_prefix(section)?)))
+            .expect("Every flat key starts with a section");
+        let Schema::Object(SchemaObject {
+            object: Some(section),
+            ..
+        }) = section
+        else {
+            unreachable!("Sections are objects");
+        };
+        let name = format!("{prefix}{key}");
+        if section.required.contains(field) {
+            flat.required.insert(name.clone());
+        }
+        flat.properties
+            .insert(name, section.properties[field].clone());
+    }
+
+    RootSchema {
+        meta_schema: generator.settings().meta_schema.clone(),
+        schema: SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                title: Some("FlatClient".to_owned()),
+                description: Some(
+                    "The fields of a parsed Client as flat top level keys".to_owned(),
+                ),
+                ..Metadata::default()
+            })),
+            instance_type: Some(InstanceType::Object.into()),
+            object: Some(Box::new(flat)),
+            ..SchemaObject::default()
+        },
+        definitions: schemars::Map::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use schemars::schema_for;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::Client;
+
+    fn definition<'a>(schema: &'a Value, name: &str) -> &'a Value {
+        &schema["definitions"][name]
+    }
+
+    #[test]
+    fn nested_schema_shape() {
+        let schema = serde_json::to_value(schema_for!(Client)).unwrap();
+
+        assert_eq!(schema["required"], json!(["device", "os", "user_agent"]));
+        for name in ["Device", "OS", "UserAgent"] {
+            let definition = definition(&schema, name);
+            assert_eq!(definition["required"], json!(["family"]), "{name}");
+            assert_eq!(definition["properties"]["family"]["type"], "string");
+        }
+        let user_agent = definition(&schema, "UserAgent");
+        assert_eq!(
+            user_agent["properties"]["major"]["type"],
+            json!(["string", "null"])
+        );
+        assert_eq!(
+            user_agent["properties"]["major"]["description"],
+            "The major version, as written in the user agent"
+        );
+        assert_eq!(
+            definition(&schema, "OS")["properties"]
+                .as_object()
+                .unwrap()
+                .len(),
+            5
+        );
+    }
+
+    #[test]
+    fn flat_schema_shape() {
+        let schema = serde_json::to_value(flat_client_schema("client_")).unwrap();
+        let properties = schema["properties"].as_object().unwrap();
+
+        assert_eq!(properties.len(), FLAT_KEYS.len());
+        assert_eq!(
+            schema["required"],
+            json!([
+                "client_device_family",
+                "client_os_family",
+                "client_ua_family"
+            ])
+        );
+        assert_eq!(
+            properties["client_os_patch_minor"]["type"],
+            json!(["string", "null"])
+        );
+        assert_eq!(
+            properties["client_device_brand"]["description"],
+            "The manufacturer, such as `Apple` or `Samsung`"
+        );
+    }
+}
@@ -0,0 +1,62 @@
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+use std::ops::Range;
+
+use super::Client;
+
+/// Byte ranges into the user agent string for each `Device` field that was
+/// taken directly from a capture group. Fields produced by a replacement
+/// template have no span.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DeviceSpans {
+    pub family: Option<Range<usize>>,
+    pub brand: Option<Range<usize>>,
+    pub model: Option<Range<usize>>,
+}
+
+/// Byte ranges into the user agent string for each `OS` field that was taken
+/// directly from a capture group. Fields produced by a replacement template
+/// have no span.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct OSSpans {
+    pub family: Option<Range<usize>>,
+    pub major: Option<Range<usize>>,
+    pub minor: Option<Range<usize>>,
+    pub patch: Option<Range<usize>>,
+    pub patch_minor: Option<Range<usize>>,
+}
+
+/// Byte ranges into the user agent string for each `UserAgent` field that was
+/// taken directly from a capture group. Fields produced by a replacement
+/// template have no span.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct UserAgentSpans {
+    pub family: Option<Range<usize>>,
+    pub major: Option<Range<usize>>,
+    pub minor: Option<Range<usize>>,
+    pub patch: Option<Range<usize>>,
+    pub patch_minor: Option<Range<usize>>,
+}
+
+/// A parsed `Client` along with where in the user agent string each of its
+/// captured fields came from
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ClientSpans<'a> {
+    pub client: Client<'a>,
+    pub device: DeviceSpans,
+    pub os: OSSpans,
+    pub user_agent: UserAgentSpans,
+}
+
+impl ClientSpans<'_> {
+    /// Copies any borrowed fields of the client, as `Client::into_owned`
+    #[must_use]
+    pub fn into_owned(self) -> ClientSpans<'static> {
+        ClientSpans {
+            client: self.client.into_owned(),
+            device: self.device,
+            os: self.os,
+            user_agent: self.user_agent,
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! Counts parse results as they stream past, such as in a log pipeline that
+//! can't hold every result. Unlike `report::summarize`, a `ClientStats`
+//! takes one client at a time, and shards counted apart combine with
+//! `merge`.
+//!
+//! ```rust
+//! # use uaparser::*;
+//! # use uaparser::stats::ClientStats;
+//! let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+//! let stats: ClientStats = ["curl/8.4.0", "curl/8.5.0", "-"]
+//!     .iter()
+//!     .map(|user_agent| parser.parse(user_agent))
+//!     .collect();
+//!
+//! assert_eq!(stats.total(), 3);
+//! assert_eq!(stats.browsers().get("curl"), 2);
+//! assert_eq!(stats.unknown(), 1);
+//! ```
+
+use std::{collections::HashMap, iter::FromIterator};
+
+use super::{Deserialize, Serialize};
+use crate::Client;
+
+/// The distinct keys `ClientStats::default` tracks per count
+pub const DEFAULT_MAX_KEYS: usize = 10_000;
+
+/// Running counts of parsed clients by browser, browser version, OS, and
+/// device family and brand. Each of these tracks at most `max_keys`
+/// distinct keys; clients with a key first seen after that are counted in
+/// its `overflow` instead, which bounds memory on unbounded input.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ClientStats {
+    max_keys: usize,
+    total: u64,
+    unknown: u64,
+    browsers: Counts,
+    browser_versions: Counts,
+    os: Counts,
+    device_families: Counts,
+    device_brands: Counts,
+}
+
+/// The counts of one value, such as the browser family, from `ClientStats`
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Counts {
+    counts: HashMap<String, u64>,
+    overflow: u64,
+}
+
+impl Default for ClientStats {
+    fn default() -> Self {
+        ClientStats::with_max_keys(DEFAULT_MAX_KEYS)
+    }
+}
+
+impl ClientStats {
+    /// Tracks up to `max_keys` distinct keys in each of the counts
+    #[must_use]
+    pub fn with_max_keys(max_keys: usize) -> ClientStats {
+        ClientStats {
+            max_keys,
+            total: 0,
+            unknown: 0,
+            browsers: Counts::default(),
+            browser_versions: Counts::default(),
+            os: Counts::default(),
+            device_families: Counts::default(),
+            device_brands: Counts::default(),
+        }
+    }
+
+    pub fn add(&mut self, client: &Client<'_>) {
+        let max = self.max_keys;
+        let family = client.user_agent.family.as_ref();
+        self.total += 1;
+        if client.is_other_user_agent() {
+            self.unknown += 1;
+        }
+        self.browsers.add(family, 1, max);
+        let version = match &client.user_agent.major {
+            Some(major) => format!("{family} {major}"),
+            None => family.to_owned(),
+        };
+        self.browser_versions.add(&version, 1, max);
+        self.os.add(&client.os.family, 1, max);
+        self.device_families.add(&client.device.family, 1, max);
+        if let Some(brand) = &client.device.brand {
+            self.device_brands.add(brand, 1, max);
+        }
+    }
+
+    /// Adds the counts of `other`, such as another shard of the same
+    /// stream. Keys `other` tracks that don't fit under this one's
+    /// `max_keys` go to the overflow, so merging capped shards is exact
+    /// only while the keys fit.
+    pub fn merge(&mut self, other: &ClientStats) {
+        let max = self.max_keys;
+        self.total += other.total;
+        self.unknown += other.unknown;
+        self.browsers.merge(&other.browsers, max);
+        self.browser_versions.merge(&other.browser_versions, max);
+        self.os.merge(&other.os, max);
+        self.device_families.merge(&other.device_families, max);
+        self.device_brands.merge(&other.device_brands, max);
+    }
+
+    #[must_use]
+    pub fn max_keys(&self) -> usize {
+        self.max_keys
+    }
+
+    /// The number of clients added
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// The number of clients no user agent rule matched
+    #[must_use]
+    pub fn unknown(&self) -> u64 {
+        self.unknown
+    }
+
+    /// By user agent family
+    #[must_use]
+    pub fn browsers(&self) -> &Counts {
+        &self.browsers
+    }
+
+    /// By user agent family and major version, such as `Chrome 120`
+    #[must_use]
+    pub fn browser_versions(&self) -> &Counts {
+        &self.browser_versions
+    }
+
+    /// By OS family
+    #[must_use]
+    pub fn os(&self) -> &Counts {
+        &self.os
+    }
+
+    /// By device family
+    #[must_use]
+    pub fn device_families(&self) -> &Counts {
+        &self.device_families
+    }
+
+    /// By device brand, for the clients that have one
+    #[must_use]
+    pub fn device_brands(&self) -> &Counts {
+        &self.device_brands
+    }
+}
+
+impl Counts {
+    /// The count of `key`, which is `0` for keys never seen or not tracked
+    #[must_use]
+    pub fn get(&self, key: &str) -> u64 {
+        self.counts.get(key).copied().unwrap_or_default()
+    }
+
+    /// The tracked keys and their counts, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.counts
+            .iter()
+            .map(|(key, &count)| (key.as_str(), count))
+    }
+
+    /// The number of tracked keys
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// The number counted under keys that didn't fit under `max_keys`
+    #[must_use]
+    pub fn overflow(&self) -> u64 {
+        self.overflow
+    }
+
+    fn add(&mut self, key: &str, count: u64, max_keys: usize) {
+        if let Some(tracked) = self.counts.get_mut(key) {
+            *tracked += count;
+        } else if self.counts.len() < max_keys {
+            self.counts.insert(key.to_owned(), count);
+        } else {
+            self.overflow += count;
+        }
+    }
+
+    fn merge(&mut self, other: &Counts, max_keys: usize) {
+        for (key, &count) in &other.counts {
+            self.add(key, count, max_keys);
+        }
+        self.overflow += other.overflow;
+    }
+}
+
+impl<'a> Extend<Client<'a>> for ClientStats {
+    fn extend<I: IntoIterator<Item = Client<'a>>>(&mut self, clients: I) {
+        for client in clients {
+            self.add(&client);
+        }
+    }
+}
+
+impl<'c, 'a> Extend<&'c Client<'a>> for ClientStats {
+    fn extend<I: IntoIterator<Item = &'c Client<'a>>>(&mut self, clients: I) {
+        for client in clients {
+            self.add(client);
+        }
+    }
+}
+
+impl<'a> FromIterator<Client<'a>> for ClientStats {
+    fn from_iter<I: IntoIterator<Item = Client<'a>>>(clients: I) -> Self {
+        let mut stats = ClientStats::default();
+        stats.extend(clients);
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+    use crate::{Parser, UserAgent, UserAgentParser};
+
+    const CORPUS: &str = include_str!("../tests/fixtures/traffic.txt");
+
+    #[test]
+    fn merged_halves_equal_the_whole() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let clients: Vec<_> = CORPUS.lines().map(|line| parser.parse(line)).collect();
+        let (first, second) = clients.split_at(clients.len() / 2);
+
+        let whole: ClientStats = clients.iter().cloned().collect();
+        let mut merged = ClientStats::default();
+        merged.extend(first);
+        let mut shard = ClientStats::default();
+        shard.extend(second);
+        merged.merge(&shard);
+
+        assert_eq!(merged, whole);
+        assert_eq!(whole.total(), 20);
+        assert_eq!(whole.unknown(), 5);
+        assert_eq!(whole.browsers().get("Chrome"), 6);
+        assert_eq!(whole.os().get("Windows"), 6);
+        assert_eq!(whole.browsers().overflow(), 0);
+    }
+
+    #[test]
+    fn caps_distinct_keys() {
+        let mut stats = ClientStats::with_max_keys(100);
+        stats.extend((0..1000).map(|n| Client {
+            user_agent: UserAgent {
+                family: Cow::Owned(format!("Bot{}", n % 500)),
+                major: Some(Cow::Owned(n.to_string())),
+                ..UserAgent::default()
+            },
+            ..Client::default()
+        }));
+
+        assert_eq!(stats.total(), 1000);
+        assert_eq!(stats.browsers().len(), 100);
+        assert_eq!(stats.browsers().get("Bot0"), 2);
+        assert_eq!(stats.browsers().get("Bot499"), 0);
+        assert_eq!(stats.browsers().overflow(), 800);
+        assert_eq!(stats.browser_versions().len(), 100);
+        assert_eq!(stats.browser_versions().overflow(), 900);
+        assert_eq!(stats.os().get("Other"), 1000);
+        assert!(stats.device_brands().is_empty());
+
+        let mut merged = ClientStats::with_max_keys(100);
+        merged.merge(&stats);
+        merged.merge(&stats);
+        assert_eq!(merged.browsers().len(), 100);
+        assert_eq!(merged.browsers().overflow(), 1600);
+    }
+}
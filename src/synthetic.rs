@@ -0,0 +1,119 @@
+//! Vocabulary shared by the `arbitrary` and `proptest` generators
+
+use std::fmt::Write;
+
+pub(crate) const USER_AGENT_FAMILIES: &[&str] = &[
+    "Chrome",
+    "Chrome Mobile",
+    "Chrome Mobile WebView",
+    "Firefox",
+    "Safari",
+    "Mobile Safari",
+    "Edge",
+    "Opera",
+    "Samsung Internet",
+    "Googlebot",
+    "curl",
+    "Other",
+];
+
+pub(crate) const OS_FAMILIES: &[&str] = &[
+    "Windows",
+    "Mac OS X",
+    "iOS",
+    "Android",
+    "Linux",
+    "Ubuntu",
+    "Chrome OS",
+    "Other",
+];
+
+pub(crate) const DEVICE_FAMILIES: &[&str] = &[
+    "iPhone",
+    "iPad",
+    "Mac",
+    "Samsung SM-S901B",
+    "Pixel 8",
+    "Spider",
+    "Generic Smartphone",
+    "Other",
+];
+
+pub(crate) const BRANDS: &[&str] =
+    &["Apple", "Samsung", "Google", "Huawei", "Spider", "Generic"];
+
+pub(crate) const MODELS: &[&str] = &[
+    "iPhone",
+    "iPad",
+    "SM-S901B",
+    "Pixel 8",
+    "Smartphone",
+    "Desktop",
+];
+
+pub(crate) const PRODUCTS: &[&str] = &[
+    "Mozilla",
+    "AppleWebKit",
+    "Chrome",
+    "Safari",
+    "Firefox",
+    "Gecko",
+    "Version",
+    "Mobile",
+    "Edg",
+    "OPR",
+    "SamsungBrowser",
+    "curl",
+    "Wget",
+    "Googlebot",
+];
+
+pub(crate) const COMMENTS: &[&str] = &[
+    "Windows NT 10.0; Win64; x64",
+    "Macintosh; Intel Mac OS X 10_15_7",
+    "X11; Linux x86_64",
+    "X11; Ubuntu; Linux x86_64; rv:115.0",
+    "Linux; Android 14; Pixel 8",
+    "iPhone; CPU iPhone OS 17_2 like Mac OS X",
+    "KHTML, like Gecko",
+    "compatible; Googlebot/2.1; +http://www.google.com/bot.html",
+];
+
+/// Upper bounds of the major, minor, patch and patch minor version numbers
+pub(crate) const VERSION_LIMITS: [u32; 4] = [130, 20, 10_000, 200];
+
+/// Optional fields are `None` this many times out of `NONE_OUT_OF`
+pub(crate) const NONE_ODDS: u32 = 2;
+pub(crate) const NONE_OUT_OF: u32 = 3;
+
+/// The most product tokens in a synthetic user agent
+pub(crate) const MAX_TOKENS: usize = 6;
+
+/// One product token of a synthetic user agent, such as `Chrome/120.0`
+pub(crate) struct Token<'a> {
+    pub product: &'a str,
+    pub version: Vec<u32>,
+    pub comment: Option<&'a str>,
+}
+
+/// Joins `tokens` with spaces, followed by `noise` when there is any
+pub(crate) fn compose(tokens: &[Token<'_>], noise: Option<&str>) -> String {
+    let mut user_agent = String::new();
+    for token in tokens {
+        if !user_agent.is_empty() {
+            user_agent.push(' ');
+        }
+        user_agent.push_str(token.product);
+        for (index, part) in token.version.iter().enumerate() {
+            let separator = if index == 0 { '/' } else { '.' };
+            let _ = write!(user_agent, "{separator}{part}");
+        }
+        if let Some(comment) = token.comment {
+            let _ = write!(user_agent, " ({comment})");
+        }
+    }
+    if let Some(noise) = noise {
+        user_agent.push_str(noise);
+    }
+    user_agent
+}
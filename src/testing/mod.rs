@@ -0,0 +1,384 @@
+//! Helpers for testing code built on this crate: a corpus of realistic user
+//! agent strings, a random picker over it and a partial matcher for parse
+//! results, also available as the `assert_ua!` macro.
+//!
+//! ```rust
+//! # use uaparser::*;
+//! # use uaparser::testing::{corpus, ExpectedClient};
+//! let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+//! let client = parser.parse(corpus::FIREFOX[0]);
+//!
+//! ExpectedClient::new()
+//!     .ua_family("Firefox")
+//!     .ua_major("121")
+//!     .os_family("Windows")
+//!     .assert_matches(&client);
+//! ```
+
+pub mod corpus;
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use rand::Rng;
+
+use crate::{client::FLAT_KEYS, Client};
+
+/// The groups of user agents in `corpus`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Category {
+    ChromeDesktop,
+    ChromeAndroid,
+    AndroidWebView,
+    SamsungInternet,
+    SafariIos,
+    SafariDesktop,
+    Firefox,
+    Edge,
+    Opera,
+    InternetExplorer,
+    InApp,
+    FeaturePhones,
+    SmartTv,
+    Bots,
+    HttpTools,
+}
+
+impl Category {
+    pub const ALL: [Category; 15] = [
+        Category::ChromeDesktop,
+        Category::ChromeAndroid,
+        Category::AndroidWebView,
+        Category::SamsungInternet,
+        Category::SafariIos,
+        Category::SafariDesktop,
+        Category::Firefox,
+        Category::Edge,
+        Category::Opera,
+        Category::InternetExplorer,
+        Category::InApp,
+        Category::FeaturePhones,
+        Category::SmartTv,
+        Category::Bots,
+        Category::HttpTools,
+    ];
+
+    /// The corpus strings in this category, never empty
+    #[must_use]
+    pub fn user_agents(self) -> &'static [&'static str] {
+        match self {
+            Category::ChromeDesktop => corpus::CHROME_DESKTOP,
+            Category::ChromeAndroid => corpus::CHROME_ANDROID,
+            Category::AndroidWebView => corpus::ANDROID_WEBVIEW,
+            Category::SamsungInternet => corpus::SAMSUNG_INTERNET,
+            Category::SafariIos => corpus::SAFARI_IOS,
+            Category::SafariDesktop => corpus::SAFARI_DESKTOP,
+            Category::Firefox => corpus::FIREFOX,
+            Category::Edge => corpus::EDGE,
+            Category::Opera => corpus::OPERA,
+            Category::InternetExplorer => corpus::INTERNET_EXPLORER,
+            Category::InApp => corpus::IN_APP,
+            Category::FeaturePhones => corpus::FEATURE_PHONES,
+            Category::SmartTv => corpus::SMART_TV,
+            Category::Bots => corpus::BOTS,
+            Category::HttpTools => corpus::HTTP_TOOLS,
+        }
+    }
+}
+
+/// Picks a user agent from `category`, uniformly at random
+pub fn random_ua<R: Rng + ?Sized>(rng: &mut R, category: Category) -> &'static str {
+    let user_agents = category.user_agents();
+    user_agents[rng.gen_range(0..user_agents.len())]
+}
+
+/// The parse result a test expects, where only the fields that were set are
+/// checked. Fields use the flattened names shared with the tabular outputs,
+/// and optional fields can be expected to be `None`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExpectedClient {
+    /// Expected values keyed by their index in `FLAT_KEYS`
+    fields: BTreeMap<usize, Option<String>>,
+}
+
+impl ExpectedClient {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn ua_family(self, family: &str) -> Self {
+        self.expect(0, Some(family))
+    }
+
+    #[must_use]
+    pub fn ua_major<'a>(self, major: impl Into<Option<&'a str>>) -> Self {
+        self.expect(1, major.into())
+    }
+
+    #[must_use]
+    pub fn ua_minor<'a>(self, minor: impl Into<Option<&'a str>>) -> Self {
+        self.expect(2, minor.into())
+    }
+
+    #[must_use]
+    pub fn ua_patch<'a>(self, patch: impl Into<Option<&'a str>>) -> Self {
+        self.expect(3, patch.into())
+    }
+
+    #[must_use]
+    pub fn os_family(self, family: &str) -> Self {
+        self.expect(4, Some(family))
+    }
+
+    #[must_use]
+    pub fn os_major<'a>(self, major: impl Into<Option<&'a str>>) -> Self {
+        self.expect(5, major.into())
+    }
+
+    #[must_use]
+    pub fn os_minor<'a>(self, minor: impl Into<Option<&'a str>>) -> Self {
+        self.expect(6, minor.into())
+    }
+
+    #[must_use]
+    pub fn os_patch<'a>(self, patch: impl Into<Option<&'a str>>) -> Self {
+        self.expect(7, patch.into())
+    }
+
+    #[must_use]
+    pub fn os_patch_minor<'a>(self, patch_minor: impl Into<Option<&'a str>>) -> Self {
+        self.expect(8, patch_minor.into())
+    }
+
+    #[must_use]
+    pub fn device_family(self, family: &str) -> Self {
+        self.expect(9, Some(family))
+    }
+
+    #[must_use]
+    pub fn device_brand<'a>(self, brand: impl Into<Option<&'a str>>) -> Self {
+        self.expect(10, brand.into())
+    }
+
+    #[must_use]
+    pub fn device_model<'a>(self, model: impl Into<Option<&'a str>>) -> Self {
+        self.expect(11, model.into())
+    }
+
+    #[must_use]
+    pub fn ua_patch_minor<'a>(self, patch_minor: impl Into<Option<&'a str>>) -> Self {
+        self.expect(12, patch_minor.into())
+    }
+
+    /// Expects the field named `key` in `FLAT_KEYS` order, such as
+    /// `ua_major` or `device_brand`, which is how `assert_ua!` sets fields
+    ///
+    /// # Panics
+    ///
+    /// When `key` isn't a field name
+    #[must_use]
+    pub fn field<'a>(self, key: &str, value: impl Into<Option<&'a str>>) -> Self {
+        let index = FLAT_KEYS
+            .iter()
+            .position(|flat_key| *flat_key == key)
+            .unwrap_or_else(|| {
+                panic!("unknown field `{}`, expected one of {:?}", key, FLAT_KEYS)
+            });
+        self.expect(index, value.into())
+    }
+
+    /// One line per expected field that `client` doesn't match, empty when
+    /// it matches
+    #[must_use]
+    pub fn mismatches(&self, client: &Client<'_>) -> Vec<String> {
+        let found = client.flatten();
+        let mut mismatches = Vec::new();
+        for (&index, expected) in &self.fields {
+            if expected.as_deref() != found[index] {
+                mismatches.push(format!(
+                    "{}: expected {:?}, found {:?}",
+                    FLAT_KEYS[index],
+                    expected.as_deref(),
+                    found[index]
+                ));
+            }
+        }
+        mismatches
+    }
+
+    /// Panics, listing each mismatched field, when `client` doesn't match
+    ///
+    /// # Panics
+    ///
+    /// When any expected field differs from the parsed one
+    #[track_caller]
+    pub fn assert_matches(&self, client: &Client<'_>) {
+        self.assert_with_header(
+            client,
+            String::from("client doesn't match the expected fields"),
+        );
+    }
+
+    /// Like `assert_matches`, naming the `user_agent` that `client` was
+    /// parsed from in the panic message
+    ///
+    /// # Panics
+    ///
+    /// When any expected field differs from the parsed one
+    #[track_caller]
+    pub fn assert_matches_for(&self, user_agent: &str, client: &Client<'_>) {
+        self.assert_with_header(
+            client,
+            format!(
+                "client doesn't match the expected fields\n  user agent: {user_agent}"
+            ),
+        );
+    }
+
+    #[track_caller]
+    fn assert_with_header(&self, client: &Client<'_>, mut message: String) {
+        let mismatches = self.mismatches(client);
+        if mismatches.is_empty() {
+            return;
+        }
+
+        for mismatch in &mismatches {
+            let _ = write!(message, "\n  {mismatch}");
+        }
+        panic!("{}", message);
+    }
+
+    fn expect(mut self, index: usize, value: Option<&str>) -> Self {
+        self.fields.insert(index, value.map(str::to_owned));
+        self
+    }
+}
+
+/// Parses a user agent and checks only the listed fields, grouped by `ua`,
+/// `os` and `device`, panicking with the user agent and every mismatched
+/// field otherwise. Optional fields can be expected to be `None`.
+///
+/// ```rust
+/// # use uaparser::*;
+/// let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+///
+/// assert_ua!(
+///     parser,
+///     "Mozilla/5.0 (X11; Ubuntu; Linux x86_64; rv:115.0) Gecko/20100101 Firefox/115.0",
+///     ua: { family: "Firefox", major: "115", patch: None },
+///     os: { family: "Ubuntu" },
+/// );
+/// ```
+#[macro_export]
+macro_rules! assert_ua {
+    (
+        $parser:expr,
+        $user_agent:expr
+        $(, $section:ident: { $($field:ident: $value:expr),* $(,)? })*
+        $(,)?
+    ) => {{
+        use $crate::Parser as _;
+        let user_agent = $user_agent;
+        $crate::testing::ExpectedClient::new()
+            $($(.field(concat!(stringify!($section), "_", stringify!($field)), $value))*)*
+            .assert_matches_for(user_agent, &$parser.parse(user_agent))
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, UserAgentParser};
+
+    fn parser() -> UserAgentParser {
+        UserAgentParser::from_bytes(
+            br"
+user_agent_parsers:
+  - regex: '(Foo)/(\d+)\.(\d+)'
+os_parsers: []
+device_parsers: []
+",
+        )
+        .expect("Parser creation failed")
+    }
+
+    #[test]
+    fn checks_only_set_fields() {
+        let parser = parser();
+        let client = parser.parse("Foo/1.2");
+
+        ExpectedClient::new().assert_matches(&client);
+        ExpectedClient::new()
+            .ua_family("Foo")
+            .ua_major("1")
+            .ua_patch(None)
+            .os_family("Other")
+            .device_brand(None)
+            .assert_matches(&client);
+
+        assert_eq!(
+            ExpectedClient::new()
+                .ua_major("2")
+                .ua_minor("2")
+                .ua_patch("3")
+                .mismatches(&client),
+            [
+                r#"ua_major: expected Some("2"), found Some("1")"#,
+                r#"ua_patch: expected Some("3"), found None"#,
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "client doesn't match the expected fields\n  \
+                               device_family: expected Some(\"Spider\"), found Some(\"Other\")")]
+    fn panics_on_mismatch() {
+        let parser = parser();
+        ExpectedClient::new()
+            .ua_family("Foo")
+            .device_family("Spider")
+            .assert_matches(&parser.parse("Foo/1.2"));
+    }
+
+    #[test]
+    fn macro_checks_listed_fields() {
+        let parser = parser();
+
+        assert_ua!(parser, "Foo/1.2");
+        assert_ua!(
+            &parser,
+            "Foo/1.2",
+            ua: { family: "Foo", major: "1", minor: "2", patch: None },
+            device: { brand: None },
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "client doesn't match the expected fields\n  \
+                               user agent: Foo/1.2\n  \
+                               ua_minor: expected Some(\"3\"), found Some(\"2\")\n  \
+                               os_family: expected Some(\"Linux\"), found Some(\"Other\")")]
+    fn macro_names_the_user_agent() {
+        assert_ua!(parser(), "Foo/1.2", ua: { minor: "3" }, os: { family: "Linux" });
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown field `ua_version`")]
+    fn rejects_unknown_fields() {
+        let _ = ExpectedClient::new().field("ua_version", "1");
+    }
+
+    #[test]
+    fn every_category_is_populated() {
+        let total: usize = Category::ALL
+            .iter()
+            .map(|category| category.user_agents().len())
+            .sum();
+
+        assert!(Category::ALL
+            .iter()
+            .all(|category| !category.user_agents().is_empty()));
+        assert!(total >= 300);
+    }
+}
@@ -0,0 +1,199 @@
+//! A lexer for the raw structure of a user agent string, independent of the
+//! rule-based classification. Following RFC 7231, a user agent is a sequence
+//! of `product/version` tokens and parenthesized comments.
+//!
+//! ```rust
+//! # use uaparser::tokens::{tokenize, UaToken};
+//! let tokens = tokenize("MyApp/3.2.1 (iPhone; iOS 17.1) CFNetwork/1474");
+//!
+//! assert_eq!(
+//!     tokens,
+//!     vec![
+//!         UaToken::Product { name: "MyApp", version: Some("3.2.1") },
+//!         UaToken::Comment { parts: vec!["iPhone", "iOS 17.1"] },
+//!         UaToken::Product { name: "CFNetwork", version: Some("1474") },
+//!     ]
+//! );
+//! ```
+
+/// A single lexical element of a user agent string
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum UaToken<'a> {
+    /// A `name/version` product token, or a bare `name` without a version
+    Product {
+        name: &'a str,
+        version: Option<&'a str>,
+    },
+    /// A parenthesized comment, split into its `;` separated parts. Nested
+    /// comments are kept intact inside the part that contains them.
+    Comment { parts: Vec<&'a str> },
+}
+
+/// Splits a user agent string into its product tokens and comments, in the
+/// order they appear. Malformed input is handled on a best effort basis: an
+/// unclosed comment runs to the end of the string and stray closing
+/// parentheses are skipped.
+#[must_use]
+pub fn tokenize(user_agent: &str) -> Vec<UaToken<'_>> {
+    let bytes = user_agent.as_bytes();
+    let mut tokens = Vec::new();
+    let mut position = 0;
+
+    while position < bytes.len() {
+        match bytes[position] {
+            b if b.is_ascii_whitespace() || b == b')' => position += 1,
+            b'(' => {
+                let (parts, end) = comment(user_agent, position + 1);
+                tokens.push(UaToken::Comment { parts });
+                position = end;
+            }
+            _ => {
+                let end = bytes[position..]
+                    .iter()
+                    .position(|&b| b.is_ascii_whitespace() || b == b'(' || b == b')')
+                    .map_or(bytes.len(), |offset| position + offset);
+                tokens.push(product(&user_agent[position..end]));
+                position = end;
+            }
+        }
+    }
+
+    tokens
+}
+
+fn product(token: &str) -> UaToken<'_> {
+    match token.split_once('/') {
+        Some((name, version)) if !version.is_empty() => UaToken::Product {
+            name,
+            version: Some(version),
+        },
+        Some((name, _)) => UaToken::Product {
+            name,
+            version: None,
+        },
+        None => UaToken::Product {
+            name: token,
+            version: None,
+        },
+    }
+}
+
+/// Reads a comment whose opening parenthesis sits just before `start`,
+/// returning its parts and the position just after the closing parenthesis
+fn comment(user_agent: &str, start: usize) -> (Vec<&str>, usize) {
+    let bytes = user_agent.as_bytes();
+    let mut parts = Vec::new();
+    let mut depth = 1;
+    let mut part_start = start;
+    let mut position = start;
+
+    while position < bytes.len() {
+        match bytes[position] {
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    push_part(&mut parts, &user_agent[part_start..position]);
+                    return (parts, position + 1);
+                }
+            }
+            b';' if depth == 1 => {
+                push_part(&mut parts, &user_agent[part_start..position]);
+                part_start = position + 1;
+            }
+            _ => {}
+        }
+        position += 1;
+    }
+
+    push_part(&mut parts, &user_agent[part_start..]);
+    (parts, bytes.len())
+}
+
+fn push_part<'a>(parts: &mut Vec<&'a str>, part: &'a str) {
+    let part = part.trim();
+    if !part.is_empty() {
+        parts.push(part);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn product<'a>(name: &'a str, version: Option<&'a str>) -> UaToken<'a> {
+        UaToken::Product { name, version }
+    }
+
+    fn comment<'a>(parts: &[&'a str]) -> UaToken<'a> {
+        UaToken::Comment {
+            parts: parts.to_vec(),
+        }
+    }
+
+    #[test]
+    fn tokenizes_chrome() {
+        let tokens = tokenize(
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+             (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+        );
+
+        assert_eq!(
+            tokens,
+            vec![
+                product("Mozilla", Some("5.0")),
+                comment(&["Windows NT 10.0", "Win64", "x64"]),
+                product("AppleWebKit", Some("537.36")),
+                comment(&["KHTML, like Gecko"]),
+                product("Chrome", Some("120.0.0.0")),
+                product("Safari", Some("537.36")),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_trailing_custom_token() {
+        let tokens = tokenize(
+            "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 \
+             (KHTML, like Gecko) Chrome/120.0.6099.144 Mobile Safari/537.36 \
+             MyApp/3.2.1",
+        );
+
+        assert_eq!(tokens.last(), Some(&product("MyApp", Some("3.2.1"))));
+        assert!(tokens.contains(&product("Mobile", None)));
+    }
+
+    #[test]
+    fn handles_unclosed_comment() {
+        let tokens = tokenize("Mozilla/5.0 (Linux; Android 13; SM-G991B");
+
+        assert_eq!(
+            tokens,
+            vec![
+                product("Mozilla", Some("5.0")),
+                comment(&["Linux", "Android 13", "SM-G991B"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn handles_nested_and_stray_parentheses() {
+        let tokens = tokenize("Foo/1 (a; (b; c); d)) Bar/");
+
+        assert_eq!(
+            tokens,
+            vec![
+                product("Foo", Some("1")),
+                comment(&["a", "(b; c)", "d"]),
+                product("Bar", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn handles_empty_input() {
+        assert!(tokenize("").is_empty());
+        assert!(tokenize("   ").is_empty());
+        assert_eq!(tokenize("()"), vec![comment(&[])]);
+    }
+}
@@ -0,0 +1,110 @@
+//! Serializes parse results in the shape ua-parser-js returns, for consumers
+//! written against it. This is an interop view only; the canonical
+//! serialization of `Client` keeps the uap-core field names.
+//!
+//! ```rust
+//! # use uaparser::*;
+//! let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap();
+//! let client = parser.parse("Mozilla/5.0 (X11; Ubuntu; Linux x86_64; rv:121.0) Gecko/20100101 Firefox/121.0");
+//! let view = client.as_uaparser_js();
+//!
+//! assert_eq!(view.browser.name, Some("Firefox"));
+//! assert_eq!(view.browser.version.as_deref(), Some("121.0"));
+//! ```
+
+use std::borrow::Cow;
+
+use super::Serialize;
+use crate::{families::OTHER, otel::version, Client};
+
+/// A `Client` as ua-parser-js results: `browser`, `os` and `device` objects
+/// with unknown values left out
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize)]
+pub struct UaParserJsView<'a> {
+    pub browser: Browser<'a>,
+    pub os: Os<'a>,
+    pub device: Device<'a>,
+}
+
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, Serialize)]
+pub struct Browser<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<&'a str>,
+    /// Every known version part joined with dots, such as `120.0.6099.144`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub major: Option<&'a str>,
+}
+
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, Serialize)]
+pub struct Os<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<Cow<'a, str>>,
+}
+
+/// The device's brand and model. ua-parser-js also reports a `type`, such
+/// as `mobile` or `tablet`, which the uap-core rules don't classify, so it
+/// is always left out.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, Serialize)]
+pub struct Device<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vendor: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<&'a str>,
+}
+
+pub(crate) fn view<'a>(client: &'a Client<'_>) -> UaParserJsView<'a> {
+    let user_agent = &client.user_agent;
+    let os = &client.os;
+    let device = &client.device;
+    // ua-parser-js leaves what uap-core calls `Other` undefined
+    let known = |family: &'a str| Some(family).filter(|family| *family != OTHER);
+
+    UaParserJsView {
+        browser: Browser {
+            name: known(&user_agent.family),
+            version: version(&[
+                &user_agent.major,
+                &user_agent.minor,
+                &user_agent.patch,
+                &user_agent.patch_minor,
+            ]),
+            major: user_agent.major.as_deref(),
+        },
+        os: Os {
+            name: known(&os.family),
+            version: version(&[&os.major, &os.minor, &os.patch, &os.patch_minor]),
+        },
+        device: Device {
+            vendor: device.brand.as_deref(),
+            model: device.model.as_deref(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Parser, UserAgentParser};
+
+    #[test]
+    fn chrome_on_android() {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        let client = parser.parse(
+            "Mozilla/5.0 (Linux; Android 13; SM-S901B) AppleWebKit/537.36 \
+             (KHTML, like Gecko) Chrome/120.0.6099.144 Mobile Safari/537.36",
+        );
+
+        assert_eq!(
+            serde_json::to_string(&client.as_uaparser_js()).unwrap(),
+            r#"{"browser":{"name":"Chrome Mobile","version":"120.0.6099.144","major":"120"},"os":{"name":"Android","version":"13"},"device":{"vendor":"Samsung","model":"SM-S901B"}}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&parser.parse("").as_uaparser_js()).unwrap(),
+            r#"{"browser":{},"os":{},"device":{}}"#
+        );
+    }
+}
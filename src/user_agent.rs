@@ -1,15 +1,80 @@
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
 use std::borrow::Cow;
 
-use super::{Deserialize, Serialize};
+use super::{version, Deserialize, Serialize, Version};
 
-/// Describes the `Family` as well as the `Major`, `Minor`, and `Patch` versions
-/// of a `UserAgent` client
+/// Describes the `Family` as well as the `Major`, `Minor`, `Patch`, and
+/// `PatchMinor` versions of a `UserAgent` client
+///
+/// Fields may be added in later releases, so build values with
+/// `..UserAgent::default()` rather than listing every field.
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct UserAgent<'a> {
+    /// The browser, app or bot name, `Other` when no rule matched
+    #[serde(borrow)]
     pub family: Cow<'a, str>,
+    /// The major version, as written in the user agent
+    #[serde(borrow, default, deserialize_with = "crate::client::borrowed::option")]
     pub major: Option<Cow<'a, str>>,
+    /// The minor version, as written in the user agent
+    #[serde(borrow, default, deserialize_with = "crate::client::borrowed::option")]
     pub minor: Option<Cow<'a, str>>,
+    /// The patch version, as written in the user agent
+    #[serde(borrow, default, deserialize_with = "crate::client::borrowed::option")]
     pub patch: Option<Cow<'a, str>>,
+    /// The build number after the patch version, such as `129` in Chrome's
+    /// `120.0.6099.129`, left out of serialization when absent
+    #[serde(
+        borrow,
+        default,
+        deserialize_with = "crate::client::borrowed::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub patch_minor: Option<Cow<'a, str>>,
+}
+
+impl UserAgent<'_> {
+    /// The present version components joined with dots, such as
+    /// `120.0.6099.129`
+    #[must_use]
+    pub fn version_string(&self) -> Option<String> {
+        version::joined(self.versions())
+    }
+
+    /// The numeric version, `None` when the major version isn't a number
+    #[must_use]
+    pub fn version(&self) -> Option<Version> {
+        version::numeric(self.versions())
+    }
+
+    /// Whether the version satisfies a requirement such as `>=120`, as
+    /// `OS::satisfies`
+    #[cfg(feature = "semver")]
+    #[must_use]
+    pub fn satisfies(&self, req: &semver::VersionReq) -> Option<bool> {
+        version::satisfies(self.versions(), req)
+    }
+
+    fn versions(&self) -> [&Option<Cow<'_, str>>; 4] {
+        [&self.major, &self.minor, &self.patch, &self.patch_minor]
+    }
+
+    /// Copies any borrowed fields, so the result outlives the user agent
+    /// string and the parser
+    #[must_use]
+    pub fn into_owned(self) -> UserAgent<'static> {
+        UserAgent {
+            family: Cow::Owned(self.family.into_owned()),
+            major: self.major.map(|major| Cow::Owned(major.into_owned())),
+            minor: self.minor.map(|minor| Cow::Owned(minor.into_owned())),
+            patch: self.patch.map(|patch| Cow::Owned(patch.into_owned())),
+            patch_minor: self
+                .patch_minor
+                .map(|patch_minor| Cow::Owned(patch_minor.into_owned())),
+        }
+    }
 }
 
 impl<'a> Default for UserAgent<'a> {
@@ -19,6 +84,42 @@ impl<'a> Default for UserAgent<'a> {
             major: None,
             minor: None,
             patch: None,
+            patch_minor: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_helpers() {
+        let chrome = UserAgent {
+            family: Cow::Borrowed("Chrome"),
+            major: Some(Cow::Borrowed("120")),
+            minor: Some(Cow::Borrowed("0")),
+            patch: Some(Cow::Borrowed("6099")),
+            patch_minor: Some(Cow::Borrowed("129")),
+        };
+        assert_eq!(chrome.version_string().as_deref(), Some("120.0.6099.129"));
+        assert_eq!(
+            chrome.version(),
+            Some(Version {
+                patch_minor: 129,
+                ..Version::new(120, 0, 6099)
+            })
+        );
+
+        let beta = UserAgent {
+            family: Cow::Borrowed("Firefox Beta"),
+            major: Some(Cow::Borrowed("4")),
+            minor: Some(Cow::Borrowed("0b8pre")),
+            ..UserAgent::default()
+        };
+        assert_eq!(beta.version_string().as_deref(), Some("4.0b8pre"));
+        assert_eq!(beta.version(), Some(Version::new(4, 0, 0)));
+
+        assert_eq!(UserAgent::default().version(), None);
+    }
+}
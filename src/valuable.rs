@@ -0,0 +1,327 @@
+//! `valuable::Valuable` implementations, so tracing subscribers that support
+//! structured values record parse results as nested structures rather than
+//! as `Debug` output.
+
+use ::valuable::{
+    Fields, NamedField, NamedValues, StructDef, Structable, Valuable, Value, Visit,
+};
+use tracing::Span;
+
+use crate::{Client, Device, UserAgent, OS};
+
+/// The span fields set by `Client::record_into`, which must be declared when
+/// the span is created
+pub(crate) const SPAN_FIELDS: [&str; 7] = [
+    "user_agent.family",
+    "user_agent.major",
+    "os.family",
+    "os.major",
+    "device.family",
+    "device.brand",
+    "device.model",
+];
+
+pub(crate) fn record_into(client: &Client<'_>, span: &Span) {
+    let fields = [
+        Some(&*client.user_agent.family),
+        client.user_agent.major.as_deref(),
+        Some(&*client.os.family),
+        client.os.major.as_deref(),
+        Some(&*client.device.family),
+        client.device.brand.as_deref(),
+        client.device.model.as_deref(),
+    ];
+
+    for (name, value) in SPAN_FIELDS.iter().zip(fields) {
+        if let Some(value) = value {
+            span.record(*name, value);
+        }
+    }
+}
+
+/// Absent fields are recorded as `Value::Unit`
+fn optional(value: Option<&str>) -> Value<'_> {
+    value.map_or(Value::Unit, Value::String)
+}
+
+const CLIENT_FIELDS: &[NamedField<'static>] = &[
+    NamedField::new("device"),
+    NamedField::new("os"),
+    NamedField::new("user_agent"),
+];
+
+impl Structable for Client<'_> {
+    fn definition(&self) -> StructDef<'_> {
+        StructDef::new_static("Client", Fields::Named(CLIENT_FIELDS))
+    }
+}
+
+impl Valuable for Client<'_> {
+    fn as_value(&self) -> Value<'_> {
+        Value::Structable(self)
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        visit.visit_named_fields(&NamedValues::new(
+            CLIENT_FIELDS,
+            &[
+                self.device.as_value(),
+                self.os.as_value(),
+                self.user_agent.as_value(),
+            ],
+        ));
+    }
+}
+
+const DEVICE_FIELDS: &[NamedField<'static>] = &[
+    NamedField::new("family"),
+    NamedField::new("brand"),
+    NamedField::new("model"),
+];
+
+impl Structable for Device<'_> {
+    fn definition(&self) -> StructDef<'_> {
+        StructDef::new_static("Device", Fields::Named(DEVICE_FIELDS))
+    }
+}
+
+impl Valuable for Device<'_> {
+    fn as_value(&self) -> Value<'_> {
+        Value::Structable(self)
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        visit.visit_named_fields(&NamedValues::new(
+            DEVICE_FIELDS,
+            &[
+                Value::String(&self.family),
+                optional(self.brand.as_deref()),
+                optional(self.model.as_deref()),
+            ],
+        ));
+    }
+}
+
+const OS_FIELDS: &[NamedField<'static>] = &[
+    NamedField::new("family"),
+    NamedField::new("major"),
+    NamedField::new("minor"),
+    NamedField::new("patch"),
+    NamedField::new("patch_minor"),
+];
+
+impl Structable for OS<'_> {
+    fn definition(&self) -> StructDef<'_> {
+        StructDef::new_static("OS", Fields::Named(OS_FIELDS))
+    }
+}
+
+impl Valuable for OS<'_> {
+    fn as_value(&self) -> Value<'_> {
+        Value::Structable(self)
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        visit.visit_named_fields(&NamedValues::new(
+            OS_FIELDS,
+            &[
+                Value::String(&self.family),
+                optional(self.major.as_deref()),
+                optional(self.minor.as_deref()),
+                optional(self.patch.as_deref()),
+                optional(self.patch_minor.as_deref()),
+            ],
+        ));
+    }
+}
+
+const USER_AGENT_FIELDS: &[NamedField<'static>] = &[
+    NamedField::new("family"),
+    NamedField::new("major"),
+    NamedField::new("minor"),
+    NamedField::new("patch"),
+    NamedField::new("patch_minor"),
+];
+
+impl Structable for UserAgent<'_> {
+    fn definition(&self) -> StructDef<'_> {
+        StructDef::new_static("UserAgent", Fields::Named(USER_AGENT_FIELDS))
+    }
+}
+
+impl Valuable for UserAgent<'_> {
+    fn as_value(&self) -> Value<'_> {
+        Value::Structable(self)
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        visit.visit_named_fields(&NamedValues::new(
+            USER_AGENT_FIELDS,
+            &[
+                Value::String(&self.family),
+                optional(self.major.as_deref()),
+                optional(self.minor.as_deref()),
+                optional(self.patch.as_deref()),
+                optional(self.patch_minor.as_deref()),
+            ],
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tracing::{
+        field::{Empty, Field, Visit as FieldVisit},
+        span::{Attributes, Id, Record},
+        Event, Metadata, Subscriber,
+    };
+
+    use super::*;
+    use crate::{Parser, UserAgentParser};
+
+    const CHROME_ANDROID: &str = "Mozilla/5.0 (Linux; Android 13; SM-S901B) AppleWebKit/537.36 \
+                                  (KHTML, like Gecko) Chrome/120.0.6099.144 Mobile Safari/537.36";
+
+    fn parser() -> UserAgentParser {
+        UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed")
+    }
+
+    /// Flattens a structured value into `path=value` lines
+    #[derive(Default)]
+    struct Flatten {
+        path: Vec<String>,
+        lines: Vec<String>,
+    }
+
+    impl Visit for Flatten {
+        fn visit_value(&mut self, value: Value<'_>) {
+            let path = self.path.join(".");
+            match value {
+                Value::Structable(structable) => structable.visit(self),
+                Value::String(value) => self.lines.push(format!("{path}={value}")),
+                Value::Unit => self.lines.push(format!("{path}=()")),
+                _ => unreachable!(),
+            }
+        }
+
+        fn visit_named_fields(&mut self, named_values: &NamedValues<'_>) {
+            for (field, value) in named_values {
+                self.path.push(field.name().to_owned());
+                self.visit_value(*value);
+                self.path.pop();
+            }
+        }
+    }
+
+    #[test]
+    fn visits_nested_fields() {
+        let client = parser().parse(CHROME_ANDROID);
+        let mut flatten = Flatten::default();
+        client.as_value().visit(&mut flatten);
+
+        assert_eq!(
+            flatten.lines,
+            [
+                "device.family=Samsung SM-S901B",
+                "device.brand=Samsung",
+                "device.model=SM-S901B",
+                "os.family=Android",
+                "os.major=13",
+                "os.minor=()",
+                "os.patch=()",
+                "os.patch_minor=()",
+                "user_agent.family=Chrome Mobile",
+                "user_agent.major=120",
+                "user_agent.minor=0",
+                "user_agent.patch=6099",
+                "user_agent.patch_minor=144",
+            ]
+        );
+        assert_eq!(client.definition().name(), "Client");
+    }
+
+    /// Keeps every field recorded on any span
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<(String, String)>>>);
+
+    impl FieldVisit for Capture {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((field.name().to_owned(), value.to_owned()));
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.record_str(field, &format!("{value:?}"));
+        }
+    }
+
+    impl Subscriber for Capture {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, values: &Record<'_>) {
+            values.record(&mut self.clone());
+        }
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {}
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn records_span_fields() {
+        let parser = parser();
+        let capture = Capture::default();
+
+        tracing::subscriber::with_default(capture.clone(), || {
+            let span = tracing::info_span!(
+                "request",
+                user_agent.family = Empty,
+                user_agent.major = Empty,
+                os.family = Empty,
+                os.major = Empty,
+                device.family = Empty,
+                device.brand = Empty,
+                device.model = Empty,
+            );
+            parser.parse(CHROME_ANDROID).record_into(&span);
+            parser.parse("curl/8.4.0").record_into(&span);
+        });
+
+        let recorded = capture.0.lock().unwrap();
+        let recorded: Vec<(&str, &str)> = recorded
+            .iter()
+            .map(|(name, value)| (&**name, &**value))
+            .collect();
+        assert_eq!(
+            recorded,
+            [
+                ("user_agent.family", "Chrome Mobile"),
+                ("user_agent.major", "120"),
+                ("os.family", "Android"),
+                ("os.major", "13"),
+                ("device.family", "Samsung SM-S901B"),
+                ("device.brand", "Samsung"),
+                ("device.model", "SM-S901B"),
+                ("user_agent.family", "curl"),
+                ("user_agent.major", "8"),
+                ("os.family", "Other"),
+                ("device.family", "Other"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,227 @@
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+use std::{borrow::Cow, convert::TryFrom, fmt};
+
+use super::{Deserialize, Serialize};
+
+/// A version of up to four numeric components, for comparing the string
+/// versions of an `OS` or `UserAgent`. Missing components are zero, so `14`
+/// and `14.0.0` compare equal. Serializes as a string such as `"14.1.2"`,
+/// and also deserializes from a number such as `100`.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+)]
+#[serde(try_from = "Written", into = "String")]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    pub patch_minor: u32,
+}
+
+impl Version {
+    #[must_use]
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Version {
+        Version {
+            major,
+            minor,
+            patch,
+            patch_minor: 0,
+        }
+    }
+
+    /// Parses versions such as `14.1.2` or `10_15_7`. Only the leading digits
+    /// of a component count, so `0b3` reads as `0`, and a component without
+    /// any ends the version. `None` when the major version isn't numeric.
+    #[must_use]
+    pub fn parse(text: &str) -> Option<Version> {
+        Version::from_parts(split(text))
+    }
+
+    fn from_parts<'a>(parts: impl IntoIterator<Item = &'a str>) -> Option<Version> {
+        let mut numbers = parts
+            .into_iter()
+            .map_while(|part| {
+                let digits = part
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(part.len());
+                part[..digits].parse().ok()
+            })
+            .fuse();
+        Some(Version {
+            major: numbers.next()?,
+            minor: numbers.next().unwrap_or_default(),
+            patch: numbers.next().unwrap_or_default(),
+            patch_minor: numbers.next().unwrap_or_default(),
+        })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if self.patch_minor != 0 {
+            write!(f, ".{}", self.patch_minor)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Version> for String {
+    fn from(version: Version) -> String {
+        version.to_string()
+    }
+}
+
+/// Keeps the major, minor and patch versions; semver has no place for a
+/// fourth component, so `patch_minor` is dropped.
+#[cfg(feature = "semver")]
+impl From<Version> for semver::Version {
+    fn from(version: Version) -> semver::Version {
+        semver::Version::new(
+            version.major.into(),
+            version.minor.into(),
+            version.patch.into(),
+        )
+    }
+}
+
+/// Reads a version as written in user agents, such as `17.1` or `10_15_7`,
+/// as a semver `Version`, leniently as `Version::parse` does. Missing
+/// components are zero, so `14` becomes `14.0.0`: it satisfies `>=14.0.0`
+/// and `=14.0.0`, and also `<14.1`, though the actual release may have been
+/// any `14.x`. A fourth component is dropped, and pre-release tags such as
+/// `b3` in `115.0b3` are ignored rather than kept as semver pre-releases.
+///
+/// ```rust
+/// # use uaparser::coerce_semver;
+/// assert_eq!(coerce_semver("10_15_7"), Some(semver::Version::new(10, 15, 7)));
+/// assert_eq!(coerce_semver("14"), Some(semver::Version::new(14, 0, 0)));
+/// assert_eq!(coerce_semver("beta"), None);
+/// ```
+#[cfg(feature = "semver")]
+#[must_use]
+pub fn coerce_semver(text: &str) -> Option<semver::Version> {
+    Version::parse(text).map(Into::into)
+}
+
+/// Whether a version satisfies `req`, as `OS::satisfies` and
+/// `UserAgent::satisfies` check it
+#[cfg(feature = "semver")]
+pub(crate) fn satisfies(
+    fields: [&Option<Cow<'_, str>>; 4],
+    req: &semver::VersionReq,
+) -> Option<bool> {
+    numeric(fields).map(|version| req.matches(&version.into()))
+}
+
+/// The ways a version is written in configuration files
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Written {
+    Text(String),
+    Integer(u32),
+    Float(f64),
+}
+
+impl TryFrom<Written> for Version {
+    type Error = String;
+
+    fn try_from(written: Written) -> Result<Version, String> {
+        let text = match written {
+            Written::Text(text) => text,
+            Written::Integer(major) => return Ok(Version::new(major, 0, 0)),
+            Written::Float(version) => version.to_string(),
+        };
+        Version::parse(&text).ok_or_else(|| format!("invalid version `{text}`"))
+    }
+}
+
+/// The version components up to the first missing one, with any written as
+/// `10_15_7` in a single field split apart
+pub(crate) fn parts<'a>(
+    fields: [&'a Option<Cow<'_, str>>; 4],
+) -> impl Iterator<Item = &'a str> {
+    IntoIterator::into_iter(fields)
+        .map_while(Option::as_deref)
+        .flat_map(split)
+}
+
+/// Joins the version components with dots, `None` when there are none
+pub(crate) fn joined(fields: [&Option<Cow<'_, str>>; 4]) -> Option<String> {
+    let parts: Vec<_> = parts(fields).collect();
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("."))
+    }
+}
+
+pub(crate) fn numeric(fields: [&Option<Cow<'_, str>>; 4]) -> Option<Version> {
+    Version::from_parts(parts(fields))
+}
+
+fn split(text: &str) -> impl Iterator<Item = &str> {
+    text.split(['.', '_']).filter(|part| !part.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_leading_digits() {
+        assert_eq!(Version::parse("14.1.2"), Some(Version::new(14, 1, 2)));
+        assert_eq!(Version::parse("10_15_7"), Some(Version::new(10, 15, 7)));
+        assert_eq!(Version::parse("115.0b3"), Some(Version::new(115, 0, 0)));
+        assert_eq!(Version::parse("3.beta.1"), Some(Version::new(3, 0, 0)));
+        assert_eq!(Version::parse("beta"), None);
+        assert_eq!(
+            Version::parse("120.0.6099.129").map(|version| version.to_string()),
+            Some("120.0.6099.129".to_owned())
+        );
+    }
+
+    #[test]
+    fn serializes_as_a_string() {
+        let version = Version::new(10, 15, 7);
+        assert_eq!(serde_json::to_string(&version).unwrap(), r#""10.15.7""#);
+
+        let read = |yaml| serde_yaml::from_str::<Version>(yaml).ok();
+        assert_eq!(read("'10.15.7'"), Some(version));
+        assert_eq!(read("100"), Some(Version::new(100, 0, 0)));
+        assert_eq!(read("115.5"), Some(Version::new(115, 5, 0)));
+        assert_eq!(read("latest"), None);
+    }
+
+    #[cfg(feature = "semver")]
+    #[test]
+    fn coerces_to_semver() {
+        assert_eq!(coerce_semver("17.1"), Some(semver::Version::new(17, 1, 0)));
+        assert_eq!(
+            coerce_semver("120.0.6099.129"),
+            Some(semver::Version::new(120, 0, 6099))
+        );
+        assert_eq!(coerce_semver(""), None);
+
+        let req = semver::VersionReq::parse(">=14.0.0").unwrap();
+        assert!(coerce_semver("14").is_some_and(|version| req.matches(&version)));
+    }
+
+    #[test]
+    fn missing_components_are_zero() {
+        assert_eq!(Version::parse("14"), Version::parse("14.0.0"));
+        assert!(Version::parse("10.15.7") < Version::parse("11"));
+        assert_eq!(Version::new(14, 0, 0).to_string(), "14.0.0");
+    }
+}
@@ -0,0 +1,35 @@
+//! A warp filter that extracts the parsed `User-Agent` header.
+//!
+//! ```rust,no_run
+//! # use std::sync::Arc;
+//! # use uaparser::UserAgentParser;
+//! use warp::Filter;
+//!
+//! let parser = Arc::new(UserAgentParser::from_yaml("./src/core/regexes.yaml").unwrap());
+//! let route = warp::path("family")
+//!     .and(uaparser::warp::with_client(parser))
+//!     .map(|client: uaparser::Client<'static>| client.user_agent.family.into_owned());
+//! ```
+
+use std::{convert::Infallible, sync::Arc};
+
+use ::warp::{http::HeaderMap, Filter};
+
+use crate::{Client, UserAgentParser};
+
+/// Extracts the owned `Client` parsed from the request headers with
+/// `UserAgentParser::parse_header_pairs`, so folded `user-agent` values are
+/// split and the parser's header settings apply. Headers that aren't UTF-8
+/// are skipped, and without a user agent the `Client` is the default. It
+/// never rejects, so it composes with `and` without recovering.
+#[must_use]
+pub fn with_client(
+    parser: Arc<UserAgentParser>,
+) -> impl Filter<Extract = (Client<'static>,), Error = Infallible> + Clone {
+    ::warp::header::headers_cloned().map(move |headers: HeaderMap| {
+        let pairs = headers
+            .iter()
+            .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)));
+        parser.parse_header_pairs(pairs).into_owned()
+    })
+}
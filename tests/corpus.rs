@@ -0,0 +1,67 @@
+use rand::{rngs::StdRng, SeedableRng};
+use uaparser::{
+    assert_ua,
+    testing::{corpus, random_ua, Category},
+    Parser, UserAgentParser,
+};
+
+fn parser() -> UserAgentParser {
+    UserAgentParser::from_yaml("./src/core/regexes.yaml").expect("Parser creation failed")
+}
+
+#[test]
+fn corpus_parses() {
+    let parser = parser();
+
+    for category in Category::ALL {
+        for user_agent in category.user_agents() {
+            let client = parser.parse(user_agent);
+            assert_eq!(parser.parse_borrowed(user_agent), client, "{user_agent}");
+        }
+    }
+    for user_agent in corpus::BOTS {
+        assert_ua!(parser, user_agent, device: { family: "Spider" });
+    }
+    for user_agent in corpus::HTTP_TOOLS {
+        assert!(parser.parse(user_agent).is_http_tool(), "{}", user_agent);
+    }
+}
+
+#[test]
+fn parses_known_clients() {
+    let parser = parser();
+
+    assert_ua!(
+        parser,
+        corpus::CHROME_ANDROID[1],
+        ua: { family: "Chrome Mobile", major: "120", minor: "0", patch: "6099" },
+        os: { family: "Android", major: "13", minor: None },
+        device: { family: "Samsung SM-S901B", brand: "Samsung", model: "SM-S901B" },
+    );
+    assert_ua!(
+        parser,
+        corpus::SAFARI_IOS[0],
+        ua: { family: "Mobile Safari", major: "17" },
+        os: { family: "iOS", major: "17" },
+        device: { family: "iPhone", brand: "Apple" },
+    );
+    assert_ua!(
+        parser,
+        corpus::HTTP_TOOLS[0],
+        ua: { family: "curl", major: "8" },
+        os: { family: "Other" },
+        device: { family: "Other", brand: None },
+    );
+}
+
+#[test]
+fn picks_from_category() {
+    let mut rng = StdRng::seed_from_u64(7);
+
+    for category in Category::ALL {
+        for _ in 0..10 {
+            let user_agent = random_ua(&mut rng, category);
+            assert!(category.user_agents().contains(&user_agent));
+        }
+    }
+}
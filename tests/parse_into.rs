@@ -0,0 +1,63 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use uaparser::{ClientBuf, Parser, UserAgentParser};
+
+/// Counts every allocation, so this file holds a single test to keep other
+/// tests' allocations out of the count
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+const USER_AGENTS: [&str; 5] = [
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) \
+     Chrome/120.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (Linux; Android 13; SM-S901B) AppleWebKit/537.36 (KHTML, like Gecko) \
+     Chrome/120.0.6099.144 Mobile Safari/537.36",
+    "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1_2 like Mac OS X) AppleWebKit/605.1.15 \
+     (KHTML, like Gecko) Version/17.1 Mobile/15E148 Safari/604.1",
+    "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)",
+    "curl/8.4.0",
+];
+
+#[test]
+fn parse_into_stops_allocating_after_warmup() {
+    let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+        .expect("Parser creation failed");
+    let expected: Vec<_> = USER_AGENTS.iter().map(|ua| parser.parse(ua)).collect();
+    let mut buf = ClientBuf::new();
+
+    let mut allocations = Vec::new();
+    for _ in 0..10 {
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        for (user_agent, expected) in USER_AGENTS.iter().zip(&expected) {
+            parser.parse_into(user_agent, &mut buf);
+            assert_eq!(buf.client(), *expected, "{user_agent}");
+        }
+        allocations.push(ALLOCATIONS.load(Ordering::Relaxed) - before);
+    }
+
+    assert!(allocations[0] > 0);
+    assert_eq!(allocations[2..], [0; 8], "{allocations:?}");
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "rocket")]
+
+use std::sync::Arc;
+
+use rocket::{http::Status, local::blocking::Client};
+use uaparser::{headers::UaHeaderPolicy, rocket::UserAgentInfo, UserAgentParser};
+
+#[rocket::get("/")]
+fn family(user_agent: UserAgentInfo) -> String {
+    user_agent.user_agent.family.to_string()
+}
+
+fn client(manage_parser: bool) -> Client {
+    let mut rocket = rocket::build().mount("/", rocket::routes![family]);
+    if manage_parser {
+        let parser = UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed");
+        rocket = rocket.manage(Arc::new(parser));
+    }
+    Client::tracked(rocket).expect("Rocket instance failed")
+}
+
+#[test]
+fn echoes_the_parsed_family() {
+    let client = client(true);
+
+    let response = client
+        .get("/")
+        .header(rocket::http::Header::new("User-Agent", "curl/8.4.0"))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().as_deref(), Some("curl"));
+
+    let response = client.get("/").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().as_deref(), Some("Other"));
+}
+
+#[test]
+fn splits_folded_user_agents() {
+    let client = client(true);
+    let response = client
+        .get("/")
+        .header(rocket::http::Header::new(
+            "User-Agent",
+            "curl/8.4.0, Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) \
+             AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.2 Safari/605.1.15",
+        ))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().as_deref(), Some("curl"));
+}
+
+#[test]
+fn reads_the_parsers_header_policy() {
+    let parser = UserAgentParser::builder()
+        .ua_header_policy(UaHeaderPolicy::new(["X-Original-User-Agent", "User-Agent"]))
+        .build_from_yaml("./src/core/regexes.yaml")
+        .expect("Parser creation failed");
+    let rocket = rocket::build()
+        .mount("/", rocket::routes![family])
+        .manage(Arc::new(parser));
+    let client = Client::tracked(rocket).expect("Rocket instance failed");
+
+    let response = client
+        .get("/")
+        .header(rocket::http::Header::new("User-Agent", "CorpProxy/2.1"))
+        .header(rocket::http::Header::new(
+            "X-Original-User-Agent",
+            "curl/8.4.0",
+        ))
+        .dispatch();
+    assert_eq!(response.into_string().as_deref(), Some("curl"));
+}
+
+#[test]
+fn fails_without_a_managed_parser() {
+    let client = client(false);
+
+    let response = client
+        .get("/")
+        .header(rocket::http::Header::new("User-Agent", "curl/8.4.0"))
+        .dispatch();
+    assert_eq!(response.status(), Status::InternalServerError);
+}
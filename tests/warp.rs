@@ -0,0 +1,73 @@
+#![cfg(feature = "warp")]
+
+use std::sync::Arc;
+
+use uaparser::{headers::HeaderValuePolicy, warp::with_client, Client, UserAgentParser};
+use warp::Filter;
+
+fn parser() -> Arc<UserAgentParser> {
+    Arc::new(
+        UserAgentParser::from_yaml("./src/core/regexes.yaml")
+            .expect("Parser creation failed"),
+    )
+}
+
+#[tokio::test]
+async fn extracts_the_parsed_client() {
+    let filter = with_client(parser());
+
+    let client = warp::test::request()
+        .header("user-agent", "curl/8.4.0")
+        .filter(&filter)
+        .await
+        .unwrap();
+    assert_eq!(client.user_agent.family, "curl");
+
+    let client = warp::test::request().filter(&filter).await.unwrap();
+    assert_eq!(client, Client::default());
+
+    let client = warp::test::request()
+        .header("user-agent", &b"curl/8.4.0 \xff"[..])
+        .filter(&filter)
+        .await
+        .unwrap();
+    assert_eq!(client, Client::default());
+}
+
+#[tokio::test]
+async fn splits_folded_user_agents() {
+    let request = || {
+        warp::test::request().header(
+            "user-agent",
+            "curl/8.4.0, Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) \
+             AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.2 Safari/605.1.15",
+        )
+    };
+
+    let client = request().filter(&with_client(parser())).await.unwrap();
+    assert_eq!(client.user_agent.family, "curl");
+
+    let parser = UserAgentParser::builder()
+        .header_value_policy(HeaderValuePolicy::UseLast)
+        .build_from_yaml("./src/core/regexes.yaml")
+        .expect("Parser creation failed");
+    let client = request()
+        .filter(&with_client(Arc::new(parser)))
+        .await
+        .unwrap();
+    assert_eq!(client.user_agent.family, "Safari");
+}
+
+#[tokio::test]
+async fn composes_with_other_filters() {
+    let route = warp::path("family")
+        .and(with_client(parser()))
+        .map(|client: Client<'static>| client.user_agent.family.into_owned());
+
+    let response = warp::test::request()
+        .path("/family")
+        .header("user-agent", "curl/8.4.0")
+        .reply(&route)
+        .await;
+    assert_eq!(response.body(), "curl");
+}